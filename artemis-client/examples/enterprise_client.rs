@@ -35,6 +35,7 @@ async fn main() -> anyhow::Result<()> {
         cache_ttl_secs: 300,
         address_refresh_interval_secs: 60,
         enable_metrics: true,
+        api_key: std::env::var("ARTEMIS_API_KEY").ok(),
     };
 
     println!("1. Configuration:");
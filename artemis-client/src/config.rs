@@ -2,6 +2,9 @@ use std::time::Duration;
 
 use crate::error::ClientError;
 
+/// Header carrying the application API key
+pub const API_KEY_HEADER: &str = "X-Artemis-Api-Key";
+
 /// Client configuration for Artemis service discovery
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
@@ -31,6 +34,10 @@ pub struct ClientConfig {
 
     /// Enable Prometheus metrics (default: false)
     pub enable_metrics: bool,
+
+    /// Application API key, sent as `X-Artemis-Api-Key` on registry and discovery
+    /// requests when the server requires one (default: None)
+    pub api_key: Option<String>,
 }
 
 impl Default for ClientConfig {
@@ -45,6 +52,7 @@ impl Default for ClientConfig {
             cache_ttl_secs: 900,
             address_refresh_interval_secs: 60,
             enable_metrics: false,
+            api_key: None,
         }
     }
 }
@@ -88,6 +96,23 @@ impl ClientConfig {
         Ok(())
     }
 
+    /// Build the HTTP client used for registry and discovery requests
+    ///
+    /// The API key, if configured, is attached to every request as a default header
+    pub fn http_client(&self) -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(value) =
+            self.api_key.as_deref().and_then(|key| reqwest::header::HeaderValue::from_str(key).ok())
+        {
+            headers.insert(API_KEY_HEADER, value);
+        }
+
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new())
+    }
+
     /// Get heartbeat interval as Duration
     pub fn heartbeat_interval(&self) -> Duration {
         Duration::from_secs(self.heartbeat_interval_secs)
@@ -149,9 +174,11 @@ mod tests {
             cache_ttl_secs: 600,
             address_refresh_interval_secs: 120,
             enable_metrics: true,
+            api_key: Some("order-secret".into()),
         };
         assert_eq!(config.server_urls.len(), 2);
         assert!(config.enable_metrics);
+        assert_eq!(config.api_key.as_deref(), Some("order-secret"));
     }
 
     #[test]
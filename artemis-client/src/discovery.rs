@@ -42,7 +42,8 @@ pub struct DiscoveryClient {
 
 impl DiscoveryClient {
     pub fn new(config: ClientConfig) -> Self {
-        Self { config, client: Client::new(), cache: Arc::new(RwLock::new(HashMap::new())) }
+        let client = config.http_client();
+        Self { config, client, cache: Arc::new(RwLock::new(HashMap::new())) }
     }
 
     pub async fn get_service(&self, request: GetServiceRequest) -> Result<Option<Service>> {
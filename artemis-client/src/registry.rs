@@ -14,7 +14,8 @@ pub struct RegistryClient {
 
 impl RegistryClient {
     pub fn new(config: ClientConfig) -> Self {
-        Self { config, client: Client::new() }
+        let client = config.http_client();
+        Self { config, client }
     }
 
     pub async fn register(&self, request: RegisterRequest) -> Result<RegisterResponse> {
@@ -3,12 +3,28 @@ use super::request::ResponseStatus;
 use super::service::Service;
 use serde::{Deserialize, Serialize};
 
+// ===== 修订号 =====
+
+/// 实例修订号, 由发起变更的节点分配
+///
+/// 先比较逻辑时钟, 相同时比较节点 ID, 所有节点对同一组修订号得到相同的先后顺序。
+/// 默认值是最小的修订号, 表示来源未知 (如启动同步或反熵修复加载的实例)
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    pub clock: u64,
+    pub node_id: String,
+}
+
 // ===== 复制-注册 =====
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReplicateRegisterRequest {
     pub instances: Vec<Instance>,
+    /// 与 instances 一一对应的修订号, 为空时 (旧版本节点) 直接应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +57,9 @@ pub struct ReplicateHeartbeatResponse {
 #[serde(rename_all = "camelCase")]
 pub struct ReplicateUnregisterRequest {
     pub instance_keys: Vec<InstanceKey>,
+    /// 与 instance_keys 一一对应的修订号, 为空时 (旧版本节点) 直接应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +84,9 @@ pub struct GetAllServicesResponse {
 #[serde(rename_all = "camelCase")]
 pub struct BatchRegisterRequest {
     pub instances: Vec<Instance>,
+    /// 与 instances 一一对应的修订号, 为空时 (旧版本节点) 直接应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -95,6 +117,9 @@ pub struct BatchHeartbeatResponse {
 #[serde(rename_all = "camelCase")]
 pub struct BatchUnregisterRequest {
     pub instance_keys: Vec<InstanceKey>,
+    /// 与 instance_keys 一一对应的修订号, 为空时 (旧版本节点) 直接应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -138,3 +163,67 @@ pub struct SyncFullDataResponse {
     pub services: Vec<Service>,
     pub sync_timestamp: i64,
 }
+
+// ===== 反熵 (Anti-Entropy) =====
+
+/// 服务摘要 - 对等节点间比较注册表是否一致
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDigest {
+    pub service_id: String,
+    pub instance_count: usize,
+    /// 实例 key 和实例内容的哈希
+    pub digest: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetServiceDigestsResponse {
+    pub response_status: ResponseStatus,
+    pub digests: Vec<ServiceDigest>,
+}
+
+/// 拉取指定服务的实例 (只拉取摘要不一致的服务)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchServicesRequest {
+    pub service_ids: Vec<String>,
+}
+
+/// 带租约信息的实例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeasedInstance {
+    pub instance: Instance,
+    /// 距最近一次续约的毫秒数, 修复方按此创建租约, 避免延长已停止心跳的实例
+    pub lease_age_ms: u64,
+    /// 实例当前的修订号, 修复方只在它比本地修订号新时覆盖本地数据 (旧版本节点不发送)
+    #[serde(default)]
+    pub revision: Revision,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchServicesResponse {
+    pub response_status: ResponseStatus,
+    pub instances: Vec<LeasedInstance>,
+}
+
+// ===== 跨区域联邦 =====
+
+/// 区域导出给其他区域的实例视图
+///
+/// 只包含导出方自己注册的、状态为 Up 的实例, 其他区域只读
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FederationExportResponse {
+    pub response_status: ResponseStatus,
+    /// 导出方所在区域
+    pub region_id: String,
+    pub instances: Vec<Instance>,
+    /// 实例视图的摘要, 拉取方下次请求时携带, 未变化时导出方不返回实例
+    pub digest: String,
+    /// 与拉取方携带的摘要相同, 实例视图没有变化 (instances 为空)
+    #[serde(default)]
+    pub unchanged: bool,
+}
@@ -0,0 +1,23 @@
+-- Artemis 细粒度 RBAC: 自定义角色和多角色授权
+
+-- 26. 角色表 (auth_roles) - 自定义角色, permissions 为 JSON 数组
+CREATE TABLE IF NOT EXISTS auth_roles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    role_id TEXT NOT NULL UNIQUE,
+    description TEXT,
+    permissions TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+-- 27. 用户角色关联表 (auth_user_roles) - 用户的附加角色
+CREATE TABLE IF NOT EXISTS auth_user_roles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    role_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE(user_id, role_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_user ON auth_user_roles(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_roles_role ON auth_user_roles(role_id);
@@ -0,0 +1,24 @@
+-- Artemis API Token 和服务账号
+
+-- 28. API Token 表 (auth_api_tokens) - 只保存令牌 SHA-256 摘要, scopes 为 JSON 数组
+CREATE TABLE IF NOT EXISTS auth_api_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_id TEXT NOT NULL UNIQUE,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_prefix TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT,
+    last_used_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user ON auth_api_tokens(user_id);
+
+-- 29. 服务账号表 (auth_service_accounts) - 标记为服务账号的用户
+CREATE TABLE IF NOT EXISTS auth_service_accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+);
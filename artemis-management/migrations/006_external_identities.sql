@@ -0,0 +1,11 @@
+-- Artemis 控制台单点登录 (OIDC)
+
+-- 35. 外部身份表 (auth_external_identities) - 身份提供方用户与本地账号的绑定
+CREATE TABLE IF NOT EXISTS auth_external_identities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id TEXT NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL,
+    UNIQUE(provider, subject)
+);
@@ -0,0 +1,12 @@
+-- Artemis 控制台访问令牌 (JWT) 签名密钥
+
+-- 36. 签名密钥表 (auth_signing_keys) - retired_at 为空的是当前签名密钥,
+--     其余为轮换下来、仍用于校验未过期令牌的旧密钥
+CREATE TABLE IF NOT EXISTS auth_signing_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kid TEXT NOT NULL UNIQUE,
+    algorithm TEXT NOT NULL,
+    private_key TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    retired_at BIGINT
+);
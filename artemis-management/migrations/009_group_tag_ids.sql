@@ -0,0 +1,10 @@
+-- Artemis 分组标签关联修正
+
+-- 分组标签 (service_group_tag) 的 GROUP_ID 曾写入分组名称, 转换为分组主键 id
+DELETE FROM service_group_tag
+WHERE typeof(GROUP_ID) = 'text'
+  AND NOT EXISTS (SELECT 1 FROM service_group g WHERE g.NAME = service_group_tag.GROUP_ID);
+
+UPDATE service_group_tag
+SET GROUP_ID = (SELECT g.id FROM service_group g WHERE g.NAME = service_group_tag.GROUP_ID)
+WHERE typeof(GROUP_ID) = 'text';
@@ -0,0 +1,21 @@
+-- Artemis 高风险操作审批
+
+-- 38. 变更请求表 (change_request) - 命中审批策略的操作在批准前保存在此,
+--     operation 为待执行操作的 JSON, 记录保留作为审批轨迹
+CREATE TABLE IF NOT EXISTS change_request (
+    request_id TEXT PRIMARY KEY,
+    operation_type TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    affected_instances BIGINT NOT NULL DEFAULT 0,
+    status TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    reviewed_by TEXT,
+    reviewed_at BIGINT,
+    comment TEXT,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_request_status ON change_request(status);
@@ -0,0 +1,2 @@
+-- 回滚 012_auth_change_markers: 删除失效记录类型
+ALTER TABLE auth_session_revocations DROP COLUMN kind;
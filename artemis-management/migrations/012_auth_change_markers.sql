@@ -0,0 +1,6 @@
+-- Artemis 控制台用户和角色变更集群同步
+
+-- 37. 会话失效记录 (auth_session_revocations) 增加记录类型: session 为会话失效 (原有记录);
+--     user 表示用户信息、角色分配或两步验证变更, user_id 为该用户; role 表示角色定义变更,
+--     user_id 列为角色 ID. 各节点轮询后按数据库刷新本地缓存
+ALTER TABLE auth_session_revocations ADD COLUMN kind TEXT NOT NULL DEFAULT 'session';
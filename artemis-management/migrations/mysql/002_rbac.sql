@@ -0,0 +1,24 @@
+-- Artemis 细粒度 RBAC: 自定义角色和多角色授权
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 26. 角色表 (auth_roles) - 自定义角色, permissions 为 JSON 数组
+CREATE TABLE IF NOT EXISTS auth_roles (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    role_id VARCHAR(191) NOT NULL UNIQUE,
+    description TEXT,
+    permissions TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 27. 用户角色关联表 (auth_user_roles) - 用户的附加角色
+CREATE TABLE IF NOT EXISTS auth_user_roles (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL,
+    role_id VARCHAR(191) NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE(user_id, role_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_user_roles_user ON auth_user_roles(user_id);
+CREATE INDEX idx_user_roles_role ON auth_user_roles(role_id);
@@ -0,0 +1,25 @@
+-- Artemis API Token 和服务账号
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 28. API Token 表 (auth_api_tokens) - 只保存令牌 SHA-256 摘要, scopes 为 JSON 数组
+CREATE TABLE IF NOT EXISTS auth_api_tokens (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    token_id VARCHAR(191) NOT NULL UNIQUE,
+    user_id VARCHAR(191) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    token_prefix VARCHAR(255) NOT NULL,
+    token_hash VARCHAR(191) NOT NULL UNIQUE,
+    scopes TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT,
+    last_used_at BIGINT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_api_tokens_user ON auth_api_tokens(user_id);
+
+-- 29. 服务账号表 (auth_service_accounts) - 标记为服务账号的用户
+CREATE TABLE IF NOT EXISTS auth_service_accounts (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
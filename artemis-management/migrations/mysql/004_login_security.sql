@@ -0,0 +1,19 @@
+-- Artemis 控制台登录安全: 密码历史和强制改密
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 30. 密码历史表 (auth_password_history) - 保存用户曾经使用过的密码摘要, 用于禁止重复使用
+CREATE TABLE IF NOT EXISTS auth_password_history (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL,
+    password_hash TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_password_history_user ON auth_password_history(user_id);
+
+-- 31. 强制改密表 (auth_password_change_required) - 下次登录后必须先修改密码的用户
+CREATE TABLE IF NOT EXISTS auth_password_change_required (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
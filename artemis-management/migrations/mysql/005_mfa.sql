@@ -0,0 +1,28 @@
+-- Artemis 控制台两步验证 (TOTP)
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 32. TOTP 密钥表 (auth_user_totp) - enabled = 0 表示已发起绑定但尚未验证
+CREATE TABLE IF NOT EXISTS auth_user_totp (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL UNIQUE,
+    secret TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- 33. 恢复码表 (auth_recovery_codes) - 只保存恢复码 SHA-256 摘要
+CREATE TABLE IF NOT EXISTS auth_recovery_codes (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(191) NOT NULL,
+    code_hash VARCHAR(255) NOT NULL,
+    used_at BIGINT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_recovery_codes_user ON auth_recovery_codes(user_id);
+
+-- 34. 强制两步验证的角色 (auth_mfa_required_roles)
+CREATE TABLE IF NOT EXISTS auth_mfa_required_roles (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    role_id VARCHAR(191) NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,12 @@
+-- Artemis 控制台单点登录 (OIDC)
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 35. 外部身份表 (auth_external_identities) - 身份提供方用户与本地账号的绑定
+CREATE TABLE IF NOT EXISTS auth_external_identities (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    provider VARCHAR(191) NOT NULL,
+    subject VARCHAR(191) NOT NULL,
+    user_id VARCHAR(191) NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL,
+    UNIQUE(provider, subject)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
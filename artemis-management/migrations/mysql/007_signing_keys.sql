@@ -0,0 +1,13 @@
+-- Artemis 控制台访问令牌 (JWT) 签名密钥
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 36. 签名密钥表 (auth_signing_keys) - retired_at 为空的是当前签名密钥,
+--     其余为轮换下来、仍用于校验未过期令牌的旧密钥
+CREATE TABLE IF NOT EXISTS auth_signing_keys (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    kid VARCHAR(191) NOT NULL UNIQUE,
+    algorithm VARCHAR(255) NOT NULL,
+    private_key TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    retired_at BIGINT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
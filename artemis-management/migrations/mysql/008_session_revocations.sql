@@ -0,0 +1,13 @@
+-- Artemis 控制台会话集群同步
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 37. 会话失效记录 (auth_session_revocations) - 会话被撤销或令牌刷新后写入,
+--     各节点轮询后清除本地缓存的会话, session_id 为空表示该用户的全部会话
+CREATE TABLE IF NOT EXISTS auth_session_revocations (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    session_id VARCHAR(255),
+    user_id VARCHAR(255) NOT NULL,
+    revoked_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_session_revocations_time ON auth_session_revocations(revoked_at);
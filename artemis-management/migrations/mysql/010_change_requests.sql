@@ -0,0 +1,22 @@
+-- Artemis 高风险操作审批
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 38. 变更请求表 (change_request) - 命中审批策略的操作在批准前保存在此,
+--     operation 为待执行操作的 JSON, 记录保留作为审批轨迹
+CREATE TABLE IF NOT EXISTS change_request (
+    request_id VARCHAR(191) PRIMARY KEY,
+    operation_type VARCHAR(255) NOT NULL,
+    target_id VARCHAR(255) NOT NULL,
+    operation TEXT NOT NULL,
+    affected_instances BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(32) NOT NULL,
+    requested_by VARCHAR(255) NOT NULL,
+    requested_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    reviewed_by VARCHAR(255),
+    reviewed_at BIGINT,
+    comment TEXT,
+    error TEXT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_change_request_status ON change_request(status);
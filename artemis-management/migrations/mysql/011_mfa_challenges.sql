@@ -0,0 +1,16 @@
+-- Artemis 控制台两步验证挑战集群共享
+-- MySQL 8.0+ 版本 (TEXT 列默认值使用表达式语法, 索引列使用 VARCHAR)
+
+-- 39. 两步验证挑战 (auth_mfa_challenges) - 密码校验通过后写入, 提交验证码时
+--     可落在任一节点; 完成登录、错误次数过多或过期后删除
+CREATE TABLE IF NOT EXISTS auth_mfa_challenges (
+    challenge_token VARCHAR(191) PRIMARY KEY,
+    user_id VARCHAR(191) NOT NULL,
+    username VARCHAR(255) NOT NULL,
+    ip_address VARCHAR(255),
+    user_agent TEXT,
+    expires_at BIGINT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE INDEX idx_mfa_challenges_expires ON auth_mfa_challenges(expires_at);
@@ -0,0 +1,24 @@
+-- Artemis 细粒度 RBAC: 自定义角色和多角色授权
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 26. 角色表 (auth_roles) - 自定义角色, permissions 为 JSON 数组
+CREATE TABLE IF NOT EXISTS auth_roles (
+    id BIGSERIAL PRIMARY KEY,
+    role_id TEXT NOT NULL UNIQUE,
+    description TEXT,
+    permissions TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+-- 27. 用户角色关联表 (auth_user_roles) - 用户的附加角色
+CREATE TABLE IF NOT EXISTS auth_user_roles (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    role_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE(user_id, role_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_user ON auth_user_roles(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_roles_role ON auth_user_roles(role_id);
@@ -0,0 +1,19 @@
+-- Artemis 控制台登录安全: 密码历史和强制改密
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 30. 密码历史表 (auth_password_history) - 保存用户曾经使用过的密码摘要, 用于禁止重复使用
+CREATE TABLE IF NOT EXISTS auth_password_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    password_hash TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_history_user ON auth_password_history(user_id);
+
+-- 31. 强制改密表 (auth_password_change_required) - 下次登录后必须先修改密码的用户
+CREATE TABLE IF NOT EXISTS auth_password_change_required (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+);
@@ -0,0 +1,28 @@
+-- Artemis 控制台两步验证 (TOTP)
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 32. TOTP 密钥表 (auth_user_totp) - enabled = 0 表示已发起绑定但尚未验证
+CREATE TABLE IF NOT EXISTS auth_user_totp (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL UNIQUE,
+    secret TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+);
+
+-- 33. 恢复码表 (auth_recovery_codes) - 只保存恢复码 SHA-256 摘要
+CREATE TABLE IF NOT EXISTS auth_recovery_codes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    used_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_recovery_codes_user ON auth_recovery_codes(user_id);
+
+-- 34. 强制两步验证的角色 (auth_mfa_required_roles)
+CREATE TABLE IF NOT EXISTS auth_mfa_required_roles (
+    id BIGSERIAL PRIMARY KEY,
+    role_id TEXT NOT NULL UNIQUE,
+    created_at BIGINT NOT NULL
+);
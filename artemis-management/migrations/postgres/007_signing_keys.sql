@@ -0,0 +1,13 @@
+-- Artemis 控制台访问令牌 (JWT) 签名密钥
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 36. 签名密钥表 (auth_signing_keys) - retired_at 为空的是当前签名密钥,
+--     其余为轮换下来、仍用于校验未过期令牌的旧密钥
+CREATE TABLE IF NOT EXISTS auth_signing_keys (
+    id BIGSERIAL PRIMARY KEY,
+    kid TEXT NOT NULL UNIQUE,
+    algorithm TEXT NOT NULL,
+    private_key TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    retired_at BIGINT
+);
@@ -0,0 +1,13 @@
+-- Artemis 控制台会话集群同步
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 37. 会话失效记录 (auth_session_revocations) - 会话被撤销或令牌刷新后写入,
+--     各节点轮询后清除本地缓存的会话, session_id 为空表示该用户的全部会话
+CREATE TABLE IF NOT EXISTS auth_session_revocations (
+    id BIGSERIAL PRIMARY KEY,
+    session_id TEXT,
+    user_id TEXT NOT NULL,
+    revoked_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_revocations_time ON auth_session_revocations(revoked_at);
@@ -0,0 +1,4 @@
+-- Artemis 分组标签关联修正
+-- PostgreSQL 版本
+
+-- GROUP_ID 为整数列, 不会写入分组名称, 无需转换
@@ -0,0 +1,16 @@
+-- Artemis 控制台两步验证挑战集群共享
+-- PostgreSQL 版本 (自增主键使用 BIGSERIAL, 被读取为 i64 的关联 ID 使用 BIGINT)
+
+-- 39. 两步验证挑战 (auth_mfa_challenges) - 密码校验通过后写入, 提交验证码时
+--     可落在任一节点; 完成登录、错误次数过多或过期后删除
+CREATE TABLE IF NOT EXISTS auth_mfa_challenges (
+    challenge_token TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    username TEXT NOT NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    expires_at BIGINT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_mfa_challenges_expires ON auth_mfa_challenges(expires_at);
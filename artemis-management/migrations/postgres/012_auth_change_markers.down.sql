@@ -0,0 +1,3 @@
+-- 回滚 012_auth_change_markers: 删除失效记录类型
+-- PostgreSQL 版本
+ALTER TABLE auth_session_revocations DROP COLUMN IF EXISTS kind;
@@ -0,0 +1,944 @@
+//! Declarative management configuration (GitOps apply)
+//!
+//! A [`ManagementDocument`] describes the desired groups, route rules, canary
+//! configs and zone operations in YAML, TOML or JSON. [`ConfigApplier`] diffs
+//! the document against the managers and returns the plan; unless it is a dry
+//! run the managers are then reconciled to match. Objects missing from the
+//! document are only deleted when pruning.
+
+use crate::model::{
+    CanaryConfig, GroupStatus, GroupTag, GroupType, RouteRule, RouteRuleStatus, RouteStrategy,
+    ServiceGroup, ZoneOperation,
+};
+use crate::{CanaryManager, GroupManager, RouteManager, ZoneManager};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use tracing::info;
+
+/// 文档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl DocumentFormat {
+    /// 根据文件扩展名判断格式, 无法识别时按 YAML 处理
+    pub fn from_path(path: &str) -> Self {
+        let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("toml") => DocumentFormat::Toml,
+            Some("json") => DocumentFormat::Json,
+            _ => DocumentFormat::Yaml,
+        }
+    }
+
+    /// 根据 Content-Type 判断格式, 无法识别时按 YAML 处理
+    pub fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if mime.ends_with("toml") {
+            DocumentFormat::Toml
+        } else if mime.ends_with("json") {
+            DocumentFormat::Json
+        } else {
+            DocumentFormat::Yaml
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            DocumentFormat::Yaml => "application/yaml",
+            DocumentFormat::Toml => "application/toml",
+            DocumentFormat::Json => "application/json",
+        }
+    }
+}
+
+/// 声明式管理配置文档
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManagementDocument {
+    #[serde(default)]
+    pub groups: Vec<GroupSpec>,
+    #[serde(default)]
+    pub route_rules: Vec<RouteRuleSpec>,
+    #[serde(default)]
+    pub canary_configs: Vec<CanarySpec>,
+    #[serde(default)]
+    pub zone_operations: Vec<ZoneOperationSpec>,
+}
+
+impl ManagementDocument {
+    /// 解析文档
+    pub fn parse(content: &str, format: DocumentFormat) -> Result<Self, String> {
+        match format {
+            DocumentFormat::Yaml => {
+                // 空文档表示不声明任何对象
+                if content.trim().is_empty() {
+                    return Ok(Self::default());
+                }
+                serde_yaml::from_str(content).map_err(|e| format!("Invalid YAML document: {}", e))
+            }
+            DocumentFormat::Toml => {
+                toml::from_str(content).map_err(|e| format!("Invalid TOML document: {}", e))
+            }
+            DocumentFormat::Json => {
+                serde_json::from_str(content).map_err(|e| format!("Invalid JSON document: {}", e))
+            }
+        }
+    }
+}
+
+/// 分组声明, 以 `serviceId:regionId:zoneId:name` 标识
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GroupSpec {
+    pub service_id: String,
+    pub region_id: String,
+    pub zone_id: String,
+    pub name: String,
+    #[serde(default = "default_group_type")]
+    pub group_type: GroupType,
+    #[serde(default = "default_group_status")]
+    pub status: GroupStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl GroupSpec {
+    pub fn group_key(&self) -> String {
+        format!("{}:{}:{}:{}", self.service_id, self.region_id, self.zone_id, self.name)
+    }
+}
+
+/// 路由规则声明, 以 `routeId` 标识 (持久化时规则名称即 routeId)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RouteRuleSpec {
+    pub route_id: String,
+    pub service_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_rule_status")]
+    pub status: RouteRuleStatus,
+    #[serde(default = "default_strategy")]
+    pub strategy: RouteStrategy,
+    #[serde(default)]
+    pub groups: Vec<RouteGroupSpec>,
+}
+
+/// 路由规则中的分组及权重
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RouteGroupSpec {
+    /// 分组名称 (同一服务下的分组)
+    pub group: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// 金丝雀配置声明, 以 `serviceId` 标识
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CanarySpec {
+    pub service_id: String,
+    #[serde(default)]
+    pub ip_whitelist: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Zone 操作声明: `pullout` 表示 Zone 应处于拉出状态, `pullin` 表示应处于正常状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ZoneOperationSpec {
+    pub zone_id: String,
+    pub region_id: String,
+    #[serde(default = "default_zone_operation")]
+    pub operation: ZoneOperation,
+}
+
+impl ZoneOperationSpec {
+    /// 标识: zoneId:regionId
+    pub fn key(&self) -> String {
+        zone_key(&self.zone_id, &self.region_id)
+    }
+}
+
+fn default_group_type() -> GroupType {
+    GroupType::Physical
+}
+
+fn default_group_status() -> GroupStatus {
+    GroupStatus::Active
+}
+
+fn default_rule_status() -> RouteRuleStatus {
+    RouteRuleStatus::Active
+}
+
+fn default_strategy() -> RouteStrategy {
+    RouteStrategy::WeightedRoundRobin
+}
+
+fn default_weight() -> u32 {
+    100
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_zone_operation() -> ZoneOperation {
+    ZoneOperation::PullOut
+}
+
+fn zone_key(zone_id: &str, region_id: &str) -> String {
+    format!("{}:{}", zone_id, region_id)
+}
+
+/// 配置对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResourceKind {
+    Group,
+    RouteRule,
+    CanaryConfig,
+    ZoneOperation,
+}
+
+impl ResourceKind {
+    /// 对应的权限资源
+    pub fn permission_resource(&self) -> &'static str {
+        match self {
+            ResourceKind::Group => "groups",
+            ResourceKind::RouteRule => "routing",
+            ResourceKind::CanaryConfig => "canary",
+            ResourceKind::ZoneOperation => "zones",
+        }
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceKind::Group => write!(f, "group"),
+            ResourceKind::RouteRule => write!(f, "route-rule"),
+            ResourceKind::CanaryConfig => write!(f, "canary-config"),
+            ResourceKind::ZoneOperation => write!(f, "zone-operation"),
+        }
+    }
+}
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// 单个对象的变更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    pub kind: ResourceKind,
+    pub key: String,
+    pub action: ChangeAction,
+    /// 更新时发生变化的字段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.action {
+            ChangeAction::Create => '+',
+            ChangeAction::Update => '~',
+            ChangeAction::Delete => '-',
+        };
+        write!(f, "{} {} {}", symbol, self.kind, self.key)?;
+        if !self.fields.is_empty() {
+            write!(f, " ({})", self.fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// 应用计划 (dry-run 时为将要执行的变更, 否则为已执行的变更)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPlan {
+    pub dry_run: bool,
+    pub prune: bool,
+    pub changes: Vec<Change>,
+    /// 与文档一致、无需变更的对象数
+    pub unchanged: usize,
+}
+
+impl ApplyPlan {
+    pub fn count(&self, action: ChangeAction) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+
+    /// 变更摘要, 如 `2 to create, 1 to update, 0 to delete, 3 unchanged`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} to create, {} to update, {} to delete, {} unchanged",
+            self.count(ChangeAction::Create),
+            self.count(ChangeAction::Update),
+            self.count(ChangeAction::Delete),
+            self.unchanged
+        )
+    }
+}
+
+/// 应用选项
+#[derive(Debug, Clone, Default)]
+pub struct ApplyOptions {
+    /// 只计算变更, 不修改配置
+    pub dry_run: bool,
+    /// 删除文档中未声明的对象
+    pub prune: bool,
+    /// 操作人 (记录在 Zone 操作中)
+    pub operator_id: String,
+}
+
+/// 计划中的单步操作
+enum Operation {
+    CreateGroup(ServiceGroup),
+    UpdateGroup(ServiceGroup),
+    DeleteGroup(String),
+    CreateRule(RouteRule),
+    UpdateRule(RouteRule),
+    DeleteRule(String),
+    SetCanary(CanaryConfig),
+    RemoveCanary(String),
+    PullOutZone { zone_id: String, region_id: String },
+    PullInZone { zone_id: String, region_id: String },
+}
+
+/// 声明式配置应用器
+pub struct ConfigApplier {
+    group_manager: Arc<GroupManager>,
+    route_manager: Arc<RouteManager>,
+    canary_manager: Arc<CanaryManager>,
+    zone_manager: Arc<ZoneManager>,
+}
+
+impl ConfigApplier {
+    pub fn new(
+        group_manager: Arc<GroupManager>,
+        route_manager: Arc<RouteManager>,
+        canary_manager: Arc<CanaryManager>,
+        zone_manager: Arc<ZoneManager>,
+    ) -> Self {
+        Self { group_manager, route_manager, canary_manager, zone_manager }
+    }
+
+    /// 计算文档与当前配置的差异, 不修改配置
+    pub fn plan(&self, document: &ManagementDocument, prune: bool) -> Result<ApplyPlan, String> {
+        let (plan, _) = self.diff(document, prune)?;
+        Ok(plan)
+    }
+
+    /// 应用文档: 校验并计算差异, 非 dry-run 时按顺序执行变更
+    ///
+    /// 先创建/更新分组, 再处理路由规则、金丝雀配置和 Zone 操作, 最后删除
+    /// (先删规则再删分组), 保证规则引用的分组始终存在
+    pub fn apply(
+        &self,
+        document: &ManagementDocument,
+        options: &ApplyOptions,
+    ) -> Result<ApplyPlan, String> {
+        let (mut plan, operations) = self.diff(document, options.prune)?;
+        plan.dry_run = options.dry_run;
+        if options.dry_run {
+            return Ok(plan);
+        }
+
+        info!(
+            "Applying management document: {} (operator: {})",
+            plan.summary(),
+            options.operator_id
+        );
+        for operation in operations {
+            self.execute(operation, &options.operator_id)?;
+        }
+        Ok(plan)
+    }
+
+    fn execute(&self, operation: Operation, operator_id: &str) -> Result<(), String> {
+        match operation {
+            Operation::CreateGroup(group) => self.group_manager.create_group(group),
+            Operation::UpdateGroup(group) => self.group_manager.update_group(group),
+            Operation::DeleteGroup(group_key) => self.group_manager.delete_group(&group_key),
+            Operation::CreateRule(rule) => self.route_manager.create_rule(rule),
+            Operation::UpdateRule(rule) => self.route_manager.update_rule(rule),
+            Operation::DeleteRule(route_id) => self.route_manager.delete_rule(&route_id),
+            Operation::SetCanary(config) => {
+                self.canary_manager.set_config(config).map_err(|e| e.to_string())
+            }
+            Operation::RemoveCanary(service_id) => {
+                self.canary_manager.remove_config(&service_id).map_err(|e| e.to_string())
+            }
+            Operation::PullOutZone { zone_id, region_id } => self
+                .zone_manager
+                .pull_out_zone(&zone_id, &region_id, operator_id.to_string())
+                .map_err(|e| e.to_string()),
+            Operation::PullInZone { zone_id, region_id } => self
+                .zone_manager
+                .pull_in_zone(&zone_id, &region_id, operator_id.to_string())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 校验文档并计算变更, 返回计划和按执行顺序排列的操作
+    fn diff(
+        &self,
+        document: &ManagementDocument,
+        prune: bool,
+    ) -> Result<(ApplyPlan, Vec<Operation>), String> {
+        self.validate(document, prune)?;
+
+        let mut plan = ApplyPlan { prune, ..Default::default() };
+        let mut operations = Vec::new();
+        let mut deletions = Vec::new();
+        let record = |plan: &mut ApplyPlan, change: Option<Change>| match change {
+            Some(change) => plan.changes.push(change),
+            None => plan.unchanged += 1,
+        };
+
+        // 分组
+        for spec in &document.groups {
+            let change = self.diff_group(spec, &mut operations);
+            record(&mut plan, change);
+        }
+
+        // 路由规则
+        for spec in &document.route_rules {
+            let change = self.diff_rule(spec, &mut operations);
+            record(&mut plan, change);
+        }
+
+        // 金丝雀配置
+        for spec in &document.canary_configs {
+            let change = self.diff_canary(spec, &mut operations);
+            record(&mut plan, change);
+        }
+
+        // Zone 操作
+        for spec in &document.zone_operations {
+            let change = self.diff_zone(spec, &mut operations);
+            record(&mut plan, change);
+        }
+
+        if prune {
+            let rule_ids: HashSet<&str> =
+                document.route_rules.iter().map(|r| r.route_id.as_str()).collect();
+            for rule in self.route_manager.list_rules() {
+                if !rule_ids.contains(rule.route_id.as_str()) {
+                    plan.changes.push(deletion(ResourceKind::RouteRule, &rule.route_id));
+                    deletions.push(Operation::DeleteRule(rule.route_id));
+                }
+            }
+
+            let group_keys: HashSet<String> =
+                document.groups.iter().map(GroupSpec::group_key).collect();
+            for group in self.group_manager.list_groups() {
+                let group_key = group.group_key();
+                if !group_keys.contains(&group_key) {
+                    plan.changes.push(deletion(ResourceKind::Group, &group_key));
+                    deletions.push(Operation::DeleteGroup(group_key));
+                }
+            }
+
+            let canary_ids: HashSet<&str> =
+                document.canary_configs.iter().map(|c| c.service_id.as_str()).collect();
+            for config in self.canary_manager.list_configs() {
+                if !canary_ids.contains(config.service_id.as_str()) {
+                    plan.changes.push(deletion(ResourceKind::CanaryConfig, &config.service_id));
+                    deletions.push(Operation::RemoveCanary(config.service_id));
+                }
+            }
+
+            let zone_keys: HashSet<String> =
+                document.zone_operations.iter().map(ZoneOperationSpec::key).collect();
+            for record in self.zone_manager.list_operations(None) {
+                let key = zone_key(&record.zone_id, &record.region_id);
+                if !zone_keys.contains(&key) {
+                    plan.changes.push(deletion(ResourceKind::ZoneOperation, &key));
+                    deletions.push(Operation::PullInZone {
+                        zone_id: record.zone_id,
+                        region_id: record.region_id,
+                    });
+                }
+            }
+        }
+
+        operations.extend(deletions);
+        Ok((plan, operations))
+    }
+
+    fn diff_group(&self, spec: &GroupSpec, operations: &mut Vec<Operation>) -> Option<Change> {
+        let group_key = spec.group_key();
+        let tags: Vec<GroupTag> = spec
+            .tags
+            .iter()
+            .map(|(key, value)| GroupTag { key: key.clone(), value: value.clone() })
+            .collect();
+        let tags = if tags.is_empty() { None } else { Some(tags) };
+
+        let Some(existing) = self.group_manager.get_group(&group_key) else {
+            operations.push(Operation::CreateGroup(ServiceGroup {
+                group_id: None,
+                service_id: spec.service_id.clone(),
+                region_id: spec.region_id.clone(),
+                zone_id: spec.zone_id.clone(),
+                name: spec.name.clone(),
+                group_type: spec.group_type,
+                status: spec.status,
+                description: spec.description.clone(),
+                tags,
+                metadata: None,
+                created_at: None,
+                updated_at: None,
+            }));
+            return Some(creation(ResourceKind::Group, &group_key));
+        };
+
+        let mut fields = Vec::new();
+        if existing.group_type != spec.group_type {
+            fields.push("groupType");
+        }
+        if existing.status != spec.status {
+            fields.push("status");
+        }
+        if existing.description != spec.description {
+            fields.push("description");
+        }
+        let existing_tags: BTreeMap<String, String> = existing
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| (tag.key.clone(), tag.value.clone()))
+            .collect();
+        if existing_tags != spec.tags {
+            fields.push("tags");
+        }
+        if fields.is_empty() {
+            return None;
+        }
+
+        operations.push(Operation::UpdateGroup(ServiceGroup {
+            group_type: spec.group_type,
+            status: spec.status,
+            description: spec.description.clone(),
+            tags,
+            ..existing
+        }));
+        Some(update(ResourceKind::Group, &group_key, fields))
+    }
+
+    fn diff_rule(&self, spec: &RouteRuleSpec, operations: &mut Vec<Operation>) -> Option<Change> {
+        let groups: Vec<artemis_common::model::service::ServiceGroup> = spec
+            .groups
+            .iter()
+            .map(|g| artemis_common::model::service::ServiceGroup {
+                group_key: g.group.clone(),
+                weight: Some(g.weight),
+                instance_ids: None,
+                instances: None,
+                metadata: None,
+            })
+            .collect();
+
+        let Some(existing) = self.route_manager.get_rule(&spec.route_id) else {
+            operations.push(Operation::CreateRule(RouteRule {
+                route_rule_id: None,
+                route_id: spec.route_id.clone(),
+                service_id: spec.service_id.clone(),
+                name: spec.route_id.clone(),
+                description: spec.description.clone(),
+                status: spec.status.clone(),
+                strategy: spec.strategy.clone(),
+                groups,
+            }));
+            return Some(creation(ResourceKind::RouteRule, &spec.route_id));
+        };
+
+        let mut fields = Vec::new();
+        if existing.service_id != spec.service_id {
+            fields.push("serviceId");
+        }
+        if existing.description != spec.description {
+            fields.push("description");
+        }
+        if existing.status != spec.status {
+            fields.push("status");
+        }
+        if existing.strategy != spec.strategy {
+            fields.push("strategy");
+        }
+        let weights = |groups: &[artemis_common::model::service::ServiceGroup]| {
+            let mut weights: Vec<(String, u32)> =
+                groups.iter().map(|g| (g.group_key.clone(), g.weight.unwrap_or(100))).collect();
+            weights.sort();
+            weights
+        };
+        if weights(&existing.groups) != weights(&groups) {
+            fields.push("groups");
+        }
+        if fields.is_empty() {
+            return None;
+        }
+
+        operations.push(Operation::UpdateRule(RouteRule {
+            service_id: spec.service_id.clone(),
+            description: spec.description.clone(),
+            status: spec.status.clone(),
+            strategy: spec.strategy.clone(),
+            groups,
+            ..existing
+        }));
+        Some(update(ResourceKind::RouteRule, &spec.route_id, fields))
+    }
+
+    fn diff_canary(&self, spec: &CanarySpec, operations: &mut Vec<Operation>) -> Option<Change> {
+        let config = CanaryConfig {
+            service_id: spec.service_id.clone(),
+            ip_whitelist: spec.ip_whitelist.clone(),
+            enabled: spec.enabled,
+        };
+
+        let Some(existing) = self.canary_manager.get_config(&spec.service_id) else {
+            operations.push(Operation::SetCanary(config));
+            return Some(creation(ResourceKind::CanaryConfig, &spec.service_id));
+        };
+
+        let mut fields = Vec::new();
+        let sorted = |ips: &[String]| {
+            let mut ips = ips.to_vec();
+            ips.sort();
+            ips
+        };
+        if sorted(&existing.ip_whitelist) != sorted(&spec.ip_whitelist) {
+            fields.push("ipWhitelist");
+        }
+        if existing.enabled != spec.enabled {
+            fields.push("enabled");
+        }
+        if fields.is_empty() {
+            return None;
+        }
+
+        operations.push(Operation::SetCanary(config));
+        Some(update(ResourceKind::CanaryConfig, &spec.service_id, fields))
+    }
+
+    fn diff_zone(
+        &self,
+        spec: &ZoneOperationSpec,
+        operations: &mut Vec<Operation>,
+    ) -> Option<Change> {
+        let is_down = self.zone_manager.is_zone_down(&spec.zone_id, &spec.region_id);
+        let zone_id = spec.zone_id.clone();
+        let region_id = spec.region_id.clone();
+        match spec.operation {
+            ZoneOperation::PullOut if !is_down => {
+                operations.push(Operation::PullOutZone { zone_id, region_id });
+                Some(creation(ResourceKind::ZoneOperation, &spec.key()))
+            }
+            ZoneOperation::PullIn if is_down => {
+                operations.push(Operation::PullInZone { zone_id, region_id });
+                Some(deletion(ResourceKind::ZoneOperation, &spec.key()))
+            }
+            _ => None,
+        }
+    }
+
+    /// 校验文档: 标识不能为空或重复, 权重在 1-100 之间,
+    /// 规则引用的分组必须在应用后存在于规则所属服务下
+    fn validate(&self, document: &ManagementDocument, prune: bool) -> Result<(), String> {
+        let mut group_keys = HashSet::new();
+        for spec in &document.groups {
+            if [&spec.service_id, &spec.region_id, &spec.zone_id, &spec.name]
+                .iter()
+                .any(|v| v.is_empty())
+            {
+                return Err(format!("Group {} has an empty identifier", spec.group_key()));
+            }
+            if !group_keys.insert(spec.group_key()) {
+                return Err(format!("Duplicate group in document: {}", spec.group_key()));
+            }
+        }
+
+        // 应用后每个服务下存在的分组名称
+        let mut service_groups: HashSet<(String, String)> =
+            document.groups.iter().map(|g| (g.service_id.clone(), g.name.clone())).collect();
+        if !prune {
+            service_groups.extend(
+                self.group_manager.list_groups().into_iter().map(|g| (g.service_id, g.name)),
+            );
+        }
+
+        let mut rule_ids = HashSet::new();
+        for spec in &document.route_rules {
+            if spec.route_id.is_empty() || spec.service_id.is_empty() {
+                return Err(format!("Route rule '{}' has an empty identifier", spec.route_id));
+            }
+            if !rule_ids.insert(spec.route_id.as_str()) {
+                return Err(format!("Duplicate route rule in document: {}", spec.route_id));
+            }
+            for group in &spec.groups {
+                if !(1..=100).contains(&group.weight) {
+                    return Err(format!(
+                        "Route rule {}: weight of group {} must be between 1 and 100",
+                        spec.route_id, group.group
+                    ));
+                }
+                if !service_groups.contains(&(spec.service_id.clone(), group.group.clone())) {
+                    return Err(format!(
+                        "Route rule {} references unknown group {} of service {}",
+                        spec.route_id, group.group, spec.service_id
+                    ));
+                }
+            }
+        }
+
+        let mut canary_ids = HashSet::new();
+        for spec in &document.canary_configs {
+            if spec.service_id.is_empty() {
+                return Err("Canary config has an empty serviceId".to_string());
+            }
+            if !canary_ids.insert(spec.service_id.as_str()) {
+                return Err(format!("Duplicate canary config in document: {}", spec.service_id));
+            }
+        }
+
+        let mut zone_keys = HashSet::new();
+        for spec in &document.zone_operations {
+            if spec.zone_id.is_empty() || spec.region_id.is_empty() {
+                return Err("Zone operation has an empty zoneId or regionId".to_string());
+            }
+            if !zone_keys.insert(spec.key()) {
+                return Err(format!("Duplicate zone operation in document: {}", spec.key()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn creation(kind: ResourceKind, key: &str) -> Change {
+    Change { kind, key: key.to_string(), action: ChangeAction::Create, fields: Vec::new() }
+}
+
+fn update(kind: ResourceKind, key: &str, fields: Vec<&str>) -> Change {
+    Change {
+        kind,
+        key: key.to_string(),
+        action: ChangeAction::Update,
+        fields: fields.into_iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+fn deletion(kind: ResourceKind, key: &str) -> Change {
+    Change { kind, key: key.to_string(), action: ChangeAction::Delete, fields: Vec::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"
+groups:
+  - serviceId: order-service
+    regionId: us-east
+    zoneId: zone-1
+    name: stable
+    tags:
+      env: prod
+  - serviceId: order-service
+    regionId: us-east
+    zoneId: zone-1
+    name: canary
+    groupType: logical
+routeRules:
+  - routeId: order-split
+    serviceId: order-service
+    groups:
+      - group: stable
+        weight: 90
+      - group: canary
+        weight: 10
+canaryConfigs:
+  - serviceId: order-service
+    ipWhitelist: ["10.0.0.1"]
+zoneOperations:
+  - zoneId: zone-2
+    regionId: us-east
+"#;
+
+    fn applier() -> ConfigApplier {
+        ConfigApplier::new(
+            Arc::new(GroupManager::new()),
+            Arc::new(RouteManager::new()),
+            Arc::new(CanaryManager::new()),
+            Arc::new(ZoneManager::new()),
+        )
+    }
+
+    fn options(dry_run: bool, prune: bool) -> ApplyOptions {
+        ApplyOptions { dry_run, prune, operator_id: "gitops".to_string() }
+    }
+
+    #[test]
+    fn test_document_formats() {
+        let yaml = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        assert_eq!(yaml.groups.len(), 2);
+        assert_eq!(yaml.groups[0].group_type, GroupType::Physical);
+        assert_eq!(yaml.route_rules[0].status, RouteRuleStatus::Active);
+        assert!(yaml.canary_configs[0].enabled);
+        assert_eq!(yaml.zone_operations[0].operation, ZoneOperation::PullOut);
+
+        let toml = toml::to_string(&yaml).unwrap();
+        assert_eq!(ManagementDocument::parse(&toml, DocumentFormat::Toml).unwrap(), yaml);
+        let json = serde_json::to_string(&yaml).unwrap();
+        assert_eq!(ManagementDocument::parse(&json, DocumentFormat::Json).unwrap(), yaml);
+
+        assert!(ManagementDocument::parse("groups: [{name: x}]", DocumentFormat::Yaml).is_err());
+        assert!(ManagementDocument::parse("unknown: []", DocumentFormat::Yaml).is_err());
+        assert_eq!(
+            ManagementDocument::parse("", DocumentFormat::Yaml).unwrap(),
+            ManagementDocument::default()
+        );
+    }
+
+    #[test]
+    fn test_document_format_detection() {
+        assert_eq!(DocumentFormat::from_path("config/prod.TOML"), DocumentFormat::Toml);
+        assert_eq!(DocumentFormat::from_path("prod.json"), DocumentFormat::Json);
+        assert_eq!(DocumentFormat::from_path("prod.yml"), DocumentFormat::Yaml);
+        assert_eq!(
+            DocumentFormat::from_content_type("application/json; charset=utf-8"),
+            DocumentFormat::Json
+        );
+        assert_eq!(DocumentFormat::from_content_type("application/toml"), DocumentFormat::Toml);
+        assert_eq!(DocumentFormat::from_content_type("text/plain"), DocumentFormat::Yaml);
+    }
+
+    #[test]
+    fn test_example_document() {
+        let content = include_str!("../../config/examples/management.yaml");
+        let document = ManagementDocument::parse(content, DocumentFormat::Yaml).unwrap();
+        let plan = applier().plan(&document, true).unwrap();
+        assert_eq!(plan.count(ChangeAction::Create), 5);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_then_apply() {
+        let applier = applier();
+        let document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+
+        let plan = applier.apply(&document, &options(true, false)).unwrap();
+        assert!(plan.dry_run);
+        assert_eq!(plan.count(ChangeAction::Create), 5);
+        assert_eq!(applier.group_manager.group_count(), 0);
+
+        let plan = applier.apply(&document, &options(false, false)).unwrap();
+        assert_eq!(plan.summary(), "5 to create, 0 to update, 0 to delete, 0 unchanged");
+        let group = applier.group_manager.get_group("order-service:us-east:zone-1:stable").unwrap();
+        assert_eq!(group.tags.unwrap()[0].value, "prod");
+        let rule = applier.route_manager.get_rule("order-split").unwrap();
+        assert_eq!(rule.groups.len(), 2);
+        assert!(applier.canary_manager.is_ip_whitelisted("order-service", "10.0.0.1"));
+        let zone = applier.zone_manager.get_zone_status("zone-2", "us-east").unwrap();
+        assert_eq!(zone.operator_id, "gitops");
+
+        // 再次应用无变更
+        let plan = applier.apply(&document, &options(false, false)).unwrap();
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.unchanged, 5);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_prune() {
+        let applier = applier();
+        let document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        applier.apply(&document, &options(false, false)).unwrap();
+
+        let mut changed = document.clone();
+        changed.groups.remove(1);
+        changed.groups[0].tags.insert("team".to_string(), "orders".to_string());
+        changed.route_rules[0].groups.remove(1);
+        changed.route_rules[0].groups[0].weight = 100;
+        changed.canary_configs.clear();
+        changed.zone_operations[0].operation = ZoneOperation::PullIn;
+
+        // 不删除未声明的对象
+        let plan = applier.plan(&changed, false).unwrap();
+        assert_eq!(plan.summary(), "0 to create, 2 to update, 1 to delete, 0 unchanged");
+        assert_eq!(plan.changes[0].fields, vec!["tags"]);
+        assert_eq!(plan.changes[1].fields, vec!["groups"]);
+
+        let plan = applier.apply(&changed, &options(false, true)).unwrap();
+        assert!(plan.prune);
+        let deleted: Vec<String> = plan
+            .changes
+            .iter()
+            .filter(|c| c.action == ChangeAction::Delete)
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            deleted,
+            vec![
+                "- zone-operation zone-2:us-east",
+                "- group order-service:us-east:zone-1:canary",
+                "- canary-config order-service",
+            ]
+        );
+        assert_eq!(applier.group_manager.group_count(), 1);
+        assert_eq!(applier.route_manager.get_rule("order-split").unwrap().groups.len(), 1);
+        assert!(applier.canary_manager.list_configs().is_empty());
+        assert!(!applier.zone_manager.is_zone_down("zone-2", "us-east"));
+    }
+
+    #[tokio::test]
+    async fn test_validation_rejects_whole_document() {
+        let applier = applier();
+        let mut document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        document.route_rules[0].groups[1].group = "missing".to_string();
+        let err = applier.apply(&document, &options(false, false)).unwrap_err();
+        assert!(err.contains("unknown group missing"));
+        // 校验失败时不做任何变更
+        assert_eq!(applier.group_manager.group_count(), 0);
+
+        let mut document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        document.route_rules[0].groups[0].weight = 0;
+        assert!(applier.plan(&document, false).unwrap_err().contains("between 1 and 100"));
+
+        let mut document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        document.groups.push(document.groups[0].clone());
+        assert!(applier.plan(&document, false).unwrap_err().contains("Duplicate group"));
+    }
+
+    #[tokio::test]
+    async fn test_prune_validates_against_document_only() {
+        let applier = applier();
+        let document = ManagementDocument::parse(DOCUMENT, DocumentFormat::Yaml).unwrap();
+        applier.apply(&document, &options(false, false)).unwrap();
+
+        // 剪除时规则不能引用文档外 (将被删除) 的分组
+        let mut without_groups = document.clone();
+        without_groups.groups.clear();
+        assert!(applier.plan(&without_groups, false).is_ok());
+        assert!(applier.plan(&without_groups, true).unwrap_err().contains("unknown group"));
+    }
+}
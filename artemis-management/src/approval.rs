@@ -0,0 +1,612 @@
+//! Approval workflow for high-risk management operations
+
+use crate::dao::ChangeRequestDao;
+use crate::db::Database;
+use crate::model::{
+    ApprovalOperation, ApprovalPolicy, ChangeRequest, ChangeRequestStatus, PendingOperation,
+};
+use crate::{AuditManager, InstanceManager, ZoneManager};
+use artemis_service::config::ApprovalConfig;
+use artemis_service::registry::RegistryRepository;
+use chrono::Utc;
+use dashmap::DashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 审批管理器
+///
+/// 命中审批策略的操作不会立即执行, 而是创建待审批的变更请求,
+/// 由另一位有权限的用户批准后才交给对应的管理器执行.
+/// 配置数据库时变更请求只保存在数据库中, 状态按 compare-and-set 更新,
+/// 集群中任一节点都可以审批, 同一请求只会被审批一次
+#[derive(Clone)]
+pub struct ApprovalManager {
+    /// 未配置数据库时的变更请求: request_id -> ChangeRequest
+    requests: Arc<DashMap<String, ChangeRequest>>,
+    policies: Vec<ApprovalPolicy>,
+    /// 待审批请求的有效期 (秒)
+    request_ttl_secs: i64,
+    instance_manager: Arc<InstanceManager>,
+    zone_manager: Arc<ZoneManager>,
+    audit_manager: Arc<AuditManager>,
+    /// 注册表, 用于统计操作影响的实例数
+    registry: Option<RegistryRepository>,
+    database: Option<Arc<Database>>,
+}
+
+impl ApprovalManager {
+    pub fn new(
+        instance_manager: Arc<InstanceManager>,
+        zone_manager: Arc<ZoneManager>,
+        audit_manager: Arc<AuditManager>,
+    ) -> Self {
+        Self {
+            requests: Arc::new(DashMap::new()),
+            policies: Vec::new(),
+            request_ttl_secs: ApprovalConfig::default().request_ttl_secs as i64,
+            instance_manager,
+            zone_manager,
+            audit_manager,
+            registry: None,
+            database: None,
+        }
+    }
+
+    /// 应用审批配置, 无法识别的操作类型会被忽略
+    pub fn with_config(mut self, config: &ApprovalConfig) -> Self {
+        self.policies = config
+            .policies
+            .iter()
+            .filter_map(|policy| match policy.operation.parse::<ApprovalOperation>() {
+                Ok(operation) => Some(ApprovalPolicy {
+                    operation,
+                    instance_threshold: policy.instance_threshold,
+                }),
+                Err(e) => {
+                    warn!("Ignoring approval policy: {}", e);
+                    None
+                }
+            })
+            .collect();
+        self.request_ttl_secs = config.request_ttl_secs as i64;
+        self
+    }
+
+    pub fn with_registry(mut self, registry: RegistryRepository) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    pub fn with_database(mut self, database: Option<Arc<Database>>) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// 已配置的审批策略
+    pub fn policies(&self) -> &[ApprovalPolicy] {
+        &self.policies
+    }
+
+    /// 统计操作影响的实例数 (未关联注册表时 Zone 和服务器操作按 0 计)
+    pub fn affected_instances(&self, operation: &PendingOperation) -> usize {
+        let Some(registry) = &self.registry else {
+            return match operation {
+                PendingOperation::InstancePullOut { .. } => 1,
+                _ => 0,
+            };
+        };
+        match operation {
+            PendingOperation::ZonePullOut { zone_id, region_id, .. } => registry
+                .get_all_instances()
+                .iter()
+                .filter(|inst| &inst.zone_id == zone_id && &inst.region_id == region_id)
+                .count(),
+            PendingOperation::ServerPullOut { server_id, region_id, .. } => registry
+                .get_all_instances()
+                .iter()
+                .filter(|inst| &inst.ip == server_id && &inst.region_id == region_id)
+                .count(),
+            PendingOperation::InstancePullOut { .. } => 1,
+        }
+    }
+
+    /// 操作是否命中审批策略
+    pub fn requires_approval(&self, operation: &PendingOperation) -> bool {
+        let affected_instances = self.affected_instances(operation);
+        self.policies.iter().any(|policy| policy.applies(operation, affected_instances))
+    }
+
+    /// 提交操作: 命中审批策略时创建变更请求并返回, 否则返回 None 由调用方直接执行
+    ///
+    /// 同一操作已有待审批的请求时返回该请求, 不重复创建;
+    /// 变更请求保存失败时返回错误, 调用方不能直接执行操作
+    pub async fn submit(
+        &self,
+        operation: PendingOperation,
+        requested_by: &str,
+    ) -> Result<Option<ChangeRequest>, String> {
+        if !self.requires_approval(&operation) {
+            return Ok(None);
+        }
+        let affected_instances = self.affected_instances(&operation);
+
+        self.expire_stale().await?;
+        if let Some(existing) = self.find_pending(&operation).await? {
+            return Ok(Some(existing));
+        }
+
+        let now = Utc::now().timestamp();
+        let request = ChangeRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            operation,
+            affected_instances,
+            status: ChangeRequestStatus::Pending,
+            requested_by: requested_by.to_string(),
+            requested_at: now,
+            expires_at: now + self.request_ttl_secs,
+            reviewed_by: None,
+            reviewed_at: None,
+            comment: None,
+            error: None,
+        };
+
+        match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .insert_request(&request)
+                .await
+                .map_err(|e| format!("Failed to save change request: {}", e))?,
+            None => {
+                self.requests.insert(request.request_id.clone(), request.clone());
+            }
+        }
+        info!(
+            "Change request {} created for {} {} ({} instances) by {}",
+            request.request_id,
+            request.operation.kind(),
+            request.operation.target(),
+            affected_instances,
+            requested_by
+        );
+        self.audit(&request, "request", requested_by);
+
+        Ok(Some(request))
+    }
+
+    /// 列出变更请求 (按提交时间倒序)
+    pub async fn list_requests(
+        &self,
+        status: Option<ChangeRequestStatus>,
+    ) -> Result<Vec<ChangeRequest>, String> {
+        self.expire_stale().await?;
+        let mut requests: Vec<ChangeRequest> = match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .list_requests(status)
+                .await
+                .map_err(|e| format!("Failed to load change requests: {}", e))?,
+            None => self
+                .requests
+                .iter()
+                .filter(|entry| status.is_none_or(|status| entry.status == status))
+                .map(|entry| entry.value().clone())
+                .collect(),
+        };
+        requests.sort_by(|a, b| b.requested_at.cmp(&a.requested_at));
+        Ok(requests)
+    }
+
+    pub async fn get_request(&self, request_id: &str) -> Result<Option<ChangeRequest>, String> {
+        self.expire_stale().await?;
+        self.load(request_id).await
+    }
+
+    /// 批准并执行变更请求, 申请人不能批准自己的请求
+    ///
+    /// 执行失败时请求状态为 failed, 返回的请求中包含失败原因
+    pub async fn approve(
+        &self,
+        request_id: &str,
+        approver: &str,
+        comment: Option<String>,
+    ) -> Result<ChangeRequest, String> {
+        let request =
+            self.review(request_id, approver, comment, ChangeRequestStatus::Approved).await?;
+        self.audit(&request, "approve", approver);
+
+        match self.execute(&request.operation) {
+            Ok(()) => Ok(request),
+            Err(e) => {
+                warn!("Change request {} failed: {}", request_id, e);
+                let mut failed = request;
+                failed.status = ChangeRequestStatus::Failed;
+                failed.error = Some(e.to_string());
+                self.compare_and_set(&failed, ChangeRequestStatus::Approved).await?;
+                self.audit(&failed, "fail", approver);
+                Ok(failed)
+            }
+        }
+    }
+
+    /// 拒绝变更请求
+    pub async fn reject(
+        &self,
+        request_id: &str,
+        reviewer: &str,
+        comment: Option<String>,
+    ) -> Result<ChangeRequest, String> {
+        let request =
+            self.review(request_id, reviewer, comment, ChangeRequestStatus::Rejected).await?;
+        self.audit(&request, "reject", reviewer);
+        Ok(request)
+    }
+
+    /// 撤回变更请求, 只有申请人可以撤回
+    pub async fn cancel(
+        &self,
+        request_id: &str,
+        user_id: &str,
+        comment: Option<String>,
+    ) -> Result<ChangeRequest, String> {
+        let request = self.pending_request(request_id).await?;
+        if request.requested_by != user_id {
+            return Err("Only the requester can cancel a change request".to_string());
+        }
+
+        let request =
+            self.transition(request, user_id, comment, ChangeRequestStatus::Cancelled).await?;
+        self.audit(&request, "cancel", user_id);
+        Ok(request)
+    }
+
+    /// 将待审批请求转为审批结果, 同一请求只能被审批一次
+    async fn review(
+        &self,
+        request_id: &str,
+        reviewer: &str,
+        comment: Option<String>,
+        status: ChangeRequestStatus,
+    ) -> Result<ChangeRequest, String> {
+        let request = self.pending_request(request_id).await?;
+        if request.requested_by == reviewer {
+            return Err("Change requests must be reviewed by a different user".to_string());
+        }
+        self.transition(request, reviewer, comment, status).await
+    }
+
+    /// 获取仍处于待审批状态的请求
+    async fn pending_request(&self, request_id: &str) -> Result<ChangeRequest, String> {
+        let request = self
+            .get_request(request_id)
+            .await?
+            .ok_or_else(|| format!("Change request not found: {}", request_id))?;
+        if request.status != ChangeRequestStatus::Pending {
+            return Err(format!("Change request is already {}", request.status));
+        }
+        Ok(request)
+    }
+
+    /// 以 compare-and-set 将待审批请求转为 status, 其他审批先完成时返回错误
+    async fn transition(
+        &self,
+        mut request: ChangeRequest,
+        reviewer: &str,
+        comment: Option<String>,
+        status: ChangeRequestStatus,
+    ) -> Result<ChangeRequest, String> {
+        request.status = status;
+        request.reviewed_by = Some(reviewer.to_string());
+        request.reviewed_at = Some(Utc::now().timestamp());
+        request.comment = comment;
+        if self.compare_and_set(&request, ChangeRequestStatus::Pending).await? {
+            return Ok(request);
+        }
+
+        let current = self.load(&request.request_id).await?;
+        Err(match current {
+            Some(current) => format!("Change request is already {}", current.status),
+            None => format!("Change request not found: {}", request.request_id),
+        })
+    }
+
+    /// 执行已批准的操作
+    fn execute(&self, operation: &PendingOperation) -> anyhow::Result<()> {
+        match operation {
+            PendingOperation::ZonePullOut { zone_id, region_id, operator_id } => {
+                self.zone_manager.pull_out_zone(zone_id, region_id, operator_id.clone())
+            }
+            PendingOperation::ServerPullOut {
+                server_id,
+                region_id,
+                operation_complete,
+                operator_id,
+            } => self.instance_manager.pull_out_server(
+                server_id,
+                region_id,
+                operator_id.clone(),
+                *operation_complete,
+            ),
+            PendingOperation::InstancePullOut { instance_key, operation_complete, operator_id } => {
+                self.instance_manager.pull_out_instance(
+                    instance_key,
+                    operator_id.clone(),
+                    *operation_complete,
+                )
+            }
+        }
+    }
+
+    /// 将超过有效期的待审批请求标记为过期
+    async fn expire_stale(&self) -> Result<(), String> {
+        let now = Utc::now().timestamp();
+        let stale: Vec<ChangeRequest> = match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .list_expired_pending(now)
+                .await
+                .map_err(|e| format!("Failed to load change requests: {}", e))?,
+            None => self
+                .requests
+                .iter()
+                .filter(|entry| {
+                    entry.status == ChangeRequestStatus::Pending && entry.expires_at <= now
+                })
+                .map(|entry| entry.value().clone())
+                .collect(),
+        };
+
+        for mut request in stale {
+            request.status = ChangeRequestStatus::Expired;
+            // 其他节点已处理时跳过
+            if self.compare_and_set(&request, ChangeRequestStatus::Pending).await? {
+                info!("Change request {} expired", request.request_id);
+                self.audit(&request, "expire", "system");
+            }
+        }
+        Ok(())
+    }
+
+    /// 查找同一操作的待审批请求
+    async fn find_pending(
+        &self,
+        operation: &PendingOperation,
+    ) -> Result<Option<ChangeRequest>, String> {
+        let pending = match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .list_pending_for(operation)
+                .await
+                .map_err(|e| format!("Failed to load change requests: {}", e))?,
+            None => self
+                .requests
+                .iter()
+                .filter(|entry| entry.status == ChangeRequestStatus::Pending)
+                .map(|entry| entry.value().clone())
+                .collect(),
+        };
+        Ok(pending.into_iter().find(|request| &request.operation == operation))
+    }
+
+    async fn load(&self, request_id: &str) -> Result<Option<ChangeRequest>, String> {
+        match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .get_request(request_id)
+                .await
+                .map_err(|e| format!("Failed to load change request: {}", e)),
+            None => Ok(self.requests.get(request_id).map(|r| r.clone())),
+        }
+    }
+
+    /// 请求当前状态为 expected 时保存审批结果, 返回是否更新
+    async fn compare_and_set(
+        &self,
+        request: &ChangeRequest,
+        expected: ChangeRequestStatus,
+    ) -> Result<bool, String> {
+        match &self.database {
+            Some(db) => ChangeRequestDao::new(db.conn().clone())
+                .update_review_if(request, expected)
+                .await
+                .map_err(|e| format!("Failed to save change request: {}", e)),
+            None => match self.requests.get_mut(&request.request_id) {
+                Some(mut entry) if entry.status == expected => {
+                    *entry = request.clone();
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+        }
+    }
+
+    /// 记录审批轨迹: target 为请求 ID, 操作为 "<动作>:<操作类型>"
+    fn audit(&self, request: &ChangeRequest, action: &str, operator_id: &str) {
+        self.audit_manager.log_operation(
+            "approval".to_string(),
+            request.request_id.clone(),
+            format!("{}:{}", action, request.operation.kind()),
+            operator_id.to_string(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use artemis_common::model::{Instance, InstanceStatus};
+    use artemis_service::config::ApprovalPolicyConfig;
+
+    fn manager(policies: Vec<(&str, Option<usize>)>) -> ApprovalManager {
+        let config = ApprovalConfig {
+            policies: policies
+                .into_iter()
+                .map(|(operation, instance_threshold)| ApprovalPolicyConfig {
+                    operation: operation.to_string(),
+                    instance_threshold,
+                })
+                .collect(),
+            request_ttl_secs: 3600,
+        };
+        ApprovalManager::new(
+            Arc::new(InstanceManager::new()),
+            Arc::new(ZoneManager::new()),
+            Arc::new(AuditManager::new()),
+        )
+        .with_config(&config)
+    }
+
+    fn zone_pull_out() -> PendingOperation {
+        PendingOperation::ZonePullOut {
+            zone_id: "zone-1".to_string(),
+            region_id: "us-east".to_string(),
+            operator_id: "ops".to_string(),
+        }
+    }
+
+    fn server_pull_out() -> PendingOperation {
+        PendingOperation::ServerPullOut {
+            server_id: "10.0.0.1".to_string(),
+            region_id: "us-east".to_string(),
+            operation_complete: true,
+            operator_id: "ops".to_string(),
+        }
+    }
+
+    fn instance(id: &str, ip: &str) -> Instance {
+        Instance {
+            region_id: "us-east".to_string(),
+            zone_id: "zone-1".to_string(),
+            group_id: None,
+            service_id: "order-service".to_string(),
+            instance_id: id.to_string(),
+            machine_name: None,
+            ip: ip.to_string(),
+            port: 8080,
+            protocol: None,
+            url: format!("http://{}:8080", ip),
+            health_check_url: None,
+            status: InstanceStatus::Up,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operations_without_policy_run_directly() {
+        let manager = manager(vec![("zone-pull-out", None), ("unknown-op", None)]);
+        assert_eq!(manager.policies().len(), 1);
+        assert!(manager.submit(server_pull_out(), "user-ops").await.unwrap().is_none());
+        assert!(manager.list_requests(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_approve_executes_operation() {
+        let manager = manager(vec![("zone-pull-out", None)]);
+        let request = manager.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap();
+        assert_eq!(request.status, ChangeRequestStatus::Pending);
+        assert!(!manager.zone_manager.is_zone_down("zone-1", "us-east"));
+
+        // 重复提交返回同一请求
+        let again = manager.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap();
+        assert_eq!(again.request_id, request.request_id);
+
+        // 申请人不能批准自己的请求
+        let err = manager.approve(&request.request_id, "user-ops", None).await.unwrap_err();
+        assert!(err.contains("different user"));
+
+        let approved = manager
+            .approve(&request.request_id, "user-lead", Some("maintenance".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(approved.status, ChangeRequestStatus::Approved);
+        assert_eq!(approved.reviewed_by.as_deref(), Some("user-lead"));
+        assert!(manager.zone_manager.is_zone_down("zone-1", "us-east"));
+
+        // 已审批的请求不能再次审批
+        assert!(manager.reject(&request.request_id, "user-admin", None).await.is_err());
+
+        let trail: Vec<String> = manager
+            .audit_manager
+            .query_logs(Some("approval"), None, None)
+            .into_iter()
+            .map(|log| log.operation)
+            .collect();
+        assert!(trail.contains(&"request:zone-pull-out".to_string()));
+        assert!(trail.contains(&"approve:zone-pull-out".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_reject_and_cancel() {
+        let manager = manager(vec![("server-pull-out", None)]);
+        let request = manager.submit(server_pull_out(), "user-ops").await.unwrap().unwrap();
+        let rejected = manager.reject(&request.request_id, "user-lead", None).await.unwrap();
+        assert_eq!(rejected.status, ChangeRequestStatus::Rejected);
+        assert!(!manager.instance_manager.is_server_down("10.0.0.1", "us-east"));
+
+        let request = manager.submit(server_pull_out(), "user-ops").await.unwrap().unwrap();
+        assert!(manager.cancel(&request.request_id, "user-lead", None).await.is_err());
+        let cancelled = manager.cancel(&request.request_id, "user-ops", None).await.unwrap();
+        assert_eq!(cancelled.status, ChangeRequestStatus::Cancelled);
+        assert_eq!(
+            manager.list_requests(Some(ChangeRequestStatus::Pending)).await.unwrap().len(),
+            0
+        );
+        assert_eq!(manager.list_requests(None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_request_cannot_be_approved() {
+        let manager = manager(vec![("zone-pull-out", None)]);
+        let request = manager.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap();
+        manager.requests.get_mut(&request.request_id).unwrap().expires_at = 0;
+
+        let err = manager.approve(&request.request_id, "user-lead", None).await.unwrap_err();
+        assert!(err.contains("expired"));
+        assert_eq!(
+            manager.get_request(&request.request_id).await.unwrap().unwrap().status,
+            ChangeRequestStatus::Expired
+        );
+        assert!(!manager.zone_manager.is_zone_down("zone-1", "us-east"));
+    }
+
+    #[tokio::test]
+    async fn test_instance_threshold() {
+        let registry = RegistryRepository::new();
+        registry.register(instance("inst-1", "10.0.0.1"));
+        registry.register(instance("inst-2", "10.0.0.1"));
+        registry.register(instance("inst-3", "10.0.0.2"));
+        let manager = manager(vec![("zone-pull-out", Some(2)), ("server-pull-out", Some(2))])
+            .with_registry(registry);
+
+        // Zone 内 3 个实例, 超过阈值; 服务器上只有 2 个实例
+        assert_eq!(manager.affected_instances(&zone_pull_out()), 3);
+        assert_eq!(
+            manager.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap().affected_instances,
+            3
+        );
+        assert!(manager.submit(server_pull_out(), "user-ops").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_requests_shared_through_database() {
+        let database = Arc::new(crate::db::test_database().await);
+        let node_a = manager(vec![("zone-pull-out", None)]).with_database(Some(database.clone()));
+        let node_b = manager(vec![("zone-pull-out", None)]).with_database(Some(database));
+        let request = node_a.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap();
+
+        // 其他节点看到同一请求, 重复提交不会新建
+        let again = node_b.submit(zone_pull_out(), "user-ops").await.unwrap().unwrap();
+        assert_eq!(again.request_id, request.request_id);
+        assert_eq!(node_b.list_requests(None).await.unwrap().len(), 1);
+
+        // 两个节点同时审批, 只有一个成功
+        let (approved, rejected) = tokio::join!(
+            node_a.approve(&request.request_id, "user-lead", None),
+            node_b.reject(&request.request_id, "user-admin", None),
+        );
+        assert!(approved.is_ok() != rejected.is_ok());
+        let status = node_b.get_request(&request.request_id).await.unwrap().unwrap().status;
+        match approved {
+            Ok(_) => assert_eq!(status, ChangeRequestStatus::Approved),
+            Err(e) => {
+                assert!(e.contains("already rejected"), "{}", e);
+                assert_eq!(status, ChangeRequestStatus::Rejected);
+            }
+        }
+        assert!(node_a.cancel(&request.request_id, "user-ops", None).await.is_err());
+    }
+}
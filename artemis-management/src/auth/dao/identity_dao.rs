@@ -0,0 +1,106 @@
+use crate::db::statement;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+pub struct IdentityDao {
+    conn: DatabaseConnection,
+}
+
+impl IdentityDao {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// 绑定外部身份到本地用户
+    pub async fn insert_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+        user_id: &str,
+    ) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_external_identities (provider, subject, user_id, created_at) VALUES (?, ?, ?, ?)",
+            vec![
+                Value::from(provider),
+                Value::from(subject),
+                Value::from(user_id),
+                Value::from(chrono::Utc::now().timestamp()),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除用户的外部身份绑定
+    pub async fn delete_user_identity(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_external_identities WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 查找外部身份绑定的本地用户 ID
+    pub async fn get_user_id(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id FROM auth_external_identities WHERE provider = ? AND subject = ?",
+            vec![Value::from(provider), Value::from(subject)],
+        );
+
+        let result = self.conn.query_one(stmt).await?;
+        Ok(result.map(|row| row.try_get("", "user_id")).transpose()?)
+    }
+
+    /// 列出用户的外部身份绑定 (provider, subject)
+    pub async fn list_user_identities(
+        &self,
+        user_id: &str,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT provider, subject FROM auth_external_identities WHERE user_id = ? ORDER BY id",
+            vec![Value::from(user_id)],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut identities = Vec::new();
+        for row in results {
+            identities.push((row.try_get("", "provider")?, row.try_get("", "subject")?));
+        }
+
+        Ok(identities)
+    }
+
+    /// 列出所有外部身份绑定 (provider, subject, user_id)
+    pub async fn list_identities(&self) -> anyhow::Result<Vec<(String, String, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT provider, subject, user_id FROM auth_external_identities ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut identities = Vec::new();
+        for row in results {
+            identities.push((
+                row.try_get("", "provider")?,
+                row.try_get("", "subject")?,
+                row.try_get("", "user_id")?,
+            ));
+        }
+
+        Ok(identities)
+    }
+}
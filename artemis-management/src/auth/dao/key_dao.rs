@@ -0,0 +1,89 @@
+use crate::auth::keys::SigningKey;
+use crate::db::statement;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use std::str::FromStr;
+
+pub struct KeyDao {
+    conn: DatabaseConnection,
+}
+
+impl KeyDao {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// 保存签名密钥 (私钥以 Base64 编码的 DER 保存)
+    pub async fn insert_key(&self, key: &SigningKey) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_signing_keys (kid, algorithm, private_key, created_at, retired_at) VALUES (?, ?, ?, ?, ?)",
+            vec![
+                Value::from(key.kid.as_str()),
+                Value::from(format!("{:?}", key.algorithm)),
+                Value::from(STANDARD.encode(&key.private_der)),
+                Value::from(key.created_at),
+                Value::from(key.retired_at),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 标记密钥已被轮换
+    pub async fn retire_key(&self, kid: &str, retired_at: i64) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_signing_keys SET retired_at = ? WHERE kid = ? AND retired_at IS NULL",
+            vec![Value::from(retired_at), Value::from(kid)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除密钥
+    pub async fn delete_key(&self, kid: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_signing_keys WHERE kid = ?",
+            vec![Value::from(kid)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出所有签名密钥 (按创建时间排序)
+    pub async fn list_keys(&self) -> anyhow::Result<Vec<SigningKey>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT kid, algorithm, private_key, created_at, retired_at FROM auth_signing_keys ORDER BY created_at, id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut keys = Vec::new();
+        for row in results {
+            let algorithm: String = row.try_get("", "algorithm")?;
+            let private_key: String = row.try_get("", "private_key")?;
+            let algorithm = jsonwebtoken::Algorithm::from_str(&algorithm)?;
+            keys.push(
+                SigningKey::from_der(
+                    row.try_get("", "kid")?,
+                    algorithm,
+                    STANDARD.decode(private_key)?,
+                    row.try_get("", "created_at")?,
+                    row.try_get("", "retired_at")?,
+                )
+                .map_err(|e| anyhow::anyhow!(e))?,
+            );
+        }
+
+        Ok(keys)
+    }
+}
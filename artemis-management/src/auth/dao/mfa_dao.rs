@@ -0,0 +1,406 @@
+use crate::auth::model::MfaChallenge;
+use crate::db::statement;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+pub struct MfaDao {
+    conn: DatabaseConnection,
+}
+
+impl MfaDao {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// 保存用户 TOTP 密钥 (覆盖已有密钥)
+    pub async fn save_totp(
+        &self,
+        user_id: &str,
+        secret: &str,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        self.delete_totp(user_id).await?;
+
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_user_totp (user_id, secret, enabled, created_at) VALUES (?, ?, ?, ?)",
+            vec![
+                Value::from(user_id),
+                Value::from(secret),
+                Value::from(enabled as i32),
+                Value::from(chrono::Utc::now().timestamp()),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除用户 TOTP 密钥
+    pub async fn delete_totp(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_user_totp WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出所有 TOTP 密钥 (user_id, secret, enabled)
+    pub async fn list_totp(&self) -> anyhow::Result<Vec<(String, String, bool)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id, secret, enabled FROM auth_user_totp ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut secrets = Vec::new();
+        for row in results {
+            let enabled: i32 = row.try_get("", "enabled")?;
+            secrets.push((row.try_get("", "user_id")?, row.try_get("", "secret")?, enabled != 0));
+        }
+
+        Ok(secrets)
+    }
+
+    /// 获取用户的 TOTP 密钥 (secret, enabled)
+    pub async fn get_totp(&self, user_id: &str) -> anyhow::Result<Option<(String, bool)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT secret, enabled FROM auth_user_totp WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        match self.conn.query_one(stmt).await? {
+            Some(row) => {
+                let enabled: i32 = row.try_get("", "enabled")?;
+                Ok(Some((row.try_get("", "secret")?, enabled != 0)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 替换用户的恢复码 (只保存摘要)
+    pub async fn replace_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: &[String],
+    ) -> anyhow::Result<()> {
+        self.delete_recovery_codes(user_id).await?;
+
+        for code_hash in code_hashes {
+            let stmt = statement(
+                self.conn.get_database_backend(),
+                "INSERT INTO auth_recovery_codes (user_id, code_hash, used_at) VALUES (?, ?, NULL)",
+                vec![Value::from(user_id), Value::from(code_hash)],
+            );
+            self.conn.execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 标记恢复码已使用
+    pub async fn mark_recovery_code_used(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+        used_at: i64,
+    ) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_recovery_codes SET used_at = ? WHERE user_id = ? AND code_hash = ?",
+            vec![Value::from(used_at), Value::from(user_id), Value::from(code_hash)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除用户的恢复码
+    pub async fn delete_recovery_codes(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_recovery_codes WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出所有未使用的恢复码 (user_id, code_hash)
+    pub async fn list_unused_recovery_codes(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id, code_hash FROM auth_recovery_codes WHERE used_at IS NULL ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut codes = Vec::new();
+        for row in results {
+            codes.push((row.try_get("", "user_id")?, row.try_get("", "code_hash")?));
+        }
+
+        Ok(codes)
+    }
+
+    /// 列出用户未使用的恢复码摘要
+    pub async fn list_user_recovery_codes(&self, user_id: &str) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT code_hash FROM auth_recovery_codes WHERE user_id = ? AND used_at IS NULL ORDER BY id",
+            vec![Value::from(user_id)],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut codes = Vec::new();
+        for row in results {
+            codes.push(row.try_get("", "code_hash")?);
+        }
+
+        Ok(codes)
+    }
+
+    /// 保存两步验证挑战
+    pub async fn insert_challenge(&self, challenge: &MfaChallenge) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO auth_mfa_challenges
+                (challenge_token, user_id, username, ip_address, user_agent, expires_at, failed_attempts)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            vec![
+                Value::from(&challenge.challenge_token),
+                Value::from(&challenge.user_id),
+                Value::from(&challenge.username),
+                Value::from(challenge.ip_address.clone()),
+                Value::from(challenge.user_agent.clone()),
+                Value::from(challenge.expires_at),
+                Value::from(challenge.failed_attempts as i32),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 获取两步验证挑战
+    pub async fn get_challenge(
+        &self,
+        challenge_token: &str,
+    ) -> anyhow::Result<Option<MfaChallenge>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            SELECT challenge_token, user_id, username, ip_address, user_agent, expires_at, failed_attempts
+            FROM auth_mfa_challenges WHERE challenge_token = ?
+            "#,
+            vec![Value::from(challenge_token)],
+        );
+
+        let Some(row) = self.conn.query_one(stmt).await? else {
+            return Ok(None);
+        };
+        let failed_attempts: i32 = row.try_get("", "failed_attempts")?;
+        Ok(Some(MfaChallenge {
+            challenge_token: row.try_get("", "challenge_token")?,
+            user_id: row.try_get("", "user_id")?,
+            username: row.try_get("", "username")?,
+            ip_address: row.try_get("", "ip_address")?,
+            user_agent: row.try_get("", "user_agent")?,
+            expires_at: row.try_get("", "expires_at")?,
+            failed_attempts: failed_attempts as u32,
+        }))
+    }
+
+    /// 验证码错误次数加一
+    pub async fn increment_challenge_attempts(&self, challenge_token: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_mfa_challenges SET failed_attempts = failed_attempts + 1 WHERE challenge_token = ?",
+            vec![Value::from(challenge_token)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除两步验证挑战, 返回是否存在
+    pub async fn delete_challenge(&self, challenge_token: &str) -> anyhow::Result<bool> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_mfa_challenges WHERE challenge_token = ?",
+            vec![Value::from(challenge_token)],
+        );
+
+        Ok(self.conn.execute(stmt).await?.rows_affected() > 0)
+    }
+
+    /// 删除用户的全部两步验证挑战
+    pub async fn delete_user_challenges(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_mfa_challenges WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除过期的两步验证挑战
+    pub async fn delete_expired_challenges(&self, now: i64) -> anyhow::Result<u64> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_mfa_challenges WHERE expires_at <= ?",
+            vec![Value::from(now)],
+        );
+
+        Ok(self.conn.execute(stmt).await?.rows_affected())
+    }
+
+    /// 要求角色启用两步验证
+    pub async fn insert_required_role(&self, role_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO auth_mfa_required_roles (role_id, created_at)
+            SELECT ?, ? WHERE NOT EXISTS (
+                SELECT 1 FROM auth_mfa_required_roles WHERE role_id = ?
+            )
+            "#,
+            vec![
+                Value::from(role_id),
+                Value::from(chrono::Utc::now().timestamp()),
+                Value::from(role_id),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 取消角色的两步验证要求
+    pub async fn delete_required_role(&self, role_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_mfa_required_roles WHERE role_id = ?",
+            vec![Value::from(role_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出要求两步验证的角色 ID
+    pub async fn list_required_roles(&self) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT role_id FROM auth_mfa_required_roles ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut role_ids = Vec::new();
+        for row in results {
+            role_ids.push(row.try_get("", "role_id")?);
+        }
+
+        Ok(role_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_dao() -> MfaDao {
+        MfaDao::new(crate::db::test_database().await.conn().clone())
+    }
+
+    #[tokio::test]
+    async fn test_totp_secrets() {
+        let dao = create_test_dao().await;
+        dao.save_totp("user-1", "SECRET1", false).await.unwrap();
+        dao.save_totp("user-1", "SECRET2", true).await.unwrap();
+        assert_eq!(
+            dao.list_totp().await.unwrap(),
+            vec![("user-1".to_string(), "SECRET2".to_string(), true)]
+        );
+        assert_eq!(dao.get_totp("user-1").await.unwrap(), Some(("SECRET2".to_string(), true)));
+        assert_eq!(dao.get_totp("user-2").await.unwrap(), None);
+
+        dao.delete_totp("user-1").await.unwrap();
+        assert!(dao.list_totp().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recovery_codes() {
+        let dao = create_test_dao().await;
+        let hashes = vec!["h1".to_string(), "h2".to_string()];
+        dao.replace_recovery_codes("user-1", &hashes).await.unwrap();
+        dao.mark_recovery_code_used("user-1", "h1", 1_700_000_000).await.unwrap();
+        assert_eq!(
+            dao.list_unused_recovery_codes().await.unwrap(),
+            vec![("user-1".to_string(), "h2".to_string())]
+        );
+        assert_eq!(dao.list_user_recovery_codes("user-1").await.unwrap(), vec!["h2"]);
+        assert!(dao.list_user_recovery_codes("user-2").await.unwrap().is_empty());
+
+        dao.replace_recovery_codes("user-1", &["h3".to_string()]).await.unwrap();
+        assert_eq!(dao.list_unused_recovery_codes().await.unwrap().len(), 1);
+        dao.delete_recovery_codes("user-1").await.unwrap();
+        assert!(dao.list_unused_recovery_codes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_required_roles() {
+        let dao = create_test_dao().await;
+        dao.insert_required_role("admin").await.unwrap();
+        dao.insert_required_role("admin").await.unwrap();
+        dao.insert_required_role("operator").await.unwrap();
+        assert_eq!(dao.list_required_roles().await.unwrap(), vec!["admin", "operator"]);
+
+        dao.delete_required_role("admin").await.unwrap();
+        assert_eq!(dao.list_required_roles().await.unwrap(), vec!["operator"]);
+    }
+
+    #[tokio::test]
+    async fn test_challenges() {
+        let dao = create_test_dao().await;
+        let challenge = MfaChallenge {
+            challenge_token: "mfa_1".to_string(),
+            user_id: "user-1".to_string(),
+            username: "alice".to_string(),
+            ip_address: Some("10.0.0.1".to_string()),
+            user_agent: None,
+            expires_at: 1_700_000_300,
+            failed_attempts: 0,
+        };
+        dao.insert_challenge(&challenge).await.unwrap();
+        dao.increment_challenge_attempts("mfa_1").await.unwrap();
+
+        let loaded = dao.get_challenge("mfa_1").await.unwrap().unwrap();
+        assert_eq!(loaded.username, "alice");
+        assert_eq!(loaded.ip_address.as_deref(), Some("10.0.0.1"));
+        assert_eq!(loaded.failed_attempts, 1);
+
+        assert!(dao.delete_challenge("mfa_1").await.unwrap());
+        assert!(!dao.delete_challenge("mfa_1").await.unwrap());
+
+        dao.insert_challenge(&challenge).await.unwrap();
+        assert_eq!(dao.delete_expired_challenges(1_700_000_299).await.unwrap(), 0);
+        assert_eq!(dao.delete_expired_challenges(1_700_000_300).await.unwrap(), 1);
+        assert!(dao.get_challenge("mfa_1").await.unwrap().is_none());
+    }
+}
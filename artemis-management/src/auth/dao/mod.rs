@@ -1,7 +1,19 @@
 // DAO modules - to be completed in next step
 
+pub mod identity_dao;
+pub mod key_dao;
+pub mod mfa_dao;
+pub mod password_dao;
+pub mod role_dao;
 pub mod session_dao;
+pub mod token_dao;
 pub mod user_dao;
 
+pub use identity_dao::IdentityDao;
+pub use key_dao::KeyDao;
+pub use mfa_dao::MfaDao;
+pub use password_dao::PasswordDao;
+pub use role_dao::RoleDao;
 pub use session_dao::SessionDao;
+pub use token_dao::TokenDao;
 pub use user_dao::UserDao;
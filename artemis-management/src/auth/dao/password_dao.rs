@@ -0,0 +1,197 @@
+use crate::db::statement;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+pub struct PasswordDao<C = DatabaseConnection> {
+    conn: C,
+}
+
+impl<C: ConnectionTrait> PasswordDao<C> {
+    pub fn new(conn: C) -> Self {
+        Self { conn }
+    }
+
+    /// 记录一条密码历史
+    pub async fn insert_history(
+        &self,
+        user_id: &str,
+        password_hash: &str,
+        created_at: i64,
+    ) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_password_history (user_id, password_hash, created_at) VALUES (?, ?, ?)",
+            vec![Value::from(user_id), Value::from(password_hash), Value::from(created_at)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 只保留用户最近 keep 条密码历史
+    pub async fn trim_history(&self, user_id: &str, keep: usize) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            DELETE FROM auth_password_history
+            WHERE user_id = ? AND id NOT IN (
+                SELECT id FROM auth_password_history WHERE user_id = ? ORDER BY id DESC LIMIT ?
+            )
+            "#,
+            vec![Value::from(user_id), Value::from(user_id), Value::from(keep as i64)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除用户的密码历史
+    pub async fn delete_history(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_password_history WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出所有密码历史 (user_id, password_hash), 按时间先后排序
+    pub async fn list_history(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id, password_hash FROM auth_password_history ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut history = Vec::new();
+        for row in results {
+            history.push((row.try_get("", "user_id")?, row.try_get("", "password_hash")?));
+        }
+
+        Ok(history)
+    }
+
+    /// 列出用户的密码历史摘要, 按时间先后排序
+    pub async fn list_user_history(&self, user_id: &str) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT password_hash FROM auth_password_history WHERE user_id = ? ORDER BY id",
+            vec![Value::from(user_id)],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut history = Vec::new();
+        for row in results {
+            history.push(row.try_get("", "password_hash")?);
+        }
+
+        Ok(history)
+    }
+
+    /// 标记用户下次登录后必须修改密码
+    pub async fn require_change(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO auth_password_change_required (user_id, created_at)
+            SELECT ?, ? WHERE NOT EXISTS (
+                SELECT 1 FROM auth_password_change_required WHERE user_id = ?
+            )
+            "#,
+            vec![
+                Value::from(user_id),
+                Value::from(chrono::Utc::now().timestamp()),
+                Value::from(user_id),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 清除强制改密标记
+    pub async fn clear_change_required(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_password_change_required WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 用户是否需要强制改密
+    pub async fn is_change_required(&self, user_id: &str) -> anyhow::Result<bool> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id FROM auth_password_change_required WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        Ok(self.conn.query_one(stmt).await?.is_some())
+    }
+
+    /// 列出所有需要强制改密的用户 ID
+    pub async fn list_change_required(&self) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id FROM auth_password_change_required ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut user_ids = Vec::new();
+        for row in results {
+            user_ids.push(row.try_get("", "user_id")?);
+        }
+
+        Ok(user_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_dao() -> PasswordDao {
+        PasswordDao::new(crate::db::test_database().await.conn().clone())
+    }
+
+    #[tokio::test]
+    async fn test_password_history() {
+        let dao = create_test_dao().await;
+        for (i, hash) in ["h1", "h2", "h3"].iter().enumerate() {
+            dao.insert_history("user-1", hash, i as i64).await.unwrap();
+        }
+        dao.insert_history("user-2", "other", 0).await.unwrap();
+
+        dao.trim_history("user-1", 2).await.unwrap();
+        let history = dao.list_history().await.unwrap();
+        let user_1: Vec<&str> =
+            history.iter().filter(|(u, _)| u == "user-1").map(|(_, h)| h.as_str()).collect();
+        assert_eq!(user_1, vec!["h2", "h3"]);
+
+        dao.delete_history("user-1").await.unwrap();
+        assert_eq!(dao.list_history().await.unwrap(), vec![("user-2".into(), "other".into())]);
+    }
+
+    #[tokio::test]
+    async fn test_change_required() {
+        let dao = create_test_dao().await;
+        dao.require_change("admin").await.unwrap();
+        dao.require_change("admin").await.unwrap();
+        assert_eq!(dao.list_change_required().await.unwrap(), vec!["admin"]);
+        assert!(dao.is_change_required("admin").await.unwrap());
+
+        dao.clear_change_required("admin").await.unwrap();
+        assert!(dao.list_change_required().await.unwrap().is_empty());
+        assert!(!dao.is_change_required("admin").await.unwrap());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::auth::model::{Permission, Role};
+use crate::db::statement;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+pub struct RoleDao<C = DatabaseConnection> {
+    conn: C,
+}
+
+impl<C: ConnectionTrait> RoleDao<C> {
+    pub fn new(conn: C) -> Self {
+        Self { conn }
+    }
+
+    /// 插入自定义角色
+    pub async fn insert_role(&self, role: &Role) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO auth_roles (role_id, description, permissions, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            vec![
+                Value::from(&role.role_id),
+                Value::from(role.description.as_deref().unwrap_or("")),
+                Value::from(serde_json::to_string(&role.permissions)?),
+                Value::from(role.created_at),
+                Value::from(role.updated_at),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 更新自定义角色
+    pub async fn update_role(&self, role: &Role) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_roles SET description = ?, permissions = ?, updated_at = ? WHERE role_id = ?",
+            vec![
+                Value::from(role.description.as_deref().unwrap_or("")),
+                Value::from(serde_json::to_string(&role.permissions)?),
+                Value::from(role.updated_at),
+                Value::from(&role.role_id),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除自定义角色 (同时删除用户关联)
+    pub async fn delete_role(&self, role_id: &str) -> anyhow::Result<()> {
+        let backend = self.conn.get_database_backend();
+
+        let stmt = statement(
+            backend,
+            "DELETE FROM auth_user_roles WHERE role_id = ?",
+            vec![Value::from(role_id)],
+        );
+        self.conn.execute(stmt).await?;
+
+        let stmt = statement(
+            backend,
+            "DELETE FROM auth_roles WHERE role_id = ?",
+            vec![Value::from(role_id)],
+        );
+        self.conn.execute(stmt).await?;
+
+        Ok(())
+    }
+
+    /// 列出所有自定义角色
+    pub async fn list_roles(&self) -> anyhow::Result<Vec<Role>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT role_id, description, permissions, created_at, updated_at FROM auth_roles ORDER BY role_id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut roles = Vec::new();
+        for row in results {
+            roles.push(self.row_to_role(row)?);
+        }
+
+        Ok(roles)
+    }
+
+    /// 设置用户的附加角色 (覆盖原有关联)
+    pub async fn set_user_roles(&self, user_id: &str, role_ids: &[String]) -> anyhow::Result<()> {
+        self.delete_user_roles(user_id).await?;
+
+        let now = chrono::Utc::now().timestamp();
+        for role_id in role_ids {
+            let stmt = statement(
+                self.conn.get_database_backend(),
+                "INSERT INTO auth_user_roles (user_id, role_id, created_at) VALUES (?, ?, ?)",
+                vec![Value::from(user_id), Value::from(role_id), Value::from(now)],
+            );
+            self.conn.execute(stmt).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 删除用户的所有附加角色
+    pub async fn delete_user_roles(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_user_roles WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出用户的自定义角色 ID
+    pub async fn list_roles_of_user(&self, user_id: &str) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT role_id FROM auth_user_roles WHERE user_id = ? ORDER BY id",
+            vec![Value::from(user_id)],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut role_ids = Vec::new();
+        for row in results {
+            role_ids.push(row.try_get("", "role_id")?);
+        }
+
+        Ok(role_ids)
+    }
+
+    /// 列出所有用户角色关联 (user_id, role_id)
+    pub async fn list_user_roles(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id, role_id FROM auth_user_roles ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut assignments = Vec::new();
+        for row in results {
+            let user_id: String = row.try_get("", "user_id")?;
+            let role_id: String = row.try_get("", "role_id")?;
+            assignments.push((user_id, role_id));
+        }
+
+        Ok(assignments)
+    }
+
+    /// 将数据库行转换为 Role 对象
+    fn row_to_role(&self, row: sea_orm::QueryResult) -> anyhow::Result<Role> {
+        let role_id: String = row.try_get("", "role_id")?;
+        let description_str: String = row.try_get("", "description").unwrap_or_default();
+        let description = if description_str.is_empty() { None } else { Some(description_str) };
+        let permissions_json: String = row.try_get("", "permissions")?;
+        let permissions: Vec<Permission> = serde_json::from_str(&permissions_json)?;
+        let created_at: i64 = row.try_get("", "created_at")?;
+        let updated_at: i64 = row.try_get("", "updated_at")?;
+
+        Ok(Role { role_id, description, permissions, builtin: false, created_at, updated_at })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_dao() -> RoleDao {
+        RoleDao::new(crate::db::test_database().await.conn().clone())
+    }
+
+    #[tokio::test]
+    async fn test_role_crud() {
+        let dao = create_test_dao().await;
+        let mut role = Role::new(
+            "team-a-ops".to_string(),
+            Some("Team A operators".to_string()),
+            vec![Permission::scoped("instances", "*", "team-a-*")],
+        );
+
+        dao.insert_role(&role).await.unwrap();
+        let roles = dao.list_roles().await.unwrap();
+        assert_eq!(roles.len(), 1);
+        assert_eq!(roles[0].permissions, role.permissions);
+        assert_eq!(roles[0].description.as_deref(), Some("Team A operators"));
+
+        role.permissions.push(Permission::new("audit", "read"));
+        dao.update_role(&role).await.unwrap();
+        assert_eq!(dao.list_roles().await.unwrap()[0].permissions.len(), 2);
+
+        dao.delete_role("team-a-ops").await.unwrap();
+        assert!(dao.list_roles().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_user_role_assignments() {
+        let dao = create_test_dao().await;
+        let role = Role::new("team-a-ops".to_string(), None, vec![]);
+        dao.insert_role(&role).await.unwrap();
+
+        dao.set_user_roles("user-1", &["team-a-ops".to_string(), "viewer".to_string()])
+            .await
+            .unwrap();
+        dao.set_user_roles("user-2", &["team-a-ops".to_string()]).await.unwrap();
+        assert_eq!(dao.list_user_roles().await.unwrap().len(), 3);
+        assert_eq!(dao.list_roles_of_user("user-1").await.unwrap(), vec!["team-a-ops", "viewer"]);
+
+        // 覆盖设置
+        dao.set_user_roles("user-1", &["viewer".to_string()]).await.unwrap();
+        assert_eq!(dao.list_user_roles().await.unwrap().len(), 2);
+
+        // 删除角色时清理关联
+        dao.delete_role("team-a-ops").await.unwrap();
+        let assignments = dao.list_user_roles().await.unwrap();
+        assert_eq!(assignments, vec![("user-1".to_string(), "viewer".to_string())]);
+    }
+}
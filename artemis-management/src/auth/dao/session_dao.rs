@@ -1,6 +1,7 @@
 use crate::auth::model::{LoginHistory, LoginStatus, Session};
+use crate::db::statement;
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 use std::str::FromStr;
 
 pub struct SessionDao {
@@ -16,7 +17,7 @@ impl SessionDao {
     pub async fn insert_session(&self, session: &Session) -> anyhow::Result<()> {
         let token = session.token.as_deref().unwrap_or("");
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO auth_sessions (session_id, user_id, token, ip_address, user_agent, created_at, expires_at, last_activity)
@@ -44,7 +45,7 @@ impl SessionDao {
         session_id: &str,
         timestamp: i64,
     ) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "UPDATE auth_sessions SET last_activity = ? WHERE session_id = ?",
             vec![Value::from(timestamp), Value::from(session_id)],
@@ -56,7 +57,7 @@ impl SessionDao {
 
     /// 删除会话
     pub async fn delete_session(&self, session_id: &str) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM auth_sessions WHERE session_id = ?",
             vec![Value::from(session_id)],
@@ -68,7 +69,7 @@ impl SessionDao {
 
     /// 根据 session_id 获取会话
     pub async fn get_session(&self, session_id: &str) -> anyhow::Result<Option<Session>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT session_id, user_id, token, ip_address, user_agent, created_at, expires_at, last_activity FROM auth_sessions WHERE session_id = ?",
             vec![Value::from(session_id)],
@@ -81,7 +82,7 @@ impl SessionDao {
 
     /// 根据 token 获取会话
     pub async fn get_session_by_token(&self, token: &str) -> anyhow::Result<Option<Session>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT session_id, user_id, token, ip_address, user_agent, created_at, expires_at, last_activity FROM auth_sessions WHERE token = ?",
             vec![Value::from(token)],
@@ -94,7 +95,7 @@ impl SessionDao {
 
     /// 列出用户的所有会话
     pub async fn list_sessions_by_user(&self, user_id: &str) -> anyhow::Result<Vec<Session>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT session_id, user_id, token, ip_address, user_agent, created_at, expires_at, last_activity FROM auth_sessions WHERE user_id = ? ORDER BY created_at DESC",
             vec![Value::from(user_id)],
@@ -112,7 +113,7 @@ impl SessionDao {
 
     /// 删除过期会话
     pub async fn delete_expired_sessions(&self, current_time: i64) -> anyhow::Result<u64> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM auth_sessions WHERE expires_at < ?",
             vec![Value::from(current_time)],
@@ -124,7 +125,7 @@ impl SessionDao {
 
     /// 删除用户的所有会话
     pub async fn delete_user_sessions(&self, user_id: &str) -> anyhow::Result<u64> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM auth_sessions WHERE user_id = ?",
             vec![Value::from(user_id)],
@@ -134,9 +135,127 @@ impl SessionDao {
         Ok(result.rows_affected())
     }
 
+    /// 更新会话令牌 (刷新令牌)
+    pub async fn update_token(
+        &self,
+        session_id: &str,
+        token: &str,
+        expires_at: i64,
+    ) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_sessions SET token = ?, expires_at = ? WHERE session_id = ?",
+            vec![Value::from(token), Value::from(expires_at), Value::from(session_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 写入会话失效记录 (session_id 为 None 表示用户的全部会话)
+    pub async fn insert_revocation(
+        &self,
+        session_id: Option<&str>,
+        user_id: &str,
+    ) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_session_revocations (session_id, user_id, revoked_at) VALUES (?, ?, ?)",
+            vec![
+                Value::from(session_id.map(|s| s.to_string())),
+                Value::from(user_id),
+                Value::from(chrono::Utc::now().timestamp()),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 写入用户变更记录 (用户信息、角色分配或两步验证), 其他节点据此刷新缓存的用户
+    pub async fn insert_user_change(&self, user_id: &str) -> anyhow::Result<()> {
+        self.insert_change("user", user_id).await
+    }
+
+    /// 写入角色定义变更记录, 其他节点据此刷新缓存的角色
+    pub async fn insert_role_change(&self, role_id: &str) -> anyhow::Result<()> {
+        self.insert_change("role", role_id).await
+    }
+
+    async fn insert_change(&self, kind: &str, target_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_session_revocations (session_id, user_id, revoked_at, kind) VALUES (?, ?, ?, ?)",
+            vec![
+                Value::from(None::<String>),
+                Value::from(target_id),
+                Value::from(chrono::Utc::now().timestamp()),
+                Value::from(kind),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出 id 大于 `after_id` 的失效和变更记录 (id, kind, session_id, user_id)
+    ///
+    /// kind 为 session 时是会话或 API Token 失效; user / role 时 user_id 为变更的用户或角色 ID
+    pub async fn list_revocations_after(
+        &self,
+        after_id: i64,
+    ) -> anyhow::Result<Vec<(i64, String, Option<String>, String)>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT id, kind, session_id, user_id FROM auth_session_revocations WHERE id > ? ORDER BY id",
+            vec![Value::from(after_id)],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut revocations = Vec::new();
+        for row in results {
+            revocations.push((
+                row.try_get("", "id")?,
+                row.try_get("", "kind")?,
+                row.try_get("", "session_id")?,
+                row.try_get("", "user_id")?,
+            ));
+        }
+
+        Ok(revocations)
+    }
+
+    /// 最新的失效记录 id (没有记录时为 0)
+    pub async fn max_revocation_id(&self) -> anyhow::Result<i64> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT MAX(id) AS max_id FROM auth_session_revocations",
+            vec![],
+        );
+
+        let result = self.conn.query_one(stmt).await?;
+        Ok(result
+            .and_then(|row| row.try_get::<Option<i64>>("", "max_id").ok())
+            .flatten()
+            .unwrap_or(0))
+    }
+
+    /// 删除早于指定时间的失效记录
+    pub async fn delete_revocations_before(&self, before: i64) -> anyhow::Result<u64> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_session_revocations WHERE revoked_at < ?",
+            vec![Value::from(before)],
+        );
+
+        let result = self.conn.execute(stmt).await?;
+        Ok(result.rows_affected())
+    }
+
     /// 插入登录历史
     pub async fn insert_login_history(&self, history: &LoginHistory) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO auth_login_history (user_id, login_time, ip_address, user_agent, status)
@@ -161,7 +280,7 @@ impl SessionDao {
         user_id: &str,
         limit: u32,
     ) -> anyhow::Result<Vec<LoginHistory>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT id, user_id, login_time, ip_address, user_agent, status FROM auth_login_history WHERE user_id = ? ORDER BY login_time DESC LIMIT ?",
             vec![Value::from(user_id), Value::from(limit as i32)],
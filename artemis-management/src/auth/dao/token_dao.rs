@@ -0,0 +1,250 @@
+use crate::auth::model::{ApiToken, Permission};
+use crate::db::statement;
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+pub struct TokenDao<C = DatabaseConnection> {
+    conn: C,
+}
+
+impl<C: ConnectionTrait> TokenDao<C> {
+    pub fn new(conn: C) -> Self {
+        Self { conn }
+    }
+
+    /// 插入 API Token (只保存摘要)
+    pub async fn insert_token(&self, token: &ApiToken) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO auth_api_tokens
+                (token_id, user_id, name, token_prefix, token_hash, scopes, created_at, expires_at, last_used_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            vec![
+                Value::from(&token.token_id),
+                Value::from(&token.user_id),
+                Value::from(&token.name),
+                Value::from(&token.token_prefix),
+                Value::from(&token.token_hash),
+                Value::from(serde_json::to_string(&token.scopes)?),
+                Value::from(token.created_at),
+                Value::from(token.expires_at),
+                Value::from(token.last_used_at),
+            ],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 更新最近使用时间
+    pub async fn update_last_used(&self, token_id: &str, last_used_at: i64) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "UPDATE auth_api_tokens SET last_used_at = ? WHERE token_id = ?",
+            vec![Value::from(last_used_at), Value::from(token_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除 API Token
+    pub async fn delete_token(&self, token_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_api_tokens WHERE token_id = ?",
+            vec![Value::from(token_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除用户的所有 API Token
+    pub async fn delete_user_tokens(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_api_tokens WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 列出所有 API Token
+    pub async fn list_tokens(&self) -> anyhow::Result<Vec<ApiToken>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            SELECT token_id, user_id, name, token_prefix, token_hash, scopes, created_at, expires_at, last_used_at
+            FROM auth_api_tokens ORDER BY created_at
+            "#,
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut tokens = Vec::new();
+        for row in results {
+            tokens.push(self.row_to_token(row)?);
+        }
+
+        Ok(tokens)
+    }
+
+    /// 根据摘要获取 API Token
+    pub async fn get_token_by_hash(&self, token_hash: &str) -> anyhow::Result<Option<ApiToken>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            SELECT token_id, user_id, name, token_prefix, token_hash, scopes, created_at, expires_at, last_used_at
+            FROM auth_api_tokens WHERE token_hash = ?
+            "#,
+            vec![Value::from(token_hash)],
+        );
+
+        match self.conn.query_one(stmt).await? {
+            Some(row) => Ok(Some(self.row_to_token(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 标记用户为服务账号
+    pub async fn insert_service_account(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "INSERT INTO auth_service_accounts (user_id, created_at) VALUES (?, ?)",
+            vec![Value::from(user_id), Value::from(chrono::Utc::now().timestamp())],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 删除服务账号标记
+    pub async fn delete_service_account(&self, user_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM auth_service_accounts WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 用户是否为服务账号
+    pub async fn is_service_account(&self, user_id: &str) -> anyhow::Result<bool> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id FROM auth_service_accounts WHERE user_id = ?",
+            vec![Value::from(user_id)],
+        );
+
+        Ok(self.conn.query_one(stmt).await?.is_some())
+    }
+
+    /// 列出所有服务账号的用户 ID
+    pub async fn list_service_accounts(&self) -> anyhow::Result<Vec<String>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "SELECT user_id FROM auth_service_accounts ORDER BY id",
+            vec![],
+        );
+
+        let results = self.conn.query_all(stmt).await?;
+
+        let mut user_ids = Vec::new();
+        for row in results {
+            user_ids.push(row.try_get("", "user_id")?);
+        }
+
+        Ok(user_ids)
+    }
+
+    /// 将数据库行转换为 ApiToken 对象
+    fn row_to_token(&self, row: sea_orm::QueryResult) -> anyhow::Result<ApiToken> {
+        let scopes_json: String = row.try_get("", "scopes")?;
+        let scopes: Vec<Permission> = serde_json::from_str(&scopes_json)?;
+
+        Ok(ApiToken {
+            token_id: row.try_get("", "token_id")?,
+            user_id: row.try_get("", "user_id")?,
+            name: row.try_get("", "name")?,
+            token_prefix: row.try_get("", "token_prefix")?,
+            token_hash: row.try_get("", "token_hash")?,
+            scopes,
+            created_at: row.try_get("", "created_at")?,
+            expires_at: row.try_get("", "expires_at")?,
+            last_used_at: row.try_get("", "last_used_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_dao() -> TokenDao {
+        TokenDao::new(crate::db::test_database().await.conn().clone())
+    }
+
+    #[tokio::test]
+    async fn test_token_crud() {
+        let dao = create_test_dao().await;
+        let (token, plaintext) = ApiToken::generate(
+            "user-1".to_string(),
+            "ci".to_string(),
+            vec![Permission::scoped("zones", "write", "order-*")],
+            Some(chrono::Utc::now().timestamp() + 3600),
+        );
+
+        dao.insert_token(&token).await.unwrap();
+        let tokens = dao.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].token_hash, ApiToken::hash(&plaintext));
+        assert_eq!(tokens[0].scopes, token.scopes);
+        assert_eq!(tokens[0].expires_at, token.expires_at);
+        assert_eq!(tokens[0].last_used_at, None);
+        let found = dao.get_token_by_hash(&ApiToken::hash(&plaintext)).await.unwrap().unwrap();
+        assert_eq!(found.token_id, token.token_id);
+        assert!(dao.get_token_by_hash("unknown").await.unwrap().is_none());
+
+        dao.update_last_used(&token.token_id, 1_700_000_000).await.unwrap();
+        assert_eq!(dao.list_tokens().await.unwrap()[0].last_used_at, Some(1_700_000_000));
+
+        dao.delete_token(&token.token_id).await.unwrap();
+        assert!(dao.list_tokens().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_user_tokens() {
+        let dao = create_test_dao().await;
+        for (user_id, name) in [("user-1", "a"), ("user-1", "b"), ("user-2", "c")] {
+            let (token, _) =
+                ApiToken::generate(user_id.to_string(), name.to_string(), vec![], None);
+            dao.insert_token(&token).await.unwrap();
+        }
+
+        dao.delete_user_tokens("user-1").await.unwrap();
+        let tokens = dao.list_tokens().await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].user_id, "user-2");
+    }
+
+    #[tokio::test]
+    async fn test_service_accounts() {
+        let dao = create_test_dao().await;
+        dao.insert_service_account("svc-1").await.unwrap();
+        dao.insert_service_account("svc-2").await.unwrap();
+        assert_eq!(dao.list_service_accounts().await.unwrap(), vec!["svc-1", "svc-2"]);
+
+        dao.delete_service_account("svc-1").await.unwrap();
+        assert_eq!(dao.list_service_accounts().await.unwrap(), vec!["svc-2"]);
+        assert!(dao.is_service_account("svc-2").await.unwrap());
+        assert!(!dao.is_service_account("svc-1").await.unwrap());
+    }
+}
@@ -1,20 +1,21 @@
 use crate::auth::model::{User, UserRole, UserStatus};
+use crate::db::statement;
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 use std::str::FromStr;
 
-pub struct UserDao {
-    conn: DatabaseConnection,
+pub struct UserDao<C = DatabaseConnection> {
+    conn: C,
 }
 
-impl UserDao {
-    pub fn new(conn: DatabaseConnection) -> Self {
+impl<C: ConnectionTrait> UserDao<C> {
+    pub fn new(conn: C) -> Self {
         Self { conn }
     }
 
     /// 插入用户
     pub async fn insert_user(&self, user: &User) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO auth_users (user_id, username, email, description, password_hash, role, status, created_at, updated_at)
@@ -39,7 +40,7 @@ impl UserDao {
 
     /// 更新用户
     pub async fn update_user(&self, user: &User) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             UPDATE auth_users
@@ -63,7 +64,7 @@ impl UserDao {
 
     /// 删除用户
     pub async fn delete_user(&self, user_id: &str) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM auth_users WHERE user_id = ?",
             vec![Value::from(user_id)],
@@ -75,7 +76,7 @@ impl UserDao {
 
     /// 根据 user_id 获取用户
     pub async fn get_user(&self, user_id: &str) -> anyhow::Result<Option<User>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT user_id, username, email, description, password_hash, role, status, created_at, updated_at FROM auth_users WHERE user_id = ?",
             vec![Value::from(user_id)],
@@ -88,7 +89,7 @@ impl UserDao {
 
     /// 根据 username 获取用户
     pub async fn get_user_by_username(&self, username: &str) -> anyhow::Result<Option<User>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT user_id, username, email, description, password_hash, role, status, created_at, updated_at FROM auth_users WHERE username = ?",
             vec![Value::from(username)],
@@ -101,7 +102,7 @@ impl UserDao {
 
     /// 列出所有用户
     pub async fn list_users(&self) -> anyhow::Result<Vec<User>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT user_id, username, email, description, password_hash, role, status, created_at, updated_at FROM auth_users ORDER BY created_at DESC",
             vec![],
@@ -119,7 +120,7 @@ impl UserDao {
 
     /// 按角色列出用户
     pub async fn list_users_by_role(&self, role: UserRole) -> anyhow::Result<Vec<User>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT user_id, username, email, description, password_hash, role, status, created_at, updated_at FROM auth_users WHERE role = ? ORDER BY created_at DESC",
             vec![Value::from(role.as_str())],
@@ -137,7 +138,7 @@ impl UserDao {
 
     /// 按状态列出用户
     pub async fn list_users_by_status(&self, status: UserStatus) -> anyhow::Result<Vec<User>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT user_id, username, email, description, password_hash, role, status, created_at, updated_at FROM auth_users WHERE status = ? ORDER BY created_at DESC",
             vec![Value::from(status.as_str())],
@@ -177,7 +178,12 @@ impl UserDao {
             description,
             password_hash,
             role,
+            roles: Vec::new(),
             status,
+            service_account: false,
+            must_change_password: false,
+            mfa_enabled: false,
+            identity_provider: None,
             created_at,
             updated_at,
         })
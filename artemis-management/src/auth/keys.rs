@@ -0,0 +1,305 @@
+//! Asymmetric JWT signing keys (ES256 / RS256): key generation, the key set
+//! holding the active key and rotated keys still valid for verification, and
+//! the public JWKS document.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use ring::rand::SystemRandom;
+use ring::signature::{ECDSA_P256_SHA256_FIXED_SIGNING, EcdsaKeyPair, KeyPair};
+use rsa::pkcs1::{DecodeRsaPrivateKey, EncodeRsaPrivateKey};
+use rsa::traits::PublicKeyParts;
+use std::sync::RwLock;
+
+/// RSA 密钥长度 (位)
+pub const RSA_KEY_BITS: usize = 2048;
+
+/// 支持的签名算法
+pub const SUPPORTED_ALGORITHMS: [Algorithm; 2] = [Algorithm::ES256, Algorithm::RS256];
+
+/// JWT 签名密钥
+#[derive(Clone)]
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    /// 私钥 DER (ES256 为 PKCS#8, RS256 为 PKCS#1)
+    pub private_der: Vec<u8>,
+    pub created_at: i64,
+    /// 被轮换下来的时间, None 表示当前签名密钥
+    pub retired_at: Option<i64>,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    jwk: Jwk,
+}
+
+impl SigningKey {
+    /// 生成新密钥
+    pub fn generate(algorithm: Algorithm) -> Result<Self, String> {
+        let private_der = match algorithm {
+            Algorithm::ES256 => {
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+                    .map_err(|e| format!("Failed to generate EC key: {}", e))?
+                    .as_ref()
+                    .to_vec()
+            }
+            Algorithm::RS256 => rsa::RsaPrivateKey::new(&mut rand::thread_rng(), RSA_KEY_BITS)
+                .map_err(|e| format!("Failed to generate RSA key: {}", e))?
+                .to_pkcs1_der()
+                .map_err(|e| format!("Failed to encode RSA key: {}", e))?
+                .as_bytes()
+                .to_vec(),
+            other => return Err(format!("Unsupported JWT algorithm: {:?}", other)),
+        };
+
+        let kid = uuid::Uuid::new_v4().simple().to_string();
+        Self::from_der(kid, algorithm, private_der, chrono::Utc::now().timestamp(), None)
+    }
+
+    /// 从私钥 DER 恢复密钥 (公钥由私钥推导)
+    pub fn from_der(
+        kid: String,
+        algorithm: Algorithm,
+        private_der: Vec<u8>,
+        created_at: i64,
+        retired_at: Option<i64>,
+    ) -> Result<Self, String> {
+        let (encoding, decoding, mut jwk) = match algorithm {
+            Algorithm::ES256 => {
+                let key_pair = EcdsaKeyPair::from_pkcs8(
+                    &ECDSA_P256_SHA256_FIXED_SIGNING,
+                    &private_der,
+                    &SystemRandom::new(),
+                )
+                .map_err(|e| format!("Invalid EC private key: {}", e))?;
+
+                // 未压缩点: 0x04 || x || y
+                let point = key_pair.public_key().as_ref();
+                let x = URL_SAFE_NO_PAD.encode(&point[1..33]);
+                let y = URL_SAFE_NO_PAD.encode(&point[33..65]);
+                let decoding = DecodingKey::from_ec_components(&x, &y)
+                    .map_err(|e| format!("Invalid EC public key: {}", e))?;
+                let jwk = serde_json::json!({"kty": "EC", "crv": "P-256", "x": x, "y": y});
+                (EncodingKey::from_ec_der(&private_der), decoding, jwk)
+            }
+            Algorithm::RS256 => {
+                let key = rsa::RsaPrivateKey::from_pkcs1_der(&private_der)
+                    .map_err(|e| format!("Invalid RSA private key: {}", e))?;
+                let n = URL_SAFE_NO_PAD.encode(key.n().to_bytes_be());
+                let e = URL_SAFE_NO_PAD.encode(key.e().to_bytes_be());
+                let decoding = DecodingKey::from_rsa_components(&n, &e)
+                    .map_err(|e| format!("Invalid RSA public key: {}", e))?;
+                let jwk = serde_json::json!({"kty": "RSA", "n": n, "e": e});
+                (EncodingKey::from_rsa_der(&private_der), decoding, jwk)
+            }
+            other => return Err(format!("Unsupported JWT algorithm: {:?}", other)),
+        };
+
+        jwk["kid"] = kid.clone().into();
+        jwk["use"] = "sig".into();
+        jwk["alg"] = format!("{:?}", algorithm).into();
+        let jwk: Jwk = serde_json::from_value(jwk).map_err(|e| format!("Invalid JWK: {}", e))?;
+
+        Ok(Self { kid, algorithm, private_der, created_at, retired_at, encoding, decoding, jwk })
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding
+    }
+
+    /// 公钥 JWK
+    pub fn jwk(&self) -> &Jwk {
+        &self.jwk
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.retired_at.is_none()
+    }
+}
+
+/// 签名密钥集合: 一个当前签名密钥 + 轮换下来、仍用于校验未过期令牌的旧密钥
+pub struct KeySet {
+    keys: RwLock<Vec<SigningKey>>, // 按创建时间排序
+}
+
+impl KeySet {
+    pub fn new() -> Self {
+        Self { keys: RwLock::new(Vec::new()) }
+    }
+
+    /// 当前签名密钥
+    pub fn active(&self) -> Option<SigningKey> {
+        self.keys.read().unwrap().iter().rev().find(|k| k.is_active()).cloned()
+    }
+
+    /// 根据 kid 查找密钥 (含已轮换的旧密钥)
+    pub fn find(&self, kid: &str) -> Option<SigningKey> {
+        self.keys.read().unwrap().iter().find(|k| k.kid == kid).cloned()
+    }
+
+    pub fn list(&self) -> Vec<SigningKey> {
+        self.keys.read().unwrap().clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.read().unwrap().is_empty()
+    }
+
+    /// 加入密钥; 新密钥为当前签名密钥时, 原签名密钥转为旧密钥
+    ///
+    /// 加入的密钥比现有签名密钥更早创建时 (其他节点生成的密钥), 它本身转为旧密钥,
+    /// 各节点最终都使用最新的签名密钥. 返回被轮换下来的密钥 kid
+    pub fn insert(&self, mut key: SigningKey) -> Vec<String> {
+        let mut keys = self.keys.write().unwrap();
+        let mut retired = Vec::new();
+        let now = chrono::Utc::now().timestamp();
+        if key.is_active()
+            && keys
+                .iter()
+                .any(|k| k.is_active() && k.kid != key.kid && k.created_at > key.created_at)
+        {
+            key.retired_at = Some(now);
+            retired.push(key.kid.clone());
+        }
+        if key.is_active() {
+            for existing in keys.iter_mut().filter(|k| k.is_active()) {
+                existing.retired_at = Some(now);
+                retired.push(existing.kid.clone());
+            }
+        }
+        keys.retain(|k| k.kid != key.kid);
+        keys.push(key);
+        keys.sort_by_key(|k| k.created_at);
+        retired
+    }
+
+    /// 只在当前没有签名密钥时加入 (避免并发生成多个签名密钥)
+    pub fn insert_if_no_active(&self, key: SigningKey) -> Option<SigningKey> {
+        let mut keys = self.keys.write().unwrap();
+        if let Some(active) = keys.iter().rev().find(|k| k.is_active()) {
+            return Some(active.clone());
+        }
+        keys.push(key);
+        None
+    }
+
+    /// 移除轮换时间早于 `retired_before` 的旧密钥, 返回移除的 kid
+    pub fn prune(&self, retired_before: i64) -> Vec<String> {
+        let mut keys = self.keys.write().unwrap();
+        let removed: Vec<String> = keys
+            .iter()
+            .filter(|k| k.retired_at.is_some_and(|at| at < retired_before))
+            .map(|k| k.kid.clone())
+            .collect();
+        keys.retain(|k| !removed.contains(&k.kid));
+        removed
+    }
+
+    /// 公开的 JWKS 文档 (当前签名密钥和旧密钥的公钥)
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet { keys: self.keys.read().unwrap().iter().map(|k| k.jwk.clone()).collect() }
+    }
+}
+
+impl Default for KeySet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{Header, Validation, decode, encode};
+
+    fn sign_and_verify(key: &SigningKey) {
+        let claims = serde_json::json!({"sub": "u1", "exp": chrono::Utc::now().timestamp() + 60});
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+        let token = encode(&header, &claims, key.encoding_key()).unwrap();
+
+        // 从 JWKS 中的公钥校验
+        let jwk = key.jwk();
+        let decoding = DecodingKey::from_jwk(jwk).unwrap();
+        let decoded =
+            decode::<serde_json::Value>(&token, &decoding, &Validation::new(key.algorithm))
+                .unwrap();
+        assert_eq!(decoded.claims["sub"], "u1");
+        assert_eq!(decoded.header.kid.as_deref(), Some(key.kid.as_str()));
+    }
+
+    #[test]
+    fn test_es256_key() {
+        let key = SigningKey::generate(Algorithm::ES256).unwrap();
+        sign_and_verify(&key);
+
+        // 从持久化的 DER 恢复
+        let restored = SigningKey::from_der(
+            key.kid.clone(),
+            key.algorithm,
+            key.private_der.clone(),
+            key.created_at,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(restored.jwk()).unwrap(),
+            serde_json::to_value(key.jwk()).unwrap()
+        );
+        sign_and_verify(&restored);
+    }
+
+    #[test]
+    fn test_rs256_key() {
+        let key = SigningKey::generate(Algorithm::RS256).unwrap();
+        sign_and_verify(&key);
+        assert_eq!(serde_json::to_value(key.jwk()).unwrap()["kty"], "RSA");
+    }
+
+    #[test]
+    fn test_unsupported_algorithm() {
+        assert!(SigningKey::generate(Algorithm::HS256).is_err());
+    }
+
+    #[test]
+    fn test_key_set_rotation() {
+        let keys = KeySet::new();
+        let first = SigningKey::generate(Algorithm::ES256).unwrap();
+        assert!(keys.insert(first.clone()).is_empty());
+
+        let second = SigningKey::generate(Algorithm::ES256).unwrap();
+        assert_eq!(keys.insert(second.clone()), vec![first.kid.clone()]);
+
+        assert_eq!(keys.active().unwrap().kid, second.kid);
+        assert!(!keys.find(&first.kid).unwrap().is_active());
+        assert_eq!(keys.jwks().keys.len(), 2);
+
+        // 只有当前签名密钥时不会重复生成
+        let third = SigningKey::generate(Algorithm::ES256).unwrap();
+        assert_eq!(keys.insert_if_no_active(third).unwrap().kid, second.kid);
+
+        let retired_at = keys.find(&first.kid).unwrap().retired_at.unwrap();
+        assert!(keys.prune(retired_at).is_empty());
+        assert_eq!(keys.prune(retired_at + 1), vec![first.kid.clone()]);
+        assert!(keys.find(&first.kid).is_none());
+        assert_eq!(keys.jwks().keys.len(), 1);
+    }
+
+    #[test]
+    fn test_key_set_keeps_newest_active() {
+        let keys = KeySet::new();
+        let mut older = SigningKey::generate(Algorithm::ES256).unwrap();
+        older.created_at -= 10;
+        let newer = SigningKey::generate(Algorithm::ES256).unwrap();
+        keys.insert(newer.clone());
+
+        // 其他节点生成的更早的签名密钥加入后直接作为旧密钥
+        assert_eq!(keys.insert(older.clone()), vec![older.kid.clone()]);
+        assert_eq!(keys.active().unwrap().kid, newer.kid);
+        assert!(!keys.find(&older.kid).unwrap().is_active());
+    }
+}
@@ -1,13 +1,39 @@
-use crate::auth::dao::{SessionDao, UserDao};
+use crate::auth::dao::{
+    IdentityDao, KeyDao, MfaDao, PasswordDao, RoleDao, SessionDao, TokenDao, UserDao,
+};
+use crate::auth::keys::{KeySet, SUPPORTED_ALGORITHMS, SigningKey};
 use crate::auth::model::{
-    JwtClaims, LoginHistory, LoginStatus, Session, User, UserRole, UserStatus,
+    ApiToken, JwtClaims, LoginHistory, LoginOutcome, LoginStatus, MFA_CHALLENGE_MAX_ATTEMPTS,
+    MfaChallenge, Permission, Role, Session, User, UserRole, UserStatus, UserTotp,
 };
+use crate::auth::oidc::{OIDC_LOGIN_TTL_SECS, OidcIdentity, OidcLogin, OidcProvider};
+use crate::auth::policy::{LoginThrottle, validate_password};
+use crate::auth::totp;
 use crate::db::Database;
-use dashmap::DashMap;
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use artemis_service::config::{AuthConfig, PasswordPolicyConfig};
+use dashmap::{DashMap, DashSet};
+use jsonwebtoken::{Algorithm, Header, Validation, decode, decode_header, encode};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicI64, Ordering};
 
+/// API Token 最近使用时间持久化的最小间隔 (秒)
+const API_TOKEN_LAST_USED_PERSIST_INTERVAL_SECS: i64 = 60;
+
+/// 遇到未知 kid 时从数据库重新加载签名密钥的最小间隔 (秒)
+const KEY_RELOAD_MIN_INTERVAL_SECS: i64 = 5;
+
+/// 签名后的 OIDC 登录状态的用途标记, 与访问令牌区分
+const OIDC_LOGIN_PURPOSE: &str = "oidc-login";
+
+/// 签名后保存在浏览器 Cookie 中的 OIDC 登录状态
+#[derive(serde::Serialize, serde::Deserialize)]
+struct OidcLoginClaims {
+    purpose: String,
+    exp: i64,
+    #[serde(flatten)]
+    login: OidcLogin,
+}
+
 pub struct AuthManager {
     // 内存存储
     users: Arc<DashMap<String, User>>,          // user_id -> User
@@ -15,14 +41,35 @@ pub struct AuthManager {
     sessions: Arc<DashMap<String, Session>>,    // session_id -> Session
     token_map: Arc<DashMap<String, String>>,    // token -> session_id
     login_history: Arc<DashMap<i64, LoginHistory>>, // history_id -> LoginHistory
+    roles: Arc<DashMap<String, Role>>,          // role_id -> Role (内置 + 自定义)
+    api_tokens: Arc<DashMap<String, ApiToken>>, // token_id -> ApiToken
+    api_token_hashes: Arc<DashMap<String, String>>, // token_hash -> token_id
+    password_history: Arc<DashMap<String, Vec<String>>>, // user_id -> 历史密码摘要 (旧 -> 新)
+    totp_secrets: Arc<DashMap<String, UserTotp>>, // user_id -> UserTotp
+    recovery_codes: Arc<DashMap<String, Vec<String>>>, // user_id -> 未使用的恢复码摘要
+    mfa_challenges: Arc<DashMap<String, MfaChallenge>>, // challenge_token -> MfaChallenge
+    mfa_required_roles: Arc<DashSet<String>>,   // 强制两步验证的角色 ID
+    external_identities: Arc<DashMap<(String, String), String>>, // (provider, subject) -> user_id
 
     // ID 生成
     next_history_id: Arc<AtomicI64>,
 
+    // 集群会话同步
+    revocation_cursor: Arc<AtomicI64>, // 已处理的最新会话失效记录 id
+    last_key_reload: Arc<AtomicI64>,   // 上次从数据库重新加载签名密钥的时间
+
     // JWT 配置
-    jwt_secret: String,
+    signing_keys: Arc<KeySet>,
+    jwt_algorithm: Algorithm,
     jwt_expiry_seconds: i64,
 
+    // 登录安全
+    password_policy: PasswordPolicyConfig,
+    login_throttle: Arc<LoginThrottle>,
+
+    // 单点登录
+    oidc: Option<Arc<OidcProvider>>,
+
     // 持久化
     database: Option<Arc<Database>>,
 }
@@ -30,32 +77,100 @@ pub struct AuthManager {
 impl AuthManager {
     /// 创建新的 AuthManager (无持久化)
     pub fn new() -> Self {
-        Self::with_database(None, "artemis-default-secret-change-in-production".to_string())
+        Self::with_database(None)
     }
 
     /// 创建带数据库持久化的 AuthManager
-    pub fn with_database(database: Option<Arc<Database>>, jwt_secret: String) -> Self {
+    pub fn with_database(database: Option<Arc<Database>>) -> Self {
+        let roles = DashMap::new();
+        for role in UserRole::all() {
+            roles.insert(role.as_str().to_string(), Role::builtin(&role));
+        }
+
         Self {
             users: Arc::new(DashMap::new()),
             username_map: Arc::new(DashMap::new()),
             sessions: Arc::new(DashMap::new()),
             token_map: Arc::new(DashMap::new()),
             login_history: Arc::new(DashMap::new()),
+            roles: Arc::new(roles),
+            api_tokens: Arc::new(DashMap::new()),
+            api_token_hashes: Arc::new(DashMap::new()),
+            password_history: Arc::new(DashMap::new()),
+            totp_secrets: Arc::new(DashMap::new()),
+            recovery_codes: Arc::new(DashMap::new()),
+            mfa_challenges: Arc::new(DashMap::new()),
+            mfa_required_roles: Arc::new(DashSet::new()),
+            external_identities: Arc::new(DashMap::new()),
             next_history_id: Arc::new(AtomicI64::new(1)),
-            jwt_secret,
+            revocation_cursor: Arc::new(AtomicI64::new(0)),
+            last_key_reload: Arc::new(AtomicI64::new(0)),
+            signing_keys: Arc::new(KeySet::new()),
+            jwt_algorithm: Algorithm::ES256,
             jwt_expiry_seconds: std::env::var("JWT_EXPIRY_SECONDS")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(3600), // 默认1小时
+            password_policy: PasswordPolicyConfig::default(),
+            login_throttle: Arc::new(LoginThrottle::new(Default::default())),
+            oidc: None,
             database,
         }
     }
 
+    /// 应用登录安全配置 (密码策略、失败锁定和单点登录)
+    pub fn with_auth_config(mut self, config: &AuthConfig) -> Self {
+        self.password_policy = config.password_policy.clone();
+        self.login_throttle = Arc::new(LoginThrottle::new(config.lockout.clone()));
+        self.oidc = config.oidc.clone().map(|oidc| Arc::new(OidcProvider::new(oidc)));
+        match config.jwt.algorithm.parse::<Algorithm>() {
+            Ok(algorithm) if SUPPORTED_ALGORITHMS.contains(&algorithm) => {
+                self.jwt_algorithm = algorithm
+            }
+            _ => tracing::warn!(
+                "Unsupported JWT algorithm '{}', falling back to {:?}",
+                config.jwt.algorithm,
+                self.jwt_algorithm
+            ),
+        }
+        self
+    }
+
+    /// 已配置的 OIDC 身份提供方
+    pub fn oidc(&self) -> Option<Arc<OidcProvider>> {
+        self.oidc.clone()
+    }
+
+    /// 是否信任 X-Forwarded-For 作为客户端 IP
+    pub fn trust_forwarded_for(&self) -> bool {
+        self.login_throttle.config().trust_forwarded_for
+    }
+
     /// 从数据库加载数据(异步方法)
     pub async fn load_from_database(&self) -> anyhow::Result<()> {
         if let Some(database) = &self.database {
             let user_dao = UserDao::new(database.conn().clone());
             let session_dao = SessionDao::new(database.conn().clone());
+            let role_dao = RoleDao::new(database.conn().clone());
+            let token_dao = TokenDao::new(database.conn().clone());
+            let password_dao = PasswordDao::new(database.conn().clone());
+            let mfa_dao = MfaDao::new(database.conn().clone());
+            let identity_dao = IdentityDao::new(database.conn().clone());
+            let key_dao = KeyDao::new(database.conn().clone());
+
+            // 加载签名密钥 (多个节点同时初始化时可能各自生成了签名密钥, 只保留最新的)
+            for key in key_dao.list_keys().await? {
+                for kid in self.signing_keys.insert(key) {
+                    if let Err(e) = key_dao.retire_key(&kid, chrono::Utc::now().timestamp()).await {
+                        tracing::error!("Failed to retire signing key {}: {:?}", kid, e);
+                    }
+                }
+            }
+
+            // 加载自定义角色
+            for role in role_dao.list_roles().await? {
+                self.roles.insert(role.role_id.clone(), role);
+            }
 
             // 加载用户
             let users = user_dao.list_users().await?;
@@ -64,6 +179,64 @@ impl AuthManager {
                 self.users.insert(user.user_id.clone(), user);
             }
 
+            // 加载用户附加角色
+            for (user_id, role_id) in role_dao.list_user_roles().await? {
+                if let Some(mut user) = self.users.get_mut(&user_id)
+                    && !user.roles.contains(&role_id)
+                {
+                    user.roles.push(role_id);
+                }
+            }
+
+            // 加载服务账号标记
+            for user_id in token_dao.list_service_accounts().await? {
+                if let Some(mut user) = self.users.get_mut(&user_id) {
+                    user.service_account = true;
+                }
+            }
+
+            // 加载强制改密标记和密码历史
+            for user_id in password_dao.list_change_required().await? {
+                if let Some(mut user) = self.users.get_mut(&user_id) {
+                    user.must_change_password = true;
+                }
+            }
+            for (user_id, password_hash) in password_dao.list_history().await? {
+                self.password_history.entry(user_id).or_default().push(password_hash);
+            }
+
+            // 加载两步验证配置
+            for (user_id, secret, enabled) in mfa_dao.list_totp().await? {
+                if enabled && let Some(mut user) = self.users.get_mut(&user_id) {
+                    user.mfa_enabled = true;
+                }
+                self.totp_secrets
+                    .insert(user_id, UserTotp { secret, enabled, last_used_step: None });
+            }
+            for (user_id, code_hash) in mfa_dao.list_unused_recovery_codes().await? {
+                self.recovery_codes.entry(user_id).or_default().push(code_hash);
+            }
+            for role_id in mfa_dao.list_required_roles().await? {
+                self.mfa_required_roles.insert(role_id);
+            }
+
+            // 加载外部身份绑定
+            for (provider, subject, user_id) in identity_dao.list_identities().await? {
+                if let Some(mut user) = self.users.get_mut(&user_id) {
+                    user.identity_provider = Some(provider.clone());
+                }
+                self.external_identities.insert((provider, subject), user_id);
+            }
+
+            // 加载 API Token
+            for token in token_dao.list_tokens().await? {
+                self.api_token_hashes.insert(token.token_hash.clone(), token.token_id.clone());
+                self.api_tokens.insert(token.token_id.clone(), token);
+            }
+
+            // 之前的会话失效记录已反映在数据库中, 只需处理之后的
+            self.revocation_cursor.store(session_dao.max_revocation_id().await?, Ordering::Relaxed);
+
             // 加载会话
             let current_time = chrono::Utc::now().timestamp();
             for user_ref in self.users.iter() {
@@ -79,9 +252,15 @@ impl AuthManager {
                 }
             }
 
+            self.prune_signing_keys();
+            self.ensure_signing_key().map_err(|e| anyhow::anyhow!(e))?;
+
             tracing::info!(
-                "Loaded {} users and {} active sessions from database",
+                "Loaded {} users, {} roles, {} API tokens, {} signing keys and {} active sessions from database",
                 self.users.len(),
+                self.roles.len(),
+                self.api_tokens.len(),
+                self.signing_keys.list().len(),
                 self.sessions.len()
             );
         }
@@ -89,7 +268,19 @@ impl AuthManager {
         Ok(())
     }
 
-    /// 用户认证
+    /// 账号或来源 IP 因连续登录失败被锁定时, 返回剩余锁定秒数
+    pub fn login_locked_for(&self, username: &str, ip: Option<&str>) -> Option<i64> {
+        self.login_throttle.locked_for(username, ip, chrono::Utc::now().timestamp())
+    }
+
+    /// 解除账号的登录锁定
+    pub fn unlock_login(&self, username: &str) {
+        self.login_throttle.unlock(username);
+    }
+
+    /// 用户认证 (仅密码)
+    ///
+    /// 已启用两步验证的用户无法通过此方法登录, 需使用 `login` + `verify_mfa_login`
     pub fn authenticate(
         &self,
         username: &str,
@@ -97,16 +288,73 @@ impl AuthManager {
         ip: Option<String>,
         user_agent: Option<String>,
     ) -> Result<String, String> {
-        // 获取用户
-        let user_id = self
-            .username_map
-            .get(username)
-            .ok_or_else(|| "Invalid username or password".to_string())?;
+        match self.login(username, password, ip, user_agent)? {
+            LoginOutcome::Authenticated(token) => Ok(token),
+            LoginOutcome::MfaRequired(challenge) => {
+                self.discard_mfa_challenge(&challenge.challenge_token);
+                Err("Two-factor authentication required".to_string())
+            }
+        }
+    }
 
-        let user = self
-            .users
-            .get(user_id.value())
-            .ok_or_else(|| "Invalid username or password".to_string())?;
+    /// 密码登录
+    ///
+    /// 失败次数按账号和来源 IP 分别统计, 超过阈值后在锁定期内直接拒绝;
+    /// 用户已启用两步验证时返回挑战, 提交验证码后才签发令牌
+    pub fn login(
+        &self,
+        username: &str,
+        password: &str,
+        ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<LoginOutcome, String> {
+        let now = chrono::Utc::now().timestamp();
+        let user = self.get_user_by_username(username);
+
+        if let Some(remaining) = self.login_throttle.locked_for(username, ip.as_deref(), now) {
+            if let Some(user) = &user {
+                self.record_login_history(
+                    &user.user_id,
+                    ip.clone().unwrap_or_default(),
+                    user_agent.clone().unwrap_or_default(),
+                    LoginStatus::Failed,
+                );
+            }
+            return Err(format!(
+                "Too many failed login attempts, try again in {} seconds",
+                remaining
+            ));
+        }
+
+        let result = self.authenticate_user(user, password, ip.clone(), user_agent);
+        match &result {
+            Ok(LoginOutcome::Authenticated(_)) => self.login_throttle.record_success(username),
+            // 第二因素通过后才清除失败计数
+            Ok(LoginOutcome::MfaRequired(_)) => {}
+            Err(_) => self.login_throttle.record_failure(username, ip.as_deref(), now),
+        }
+        result
+    }
+
+    fn authenticate_user(
+        &self,
+        user: Option<User>,
+        password: &str,
+        ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<LoginOutcome, String> {
+        let user = user.ok_or_else(|| "Invalid username or password".to_string())?;
+
+        // 服务账号只能通过 API Token 访问
+        if user.service_account {
+            self.record_login_history(
+                &user.user_id,
+                ip.clone().unwrap_or_default(),
+                user_agent.clone().unwrap_or_default(),
+                LoginStatus::Failed,
+            );
+            return Err("Service accounts cannot log in with a password".to_string());
+        }
 
         // 检查用户状态
         if user.status != UserStatus::Active {
@@ -130,8 +378,319 @@ impl AuthManager {
             return Err("Invalid username or password".to_string());
         }
 
+        // 两步验证
+        if user.mfa_enabled {
+            self.mfa_challenges.retain(|_, c| !c.is_expired());
+            let challenge = MfaChallenge::new(&user, ip, user_agent);
+            self.mfa_challenges.insert(challenge.challenge_token.clone(), challenge.clone());
+
+            // 持久化, 验证码可提交到集群任一节点
+            if let Some(db) = &self.database {
+                let mfa_dao = MfaDao::new(db.conn().clone());
+                let challenge_clone = challenge.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = mfa_dao.insert_challenge(&challenge_clone).await {
+                        tracing::error!("Failed to persist MFA challenge: {:?}", e);
+                    }
+                });
+            }
+            return Ok(LoginOutcome::MfaRequired(challenge));
+        }
+
+        self.issue_session(&user, ip, user_agent).map(LoginOutcome::Authenticated)
+    }
+
+    /// 获取未过期的两步验证挑战
+    pub fn get_mfa_challenge(&self, challenge_token: &str) -> Option<MfaChallenge> {
+        self.mfa_challenges.get(challenge_token).filter(|c| !c.is_expired()).map(|c| c.clone())
+    }
+
+    /// 从数据库加载其他节点发起的两步验证挑战到本地缓存
+    pub async fn load_mfa_challenge(&self, challenge_token: &str) -> Result<(), String> {
+        if self.mfa_challenges.contains_key(challenge_token) {
+            return Ok(());
+        }
+        let Some(db) = &self.database else {
+            return Ok(());
+        };
+        let Some(challenge) = MfaDao::new(db.conn().clone())
+            .get_challenge(challenge_token)
+            .await
+            .map_err(|e| format!("Failed to load MFA challenge: {}", e))?
+            .filter(|c| !c.is_expired())
+        else {
+            return Ok(());
+        };
+        if !self.users.contains_key(&challenge.user_id) {
+            self.load_user_from_database(&challenge.user_id)
+                .await
+                .map_err(|e| format!("Failed to load user: {}", e))?;
+        }
+        self.mfa_challenges.insert(challenge.challenge_token.clone(), challenge);
+        Ok(())
+    }
+
+    /// 移除两步验证挑战 (含数据库)
+    fn discard_mfa_challenge(&self, challenge_token: &str) {
+        self.mfa_challenges.remove(challenge_token);
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let challenge_token = challenge_token.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = mfa_dao.delete_challenge(&challenge_token).await {
+                    tracing::error!("Failed to delete MFA challenge from database: {:?}", e);
+                }
+            });
+        }
+    }
+
+    /// 提交第二因素 (TOTP 验证码或恢复码) 完成登录
+    ///
+    /// 错误计入账号和来源 IP 的失败次数, 单个挑战错误过多后失效
+    pub fn verify_mfa_login(&self, challenge_token: &str, code: &str) -> Result<String, String> {
+        let now = chrono::Utc::now().timestamp();
+        let challenge = self
+            .get_mfa_challenge(challenge_token)
+            .ok_or_else(|| "Invalid or expired challenge".to_string())?;
+
+        let record_failure = || {
+            self.record_login_history(
+                &challenge.user_id,
+                challenge.ip_address.clone().unwrap_or_default(),
+                challenge.user_agent.clone().unwrap_or_default(),
+                LoginStatus::Failed,
+            );
+        };
+
+        if let Some(remaining) = self.login_throttle.locked_for(
+            &challenge.username,
+            challenge.ip_address.as_deref(),
+            now,
+        ) {
+            self.discard_mfa_challenge(challenge_token);
+            record_failure();
+            return Err(format!(
+                "Too many failed login attempts, try again in {} seconds",
+                remaining
+            ));
+        }
+
+        let user = self
+            .get_user(&challenge.user_id)
+            .filter(|u| u.status == UserStatus::Active)
+            .ok_or_else(|| "Invalid or expired challenge".to_string())?;
+
+        if !self.verify_second_factor(&user.user_id, code, now) {
+            record_failure();
+            self.login_throttle.record_failure(
+                &challenge.username,
+                challenge.ip_address.as_deref(),
+                now,
+            );
+            let exhausted = self
+                .mfa_challenges
+                .remove_if_mut(challenge_token, |_, c| {
+                    c.failed_attempts += 1;
+                    c.failed_attempts >= MFA_CHALLENGE_MAX_ATTEMPTS
+                })
+                .is_some();
+            if exhausted {
+                self.discard_mfa_challenge(challenge_token);
+            } else if let Some(db) = &self.database {
+                let mfa_dao = MfaDao::new(db.conn().clone());
+                let challenge_token = challenge_token.to_string();
+                tokio::spawn(async move {
+                    if let Err(e) = mfa_dao.increment_challenge_attempts(&challenge_token).await {
+                        tracing::error!("Failed to update MFA challenge: {:?}", e);
+                    }
+                });
+            }
+            return Err("Invalid verification code".to_string());
+        }
+
+        self.discard_mfa_challenge(challenge_token);
+        self.login_throttle.record_success(&challenge.username);
+        self.issue_session(&user, challenge.ip_address, challenge.user_agent)
+    }
+
+    /// 用令牌签名密钥签名发起中的 OIDC 登录状态, 写入浏览器 Cookie
+    ///
+    /// 签名密钥在集群节点间共享, 回调落在任一节点都可以校验
+    pub fn seal_oidc_login(&self, login: &OidcLogin) -> Result<String, String> {
+        let claims = OidcLoginClaims {
+            purpose: OIDC_LOGIN_PURPOSE.to_string(),
+            exp: chrono::Utc::now().timestamp() + OIDC_LOGIN_TTL_SECS,
+            login: login.clone(),
+        };
+        let key = self.ensure_signing_key()?;
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+        encode(&header, &claims, key.encoding_key())
+            .map_err(|e| format!("Failed to seal login state: {}", e))
+    }
+
+    /// 校验 Cookie 中的 OIDC 登录状态, 其他节点签发时从数据库加载签名密钥
+    pub async fn open_oidc_login(&self, sealed: &str) -> Result<OidcLogin, String> {
+        let invalid = || "Invalid or expired login state".to_string();
+        let kid = decode_header(sealed).ok().and_then(|header| header.kid).ok_or_else(invalid)?;
+        if self.signing_keys.find(&kid).is_none() {
+            self.reload_signing_keys(false).await;
+        }
+        let key = self.signing_keys.find(&kid).ok_or_else(invalid)?;
+
+        let claims =
+            decode::<OidcLoginClaims>(sealed, key.decoding_key(), &Validation::new(key.algorithm))
+                .map_err(|_| invalid())?
+                .claims;
+        if claims.purpose != OIDC_LOGIN_PURPOSE {
+            return Err(invalid());
+        }
+        Ok(claims.login)
+    }
+
+    /// 单点登录: 按外部身份登录, 首次登录时自动开通账号
+    ///
+    /// 每次登录都按身份提供方声明同步角色: 映射到的最高内置角色作为主角色
+    /// (未映射内置角色时为 viewer), 其余角色作为附加角色。本节点未缓存的外部身份
+    /// 先从共享数据库查找, 避免为其他节点开通过的账号重复开通
+    pub async fn login_oidc(
+        &self,
+        identity: &OidcIdentity,
+        ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<String, String> {
+        if identity.roles.is_empty() {
+            return Err("No Artemis role is mapped for this account".to_string());
+        }
+        for role_id in &identity.roles {
+            if !self.roles.contains_key(role_id) {
+                return Err(format!("Role not found: {}", role_id));
+            }
+        }
+
+        let primary = UserRole::all()
+            .into_iter()
+            .find(|role| identity.roles.iter().any(|id| id == role.as_str()))
+            .unwrap_or(UserRole::Viewer);
+
+        let key = (identity.issuer.clone(), identity.subject.clone());
+        if !self.external_identities.contains_key(&key)
+            && let Some(db) = &self.database
+            && let Some(user_id) = IdentityDao::new(db.conn().clone())
+                .get_user_id(&identity.issuer, &identity.subject)
+                .await
+                .map_err(|e| format!("Failed to load external identity: {}", e))?
+        {
+            self.load_user_from_database(&user_id)
+                .await
+                .map_err(|e| format!("Failed to load user: {}", e))?;
+        }
+        let existing = self.external_identities.get(&key).and_then(|id| self.get_user(&id));
+        let user = match existing {
+            Some(user) => {
+                if user.role != primary
+                    || (identity.email.is_some() && user.email != identity.email)
+                {
+                    self.update_user(&user.user_id, identity.email.clone(), None, Some(primary))?;
+                }
+                self.set_user_roles(&user.user_id, identity.roles.clone())?
+            }
+            None => self.provision_external_user(identity, primary).await?,
+        };
+
+        if user.status != UserStatus::Active {
+            self.record_login_history(
+                &user.user_id,
+                ip.unwrap_or_default(),
+                user_agent.unwrap_or_default(),
+                LoginStatus::Failed,
+            );
+            return Err("User account is inactive".to_string());
+        }
+
+        self.issue_session(&user, ip, user_agent)
+    }
+
+    /// 开通单点登录用户, 配置了数据库时写入成功后才加入本地缓存, 写入失败时登录失败
+    async fn provision_external_user(
+        &self,
+        identity: &OidcIdentity,
+        role: UserRole,
+    ) -> Result<User, String> {
+        // 不接管同名的本地账号 (包括其他节点创建、本节点尚未缓存的账号)
+        let mut exists = self.username_map.contains_key(&identity.username);
+        if !exists && let Some(db) = &self.database {
+            exists = UserDao::new(db.conn().clone())
+                .get_user_by_username(&identity.username)
+                .await
+                .map_err(|e| format!("Failed to load user: {}", e))?
+                .is_some();
+        }
+        if exists {
+            return Err(format!("Username '{}' already exists", identity.username));
+        }
+
+        // "!" 不是合法的 bcrypt 摘要, 单点登录用户无法使用密码登录
+        let mut user = User::new(
+            identity.username.clone(),
+            identity.email.clone(),
+            Some("Provisioned by single sign-on".to_string()),
+            "!".to_string(),
+            role,
+        );
+        user.identity_provider = Some(identity.issuer.clone());
+        for role_id in &identity.roles {
+            if role_id != user.role.as_str() && !user.roles.contains(role_id) {
+                user.roles.push(role_id.clone());
+            }
+        }
+
+        // 持久化 (并发开通同一账号时, 用户名或外部身份的唯一约束使后写入的节点失败)
+        if let Some(db) = &self.database {
+            let user_dao = UserDao::new(db.conn().clone());
+            let identity_dao = IdentityDao::new(db.conn().clone());
+            let role_dao = RoleDao::new(db.conn().clone());
+            let persisted = async {
+                user_dao.insert_user(&user).await?;
+                identity_dao
+                    .insert_identity(&identity.issuer, &identity.subject, &user.user_id)
+                    .await?;
+                role_dao.set_user_roles(&user.user_id, &user.roles).await
+            }
+            .await;
+            if let Err(e) = persisted {
+                tracing::error!("Failed to provision user '{}': {:?}", user.username, e);
+                // 清理已写入的部分
+                let _ = role_dao.delete_user_roles(&user.user_id).await;
+                let _ = identity_dao.delete_user_identity(&user.user_id).await;
+                let _ = user_dao.delete_user(&user.user_id).await;
+                return Err("Failed to provision user account".to_string());
+            }
+            publish_user_change(&SessionDao::new(db.conn().clone()), &user.user_id).await;
+        }
+
+        self.username_map.insert(user.username.clone(), user.user_id.clone());
+        self.users.insert(user.user_id.clone(), user.clone());
+        self.external_identities
+            .insert((identity.issuer.clone(), identity.subject.clone()), user.user_id.clone());
+
+        tracing::info!(
+            "Provisioned user '{}' from identity provider {}",
+            user.username,
+            identity.issuer
+        );
+        Ok(user)
+    }
+
+    /// 创建会话并签发 JWT
+    fn issue_session(
+        &self,
+        user: &User,
+        ip: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<String, String> {
         // 生成 JWT token
-        let token = self.generate_jwt_token(&user)?;
+        let token = self.generate_jwt_token(user)?;
 
         // 创建会话
         let session = Session::new(
@@ -198,18 +757,12 @@ impl AuthManager {
         let session_id_value = session_id.value().clone();
         drop(session_id);
 
-        self.sessions.remove(&session_id_value);
+        let session = self.sessions.remove(&session_id_value);
         self.token_map.remove(token);
 
         // 持久化删除
-        if let Some(db) = &self.database {
-            let session_dao = SessionDao::new(db.conn().clone());
-            let session_id_clone = session_id_value.clone();
-            tokio::spawn(async move {
-                if let Err(e) = session_dao.delete_session(&session_id_clone).await {
-                    tracing::error!("Failed to delete session from database: {:?}", e);
-                }
-            });
+        if let Some((_, session)) = session {
+            self.persist_revocation(Some(session.session_id), session.user_id);
         }
 
         Ok(())
@@ -234,11 +787,32 @@ impl AuthManager {
 
         session_mut.token = Some(new_token.clone());
         session_mut.expires_at = chrono::Utc::now().timestamp() + self.jwt_expiry_seconds;
+        let expires_at = session_mut.expires_at;
+        drop(session_mut);
 
         // 更新映射
         self.token_map.remove(old_token);
         self.token_map.insert(new_token.clone(), session.session_id.clone());
 
+        // 持久化新令牌, 并让其他节点丢弃缓存的旧令牌
+        if let Some(db) = &self.database {
+            let session_dao = SessionDao::new(db.conn().clone());
+            let token = new_token.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    session_dao.update_token(&session.session_id, &token, expires_at).await
+                {
+                    tracing::error!("Failed to update session token in database: {:?}", e);
+                    return;
+                }
+                if let Err(e) =
+                    session_dao.insert_revocation(Some(&session.session_id), &session.user_id).await
+                {
+                    tracing::error!("Failed to record session revocation: {:?}", e);
+                }
+            });
+        }
+
         Ok(new_token)
     }
 
@@ -256,6 +830,39 @@ impl AuthManager {
             return Err(format!("Username '{}' already exists", username));
         }
 
+        validate_password(&self.password_policy, username, password)?;
+
+        self.insert_new_user(username, email, description, password, role)
+    }
+
+    /// 创建首次登录后必须修改密码的用户 (如默认管理员)
+    ///
+    /// 初始密码不受密码策略约束, 首次登录后只能访问个人接口直到修改密码
+    pub fn create_user_requiring_password_change(
+        &self,
+        username: &str,
+        email: Option<String>,
+        description: Option<String>,
+        password: &str,
+        role: UserRole,
+    ) -> Result<User, String> {
+        if self.username_map.contains_key(username) {
+            return Err(format!("Username '{}' already exists", username));
+        }
+
+        let user = self.insert_new_user(username, email, description, password, role)?;
+        self.require_password_change(&user.user_id)?;
+        Ok(User { must_change_password: true, ..user })
+    }
+
+    fn insert_new_user(
+        &self,
+        username: &str,
+        email: Option<String>,
+        description: Option<String>,
+        password: &str,
+        role: UserRole,
+    ) -> Result<User, String> {
         // 哈希密码
         let password_hash = self.hash_password(password)?;
 
@@ -269,11 +876,14 @@ impl AuthManager {
         // 持久化
         if let Some(db) = &self.database {
             let user_dao = UserDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
             let user_clone = user.clone();
             tokio::spawn(async move {
                 if let Err(e) = user_dao.insert_user(&user_clone).await {
                     tracing::error!("Failed to persist user: {:?}", e);
+                    return;
                 }
+                publish_user_change(&session_dao, &user_clone.user_id).await;
             });
         }
 
@@ -307,11 +917,13 @@ impl AuthManager {
         // 持久化
         if let Some(db) = &self.database {
             let user_dao = UserDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
             let user_clone = updated_user.clone();
             tokio::spawn(async move {
                 if let Err(e) = user_dao.update_user(&user_clone).await {
                     tracing::error!("Failed to update user in database: {:?}", e);
                 }
+                publish_user_change(&session_dao, &user_clone.user_id).await;
             });
         }
 
@@ -344,10 +956,21 @@ impl AuthManager {
             }
         }
 
+        // 删除用户的所有 API Token
+        self.api_tokens.retain(|_, token| token.user_id != user_id);
+        self.api_token_hashes.retain(|_, token_id| self.api_tokens.contains_key(token_id));
+        self.password_history.remove(user_id);
+        self.external_identities.retain(|_, id| id != user_id);
+        self.remove_mfa(user_id);
+
         // 持久化删除
         if let Some(db) = &self.database {
             let user_dao = UserDao::new(db.conn().clone());
             let session_dao = SessionDao::new(db.conn().clone());
+            let role_dao = RoleDao::new(db.conn().clone());
+            let token_dao = TokenDao::new(db.conn().clone());
+            let password_dao = PasswordDao::new(db.conn().clone());
+            let identity_dao = IdentityDao::new(db.conn().clone());
             let user_id_clone = user_id.to_string();
             tokio::spawn(async move {
                 if let Err(e) = user_dao.delete_user(&user_id_clone).await {
@@ -356,6 +979,28 @@ impl AuthManager {
                 if let Err(e) = session_dao.delete_user_sessions(&user_id_clone).await {
                     tracing::error!("Failed to delete user sessions from database: {:?}", e);
                 }
+                if let Err(e) = role_dao.delete_user_roles(&user_id_clone).await {
+                    tracing::error!("Failed to delete user roles from database: {:?}", e);
+                }
+                if let Err(e) = token_dao.delete_user_tokens(&user_id_clone).await {
+                    tracing::error!("Failed to delete user API tokens from database: {:?}", e);
+                }
+                if let Err(e) = token_dao.delete_service_account(&user_id_clone).await {
+                    tracing::error!("Failed to delete service account from database: {:?}", e);
+                }
+                if let Err(e) = password_dao.delete_history(&user_id_clone).await {
+                    tracing::error!("Failed to delete password history from database: {:?}", e);
+                }
+                if let Err(e) = password_dao.clear_change_required(&user_id_clone).await {
+                    tracing::error!("Failed to clear password change requirement: {:?}", e);
+                }
+                if let Err(e) = identity_dao.delete_user_identity(&user_id_clone).await {
+                    tracing::error!("Failed to delete external identity from database: {:?}", e);
+                }
+                // 其他节点据此清除缓存的用户、会话和 API Token
+                if let Err(e) = session_dao.insert_revocation(None, &user_id_clone).await {
+                    tracing::error!("Failed to record user revocation: {:?}", e);
+                }
             });
         }
 
@@ -385,57 +1030,135 @@ impl AuthManager {
         old_password: &str,
         new_password: &str,
     ) -> Result<(), String> {
-        let mut user = self.users.get_mut(user_id).ok_or_else(|| "User not found".to_string())?;
+        let user = self.get_user(user_id).ok_or_else(|| "User not found".to_string())?;
 
         // 验证旧密码
         if !self.verify_password(old_password, &user.password_hash) {
             return Err("Old password is incorrect".to_string());
         }
 
-        // 设置新密码
-        user.password_hash = self.hash_password(new_password)?;
-        user.updated_at = chrono::Utc::now().timestamp();
+        self.set_password(user, new_password, false)
+    }
 
-        let updated_user = user.clone();
-        drop(user);
+    /// 重置密码 (管理员操作)
+    ///
+    /// 同时解除账号的登录锁定; 管理员知道新密码, 用户登录后必须先修改
+    pub fn reset_password(&self, user_id: &str, new_password: &str) -> Result<(), String> {
+        let user = self.get_user(user_id).ok_or_else(|| "User not found".to_string())?;
+        let username = user.username.clone();
+
+        self.set_password(user, new_password, true)?;
+        self.login_throttle.unlock(&username);
+        Ok(())
+    }
 
-        // 撤销所有会话
-        self.revoke_all_user_sessions(user_id)?;
+    /// 要求用户下次登录后先修改密码
+    pub fn require_password_change(&self, user_id: &str) -> Result<(), String> {
+        let mut user = self.users.get_mut(user_id).ok_or_else(|| "User not found".to_string())?;
+        user.must_change_password = true;
+        drop(user);
 
         // 持久化
         if let Some(db) = &self.database {
-            let user_dao = UserDao::new(db.conn().clone());
+            let password_dao = PasswordDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_id_clone = user_id.to_string();
             tokio::spawn(async move {
-                if let Err(e) = user_dao.update_user(&updated_user).await {
-                    tracing::error!("Failed to update user password in database: {:?}", e);
+                if let Err(e) = password_dao.require_change(&user_id_clone).await {
+                    tracing::error!("Failed to persist password change requirement: {:?}", e);
                 }
+                publish_user_change(&session_dao, &user_id_clone).await;
             });
         }
 
         Ok(())
     }
 
-    /// 重置密码 (管理员操作)
-    pub fn reset_password(&self, user_id: &str, new_password: &str) -> Result<(), String> {
-        let mut user = self.users.get_mut(user_id).ok_or_else(|| "User not found".to_string())?;
+    /// 用户是否必须先修改密码
+    pub fn password_change_required(&self, user_id: &str) -> bool {
+        self.users.get(user_id).is_some_and(|u| u.must_change_password)
+    }
 
-        user.password_hash = self.hash_password(new_password)?;
-        user.updated_at = chrono::Utc::now().timestamp();
+    /// 按密码策略校验并设置新密码, 撤销所有会话
+    ///
+    /// must_change_password 为 true 时要求用户登录后先修改密码
+    fn set_password(
+        &self,
+        user: User,
+        new_password: &str,
+        must_change_password: bool,
+    ) -> Result<(), String> {
+        validate_password(&self.password_policy, &user.username, new_password)?;
+
+        // 禁止重复使用当前密码和最近的历史密码
+        let history_size = self.password_policy.history_size;
+        if history_size > 0 {
+            let history = self.password_history.get(&user.user_id).map(|h| h.clone());
+            let recent = history.iter().flatten().rev().take(history_size - 1);
+            if std::iter::once(&user.password_hash)
+                .chain(recent)
+                .any(|hash| self.verify_password(new_password, hash))
+            {
+                return Err(format!(
+                    "Password must not match any of the last {} passwords",
+                    history_size
+                ));
+            }
+        }
 
-        let updated_user = user.clone();
-        drop(user);
+        let old_hash = user.password_hash.clone();
+        let password_hash = self.hash_password(new_password)?;
+        let mut user_mut =
+            self.users.get_mut(&user.user_id).ok_or_else(|| "User not found".to_string())?;
+        user_mut.password_hash = password_hash;
+        user_mut.must_change_password = must_change_password;
+        user_mut.updated_at = chrono::Utc::now().timestamp();
+
+        let updated_user = user_mut.clone();
+        drop(user_mut);
+
+        if history_size > 0 {
+            let mut history = self.password_history.entry(user.user_id.clone()).or_default();
+            history.push(old_hash.clone());
+            let excess = history.len().saturating_sub(history_size);
+            history.drain(..excess);
+        }
 
-        // 撤销所有会话
-        self.revoke_all_user_sessions(user_id)?;
+        // 撤销所有会话 (数据库中的失效记录在新密码写入之后记录)
+        self.evict_user_sessions(&user.user_id);
 
         // 持久化
         if let Some(db) = &self.database {
             let user_dao = UserDao::new(db.conn().clone());
+            let password_dao = PasswordDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
             tokio::spawn(async move {
                 if let Err(e) = user_dao.update_user(&updated_user).await {
-                    tracing::error!("Failed to reset user password in database: {:?}", e);
+                    tracing::error!("Failed to update user password in database: {:?}", e);
                 }
-            });
+                if history_size > 0 {
+                    let now = chrono::Utc::now().timestamp();
+                    if let Err(e) =
+                        password_dao.insert_history(&updated_user.user_id, &old_hash, now).await
+                    {
+                        tracing::error!("Failed to persist password history: {:?}", e);
+                    }
+                    if let Err(e) =
+                        password_dao.trim_history(&updated_user.user_id, history_size).await
+                    {
+                        tracing::error!("Failed to trim password history: {:?}", e);
+                    }
+                }
+                let result = if must_change_password {
+                    password_dao.require_change(&updated_user.user_id).await
+                } else {
+                    password_dao.clear_change_required(&updated_user.user_id).await
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to persist password change requirement: {:?}", e);
+                }
+                revoke_in_database(&session_dao, None, &updated_user.user_id).await;
+            });
         }
 
         Ok(())
@@ -451,19 +1174,29 @@ impl AuthManager {
         let updated_user = user.clone();
         drop(user);
 
-        // 如果设置为 Inactive,撤销所有会话
-        if status == UserStatus::Inactive {
-            self.revoke_all_user_sessions(user_id)?;
+        // 如果设置为 Inactive,撤销所有会话 (数据库中的失效记录在状态写入之后记录);
+        // 重新启用时解除登录锁定
+        let inactive = status == UserStatus::Inactive;
+        if inactive {
+            self.evict_user_sessions(user_id);
+        } else {
+            self.login_throttle.unlock(&updated_user.username);
         }
 
         // 持久化
         if let Some(db) = &self.database {
             let user_dao = UserDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
             let user_clone = updated_user.clone();
             tokio::spawn(async move {
                 if let Err(e) = user_dao.update_user(&user_clone).await {
                     tracing::error!("Failed to update user status in database: {:?}", e);
                 }
+                if inactive {
+                    revoke_in_database(&session_dao, None, &user_clone.user_id).await;
+                } else {
+                    publish_user_change(&session_dao, &user_clone.user_id).await;
+                }
             });
         }
 
@@ -493,15 +1226,7 @@ impl AuthManager {
         }
 
         // 持久化删除
-        if let Some(db) = &self.database {
-            let session_dao = SessionDao::new(db.conn().clone());
-            let session_id_clone = session_id.to_string();
-            tokio::spawn(async move {
-                if let Err(e) = session_dao.delete_session(&session_id_clone).await {
-                    tracing::error!("Failed to delete session from database: {:?}", e);
-                }
-            });
-        }
+        self.persist_revocation(Some(session.1.session_id), session.1.user_id);
 
         Ok(())
     }
@@ -525,58 +1250,1017 @@ impl AuthManager {
             }
         }
 
+        // 持久化删除 (其他节点上的会话不在本地缓存中, 也一并撤销)
+        self.persist_revocation(None, user_id.to_string());
+
+        Ok(count)
+    }
+
+    /// 删除数据库中的会话并写入失效记录, 其他节点据此清除本地缓存
+    fn persist_revocation(&self, session_id: Option<String>, user_id: String) {
+        if let Some(db) = &self.database {
+            let session_dao = SessionDao::new(db.conn().clone());
+            tokio::spawn(async move {
+                revoke_in_database(&session_dao, session_id.as_deref(), &user_id).await;
+            });
+        }
+    }
+
+    // ===== 集群会话同步 =====
+
+    /// 验证 token, 本节点未缓存的会话从共享数据库加载
+    ///
+    /// 集群中任一节点签发的令牌都可以在其他节点使用; 签名校验通过后才查询数据库,
+    /// 伪造的令牌不会产生数据库访问
+    pub async fn validate_token_shared(&self, token: &str) -> Result<Session, String> {
+        let result = self.validate_token(token);
+        let Some(db) = &self.database else {
+            return result;
+        };
+        if result.is_ok() || self.token_map.contains_key(token) {
+            return result;
+        }
+
+        let kid = decode_header(token)
+            .ok()
+            .and_then(|header| header.kid)
+            .ok_or_else(|| "Invalid or expired token".to_string())?;
+        if self.signing_keys.find(&kid).is_none() {
+            self.reload_signing_keys(false).await;
+        }
+        self.verify_jwt_token(token)?;
+
+        let session = SessionDao::new(db.conn().clone())
+            .get_session_by_token(token)
+            .await
+            .map_err(|e| format!("Failed to load session: {}", e))?
+            .ok_or_else(|| "Invalid or expired token".to_string())?;
+        self.cache_session(session).await?;
+
+        self.validate_token(token)
+    }
+
+    /// 从数据库加载用户的全部会话到本地缓存 (其他节点创建的会话)
+    pub async fn load_user_sessions(&self, user_id: &str) -> Result<(), String> {
+        if let Some(db) = &self.database {
+            let sessions = SessionDao::new(db.conn().clone())
+                .list_sessions_by_user(user_id)
+                .await
+                .map_err(|e| format!("Failed to load sessions: {}", e))?;
+            for session in sessions {
+                self.cache_session(session).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 从数据库加载指定会话到本地缓存
+    pub async fn load_session(&self, session_id: &str) -> Result<(), String> {
+        if self.sessions.contains_key(session_id) {
+            return Ok(());
+        }
+        if let Some(db) = &self.database
+            && let Some(session) = SessionDao::new(db.conn().clone())
+                .get_session(session_id)
+                .await
+                .map_err(|e| format!("Failed to load session: {}", e))?
+        {
+            self.cache_session(session).await?;
+        }
+        Ok(())
+    }
+
+    /// 同步其他节点的变更: 清除已失效的会话缓存, 刷新变更的用户和角色, 加载新的签名密钥
+    pub async fn sync_sessions(&self) -> anyhow::Result<usize> {
+        let Some(db) = &self.database else {
+            return Ok(0);
+        };
+        let session_dao = SessionDao::new(db.conn().clone());
+
+        let mut evicted = 0;
+        let cursor = self.revocation_cursor.load(Ordering::Relaxed);
+        for (id, kind, session_id, user_id) in session_dao.list_revocations_after(cursor).await? {
+            // session_id 也可以是被撤销的 API Token ID; 用户的全部会话失效时 (改密、停用、删除)
+            // 同时刷新缓存的用户
+            evicted += match (kind.as_str(), session_id) {
+                ("user", _) => {
+                    self.refresh_user(&user_id).await?;
+                    0
+                }
+                ("role", _) => {
+                    self.refresh_roles().await?;
+                    0
+                }
+                (_, Some(id)) => (self.evict_session(&id) || self.evict_api_token(&id)) as usize,
+                (_, None) => {
+                    let evicted = self.evict_user_sessions(&user_id);
+                    self.refresh_user(&user_id).await?;
+                    evicted
+                }
+            };
+            self.revocation_cursor.fetch_max(id, Ordering::Relaxed);
+        }
+
+        self.reload_signing_keys(true).await;
+
+        // 失效记录只需保留到对应令牌过期
+        let now = chrono::Utc::now().timestamp();
+        session_dao.delete_revocations_before(now - self.jwt_expiry_seconds).await?;
+        self.sessions.retain(|_, s| !s.is_expired());
+        self.token_map.retain(|_, session_id| self.sessions.contains_key(session_id));
+        MfaDao::new(db.conn().clone()).delete_expired_challenges(now).await?;
+        self.mfa_challenges.retain(|_, c| !c.is_expired());
+
+        Ok(evicted)
+    }
+
+    /// 按固定间隔同步会话 (未配置数据库时不启动)
+    pub fn start_session_sync_task(self: &Arc<Self>, interval: std::time::Duration) {
+        if self.database.is_none() {
+            return;
+        }
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match manager.sync_sessions().await {
+                    Ok(0) => {}
+                    Ok(evicted) => tracing::debug!("Evicted {} revoked sessions", evicted),
+                    Err(e) => tracing::error!("Failed to sync sessions: {:?}", e),
+                }
+            }
+        });
+    }
+
+    async fn cache_session(&self, session: Session) -> Result<(), String> {
+        if session.is_expired() {
+            return Ok(());
+        }
+        if !self.users.contains_key(&session.user_id) {
+            self.load_user_from_database(&session.user_id)
+                .await
+                .map_err(|e| format!("Failed to load user: {}", e))?;
+        }
+
+        if let Some(token) = &session.token {
+            self.token_map.insert(token.clone(), session.session_id.clone());
+        }
+        self.sessions.insert(session.session_id.clone(), session);
+        Ok(())
+    }
+
+    /// 从本地缓存移除会话 (不修改数据库)
+    fn evict_session(&self, session_id: &str) -> bool {
+        match self.sessions.remove(session_id) {
+            Some((_, session)) => {
+                if let Some(token) = &session.token {
+                    self.token_map.remove(token);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 从本地缓存移除 API Token (不修改数据库)
+    fn evict_api_token(&self, token_id: &str) -> bool {
+        match self.api_tokens.remove(token_id) {
+            Some((_, token)) => {
+                self.api_token_hashes.remove(&token.token_hash);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn evict_user_sessions(&self, user_id: &str) -> usize {
+        let session_ids: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .map(|s| s.session_id.clone())
+            .collect();
+        session_ids.iter().filter(|session_id| self.evict_session(session_id)).count()
+    }
+
+    /// 按数据库刷新本地缓存的用户, 用户已被删除时清除其会话、API Token 等缓存
+    async fn refresh_user(&self, user_id: &str) -> anyhow::Result<()> {
+        if self.database.is_none() || self.load_user_from_database(user_id).await? {
+            return Ok(());
+        }
+
+        if let Some((_, user)) = self.users.remove(user_id) {
+            self.username_map.remove(&user.username);
+        }
+        self.evict_user_sessions(user_id);
+        self.api_tokens.retain(|_, token| token.user_id != user_id);
+        self.api_token_hashes.retain(|_, token_id| self.api_tokens.contains_key(token_id));
+        self.password_history.remove(user_id);
+        self.external_identities.retain(|_, id| id != user_id);
+        self.totp_secrets.remove(user_id);
+        self.recovery_codes.remove(user_id);
+        self.mfa_challenges.retain(|_, c| c.user_id != user_id);
+        Ok(())
+    }
+
+    /// 按数据库刷新自定义角色和强制两步验证的角色, 已删除的角色同时从用户中移除
+    async fn refresh_roles(&self) -> anyhow::Result<()> {
+        let Some(db) = &self.database else {
+            return Ok(());
+        };
+        let roles = RoleDao::new(db.conn().clone()).list_roles().await?;
+        let required = MfaDao::new(db.conn().clone()).list_required_roles().await?;
+
+        self.roles
+            .retain(|role_id, role| role.builtin || roles.iter().any(|r| r.role_id == *role_id));
+        for role in roles {
+            self.roles.insert(role.role_id.clone(), role);
+        }
+        for mut user in self.users.iter_mut() {
+            user.roles.retain(|role_id| self.roles.contains_key(role_id));
+        }
+        self.mfa_required_roles.retain(|role_id| required.contains(role_id));
+        for role_id in required {
+            self.mfa_required_roles.insert(role_id);
+        }
+        Ok(())
+    }
+
+    /// 加载其他节点生成的签名密钥
+    ///
+    /// 非强制加载时限制频率, 避免携带未知 kid 的请求反复查询数据库
+    async fn reload_signing_keys(&self, force: bool) {
+        let Some(db) = &self.database else {
+            return;
+        };
+        let now = chrono::Utc::now().timestamp();
+        let last = self.last_key_reload.load(Ordering::Relaxed);
+        if !force && now - last < KEY_RELOAD_MIN_INTERVAL_SECS {
+            return;
+        }
+        self.last_key_reload.store(now, Ordering::Relaxed);
+
+        match KeyDao::new(db.conn().clone()).list_keys().await {
+            Ok(keys) => {
+                for key in keys {
+                    if self.signing_keys.find(&key.kid).is_none() {
+                        tracing::info!("Loaded JWT signing key {} from database", key.kid);
+                        self.signing_keys.insert(key);
+                    }
+                }
+            }
+            Err(e) => tracing::error!("Failed to reload signing keys: {:?}", e),
+        }
+    }
+
+    /// 从数据库加载单个用户 (其他节点创建或修改的用户), 覆盖本地缓存; 用户不存在时返回 false
+    async fn load_user_from_database(&self, user_id: &str) -> anyhow::Result<bool> {
+        let Some(db) = &self.database else {
+            return Ok(false);
+        };
+        let Some(mut user) = UserDao::new(db.conn().clone()).get_user(user_id).await? else {
+            return Ok(false);
+        };
+
+        let role_dao = RoleDao::new(db.conn().clone());
+        let token_dao = TokenDao::new(db.conn().clone());
+        let password_dao = PasswordDao::new(db.conn().clone());
+        let mfa_dao = MfaDao::new(db.conn().clone());
+        let identity_dao = IdentityDao::new(db.conn().clone());
+
+        for role_id in role_dao.list_roles_of_user(user_id).await? {
+            if !user.roles.contains(&role_id) {
+                user.roles.push(role_id);
+            }
+        }
+        user.service_account = token_dao.is_service_account(user_id).await?;
+        user.must_change_password = password_dao.is_change_required(user_id).await?;
+        let totp = mfa_dao.get_totp(user_id).await?;
+        let codes = mfa_dao.list_user_recovery_codes(user_id).await?;
+        let identities = identity_dao.list_user_identities(user_id).await?;
+        let history = password_dao.list_user_history(user_id).await?;
+
+        match totp {
+            Some((secret, enabled)) => {
+                user.mfa_enabled = enabled;
+                // 密钥未变时保留已使用的时间步, 防止验证码重放
+                let last_used_step = self
+                    .totp_secrets
+                    .get(user_id)
+                    .filter(|t| t.secret == secret)
+                    .and_then(|t| t.last_used_step);
+                self.totp_secrets
+                    .insert(user_id.to_string(), UserTotp { secret, enabled, last_used_step });
+            }
+            None => {
+                self.totp_secrets.remove(user_id);
+            }
+        }
+        if codes.is_empty() {
+            self.recovery_codes.remove(user_id);
+        } else {
+            self.recovery_codes.insert(user_id.to_string(), codes);
+        }
+        self.external_identities.retain(|_, id| id != user_id);
+        for (provider, subject) in identities {
+            user.identity_provider = Some(provider.clone());
+            self.external_identities.insert((provider, subject), user_id.to_string());
+        }
+        if history.is_empty() {
+            self.password_history.remove(user_id);
+        } else {
+            self.password_history.insert(user_id.to_string(), history);
+        }
+
+        self.username_map.insert(user.username.clone(), user.user_id.clone());
+        self.users.insert(user.user_id.clone(), user);
+        Ok(true)
+    }
+
+    // ===== 服务账号 =====
+
+    /// 创建服务账号
+    ///
+    /// 服务账号没有可用密码, 只能通过管理员为其签发的 API Token 访问
+    pub fn create_service_account(
+        &self,
+        username: &str,
+        description: Option<String>,
+        role: UserRole,
+    ) -> Result<User, String> {
+        if self.username_map.contains_key(username) {
+            return Err(format!("Username '{}' already exists", username));
+        }
+
+        // "!" 不是合法的 bcrypt 摘要, 任何密码都无法通过校验
+        let mut user = User::new(username.to_string(), None, description, "!".to_string(), role);
+        user.service_account = true;
+
+        self.username_map.insert(user.username.clone(), user.user_id.clone());
+        self.users.insert(user.user_id.clone(), user.clone());
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let user_dao = UserDao::new(db.conn().clone());
+            let token_dao = TokenDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_clone = user.clone();
+            tokio::spawn(async move {
+                if let Err(e) = user_dao.insert_user(&user_clone).await {
+                    tracing::error!("Failed to persist service account: {:?}", e);
+                    return;
+                }
+                if let Err(e) = token_dao.insert_service_account(&user_clone.user_id).await {
+                    tracing::error!("Failed to persist service account marker: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_clone.user_id).await;
+            });
+        }
+
+        Ok(user)
+    }
+
+    /// 列出所有服务账号
+    pub fn list_service_accounts(&self) -> Vec<User> {
+        self.users.iter().filter(|u| u.service_account).map(|u| u.value().clone()).collect()
+    }
+
+    // ===== API Token =====
+
+    /// 为用户签发 API Token, 返回 (令牌, 明文)
+    ///
+    /// 明文只在此处返回一次
+    pub fn create_api_token(
+        &self,
+        user_id: &str,
+        name: &str,
+        scopes: Vec<Permission>,
+        expires_in_days: Option<i64>,
+    ) -> Result<(ApiToken, String), String> {
+        if !self.users.contains_key(user_id) {
+            return Err("User not found".to_string());
+        }
+        if name.trim().is_empty() {
+            return Err("Token name must not be empty".to_string());
+        }
+        for scope in &scopes {
+            scope.validate()?;
+        }
+
+        let expires_at = match expires_in_days {
+            Some(days) if days <= 0 => {
+                return Err("expiresInDays must be greater than 0".to_string());
+            }
+            Some(days) => Some(chrono::Utc::now().timestamp() + days * 86400),
+            None => None,
+        };
+
+        let (token, plaintext) =
+            ApiToken::generate(user_id.to_string(), name.trim().to_string(), scopes, expires_at);
+
+        self.api_token_hashes.insert(token.token_hash.clone(), token.token_id.clone());
+        self.api_tokens.insert(token.token_id.clone(), token.clone());
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let token_dao = TokenDao::new(db.conn().clone());
+            let token_clone = token.clone();
+            tokio::spawn(async move {
+                if let Err(e) = token_dao.insert_token(&token_clone).await {
+                    tracing::error!("Failed to persist API token: {:?}", e);
+                }
+            });
+        }
+
+        Ok((token, plaintext))
+    }
+
+    /// 验证 API Token, 本节点未缓存的令牌从共享数据库加载
+    ///
+    /// 集群中任一节点签发的 API Token 都可以在其他节点使用
+    pub async fn validate_api_token_shared(&self, plaintext: &str) -> Result<ApiToken, String> {
+        let token_hash = ApiToken::hash(plaintext);
+        if let Some(db) = &self.database
+            && !self.api_token_hashes.contains_key(&token_hash)
+        {
+            let token = TokenDao::new(db.conn().clone())
+                .get_token_by_hash(&token_hash)
+                .await
+                .map_err(|e| format!("Failed to load API token: {}", e))?
+                .ok_or_else(|| "Invalid API token".to_string())?;
+            if !self.users.contains_key(&token.user_id) {
+                self.load_user_from_database(&token.user_id)
+                    .await
+                    .map_err(|e| format!("Failed to load user: {}", e))?;
+            }
+            self.api_token_hashes.insert(token.token_hash.clone(), token.token_id.clone());
+            self.api_tokens.insert(token.token_id.clone(), token);
+        }
+
+        self.validate_api_token(plaintext)
+    }
+
+    /// 验证 API Token 并更新最近使用时间
+    pub fn validate_api_token(&self, plaintext: &str) -> Result<ApiToken, String> {
+        let token_id = self
+            .api_token_hashes
+            .get(&ApiToken::hash(plaintext))
+            .map(|id| id.value().clone())
+            .ok_or_else(|| "Invalid API token".to_string())?;
+
+        let mut token =
+            self.api_tokens.get_mut(&token_id).ok_or_else(|| "Invalid API token".to_string())?;
+
+        if token.is_expired() {
+            return Err("API token expired".to_string());
+        }
+
+        match self.users.get(&token.user_id) {
+            Some(user) if user.status == UserStatus::Active => {}
+            _ => return Err("API token owner is inactive".to_string()),
+        }
+
+        // 最近使用时间: 内存实时更新, 持久化按最小间隔节流
+        let now = chrono::Utc::now().timestamp();
+        let should_persist = token
+            .last_used_at
+            .is_none_or(|last| now - last >= API_TOKEN_LAST_USED_PERSIST_INTERVAL_SECS);
+        token.last_used_at = Some(now);
+        let validated = token.clone();
+        drop(token);
+
+        if should_persist && let Some(db) = &self.database {
+            let token_dao = TokenDao::new(db.conn().clone());
+            let token_id = validated.token_id.clone();
+            tokio::spawn(async move {
+                if let Err(e) = token_dao.update_last_used(&token_id, now).await {
+                    tracing::error!("Failed to update API token last used time: {:?}", e);
+                }
+            });
+        }
+
+        Ok(validated)
+    }
+
+    /// 获取 API Token
+    pub fn get_api_token(&self, token_id: &str) -> Option<ApiToken> {
+        self.api_tokens.get(token_id).map(|t| t.clone())
+    }
+
+    /// 列出用户的 API Token (按创建时间排序)
+    pub fn list_api_tokens(&self, user_id: &str) -> Vec<ApiToken> {
+        let mut tokens: Vec<ApiToken> = self
+            .api_tokens
+            .iter()
+            .filter(|t| t.user_id == user_id)
+            .map(|t| t.value().clone())
+            .collect();
+        tokens.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.name.cmp(&b.name)));
+        tokens
+    }
+
+    /// 撤销 API Token
+    pub fn revoke_api_token(&self, token_id: &str) -> Result<(), String> {
+        let (_, token) =
+            self.api_tokens.remove(token_id).ok_or_else(|| "API token not found".to_string())?;
+        self.api_token_hashes.remove(&token.token_hash);
+
         // 持久化删除
         if let Some(db) = &self.database {
+            let token_dao = TokenDao::new(db.conn().clone());
             let session_dao = SessionDao::new(db.conn().clone());
+            let token_id_clone = token_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = token_dao.delete_token(&token_id_clone).await {
+                    tracing::error!("Failed to delete API token from database: {:?}", e);
+                }
+                // 其他节点据此清除缓存的 API Token
+                if let Err(e) =
+                    session_dao.insert_revocation(Some(&token_id_clone), &token.user_id).await
+                {
+                    tracing::error!("Failed to record API token revocation: {:?}", e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    // ===== 两步验证 =====
+
+    /// 发起 TOTP 绑定, 返回 (密钥, otpauth 地址)
+    ///
+    /// 提交一次正确验证码确认后才会启用
+    pub fn begin_totp_enrollment(&self, user_id: &str) -> Result<(String, String), String> {
+        let user = self.get_user(user_id).ok_or_else(|| "User not found".to_string())?;
+        if user.mfa_enabled {
+            return Err("Two-factor authentication is already enabled".to_string());
+        }
+
+        let secret = totp::generate_secret();
+        let uri = totp::provisioning_uri(&user.username, &secret);
+        self.totp_secrets.insert(
+            user_id.to_string(),
+            UserTotp { secret: secret.clone(), enabled: false, last_used_step: None },
+        );
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
             let user_id_clone = user_id.to_string();
+            let secret_clone = secret.clone();
             tokio::spawn(async move {
-                if let Err(e) = session_dao.delete_user_sessions(&user_id_clone).await {
-                    tracing::error!("Failed to delete user sessions from database: {:?}", e);
+                if let Err(e) = mfa_dao.save_totp(&user_id_clone, &secret_clone, false).await {
+                    tracing::error!("Failed to persist TOTP secret: {:?}", e);
                 }
             });
         }
 
-        Ok(count)
+        Ok((secret, uri))
     }
 
-    /// 检查权限
-    pub fn check_permission(&self, user_id: &str, resource: &str, action: &str) -> bool {
-        let user = match self.users.get(user_id) {
-            Some(u) => u,
-            None => return false,
-        };
+    /// 使用验证码确认 TOTP 绑定, 返回恢复码明文 (只返回一次)
+    pub fn confirm_totp_enrollment(
+        &self,
+        user_id: &str,
+        code: &str,
+    ) -> Result<Vec<String>, String> {
+        let now = chrono::Utc::now().timestamp();
+        let mut pending = self
+            .totp_secrets
+            .get_mut(user_id)
+            .filter(|t| !t.enabled)
+            .ok_or_else(|| "No pending two-factor enrollment".to_string())?;
+
+        let step = totp::verify(&pending.secret, code, now)
+            .ok_or_else(|| "Invalid verification code".to_string())?;
+        pending.enabled = true;
+        pending.last_used_step = Some(step);
+        let secret = pending.secret.clone();
+        drop(pending);
+
+        if let Some(mut user) = self.users.get_mut(user_id) {
+            user.mfa_enabled = true;
+        }
 
-        match user.role {
-            UserRole::Admin => true, // Admin 有全部权限
-            UserRole::Operator => match (resource, action) {
-                ("services", _) | ("instances", _) | ("routing", _) => true,
-                ("cluster", "read") | ("audit", "read") => true,
-                ("auth", "read") => true, // 可以查看自己的信息
-                _ => false,
-            },
-            UserRole::Viewer => action == "read", // Viewer 只有读权限
+        // 持久化
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_id_clone = user_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = mfa_dao.save_totp(&user_id_clone, &secret, true).await {
+                    tracing::error!("Failed to persist TOTP enrollment: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_id_clone).await;
+            });
         }
+
+        Ok(self.replace_recovery_codes(user_id))
     }
 
-    /// 获取用户权限列表
-    pub fn get_user_permissions(&self, user_id: &str) -> Vec<String> {
-        let user = match self.users.get(user_id) {
-            Some(u) => u,
+    /// 关闭两步验证 (需要提供当前验证码或恢复码)
+    ///
+    /// 所属角色要求两步验证时不允许关闭
+    pub fn disable_totp(&self, user_id: &str, code: &str) -> Result<(), String> {
+        let user = self.get_user(user_id).ok_or_else(|| "User not found".to_string())?;
+        if !user.mfa_enabled {
+            return Err("Two-factor authentication is not enabled".to_string());
+        }
+        if self.role_requires_mfa(&user) {
+            return Err("Two-factor authentication is required for your role".to_string());
+        }
+        if !self.verify_second_factor(user_id, code, chrono::Utc::now().timestamp()) {
+            return Err("Invalid verification code".to_string());
+        }
+
+        self.remove_mfa(user_id);
+        Ok(())
+    }
+
+    /// 重置用户的两步验证 (管理员操作, 如用户丢失设备)
+    pub fn reset_mfa(&self, user_id: &str) -> Result<(), String> {
+        if !self.users.contains_key(user_id) {
+            return Err("User not found".to_string());
+        }
+
+        self.remove_mfa(user_id);
+        Ok(())
+    }
+
+    /// 重新生成恢复码 (需要提供当前验证码), 旧恢复码全部失效
+    pub fn regenerate_recovery_codes(
+        &self,
+        user_id: &str,
+        code: &str,
+    ) -> Result<Vec<String>, String> {
+        let user = self.get_user(user_id).ok_or_else(|| "User not found".to_string())?;
+        if !user.mfa_enabled {
+            return Err("Two-factor authentication is not enabled".to_string());
+        }
+        if !self.verify_second_factor(user_id, code, chrono::Utc::now().timestamp()) {
+            return Err("Invalid verification code".to_string());
+        }
+
+        Ok(self.replace_recovery_codes(user_id))
+    }
+
+    /// 剩余可用的恢复码数量
+    pub fn recovery_codes_remaining(&self, user_id: &str) -> usize {
+        self.recovery_codes.get(user_id).map(|c| c.len()).unwrap_or(0)
+    }
+
+    /// 设置角色是否强制两步验证
+    pub fn set_role_mfa_required(&self, role_id: &str, required: bool) -> Result<(), String> {
+        if !self.roles.contains_key(role_id) {
+            return Err("Role not found".to_string());
+        }
+
+        if required {
+            self.mfa_required_roles.insert(role_id.to_string());
+        } else {
+            self.mfa_required_roles.remove(role_id);
+        }
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let role_id_clone = role_id.to_string();
+            tokio::spawn(async move {
+                let result = if required {
+                    mfa_dao.insert_required_role(&role_id_clone).await
+                } else {
+                    mfa_dao.delete_required_role(&role_id_clone).await
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to persist role MFA requirement: {:?}", e);
+                }
+                publish_role_change(&session_dao, &role_id_clone).await;
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 列出强制两步验证的角色
+    pub fn list_mfa_required_roles(&self) -> Vec<String> {
+        let mut roles: Vec<String> = self.mfa_required_roles.iter().map(|r| r.clone()).collect();
+        roles.sort();
+        roles
+    }
+
+    /// 用户所属角色要求两步验证但尚未启用
+    ///
+    /// 此时只允许访问个人接口 (完成绑定)
+    pub fn mfa_enrollment_required(&self, user_id: &str) -> bool {
+        self.users.get(user_id).is_some_and(|u| !u.mfa_enabled && self.role_requires_mfa(&u))
+    }
+
+    fn role_requires_mfa(&self, user: &User) -> bool {
+        // 单点登录用户的第二因素由身份提供方负责
+        !user.service_account
+            && user.identity_provider.is_none()
+            && user.role_ids().iter().any(|role_id| self.mfa_required_roles.contains(role_id))
+    }
+
+    /// 校验第二因素: TOTP 验证码 (同一时间步不能重复使用) 或一次性恢复码
+    fn verify_second_factor(&self, user_id: &str, code: &str, now: i64) -> bool {
+        if let Some(mut secret) = self.totp_secrets.get_mut(user_id)
+            && secret.enabled
+            && let Some(step) = totp::verify(&secret.secret, code, now)
+            && secret.last_used_step.is_none_or(|last| step > last)
+        {
+            secret.last_used_step = Some(step);
+            return true;
+        }
+
+        let code_hash = totp::hash_recovery_code(code);
+        let consumed = self.recovery_codes.get_mut(user_id).is_some_and(|mut codes| {
+            let position = codes.iter().position(|h| *h == code_hash);
+            position.map(|i| codes.remove(i)).is_some()
+        });
+
+        if consumed && let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_id_clone = user_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    mfa_dao.mark_recovery_code_used(&user_id_clone, &code_hash, now).await
+                {
+                    tracing::error!("Failed to mark recovery code as used: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_id_clone).await;
+            });
+        }
+
+        consumed
+    }
+
+    /// 生成新的恢复码并替换旧恢复码, 返回明文
+    fn replace_recovery_codes(&self, user_id: &str) -> Vec<String> {
+        let codes = totp::generate_recovery_codes();
+        let hashes: Vec<String> = codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+        self.recovery_codes.insert(user_id.to_string(), hashes.clone());
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_id_clone = user_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = mfa_dao.replace_recovery_codes(&user_id_clone, &hashes).await {
+                    tracing::error!("Failed to persist recovery codes: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_id_clone).await;
+            });
+        }
+
+        codes
+    }
+
+    /// 删除用户的 TOTP 密钥、恢复码和进行中的挑战
+    fn remove_mfa(&self, user_id: &str) {
+        self.totp_secrets.remove(user_id);
+        self.recovery_codes.remove(user_id);
+        self.mfa_challenges.retain(|_, c| c.user_id != user_id);
+        if let Some(mut user) = self.users.get_mut(user_id) {
+            user.mfa_enabled = false;
+        }
+
+        // 持久化删除
+        if let Some(db) = &self.database {
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_id_clone = user_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = mfa_dao.delete_totp(&user_id_clone).await {
+                    tracing::error!("Failed to delete TOTP secret from database: {:?}", e);
+                }
+                if let Err(e) = mfa_dao.delete_recovery_codes(&user_id_clone).await {
+                    tracing::error!("Failed to delete recovery codes from database: {:?}", e);
+                }
+                if let Err(e) = mfa_dao.delete_user_challenges(&user_id_clone).await {
+                    tracing::error!("Failed to delete MFA challenges from database: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_id_clone).await;
+            });
+        }
+    }
+
+    // ===== 角色管理 =====
+
+    /// 创建自定义角色
+    pub fn create_role(
+        &self,
+        role_id: &str,
+        description: Option<String>,
+        permissions: Vec<Permission>,
+    ) -> Result<Role, String> {
+        if self.roles.contains_key(role_id) {
+            return Err(format!("Role '{}' already exists", role_id));
+        }
+
+        let role = Role::new(role_id.to_string(), description, permissions);
+        role.validate()?;
+
+        self.roles.insert(role.role_id.clone(), role.clone());
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let role_dao = RoleDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let role_clone = role.clone();
+            tokio::spawn(async move {
+                if let Err(e) = role_dao.insert_role(&role_clone).await {
+                    tracing::error!("Failed to persist role: {:?}", e);
+                }
+                publish_role_change(&session_dao, &role_clone.role_id).await;
+            });
+        }
+
+        Ok(role)
+    }
+
+    /// 更新自定义角色
+    pub fn update_role(
+        &self,
+        role_id: &str,
+        description: Option<String>,
+        permissions: Option<Vec<Permission>>,
+    ) -> Result<Role, String> {
+        let mut role = self.roles.get_mut(role_id).ok_or_else(|| "Role not found".to_string())?;
+
+        if role.builtin {
+            return Err(format!("Built-in role '{}' cannot be modified", role_id));
+        }
+
+        let mut updated_role = role.clone();
+        if let Some(d) = description {
+            updated_role.description = Some(d);
+        }
+        if let Some(p) = permissions {
+            updated_role.permissions = p;
+        }
+        updated_role.validate()?;
+        updated_role.updated_at = chrono::Utc::now().timestamp();
+
+        *role = updated_role.clone();
+        drop(role);
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let role_dao = RoleDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let role_clone = updated_role.clone();
+            tokio::spawn(async move {
+                if let Err(e) = role_dao.update_role(&role_clone).await {
+                    tracing::error!("Failed to update role in database: {:?}", e);
+                }
+                publish_role_change(&session_dao, &role_clone.role_id).await;
+            });
+        }
+
+        Ok(updated_role)
+    }
+
+    /// 删除自定义角色 (同时从所有用户中移除)
+    pub fn delete_role(&self, role_id: &str) -> Result<(), String> {
+        let role = self.roles.get(role_id).ok_or_else(|| "Role not found".to_string())?;
+        if role.builtin {
+            return Err(format!("Built-in role '{}' cannot be deleted", role_id));
+        }
+        drop(role);
+
+        self.roles.remove(role_id);
+        for mut user in self.users.iter_mut() {
+            user.roles.retain(|r| r != role_id);
+        }
+        let mfa_required = self.mfa_required_roles.remove(role_id).is_some();
+
+        // 持久化删除
+        if let Some(db) = &self.database {
+            let role_dao = RoleDao::new(db.conn().clone());
+            let mfa_dao = MfaDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let role_id_clone = role_id.to_string();
+            tokio::spawn(async move {
+                if mfa_required && let Err(e) = mfa_dao.delete_required_role(&role_id_clone).await {
+                    tracing::error!("Failed to delete role MFA requirement: {:?}", e);
+                }
+                if let Err(e) = role_dao.delete_role(&role_id_clone).await {
+                    tracing::error!("Failed to delete role from database: {:?}", e);
+                }
+                publish_role_change(&session_dao, &role_id_clone).await;
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 获取角色
+    pub fn get_role(&self, role_id: &str) -> Option<Role> {
+        self.roles.get(role_id).map(|r| r.clone())
+    }
+
+    /// 列出所有角色 (内置角色在前)
+    pub fn list_roles(&self) -> Vec<Role> {
+        let mut roles: Vec<Role> = self.roles.iter().map(|r| r.value().clone()).collect();
+        roles.sort_by(|a, b| b.builtin.cmp(&a.builtin).then_with(|| a.role_id.cmp(&b.role_id)));
+        roles
+    }
+
+    /// 设置用户的附加角色
+    pub fn set_user_roles(&self, user_id: &str, role_ids: Vec<String>) -> Result<User, String> {
+        for role_id in &role_ids {
+            if !self.roles.contains_key(role_id) {
+                return Err(format!("Role not found: {}", role_id));
+            }
+        }
+
+        let mut user = self.users.get_mut(user_id).ok_or_else(|| "User not found".to_string())?;
+
+        let mut roles: Vec<String> = Vec::new();
+        for role_id in role_ids {
+            if role_id != user.role.as_str() && !roles.contains(&role_id) {
+                roles.push(role_id);
+            }
+        }
+        user.roles = roles;
+        user.updated_at = chrono::Utc::now().timestamp();
+
+        let updated_user = user.clone();
+        drop(user);
+
+        // 持久化
+        if let Some(db) = &self.database {
+            let role_dao = RoleDao::new(db.conn().clone());
+            let session_dao = SessionDao::new(db.conn().clone());
+            let user_clone = updated_user.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    role_dao.set_user_roles(&user_clone.user_id, &user_clone.roles).await
+                {
+                    tracing::error!("Failed to persist user roles: {:?}", e);
+                }
+                publish_user_change(&session_dao, &user_clone.user_id).await;
+            });
+        }
+
+        Ok(updated_user)
+    }
+
+    // ===== 权限检查 =====
+
+    /// 获取用户的有效权限 (所有角色权限的并集)
+    pub fn get_effective_permissions(&self, user_id: &str) -> Vec<Permission> {
+        let role_ids = match self.users.get(user_id) {
+            Some(u) => u.role_ids(),
             None => return vec![],
         };
 
-        match user.role {
-            UserRole::Admin => vec!["*:*".to_string()],
-            UserRole::Operator => vec![
-                "services:*".to_string(),
-                "instances:*".to_string(),
-                "routing:*".to_string(),
-                "cluster:read".to_string(),
-                "audit:read".to_string(),
-                "auth:read".to_string(),
-            ],
-            UserRole::Viewer => vec!["*:read".to_string()],
+        let mut permissions: Vec<Permission> = Vec::new();
+        for role_id in role_ids {
+            if let Some(role) = self.roles.get(&role_id) {
+                for permission in &role.permissions {
+                    if !permissions.contains(permission) {
+                        permissions.push(permission.clone());
+                    }
+                }
+            }
         }
+        permissions
+    }
+
+    /// 检查权限
+    ///
+    /// 只要用户对该资源拥有任意服务范围的授权即返回 true;
+    /// 涉及具体服务的操作应使用 `check_service_permission`
+    pub fn check_permission(&self, user_id: &str, resource: &str, action: &str) -> bool {
+        self.get_effective_permissions(user_id).iter().any(|p| p.allows(resource, action))
+    }
+
+    /// 检查对指定服务的权限
+    pub fn check_service_permission(
+        &self,
+        user_id: &str,
+        resource: &str,
+        action: &str,
+        service_id: &str,
+    ) -> bool {
+        self.get_effective_permissions(user_id)
+            .iter()
+            .any(|p| p.allows_service(resource, action, service_id))
+    }
+
+    /// 获取用户权限列表
+    ///
+    /// 格式为 `resource:action`, 限定服务范围的权限为 `resource:action:pattern`
+    pub fn get_user_permissions(&self, user_id: &str) -> Vec<String> {
+        self.get_effective_permissions(user_id).iter().map(|p| p.to_string()).collect()
     }
 
     /// 获取登录历史
@@ -617,6 +2301,100 @@ impl AuthManager {
         }
     }
 
+    // ===== 签名密钥 =====
+
+    /// 当前签名密钥, 尚无签名密钥时生成
+    fn ensure_signing_key(&self) -> Result<SigningKey, String> {
+        if let Some(key) = self.signing_keys.active() {
+            return Ok(key);
+        }
+
+        let key = SigningKey::generate(self.jwt_algorithm)?;
+        if let Some(active) = self.signing_keys.insert_if_no_active(key.clone()) {
+            return Ok(active);
+        }
+        self.persist_signing_key(&key, Vec::new());
+
+        tracing::info!("Generated {:?} JWT signing key {}", key.algorithm, key.kid);
+        Ok(key)
+    }
+
+    /// 轮换签名密钥
+    ///
+    /// 新签发的令牌使用新密钥; 旧密钥保留到其签发的令牌全部过期, 期间仍可校验
+    pub fn rotate_signing_key(&self) -> Result<SigningKey, String> {
+        let key = SigningKey::generate(self.jwt_algorithm)?;
+        let retired = self.signing_keys.insert(key.clone());
+        self.persist_signing_key(&key, retired);
+        self.prune_signing_keys();
+
+        tracing::info!("Rotated JWT signing key, new key {}", key.kid);
+        Ok(key)
+    }
+
+    /// 列出签名密钥 (当前签名密钥和仍在有效期内的旧密钥)
+    pub fn list_signing_keys(&self) -> Vec<SigningKey> {
+        self.signing_keys.list()
+    }
+
+    /// 公开的 JWKS 文档, 供其他服务校验 Artemis 签发的令牌
+    pub fn jwks(&self) -> Result<jsonwebtoken::jwk::JwkSet, String> {
+        self.ensure_signing_key()?;
+        Ok(self.signing_keys.jwks())
+    }
+
+    /// 按固定间隔自动轮换签名密钥
+    pub fn start_key_rotation_task(self: &Arc<Self>, interval: std::time::Duration) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await; // 跳过立即触发的第一次
+            loop {
+                ticker.tick().await;
+                if let Err(e) = manager.rotate_signing_key() {
+                    tracing::error!("Failed to rotate JWT signing key: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 移除已无未过期令牌的旧密钥
+    fn prune_signing_keys(&self) {
+        let retired_before = chrono::Utc::now().timestamp() - self.jwt_expiry_seconds;
+        let removed = self.signing_keys.prune(retired_before);
+
+        if !removed.is_empty()
+            && let Some(db) = &self.database
+        {
+            let key_dao = KeyDao::new(db.conn().clone());
+            tokio::spawn(async move {
+                for kid in removed {
+                    if let Err(e) = key_dao.delete_key(&kid).await {
+                        tracing::error!("Failed to delete signing key from database: {:?}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    fn persist_signing_key(&self, key: &SigningKey, retired: Vec<String>) {
+        if let Some(db) = &self.database {
+            let key_dao = KeyDao::new(db.conn().clone());
+            let key_clone = key.clone();
+            tokio::spawn(async move {
+                let now = chrono::Utc::now().timestamp();
+                for kid in retired {
+                    if let Err(e) = key_dao.retire_key(&kid, now).await {
+                        tracing::error!("Failed to retire signing key {}: {:?}", kid, e);
+                    }
+                }
+                if let Err(e) = key_dao.insert_key(&key_clone).await {
+                    tracing::error!("Failed to persist signing key: {:?}", e);
+                }
+            });
+        }
+    }
+
     /// 生成 JWT token
     fn generate_jwt_token(&self, user: &User) -> Result<String, String> {
         let now = chrono::Utc::now().timestamp();
@@ -628,19 +2406,25 @@ impl AuthManager {
             iat: now,
         };
 
-        encode(&Header::default(), &claims, &EncodingKey::from_secret(self.jwt_secret.as_bytes()))
+        let key = self.ensure_signing_key()?;
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+
+        encode(&header, &claims, key.encoding_key())
             .map_err(|e| format!("Failed to generate JWT token: {}", e))
     }
 
-    /// 验证 JWT token
+    /// 验证 JWT token (按 kid 选择签名密钥)
     fn verify_jwt_token(&self, token: &str) -> Result<JwtClaims, String> {
-        decode::<JwtClaims>(
-            token,
-            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map(|data| data.claims)
-        .map_err(|e| format!("Invalid JWT token: {}", e))
+        let header = decode_header(token).map_err(|e| format!("Invalid JWT token: {}", e))?;
+        let key = header
+            .kid
+            .and_then(|kid| self.signing_keys.find(&kid))
+            .ok_or_else(|| "Invalid JWT token: unknown signing key".to_string())?;
+
+        decode::<JwtClaims>(token, key.decoding_key(), &Validation::new(key.algorithm))
+            .map(|data| data.claims)
+            .map_err(|e| format!("Invalid JWT token: {}", e))
     }
 
     /// 哈希密码
@@ -655,6 +2439,36 @@ impl AuthManager {
     }
 }
 
+/// 写入用户变更记录, 须在用户数据写入之后调用
+async fn publish_user_change(session_dao: &SessionDao, user_id: &str) {
+    if let Err(e) = session_dao.insert_user_change(user_id).await {
+        tracing::error!("Failed to record user change: {:?}", e);
+    }
+}
+
+/// 写入角色变更记录, 须在角色数据写入之后调用
+async fn publish_role_change(session_dao: &SessionDao, role_id: &str) {
+    if let Err(e) = session_dao.insert_role_change(role_id).await {
+        tracing::error!("Failed to record role change: {:?}", e);
+    }
+}
+
+/// 删除数据库中的会话并写入失效记录 (session_id 为 None 表示用户的全部会话)
+///
+/// 用户的全部会话失效时其他节点会按数据库刷新该用户, 须在用户数据写入之后调用
+async fn revoke_in_database(session_dao: &SessionDao, session_id: Option<&str>, user_id: &str) {
+    let deleted = match session_id {
+        Some(session_id) => session_dao.delete_session(session_id).await.map(|_| ()),
+        None => session_dao.delete_user_sessions(user_id).await.map(|_| ()),
+    };
+    if let Err(e) = deleted {
+        tracing::error!("Failed to delete session from database: {:?}", e);
+    }
+    if let Err(e) = session_dao.insert_revocation(session_id, user_id).await {
+        tracing::error!("Failed to record session revocation: {:?}", e);
+    }
+}
+
 impl Default for AuthManager {
     fn default() -> Self {
         Self::new()
@@ -1,8 +1,13 @@
 pub mod dao;
+pub mod keys;
 pub mod manager;
 pub mod model;
+pub mod oidc;
+pub mod policy;
+pub mod totp;
 
 pub use manager::AuthManager;
 pub use model::{
-    JwtClaims, LoginHistory, LoginStatus, Session, User, UserResponse, UserRole, UserStatus,
+    ApiToken, JwtClaims, LoginHistory, LoginOutcome, LoginStatus, MfaChallenge, Permission, Role,
+    Session, User, UserResponse, UserRole, UserStatus,
 };
@@ -1,3 +1,4 @@
+use artemis_service::security::wildcard_match;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -33,6 +34,191 @@ impl FromStr for UserRole {
     }
 }
 
+impl UserRole {
+    /// 内置角色的默认权限集合
+    pub fn default_permissions(&self) -> Vec<Permission> {
+        match self {
+            UserRole::Admin => vec![Permission::new("*", "*")],
+            UserRole::Operator => vec![
+                Permission::new("services", "*"),
+                Permission::new("instances", "*"),
+                Permission::new("zones", "*"),
+                Permission::new("canary", "*"),
+                Permission::new("groups", "*"),
+                Permission::new("routing", "*"),
+                Permission::new("alerts", "*"),
+                Permission::new("cluster", "read"),
+                Permission::new("audit", "read"),
+                Permission::new("approvals", "read"),
+            ],
+            UserRole::Viewer => vec![Permission::new("*", "read")],
+        }
+    }
+
+    pub fn all() -> [UserRole; 3] {
+        [UserRole::Admin, UserRole::Operator, UserRole::Viewer]
+    }
+}
+
+/// 可授权的资源 (与管理 API 路由对应)
+pub const PERMISSION_RESOURCES: &[&str] = &[
+    "services",  // 服务注册/发现数据
+    "instances", // /api/management/instance, /api/management/server
+    "zones",     // /api/management/zone
+    "canary",    // /api/management/canary
+    "groups",    // /api/routing/groups
+    "routing",   // /api/routing/rules
+    "audit",     // /api/management/audit, /api/management/log
+    "approvals", // /api/management/approvals (高风险操作审批)
+    "cluster",   // /api/status, 集群节点
+    "alerts",    // /api/alerts (告警规则和状态)
+    "users",     // /api/auth/users
+    "roles",     // /api/auth/roles
+    "keys",      // /api/auth/keys (访问令牌签名密钥)
+];
+
+/// 可授权的操作
+pub const PERMISSION_ACTIONS: &[&str] = &["read", "write", "delete"];
+
+/// 权限: (资源, 操作, 服务范围)
+///
+/// `resource` 和 `action` 支持 `*` 表示全部; `service_pattern` 支持 `*` 通配符,
+/// 例如 `team-a-*` 只授权 ID 以 `team-a-` 开头的服务
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub resource: String,
+    pub action: String,
+    #[serde(default = "default_service_pattern")]
+    pub service_pattern: String,
+}
+
+fn default_service_pattern() -> String {
+    "*".to_string()
+}
+
+impl Permission {
+    /// 创建作用于所有服务的权限
+    pub fn new(resource: &str, action: &str) -> Self {
+        Self::scoped(resource, action, "*")
+    }
+
+    /// 创建限定服务范围的权限
+    pub fn scoped(resource: &str, action: &str, service_pattern: &str) -> Self {
+        Self {
+            resource: resource.to_string(),
+            action: action.to_string(),
+            service_pattern: service_pattern.to_string(),
+        }
+    }
+
+    /// 是否限定了服务范围
+    pub fn is_scoped(&self) -> bool {
+        self.service_pattern != "*"
+    }
+
+    /// 校验资源和操作名称
+    pub fn validate(&self) -> Result<(), String> {
+        if self.resource != "*" && !PERMISSION_RESOURCES.contains(&self.resource.as_str()) {
+            return Err(format!("Unknown permission resource: {}", self.resource));
+        }
+        if self.action != "*" && !PERMISSION_ACTIONS.contains(&self.action.as_str()) {
+            return Err(format!("Unknown permission action: {}", self.action));
+        }
+        if self.service_pattern.is_empty() {
+            return Err("Service pattern cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 是否授权对某资源执行某操作 (不考虑服务范围)
+    pub fn allows(&self, resource: &str, action: &str) -> bool {
+        (self.resource == "*" || self.resource == resource)
+            && (self.action == "*" || self.action == action)
+    }
+
+    /// 是否授权对指定服务的资源执行某操作
+    pub fn allows_service(&self, resource: &str, action: &str, service_id: &str) -> bool {
+        self.allows(resource, action) && wildcard_match(&self.service_pattern, service_id)
+    }
+}
+
+impl std::fmt::Display for Permission {
+    /// 格式: `resource:action`, 限定服务范围时为 `resource:action:pattern`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_scoped() {
+            write!(f, "{}:{}:{}", self.resource, self.action, self.service_pattern)
+        } else {
+            write!(f, "{}:{}", self.resource, self.action)
+        }
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.splitn(3, ':').collect();
+        match parts.as_slice() {
+            [resource, action] => Ok(Permission::new(resource, action)),
+            [resource, action, pattern] => Ok(Permission::scoped(resource, action, pattern)),
+            _ => Err(format!("Invalid permission: {}", s)),
+        }
+    }
+}
+
+/// 角色: 一组权限的集合
+///
+/// 内置角色 (admin/operator/viewer) 不可修改或删除, 自定义角色持久化在 auth_roles 表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    pub role_id: String,
+    pub description: Option<String>,
+    pub permissions: Vec<Permission>,
+    pub builtin: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Role {
+    pub fn new(role_id: String, description: Option<String>, permissions: Vec<Permission>) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self { role_id, description, permissions, builtin: false, created_at: now, updated_at: now }
+    }
+
+    /// 内置角色定义
+    pub fn builtin(role: &UserRole) -> Self {
+        Self {
+            role_id: role.as_str().to_string(),
+            description: Some(format!("Built-in {} role", role.as_str())),
+            permissions: role.default_permissions(),
+            builtin: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    /// 校验角色 ID 和权限
+    pub fn validate(&self) -> Result<(), String> {
+        if self.role_id.is_empty()
+            || !self
+                .role_id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid role id '{}': use lowercase letters, digits, '-' or '_'",
+                self.role_id
+            ));
+        }
+        for permission in &self.permissions {
+            permission.validate()?;
+        }
+        Ok(())
+    }
+}
+
 /// 用户状态
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -73,7 +259,22 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
     pub role: UserRole,
+    /// 附加角色 (内置或自定义角色 ID), 与 `role` 共同决定有效权限
+    #[serde(default)]
+    pub roles: Vec<String>,
     pub status: UserStatus,
+    /// 服务账号 (非人类账号, 不能用密码登录, 只能通过 API Token 访问)
+    #[serde(default)]
+    pub service_account: bool,
+    /// 下次登录后必须先修改密码
+    #[serde(default)]
+    pub must_change_password: bool,
+    /// 已启用两步验证 (TOTP)
+    #[serde(default)]
+    pub mfa_enabled: bool,
+    /// 通过单点登录开通的用户所属的身份提供方 (签发方), 本地账号为 None
+    #[serde(default)]
+    pub identity_provider: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -94,12 +295,28 @@ impl User {
             description,
             password_hash,
             role,
+            roles: Vec::new(),
             status: UserStatus::Active,
+            service_account: false,
+            must_change_password: false,
+            mfa_enabled: false,
+            identity_provider: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// 用户持有的全部角色 ID (主角色在前, 已去重)
+    pub fn role_ids(&self) -> Vec<String> {
+        let mut ids = vec![self.role.as_str().to_string()];
+        for role_id in &self.roles {
+            if !ids.contains(role_id) {
+                ids.push(role_id.clone());
+            }
+        }
+        ids
+    }
+
     /// 用户响应(不包含密码)
     pub fn to_response(&self) -> UserResponse {
         UserResponse {
@@ -108,7 +325,12 @@ impl User {
             email: self.email.clone(),
             description: self.description.clone(),
             role: self.role.clone(),
+            roles: self.roles.clone(),
             status: self.status.clone(),
+            service_account: self.service_account,
+            must_change_password: self.must_change_password,
+            mfa_enabled: self.mfa_enabled,
+            identity_provider: self.identity_provider.clone(),
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -124,7 +346,12 @@ pub struct UserResponse {
     pub email: Option<String>,
     pub description: Option<String>,
     pub role: UserRole,
+    pub roles: Vec<String>,
     pub status: UserStatus,
+    pub service_account: bool,
+    pub must_change_password: bool,
+    pub mfa_enabled: bool,
+    pub identity_provider: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -170,6 +397,130 @@ impl Session {
     }
 }
 
+/// API Token 明文前缀
+pub const API_TOKEN_PREFIX: &str = "art_";
+
+/// API Token (个人访问令牌 / 服务账号令牌)
+///
+/// 明文只在创建时返回一次, 存储时只保存 SHA-256 摘要;
+/// `scopes` 非空时进一步限制令牌可用的权限 (与用户权限取交集)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub token_id: String,
+    pub user_id: String,
+    pub name: String,
+    /// 明文前几位, 用于识别令牌
+    pub token_prefix: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    #[serde(default)]
+    pub scopes: Vec<Permission>,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+impl ApiToken {
+    /// 生成新令牌, 返回 (令牌, 明文)
+    pub fn generate(
+        user_id: String,
+        name: String,
+        scopes: Vec<Permission>,
+        expires_at: Option<i64>,
+    ) -> (Self, String) {
+        use rand::Rng;
+        use rand::distributions::Alphanumeric;
+
+        let secret: String =
+            rand::thread_rng().sample_iter(&Alphanumeric).take(40).map(char::from).collect();
+        let plaintext = format!("{}{}", API_TOKEN_PREFIX, secret);
+
+        let token = Self {
+            token_id: uuid::Uuid::new_v4().to_string(),
+            user_id,
+            name,
+            token_prefix: plaintext[..API_TOKEN_PREFIX.len() + 6].to_string(),
+            token_hash: Self::hash(&plaintext),
+            scopes,
+            created_at: chrono::Utc::now().timestamp(),
+            expires_at,
+            last_used_at: None,
+        };
+        (token, plaintext)
+    }
+
+    /// 计算令牌明文的摘要
+    pub fn hash(plaintext: &str) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(plaintext.as_bytes()))
+    }
+
+    /// 是否为 API Token 明文格式
+    pub fn is_api_token(token: &str) -> bool {
+        token.starts_with(API_TOKEN_PREFIX)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|t| chrono::Utc::now().timestamp() > t)
+    }
+}
+
+/// 两步验证挑战有效期 (秒)
+pub const MFA_CHALLENGE_TTL_SECS: i64 = 300;
+/// 单个两步验证挑战允许的错误次数
+pub const MFA_CHALLENGE_MAX_ATTEMPTS: u32 = 5;
+
+/// 用户 TOTP 配置
+#[derive(Debug, Clone)]
+pub struct UserTotp {
+    /// Base32 编码密钥
+    pub secret: String,
+    /// 绑定已通过验证码确认
+    pub enabled: bool,
+    /// 最近一次使用的时间步, 同一验证码不能重复使用
+    pub last_used_step: Option<i64>,
+}
+
+/// 两步验证挑战 (密码校验通过, 等待第二因素)
+#[derive(Debug, Clone)]
+pub struct MfaChallenge {
+    pub challenge_token: String,
+    pub user_id: String,
+    pub username: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub expires_at: i64,
+    pub failed_attempts: u32,
+}
+
+impl MfaChallenge {
+    pub fn new(user: &User, ip_address: Option<String>, user_agent: Option<String>) -> Self {
+        Self {
+            challenge_token: format!("mfa_{}", uuid::Uuid::new_v4().simple()),
+            user_id: user.user_id.clone(),
+            username: user.username.clone(),
+            ip_address,
+            user_agent,
+            expires_at: chrono::Utc::now().timestamp() + MFA_CHALLENGE_TTL_SECS,
+            failed_attempts: 0,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() >= self.expires_at
+    }
+}
+
+/// 密码登录结果
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    /// 登录完成, 返回访问令牌
+    Authenticated(String),
+    /// 用户已启用两步验证, 需要提交验证码完成登录
+    MfaRequired(MfaChallenge),
+}
+
 /// 登录状态
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -239,3 +590,52 @@ pub struct JwtClaims {
     pub exp: i64, // expiration time
     pub iat: i64, // issued at
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_allows_service() {
+        let permission = Permission::scoped("instances", "write", "team-a-*");
+
+        assert!(permission.allows("instances", "write"));
+        assert!(permission.allows_service("instances", "write", "team-a-order"));
+        assert!(!permission.allows_service("instances", "write", "team-b-order"));
+        assert!(!permission.allows_service("instances", "delete", "team-a-order"));
+        assert!(Permission::new("*", "read").allows_service("zones", "read", "any"));
+    }
+
+    #[test]
+    fn test_permission_string_roundtrip() {
+        let scoped: Permission = "instances:write:team-a-*".parse().unwrap();
+        assert_eq!(scoped, Permission::scoped("instances", "write", "team-a-*"));
+        assert_eq!(scoped.to_string(), "instances:write:team-a-*");
+
+        let global: Permission = "audit:read".parse().unwrap();
+        assert!(!global.is_scoped());
+        assert_eq!(global.to_string(), "audit:read");
+
+        assert!("audit".parse::<Permission>().is_err());
+    }
+
+    #[test]
+    fn test_role_validate() {
+        let role = Role::new(
+            "team-a-ops".to_string(),
+            None,
+            vec![Permission::scoped("instances", "*", "team-a-*")],
+        );
+        assert!(role.validate().is_ok());
+
+        let bad_id = Role::new("Team A".to_string(), None, vec![]);
+        assert!(bad_id.validate().is_err());
+
+        let bad_resource =
+            Role::new("ops".to_string(), None, vec![Permission::new("unknown", "read")]);
+        assert!(bad_resource.validate().is_err());
+
+        let bad_action = Role::new("ops".to_string(), None, vec![Permission::new("zones", "x")]);
+        assert!(bad_action.validate().is_err());
+    }
+}
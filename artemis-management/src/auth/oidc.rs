@@ -0,0 +1,403 @@
+//! OpenID Connect single sign-on: discovery, authorization-code exchange,
+//! ID token validation against the issuer's JWKS and claim-to-role mapping.
+
+use artemis_service::config::OidcConfig;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+/// 发起登录后等待回调的最长时间 (秒)
+pub const OIDC_LOGIN_TTL_SECS: i64 = 600;
+
+/// 保存发起中登录的 Cookie (签名后的 state、nonce 和 PKCE code_verifier)
+pub const OIDC_LOGIN_COOKIE: &str = "artemis_oidc_login";
+
+/// ID Token 允许的签名算法 (只接受非对称算法)
+const ALLOWED_ALGORITHMS: [Algorithm; 8] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+/// 发现文档中用到的字段
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// 已发起、等待回调的登录
+///
+/// 不保存在节点内存中: 签名后写入浏览器 Cookie, 回调落在集群任一节点都可以校验,
+/// 且只有发起登录的浏览器能完成回调 (防止登录 CSRF)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OidcLogin {
+    pub state: String,
+    pub nonce: String,
+    /// PKCE code_verifier, 授权地址中只携带它的 SHA-256 (code_challenge)
+    pub code_verifier: String,
+}
+
+impl OidcLogin {
+    fn generate() -> Self {
+        let random = || uuid::Uuid::new_v4().simple().to_string();
+        Self { state: random(), nonce: random(), code_verifier: random() + &random() }
+    }
+
+    /// PKCE S256 code_challenge
+    pub fn code_challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.code_verifier.as_bytes()))
+    }
+}
+
+/// 通过 ID Token 验证的外部身份
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    /// 签发方
+    pub issuer: String,
+    /// 签发方内唯一的用户标识 (sub)
+    pub subject: String,
+    pub username: String,
+    pub email: Option<String>,
+    /// 映射得到的 Artemis 角色 ID
+    pub roles: Vec<String>,
+}
+
+/// OIDC 身份提供方客户端
+pub struct OidcProvider {
+    config: OidcConfig,
+    http: reqwest::Client,
+    metadata: RwLock<Option<OidcMetadata>>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// 发起登录, 返回授权地址和需要保存到浏览器的登录状态
+    pub async fn authorization_url(&self) -> Result<(String, OidcLogin), String> {
+        let metadata = self.metadata().await?;
+        let login = OidcLogin::generate();
+        let code_challenge = login.code_challenge();
+
+        let url = reqwest::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_uri.as_str()),
+                ("scope", self.config.scopes.join(" ").as_str()),
+                ("state", login.state.as_str()),
+                ("nonce", login.nonce.as_str()),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| format!("Invalid authorization endpoint: {}", e))?;
+
+        Ok((url.to_string(), login))
+    }
+
+    /// 处理授权回调: 用授权码和 PKCE code_verifier 换取 ID Token 并校验
+    ///
+    /// `login` 为发起登录时保存到浏览器的状态, 调用方需先确认回调的 state 与之一致
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        login: &OidcLogin,
+    ) -> Result<OidcIdentity, String> {
+        let metadata = self.metadata().await?;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", login.code_verifier.as_str()),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("Token request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Token endpoint returned {}", response.status()));
+        }
+        let token: TokenResponse =
+            response.json().await.map_err(|e| format!("Invalid token response: {}", e))?;
+
+        let claims = self.validate_id_token(&token.id_token, &login.nonce).await?;
+        self.identity_from_claims(&metadata.issuer, &claims)
+    }
+
+    /// 校验 ID Token 签名、签发方、受众、有效期和 nonce
+    pub async fn validate_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<Map<String, Value>, String> {
+        let header = decode_header(id_token).map_err(|e| format!("Invalid ID token: {}", e))?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(format!("Unsupported ID token algorithm: {:?}", header.alg));
+        }
+
+        let metadata = self.metadata().await?;
+        let key = self.decoding_key(&metadata, header.kid.as_deref()).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .map_err(|e| format!("Invalid ID token: {}", e))?
+            .claims;
+
+        if claims.get("nonce").and_then(Value::as_str) != Some(nonce) {
+            return Err("ID token nonce mismatch".to_string());
+        }
+
+        Ok(claims)
+    }
+
+    /// 按配置将声明映射为 Artemis 角色, 未匹配时使用默认角色
+    pub fn map_roles(&self, claims: &Map<String, Value>) -> Vec<String> {
+        let mut roles = Vec::new();
+        for mapping in &self.config.role_mappings {
+            let matched = match claims.get(&mapping.claim) {
+                Some(Value::Array(values)) => {
+                    values.iter().any(|v| v.as_str() == Some(mapping.value.as_str()))
+                }
+                Some(Value::String(value)) => *value == mapping.value,
+                Some(Value::Bool(value)) => value.to_string() == mapping.value,
+                _ => false,
+            };
+            if matched && !roles.contains(&mapping.role) {
+                roles.push(mapping.role.clone());
+            }
+        }
+
+        if roles.is_empty()
+            && let Some(default_role) = &self.config.default_role
+        {
+            roles.push(default_role.clone());
+        }
+        roles
+    }
+
+    fn identity_from_claims(
+        &self,
+        issuer: &str,
+        claims: &Map<String, Value>,
+    ) -> Result<OidcIdentity, String> {
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "ID token has no subject".to_string())?;
+        let username = claims
+            .get(&self.config.username_claim)
+            .and_then(Value::as_str)
+            .filter(|u| !u.is_empty())
+            .ok_or_else(|| format!("ID token has no '{}' claim", self.config.username_claim))?;
+
+        Ok(OidcIdentity {
+            issuer: issuer.to_string(),
+            subject: subject.to_string(),
+            username: username.to_string(),
+            email: claims.get("email").and_then(Value::as_str).map(|e| e.to_string()),
+            roles: self.map_roles(claims),
+        })
+    }
+
+    /// 获取 (并缓存) 发现文档
+    async fn metadata(&self) -> Result<OidcMetadata, String> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer_url.trim_end_matches('/')
+        );
+        let metadata: OidcMetadata = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("OIDC discovery failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid OIDC discovery document: {}", e))?;
+
+        if metadata.issuer.trim_end_matches('/') != self.config.issuer_url.trim_end_matches('/') {
+            return Err(format!("OIDC issuer mismatch: {}", metadata.issuer));
+        }
+
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    /// 查找签名公钥, 未知 kid 时刷新一次 JWKS (签发方轮换密钥)
+    async fn decoding_key(
+        &self,
+        metadata: &OidcMetadata,
+        kid: Option<&str>,
+    ) -> Result<DecodingKey, String> {
+        for refresh in [false, true] {
+            if refresh || self.jwks.read().await.is_none() {
+                let jwks: JwkSet = self
+                    .http
+                    .get(&metadata.jwks_uri)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| format!("Failed to fetch JWKS: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid JWKS document: {}", e))?;
+                *self.jwks.write().await = Some(jwks);
+            }
+
+            let jwks = self.jwks.read().await;
+            let jwk = match (jwks.as_ref(), kid) {
+                (Some(set), Some(kid)) => set.find(kid),
+                (Some(set), None) if set.keys.len() == 1 => set.keys.first(),
+                _ => None,
+            };
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk).map_err(|e| format!("Invalid JWK: {}", e));
+            }
+        }
+
+        Err("No matching signing key in JWKS".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use artemis_service::config::OidcRoleMapping;
+
+    fn provider(default_role: Option<&str>) -> OidcProvider {
+        OidcProvider::new(OidcConfig {
+            issuer_url: "https://idp.example.com".to_string(),
+            client_id: "artemis".to_string(),
+            client_secret: None,
+            redirect_uri: "https://artemis.example.com/callback".to_string(),
+            scopes: vec!["openid".to_string()],
+            username_claim: "preferred_username".to_string(),
+            role_mappings: vec![
+                OidcRoleMapping {
+                    claim: "groups".to_string(),
+                    value: "registry-admins".to_string(),
+                    role: "admin".to_string(),
+                },
+                OidcRoleMapping {
+                    claim: "department".to_string(),
+                    value: "sre".to_string(),
+                    role: "operator".to_string(),
+                },
+            ],
+            default_role: default_role.map(|r| r.to_string()),
+            console_url: "/".to_string(),
+        })
+    }
+
+    fn claims(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_map_roles() {
+        let provider = provider(None);
+        assert_eq!(
+            provider.map_roles(&claims(serde_json::json!({
+                "groups": ["staff", "registry-admins"],
+                "department": "sre"
+            }))),
+            vec!["admin", "operator"]
+        );
+        assert_eq!(
+            provider.map_roles(&claims(serde_json::json!({"groups": "registry-admins"}))),
+            vec!["admin"]
+        );
+        assert!(provider.map_roles(&claims(serde_json::json!({"groups": ["staff"]}))).is_empty());
+
+        let provider = self::provider(Some("viewer"));
+        assert_eq!(provider.map_roles(&claims(serde_json::json!({}))), vec!["viewer"]);
+    }
+
+    #[test]
+    fn test_pkce_code_challenge() {
+        // RFC 7636 附录 B 的示例
+        let login = OidcLogin {
+            state: String::new(),
+            nonce: String::new(),
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+        };
+        assert_eq!(login.code_challenge(), "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+
+        let generated = OidcLogin::generate();
+        assert_eq!(generated.code_verifier.len(), 64);
+        assert_ne!(generated.state, generated.nonce);
+    }
+
+    #[test]
+    fn test_identity_from_claims() {
+        let provider = provider(Some("viewer"));
+        let identity = provider
+            .identity_from_claims(
+                "https://idp.example.com",
+                &claims(serde_json::json!({
+                    "sub": "u-123",
+                    "preferred_username": "alice",
+                    "email": "alice@example.com"
+                })),
+            )
+            .unwrap();
+        assert_eq!(identity.subject, "u-123");
+        assert_eq!(identity.username, "alice");
+        assert_eq!(identity.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(identity.roles, vec!["viewer"]);
+
+        assert!(
+            provider
+                .identity_from_claims("iss", &claims(serde_json::json!({"sub": "u-123"})))
+                .is_err()
+        );
+    }
+}
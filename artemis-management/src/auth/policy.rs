@@ -0,0 +1,225 @@
+//! Console login hardening: password policy checks and failed-login throttling.
+
+use artemis_service::config::{LockoutConfig, PasswordPolicyConfig};
+use dashmap::DashMap;
+
+/// 校验密码是否满足密码策略
+pub fn validate_password(
+    policy: &PasswordPolicyConfig,
+    username: &str,
+    password: &str,
+) -> Result<(), String> {
+    if password.chars().count() < policy.min_length {
+        return Err(format!("Password must be at least {} characters long", policy.min_length));
+    }
+    if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        return Err("Password must contain an uppercase letter".to_string());
+    }
+    if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        return Err("Password must contain a lowercase letter".to_string());
+    }
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Password must contain a digit".to_string());
+    }
+    if policy.require_special && password.chars().all(|c| c.is_alphanumeric()) {
+        return Err("Password must contain a special character".to_string());
+    }
+    if password.eq_ignore_ascii_case(username) {
+        return Err("Password must not be the same as the username".to_string());
+    }
+    Ok(())
+}
+
+/// 失败计数
+#[derive(Debug, Clone, Default)]
+struct FailureRecord {
+    /// 当前统计窗口的开始时间
+    window_start: i64,
+    failures: u32,
+    /// 锁定截止时间
+    locked_until: Option<i64>,
+}
+
+/// 登录失败节流器
+///
+/// 按账号和来源 IP 分别统计窗口内的失败次数, 超过阈值后临时锁定.
+/// 计数只保存在内存中, 重启后清零.
+pub struct LoginThrottle {
+    config: LockoutConfig,
+    accounts: DashMap<String, FailureRecord>, // username -> FailureRecord
+    ips: DashMap<String, FailureRecord>,      // ip -> FailureRecord
+}
+
+impl LoginThrottle {
+    pub fn new(config: LockoutConfig) -> Self {
+        Self { config, accounts: DashMap::new(), ips: DashMap::new() }
+    }
+
+    pub fn config(&self) -> &LockoutConfig {
+        &self.config
+    }
+
+    /// 检查账号或 IP 是否处于锁定状态, 返回剩余锁定秒数
+    pub fn locked_for(&self, username: &str, ip: Option<&str>, now: i64) -> Option<i64> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let account = Self::remaining(&self.accounts, username, now);
+        let ip = ip.and_then(|ip| Self::remaining(&self.ips, ip, now));
+        account.max(ip)
+    }
+
+    /// 记录一次失败登录
+    pub fn record_failure(&self, username: &str, ip: Option<&str>, now: i64) {
+        if !self.config.enabled {
+            return;
+        }
+
+        self.bump(&self.accounts, username, self.config.max_failures_per_account, now);
+        if let Some(ip) = ip {
+            self.bump(&self.ips, ip, self.config.max_failures_per_ip, now);
+        }
+
+        // 顺带清理早已过期的记录, 避免随机用户名撑大内存
+        let window = self.config.failure_window_secs as i64;
+        let stale = |r: &FailureRecord| {
+            r.locked_until.is_none_or(|until| until <= now) && r.window_start + window <= now
+        };
+        if self.accounts.len() > 10_000 {
+            self.accounts.retain(|_, r| !stale(r));
+        }
+        if self.ips.len() > 10_000 {
+            self.ips.retain(|_, r| !stale(r));
+        }
+    }
+
+    /// 登录成功后清除账号的失败计数
+    ///
+    /// IP 计数不清除, 否则持有一个有效账号即可重置对其他账号的猜测次数
+    pub fn record_success(&self, username: &str) {
+        self.accounts.remove(username);
+    }
+
+    /// 手动解除账号锁定
+    pub fn unlock(&self, username: &str) {
+        self.accounts.remove(username);
+    }
+
+    fn remaining(map: &DashMap<String, FailureRecord>, key: &str, now: i64) -> Option<i64> {
+        map.get(key)
+            .and_then(|record| record.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    fn bump(&self, map: &DashMap<String, FailureRecord>, key: &str, limit: u32, now: i64) {
+        let mut record = map.entry(key.to_string()).or_default();
+
+        if record.window_start + self.config.failure_window_secs as i64 <= now {
+            record.window_start = now;
+            record.failures = 0;
+        }
+        record.failures += 1;
+
+        if limit > 0 && record.failures >= limit {
+            record.locked_until = Some(now + self.config.lockout_duration_secs as i64);
+            record.window_start = now;
+            record.failures = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_password() {
+        let policy = PasswordPolicyConfig {
+            min_length: 8,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: true,
+            history_size: 3,
+        };
+
+        assert!(validate_password(&policy, "alice", "Sh0rt!").is_err());
+        assert!(validate_password(&policy, "alice", "lowercase1!").is_err());
+        assert!(validate_password(&policy, "alice", "UPPERCASE1!").is_err());
+        assert!(validate_password(&policy, "alice", "NoDigits!!").is_err());
+        assert!(validate_password(&policy, "alice", "NoSpecial123").is_err());
+        assert!(validate_password(&policy, "Alice-123", "alice-123").is_err());
+        assert!(validate_password(&policy, "alice", "G00d-Passw0rd").is_ok());
+
+        let default = PasswordPolicyConfig::default();
+        assert!(validate_password(&default, "alice", "short").is_err());
+        assert!(validate_password(&default, "alice", "long enough").is_ok());
+    }
+
+    fn throttle() -> LoginThrottle {
+        LoginThrottle::new(LockoutConfig {
+            max_failures_per_account: 3,
+            max_failures_per_ip: 5,
+            failure_window_secs: 60,
+            lockout_duration_secs: 300,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_account_lockout() {
+        let throttle = throttle();
+        let now = 1_000;
+
+        throttle.record_failure("alice", None, now);
+        throttle.record_failure("alice", None, now + 1);
+        assert_eq!(throttle.locked_for("alice", None, now + 1), None);
+
+        throttle.record_failure("alice", None, now + 2);
+        assert_eq!(throttle.locked_for("alice", None, now + 2), Some(300));
+        assert_eq!(throttle.locked_for("bob", None, now + 2), None);
+
+        // 锁定到期后自动解除
+        assert_eq!(throttle.locked_for("alice", None, now + 302), None);
+    }
+
+    #[test]
+    fn test_failures_outside_window_are_forgotten() {
+        let throttle = throttle();
+        throttle.record_failure("alice", None, 1_000);
+        throttle.record_failure("alice", None, 1_001);
+        throttle.record_failure("alice", None, 1_100);
+        assert_eq!(throttle.locked_for("alice", None, 1_100), None);
+    }
+
+    #[test]
+    fn test_ip_lockout_and_success_reset() {
+        let throttle = throttle();
+        let now = 1_000;
+
+        // 同一 IP 对不同账号的猜测累计到 IP 计数
+        for (i, user) in ["a", "b", "c", "d", "e"].iter().enumerate() {
+            throttle.record_failure(user, Some("10.0.0.1"), now + i as i64);
+        }
+        assert!(throttle.locked_for("anyone", Some("10.0.0.1"), now + 5).is_some());
+        assert_eq!(throttle.locked_for("anyone", Some("10.0.0.2"), now + 5), None);
+
+        throttle.record_failure("alice", None, now);
+        throttle.record_failure("alice", None, now);
+        throttle.record_success("alice");
+        throttle.record_failure("alice", None, now);
+        assert_eq!(throttle.locked_for("alice", None, now), None);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let throttle =
+            LoginThrottle::new(LockoutConfig { enabled: false, ..LockoutConfig::default() });
+        for _ in 0..100 {
+            throttle.record_failure("alice", Some("10.0.0.1"), 1_000);
+        }
+        assert_eq!(throttle.locked_for("alice", Some("10.0.0.1"), 1_000), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! RFC 6238 time-based one-time passwords (HMAC-SHA1, 6 digits, 30 s step)
+//! and recovery codes for console two-factor authentication.
+
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// 时间步长 (秒)
+pub const TOTP_STEP_SECS: i64 = 30;
+/// 验证码位数
+pub const TOTP_DIGITS: u32 = 6;
+/// 允许前后偏移的时间步数, 容忍客户端时钟偏差
+pub const TOTP_SKEW_STEPS: i64 = 1;
+/// 签发方名称 (显示在验证器应用中)
+pub const TOTP_ISSUER: &str = "Artemis";
+/// 每次生成的恢复码数量
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+const SECRET_BYTES: usize = 20;
+const BASE32: Alphabet = Alphabet::Rfc4648 { padding: false };
+
+/// 生成新的 Base32 编码密钥
+pub fn generate_secret() -> String {
+    let bytes: [u8; SECRET_BYTES] = rand::thread_rng().r#gen();
+    base32::encode(BASE32, &bytes)
+}
+
+/// 计算指定时间步的验证码
+pub fn code_at(secret: &str, step: i64) -> Result<String, String> {
+    let key = base32::decode(BASE32, secret).ok_or_else(|| "Invalid TOTP secret".to_string())?;
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(&key).map_err(|e| format!("Invalid TOTP secret: {}", e))?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // 动态截断 (RFC 4226 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    Ok(format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS), width = TOTP_DIGITS as usize))
+}
+
+/// 校验验证码, 成功时返回匹配的时间步 (用于防重放)
+pub fn verify(secret: &str, code: &str, now: i64) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = now / TOTP_STEP_SECS;
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS)
+        .find(|step| code_at(secret, *step).is_ok_and(|expected| expected == code))
+}
+
+/// 生成验证器应用使用的 otpauth:// 绑定地址
+pub fn provisioning_uri(account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = TOTP_ISSUER,
+        account =
+            account.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-', "_"),
+        secret = secret,
+        digits = TOTP_DIGITS,
+        period = TOTP_STEP_SECS,
+    )
+}
+
+/// 生成一组恢复码 (明文只在生成时返回一次)
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let bytes: [u8; 5] = rng.r#gen();
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// 恢复码摘要 (忽略大小写和分隔符)
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 附录 B 的 SHA1 测试密钥 "12345678901234567890"
+    fn rfc_secret() -> String {
+        base32::encode(BASE32, b"12345678901234567890")
+    }
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        // RFC 中为 8 位, 取后 6 位
+        let cases = [
+            (59, "287082"),
+            (1_111_111_109, "081804"),
+            (1_111_111_111, "050471"),
+            (1_234_567_890, "005924"),
+            (2_000_000_000, "279037"),
+        ];
+        for (time, expected) in cases {
+            assert_eq!(code_at(&rfc_secret(), time / TOTP_STEP_SECS).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_verify_with_skew() {
+        let secret = generate_secret();
+        let now = 1_700_000_000;
+        let step = now / TOTP_STEP_SECS;
+
+        let current = code_at(&secret, step).unwrap();
+        assert_eq!(verify(&secret, &current, now), Some(step));
+
+        let previous = code_at(&secret, step - 1).unwrap();
+        assert_eq!(verify(&secret, &previous, now), Some(step - 1));
+
+        let stale = code_at(&secret, step - 3).unwrap();
+        assert_eq!(verify(&secret, &stale, now), None);
+
+        assert_eq!(verify(&secret, "abcdef", now), None);
+        assert_eq!(verify(&secret, "12345", now), None);
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let uri = provisioning_uri("alice@example.com", "JBSWY3DPEHPK3PXP");
+        assert!(
+            uri.starts_with("otpauth://totp/Artemis:alice_example.com?secret=JBSWY3DPEHPK3PXP")
+        );
+        assert!(uri.contains("issuer=Artemis"));
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(codes[0].len(), 11);
+        assert_eq!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[0].to_uppercase()));
+        assert_eq!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[0].replace('-', "")));
+        assert_ne!(hash_recovery_code(&codes[0]), hash_recovery_code(&codes[1]));
+    }
+}
@@ -1,13 +1,14 @@
+use crate::db::statement;
 use crate::model::CanaryConfig;
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
-pub struct CanaryConfigDao {
-    conn: DatabaseConnection,
+pub struct CanaryConfigDao<C = DatabaseConnection> {
+    conn: C,
 }
 
-impl CanaryConfigDao {
-    pub fn new(conn: DatabaseConnection) -> Self {
+impl<C: ConnectionTrait> CanaryConfigDao<C> {
+    pub fn new(conn: C) -> Self {
         Self { conn }
     }
 
@@ -15,7 +16,7 @@ impl CanaryConfigDao {
     pub async fn upsert_config(&self, config: &CanaryConfig) -> anyhow::Result<()> {
         let ip_whitelist_json = serde_json::to_string(&config.ip_whitelist)?;
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO canary_config (service_id, ip_whitelist, enabled)
@@ -35,7 +36,7 @@ impl CanaryConfigDao {
 
     /// 删除金丝雀配置
     pub async fn delete_config(&self, service_id: &str) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM canary_config WHERE service_id = ?",
             vec![Value::from(service_id)],
@@ -46,7 +47,7 @@ impl CanaryConfigDao {
 
     /// 获取金丝雀配置
     pub async fn get_config(&self, service_id: &str) -> anyhow::Result<Option<CanaryConfig>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT service_id, ip_whitelist, enabled
@@ -75,7 +76,7 @@ impl CanaryConfigDao {
 
     /// 列出所有金丝雀配置
     pub async fn list_configs(&self) -> anyhow::Result<Vec<CanaryConfig>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT service_id, ip_whitelist, enabled
@@ -103,7 +104,7 @@ impl CanaryConfigDao {
 
     /// 设置启用状态
     pub async fn set_enabled(&self, service_id: &str, enabled: bool) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             UPDATE canary_config
@@ -117,3 +118,33 @@ impl CanaryConfigDao {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_canary_configs() {
+        let dao = CanaryConfigDao::new(crate::db::test_database().await.conn().clone());
+        let mut config = CanaryConfig {
+            service_id: "svc-1".to_string(),
+            ip_whitelist: vec!["10.0.0.1".to_string()],
+            enabled: true,
+        };
+        dao.upsert_config(&config).await.unwrap();
+
+        // 再次写入时更新白名单
+        config.ip_whitelist.push("10.0.0.2".to_string());
+        dao.upsert_config(&config).await.unwrap();
+        let saved = dao.get_config("svc-1").await.unwrap().unwrap();
+        assert_eq!(saved.ip_whitelist.len(), 2);
+        assert!(saved.enabled);
+
+        dao.set_enabled("svc-1", false).await.unwrap();
+        assert!(!dao.get_config("svc-1").await.unwrap().unwrap().enabled);
+        assert_eq!(dao.list_configs().await.unwrap().len(), 1);
+
+        dao.delete_config("svc-1").await.unwrap();
+        assert!(dao.get_config("svc-1").await.unwrap().is_none());
+    }
+}
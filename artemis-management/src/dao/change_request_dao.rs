@@ -0,0 +1,220 @@
+use crate::db::{RowExt, statement};
+use crate::model::{ChangeRequest, ChangeRequestStatus, PendingOperation};
+use sea_orm::sea_query::Value;
+use sea_orm::{ConnectionTrait, DatabaseConnection, QueryResult};
+
+pub struct ChangeRequestDao<C = DatabaseConnection> {
+    conn: C,
+}
+
+impl<C: ConnectionTrait> ChangeRequestDao<C> {
+    pub fn new(conn: C) -> Self {
+        Self { conn }
+    }
+
+    /// 插入变更请求
+    pub async fn insert_request(&self, request: &ChangeRequest) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            INSERT INTO change_request (request_id, operation_type, target_id, operation, affected_instances,
+                status, requested_by, requested_at, expires_at, reviewed_by, reviewed_at, comment, error)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            vec![
+                Value::from(&request.request_id),
+                Value::from(request.operation.kind().as_str()),
+                Value::from(request.operation.target()),
+                Value::from(serde_json::to_string(&request.operation)?),
+                Value::from(request.affected_instances as i64),
+                Value::from(request.status.as_str()),
+                Value::from(&request.requested_by),
+                Value::from(request.requested_at),
+                Value::from(request.expires_at),
+                Value::from(request.reviewed_by.clone()),
+                Value::from(request.reviewed_at),
+                Value::from(request.comment.clone()),
+                Value::from(request.error.clone()),
+            ],
+        );
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+
+    /// 当前状态为 expected 时更新审批结果 (状态、审批人、意见和执行错误), 返回是否更新
+    pub async fn update_review_if(
+        &self,
+        request: &ChangeRequest,
+        expected: ChangeRequestStatus,
+    ) -> anyhow::Result<bool> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            UPDATE change_request
+            SET status = ?, reviewed_by = ?, reviewed_at = ?, comment = ?, error = ?
+            WHERE request_id = ? AND status = ?
+            "#,
+            vec![
+                Value::from(request.status.as_str()),
+                Value::from(request.reviewed_by.clone()),
+                Value::from(request.reviewed_at),
+                Value::from(request.comment.clone()),
+                Value::from(request.error.clone()),
+                Value::from(&request.request_id),
+                Value::from(expected.as_str()),
+            ],
+        );
+        Ok(self.conn.execute(stmt).await?.rows_affected() > 0)
+    }
+
+    /// 获取变更请求
+    pub async fn get_request(&self, request_id: &str) -> anyhow::Result<Option<ChangeRequest>> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            r#"
+            SELECT request_id, operation, affected_instances, status, requested_by, requested_at,
+                expires_at, reviewed_by, reviewed_at, comment, error
+            FROM change_request
+            WHERE request_id = ?
+            "#,
+            vec![Value::from(request_id)],
+        );
+        match self.conn.query_one(stmt).await? {
+            Some(row) => Ok(Some(Self::from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 列出变更请求, 可按状态过滤
+    pub async fn list_requests(
+        &self,
+        status: Option<ChangeRequestStatus>,
+    ) -> anyhow::Result<Vec<ChangeRequest>> {
+        let (filter, values) = match status {
+            Some(status) => ("WHERE status = ?", vec![Value::from(status.as_str())]),
+            None => ("", vec![]),
+        };
+        self.query(&format!("{} ORDER BY requested_at", filter), values).await
+    }
+
+    /// 列出同一操作类型和目标的待审批请求
+    pub async fn list_pending_for(
+        &self,
+        operation: &PendingOperation,
+    ) -> anyhow::Result<Vec<ChangeRequest>> {
+        self.query(
+            "WHERE status = ? AND operation_type = ? AND target_id = ? ORDER BY requested_at",
+            vec![
+                Value::from(ChangeRequestStatus::Pending.as_str()),
+                Value::from(operation.kind().as_str()),
+                Value::from(operation.target()),
+            ],
+        )
+        .await
+    }
+
+    /// 列出已超过有效期但仍待审批的请求
+    pub async fn list_expired_pending(&self, now: i64) -> anyhow::Result<Vec<ChangeRequest>> {
+        self.query(
+            "WHERE status = ? AND expires_at <= ? ORDER BY requested_at",
+            vec![Value::from(ChangeRequestStatus::Pending.as_str()), Value::from(now)],
+        )
+        .await
+    }
+
+    async fn query(&self, clause: &str, values: Vec<Value>) -> anyhow::Result<Vec<ChangeRequest>> {
+        let sql = format!(
+            r#"
+            SELECT request_id, operation, affected_instances, status, requested_by, requested_at,
+                expires_at, reviewed_by, reviewed_at, comment, error
+            FROM change_request
+            {}
+            "#,
+            clause
+        );
+        let stmt = statement(self.conn.get_database_backend(), &sql, values);
+        let rows = self.conn.query_all(stmt).await?;
+        rows.iter().map(Self::from_row).collect()
+    }
+
+    fn from_row(row: &QueryResult) -> anyhow::Result<ChangeRequest> {
+        let operation: String = row.get_column("operation")?;
+        let operation: PendingOperation = serde_json::from_str(&operation)?;
+        let status: String = row.get_column("status")?;
+        let affected_instances: i64 = row.get_column("affected_instances")?;
+
+        Ok(ChangeRequest {
+            request_id: row.get_column("request_id")?,
+            operation,
+            affected_instances: affected_instances as usize,
+            status: status.parse().map_err(anyhow::Error::msg)?,
+            requested_by: row.get_column("requested_by")?,
+            requested_at: row.get_column("requested_at")?,
+            expires_at: row.get_column("expires_at")?,
+            reviewed_by: row.get_column("reviewed_by")?,
+            reviewed_at: row.get_column("reviewed_at")?,
+            comment: row.get_column("comment")?,
+            error: row.get_column("error")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_change_requests() {
+        let dao = ChangeRequestDao::new(crate::db::test_database().await.conn().clone());
+        let mut request = ChangeRequest {
+            request_id: "cr-1".to_string(),
+            operation: PendingOperation::ServerPullOut {
+                server_id: "10.0.0.1".to_string(),
+                region_id: "us-east".to_string(),
+                operation_complete: true,
+                operator_id: "ops".to_string(),
+            },
+            affected_instances: 12,
+            status: ChangeRequestStatus::Pending,
+            requested_by: "user-ops".to_string(),
+            requested_at: 100,
+            expires_at: 200,
+            reviewed_by: None,
+            reviewed_at: None,
+            comment: None,
+            error: None,
+        };
+        dao.insert_request(&request).await.unwrap();
+
+        let saved = dao.get_request("cr-1").await.unwrap().unwrap();
+        assert_eq!(saved.operation, request.operation);
+        assert_eq!(saved.affected_instances, 12);
+        assert_eq!(saved.status, ChangeRequestStatus::Pending);
+        assert!(saved.reviewed_by.is_none());
+
+        assert_eq!(dao.list_pending_for(&request.operation).await.unwrap().len(), 1);
+        assert_eq!(dao.list_expired_pending(199).await.unwrap().len(), 0);
+        assert_eq!(dao.list_expired_pending(200).await.unwrap().len(), 1);
+
+        request.status = ChangeRequestStatus::Approved;
+        request.reviewed_by = Some("user-lead".to_string());
+        request.reviewed_at = Some(150);
+        request.comment = Some("maintenance window".to_string());
+        assert!(dao.update_review_if(&request, ChangeRequestStatus::Pending).await.unwrap());
+
+        // 状态已变化, 第二次审批不会覆盖
+        let mut second = request.clone();
+        second.status = ChangeRequestStatus::Rejected;
+        second.reviewed_by = Some("user-admin".to_string());
+        assert!(!dao.update_review_if(&second, ChangeRequestStatus::Pending).await.unwrap());
+        assert!(dao.list_pending_for(&request.operation).await.unwrap().is_empty());
+
+        let requests = dao.list_requests(None).await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].status, ChangeRequestStatus::Approved);
+        assert_eq!(requests[0].reviewed_by.as_deref(), Some("user-lead"));
+        assert_eq!(requests[0].comment.as_deref(), Some("maintenance window"));
+        assert_eq!(dao.list_requests(Some(ChangeRequestStatus::Pending)).await.unwrap().len(), 0);
+        assert!(dao.get_request("cr-2").await.unwrap().is_none());
+    }
+}
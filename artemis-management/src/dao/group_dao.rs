@@ -1,13 +1,14 @@
+use crate::db::{RowExt, statement};
 use crate::model::{GroupStatus, GroupTag, GroupType, ServiceGroup};
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
-pub struct GroupDao {
-    conn: DatabaseConnection,
+pub struct GroupDao<C = DatabaseConnection> {
+    conn: C,
 }
 
-impl GroupDao {
-    pub fn new(conn: DatabaseConnection) -> Self {
+impl<C: ConnectionTrait> GroupDao<C> {
+    pub fn new(conn: C) -> Self {
         Self { conn }
     }
 
@@ -18,7 +19,7 @@ impl GroupDao {
             GroupType::Logical => "logical",
         };
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO service_group (NAME, SERVICE_ID, REGION_ID, ZONE_ID, APP_ID, DESCRIPTION, STATUS, type)
@@ -55,7 +56,7 @@ impl GroupDao {
             GroupType::Logical => "logical",
         };
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             UPDATE service_group
@@ -78,12 +79,7 @@ impl GroupDao {
         self.conn.execute(stmt).await?;
 
         // 删除旧标签并插入新标签
-        let delete_stmt = Statement::from_sql_and_values(
-            self.conn.get_database_backend(),
-            "DELETE FROM service_group_tag WHERE GROUP_ID = ?",
-            vec![Value::from(&group.name)],
-        );
-        self.conn.execute(delete_stmt).await?;
+        self.delete_tags(&group.name).await?;
 
         if let Some(tags) = &group.tags {
             for tag in tags {
@@ -96,7 +92,9 @@ impl GroupDao {
 
     /// 删除服务分组 (兼容 Java 列名)
     pub async fn delete_group(&self, group_id: &str) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        self.delete_tags(group_id).await?;
+
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM service_group WHERE NAME = ?",
             vec![Value::from(group_id)],
@@ -107,7 +105,7 @@ impl GroupDao {
 
     /// 获取服务分组 (兼容 Java 列名)
     pub async fn get_group(&self, group_id: &str) -> anyhow::Result<Option<ServiceGroup>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT NAME, SERVICE_ID, REGION_ID, ZONE_ID, DESCRIPTION, type
@@ -132,13 +130,13 @@ impl GroupDao {
 
                 Ok(Some(ServiceGroup {
                     group_id: None,
-                    service_id: row.try_get("", "SERVICE_ID")?,
-                    region_id: row.try_get("", "REGION_ID")?,
-                    zone_id: row.try_get("", "ZONE_ID")?,
-                    name: row.try_get("", "NAME")?,
+                    service_id: row.get_column("SERVICE_ID")?,
+                    region_id: row.get_column("REGION_ID")?,
+                    zone_id: row.get_column("ZONE_ID")?,
+                    name: row.get_column("NAME")?,
                     group_type,
                     status: GroupStatus::Active,
-                    description: row.try_get("", "DESCRIPTION")?,
+                    description: row.get_column("DESCRIPTION")?,
                     tags: if tags.is_empty() { None } else { Some(tags) },
                     metadata: None,
                     created_at: None,
@@ -151,7 +149,7 @@ impl GroupDao {
 
     /// 列出所有服务分组 (兼容 Java 列名)
     pub async fn list_groups(&self) -> anyhow::Result<Vec<ServiceGroup>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT NAME, SERVICE_ID, REGION_ID, ZONE_ID, DESCRIPTION, type
@@ -171,18 +169,18 @@ impl GroupDao {
                 _ => GroupType::Physical,
             };
 
-            let name: String = row.try_get("", "NAME")?;
+            let name: String = row.get_column("NAME")?;
             let tags = self.get_tags(&name).await?;
 
             groups.push(ServiceGroup {
                 group_id: None,
-                service_id: row.try_get("", "SERVICE_ID")?,
-                region_id: row.try_get("", "REGION_ID")?,
-                zone_id: row.try_get("", "ZONE_ID")?,
+                service_id: row.get_column("SERVICE_ID")?,
+                region_id: row.get_column("REGION_ID")?,
+                zone_id: row.get_column("ZONE_ID")?,
                 name,
                 group_type,
                 status: GroupStatus::Active,
-                description: row.try_get("", "DESCRIPTION")?,
+                description: row.get_column("DESCRIPTION")?,
                 tags: if tags.is_empty() { None } else { Some(tags) },
                 metadata: None,
                 created_at: None,
@@ -193,15 +191,15 @@ impl GroupDao {
         Ok(groups)
     }
 
-    /// 插入标签 (兼容 Java 列名)
+    /// 插入标签 (兼容 Java 列名, GROUP_ID 关联分组主键)
     async fn insert_tag(&self, group_id: &str, tag: &GroupTag) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO service_group_tag (GROUP_ID, TAG, VALUE)
-            VALUES (?, ?, ?)
+            SELECT id, ?, ? FROM service_group WHERE NAME = ?
             "#,
-            vec![Value::from(group_id), Value::from(&tag.key), Value::from(&tag.value)],
+            vec![Value::from(&tag.key), Value::from(&tag.value), Value::from(group_id)],
         );
         self.conn.execute(stmt).await?;
         Ok(())
@@ -209,12 +207,13 @@ impl GroupDao {
 
     /// 获取分组的所有标签 (兼容 Java 列名)
     async fn get_tags(&self, group_id: &str) -> anyhow::Result<Vec<GroupTag>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
-            SELECT TAG, VALUE
-            FROM service_group_tag
-            WHERE GROUP_ID = ?
+            SELECT t.TAG, t.VALUE
+            FROM service_group_tag t
+            JOIN service_group g ON t.GROUP_ID = g.id
+            WHERE g.NAME = ?
             "#,
             vec![Value::from(group_id)],
         );
@@ -224,9 +223,70 @@ impl GroupDao {
         Ok(rows
             .into_iter()
             .map(|row| GroupTag {
-                key: row.try_get("", "TAG").unwrap_or_default(),
-                value: row.try_get("", "VALUE").unwrap_or_default(),
+                key: row.get_column("TAG").unwrap_or_default(),
+                value: row.get_column("VALUE").unwrap_or_default(),
             })
             .collect())
     }
+
+    /// 删除分组的所有标签
+    async fn delete_tags(&self, group_id: &str) -> anyhow::Result<()> {
+        let stmt = statement(
+            self.conn.get_database_backend(),
+            "DELETE FROM service_group_tag WHERE GROUP_ID IN (SELECT id FROM service_group WHERE NAME = ?)",
+            vec![Value::from(group_id)],
+        );
+        self.conn.execute(stmt).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_group(name: &str, tags: Vec<(&str, &str)>) -> ServiceGroup {
+        ServiceGroup {
+            group_id: None,
+            service_id: "test-service".to_string(),
+            region_id: "us-east".to_string(),
+            zone_id: "zone-1".to_string(),
+            name: name.to_string(),
+            group_type: GroupType::Logical,
+            status: GroupStatus::Active,
+            description: Some("canary".to_string()),
+            tags: Some(
+                tags.into_iter()
+                    .map(|(k, v)| GroupTag { key: k.to_string(), value: v.to_string() })
+                    .collect(),
+            ),
+            metadata: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_group_tags_roundtrip() {
+        let dao = GroupDao::new(crate::db::test_database().await.conn().clone());
+        dao.insert_group(&create_test_group("g1", vec![("env", "prod")])).await.unwrap();
+        dao.insert_group(&create_test_group("g2", vec![("env", "test")])).await.unwrap();
+
+        let group = dao.get_group("g1").await.unwrap().unwrap();
+        assert_eq!(group.group_type, GroupType::Logical);
+        assert_eq!(group.tags.unwrap()[0].value, "prod");
+
+        // 更新后标签整体替换, 不影响其他分组
+        dao.update_group(&create_test_group("g1", vec![("env", "staging"), ("team", "infra")]))
+            .await
+            .unwrap();
+        let tags = dao.get_group("g1").await.unwrap().unwrap().tags.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert!(tags.iter().any(|t| t.key == "env" && t.value == "staging"));
+        assert_eq!(dao.get_group("g2").await.unwrap().unwrap().tags.unwrap()[0].value, "test");
+
+        dao.delete_group("g1").await.unwrap();
+        assert!(dao.get_group("g1").await.unwrap().is_none());
+        assert_eq!(dao.list_groups().await.unwrap().len(), 1);
+    }
 }
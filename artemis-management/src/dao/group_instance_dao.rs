@@ -1,29 +1,27 @@
+use crate::db::{RowExt, statement};
 use crate::model::GroupInstance;
 use anyhow::Result;
-use sea_orm::{ConnectionTrait, DatabaseBackend, DbConn, Statement};
+use sea_orm::{ConnectionTrait, DbConn};
 
 /// GroupInstance DAO - 分组实例绑定关系持久化 (兼容 Java 表结构)
-pub struct GroupInstanceDao {
-    db: DbConn,
+pub struct GroupInstanceDao<C = DbConn> {
+    db: C,
 }
 
-impl GroupInstanceDao {
-    pub fn new(db: DbConn) -> Self {
+impl<C: ConnectionTrait> GroupInstanceDao<C> {
+    pub fn new(db: C) -> Self {
         Self { db }
     }
 
     /// 插入分组实例绑定 (兼容 Java 表结构)
     pub async fn insert(&self, binding: &GroupInstance) -> Result<i64> {
-        let stmt = Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
+        let stmt = statement(
+            self.db.get_database_backend(),
             r#"
                 INSERT INTO service_group_instance (GROUP_ID, INSTANCE_ID)
                 VALUES (?, ?)
             "#,
-            vec![
-                binding.group_id.into(),
-                binding.instance_id.clone().into(),
-            ],
+            vec![binding.group_id.into(), binding.instance_id.clone().into()],
         );
 
         self.db.execute(stmt).await?;
@@ -38,8 +36,8 @@ impl GroupInstanceDao {
         _region_id: &str,
         _zone_id: &str,
     ) -> Result<bool> {
-        let stmt = Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
+        let stmt = statement(
+            self.db.get_database_backend(),
             r#"
                 DELETE FROM service_group_instance
                 WHERE GROUP_ID = ? AND INSTANCE_ID = ?
@@ -53,8 +51,8 @@ impl GroupInstanceDao {
 
     /// 获取分组的所有绑定实例
     pub async fn get_by_group(&self, group_id: i64) -> Result<Vec<GroupInstance>> {
-        let stmt = Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
+        let stmt = statement(
+            self.db.get_database_backend(),
             r#"
                 SELECT id, GROUP_ID, INSTANCE_ID
                 FROM service_group_instance
@@ -67,18 +65,16 @@ impl GroupInstanceDao {
 
         let bindings = result
             .iter()
-            .map(|row| {
-                GroupInstance {
-                    id: row.try_get("", "id").ok(),
-                    group_id: row.try_get::<i64>("", "GROUP_ID").unwrap_or(0),
-                    region_id: String::new(),
-                    zone_id: String::new(),
-                    service_id: String::new(),
-                    instance_id: row.try_get("", "INSTANCE_ID").unwrap_or_default(),
-                    binding_type: None,
-                    operator_id: None,
-                    created_at: None,
-                }
+            .map(|row| GroupInstance {
+                id: row.try_get("", "id").ok(),
+                group_id: row.get_column::<i64>("GROUP_ID").unwrap_or(0),
+                region_id: String::new(),
+                zone_id: String::new(),
+                service_id: String::new(),
+                instance_id: row.get_column("INSTANCE_ID").unwrap_or_default(),
+                binding_type: None,
+                operator_id: None,
+                created_at: None,
             })
             .collect();
 
@@ -92,8 +88,8 @@ impl GroupInstanceDao {
         _region_id: &str,
         _zone_id: &str,
     ) -> Result<Vec<GroupInstance>> {
-        let stmt = Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
+        let stmt = statement(
+            self.db.get_database_backend(),
             r#"
                 SELECT id, GROUP_ID, INSTANCE_ID
                 FROM service_group_instance
@@ -106,18 +102,16 @@ impl GroupInstanceDao {
 
         let bindings = result
             .iter()
-            .map(|row| {
-                GroupInstance {
-                    id: row.try_get("", "id").ok(),
-                    group_id: row.try_get::<i64>("", "GROUP_ID").unwrap_or(0),
-                    region_id: String::new(),
-                    zone_id: String::new(),
-                    service_id: String::new(),
-                    instance_id: row.try_get("", "INSTANCE_ID").unwrap_or_default(),
-                    binding_type: None,
-                    operator_id: None,
-                    created_at: None,
-                }
+            .map(|row| GroupInstance {
+                id: row.try_get("", "id").ok(),
+                group_id: row.get_column::<i64>("GROUP_ID").unwrap_or(0),
+                region_id: String::new(),
+                zone_id: String::new(),
+                service_id: String::new(),
+                instance_id: row.get_column("INSTANCE_ID").unwrap_or_default(),
+                binding_type: None,
+                operator_id: None,
+                created_at: None,
             })
             .collect();
 
@@ -137,8 +131,8 @@ impl GroupInstanceDao {
 
     /// 删除分组的所有绑定
     pub async fn delete_all_by_group(&self, group_id: i64) -> Result<u64> {
-        let stmt = Statement::from_sql_and_values(
-            DatabaseBackend::Sqlite,
+        let stmt = statement(
+            self.db.get_database_backend(),
             "DELETE FROM service_group_instance WHERE GROUP_ID = ?",
             vec![group_id.into()],
         );
@@ -146,34 +140,53 @@ impl GroupInstanceDao {
         let result = self.db.execute(stmt).await?;
         Ok(result.rows_affected())
     }
+
+    /// 列出所有绑定 (分组名称, 实例 ID)
+    pub async fn list_bindings(&self) -> Result<Vec<(String, String)>> {
+        let stmt = statement(
+            self.db.get_database_backend(),
+            r#"
+                SELECT g.NAME, gi.INSTANCE_ID
+                FROM service_group_instance gi
+                JOIN service_group g ON gi.GROUP_ID = g.id
+                ORDER BY g.NAME, gi.INSTANCE_ID
+            "#,
+            vec![],
+        );
+
+        let rows = self.db.query_all(stmt).await?;
+        rows.iter()
+            .map(|row| Ok((row.get_column("NAME")?, row.get_column("INSTANCE_ID")?)))
+            .collect()
+    }
+
+    /// 按分组名称绑定实例, 已绑定时不重复插入; 返回是否新增
+    pub async fn insert_by_group_name(&self, group_name: &str, instance_id: &str) -> Result<bool> {
+        let stmt = statement(
+            self.db.get_database_backend(),
+            r#"
+                INSERT INTO service_group_instance (GROUP_ID, INSTANCE_ID)
+                SELECT g.id, ? FROM service_group g
+                WHERE g.NAME = ? AND NOT EXISTS (
+                    SELECT 1 FROM service_group_instance gi
+                    WHERE gi.GROUP_ID = g.id AND gi.INSTANCE_ID = ?
+                )
+            "#,
+            vec![instance_id.into(), group_name.into(), instance_id.into()],
+        );
+
+        let result = self.db.execute(stmt).await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::model::BindingType;
-    use sea_orm::{Database, DatabaseConnection};
-
-    async fn create_test_db() -> DatabaseConnection {
-        let db = Database::connect("sqlite::memory:")
-            .await
-            .expect("Failed to create in-memory database");
-
-        let create_table_sql = r#"
-            CREATE TABLE IF NOT EXISTS service_group_instance (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                GROUP_ID INTEGER NOT NULL DEFAULT 0,
-                INSTANCE_ID TEXT NOT NULL DEFAULT '',
-                CREATE_TIME DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                DataChange_LastTime TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(GROUP_ID, INSTANCE_ID)
-            )
-        "#;
-
-        let stmt = Statement::from_string(DatabaseBackend::Sqlite, create_table_sql.to_string());
-        db.execute(stmt).await.expect("Failed to create table");
-
-        db
+
+    async fn create_test_db() -> DbConn {
+        crate::db::test_database().await.conn().clone()
     }
 
     fn create_test_binding(group_id: i64, instance_id: &str) -> GroupInstance {
@@ -1,10 +1,12 @@
 pub mod canary_dao;
+pub mod change_request_dao;
 pub mod group_dao;
 pub mod group_instance_dao;
 pub mod route_dao;
 pub mod zone_dao;
 
 pub use canary_dao::CanaryConfigDao;
+pub use change_request_dao::ChangeRequestDao;
 pub use group_dao::GroupDao;
 pub use group_instance_dao::GroupInstanceDao;
 pub use route_dao::RouteRuleDao;
@@ -1,14 +1,15 @@
+use crate::db::{RowExt, statement};
 use crate::model::{RouteRule, RouteRuleStatus, RouteStrategy};
 use artemis_common::model::service::ServiceGroup;
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
-pub struct RouteRuleDao {
-    conn: DatabaseConnection,
+pub struct RouteRuleDao<C = DatabaseConnection> {
+    conn: C,
 }
 
-impl RouteRuleDao {
-    pub fn new(conn: DatabaseConnection) -> Self {
+impl<C: ConnectionTrait> RouteRuleDao<C> {
+    pub fn new(conn: C) -> Self {
         Self { conn }
     }
 
@@ -25,7 +26,7 @@ impl RouteRuleDao {
         };
 
         // 使用 Java 列名: SERVICE_ID, NAME, DESCRIPTION, STATUS, strategy
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO service_route_rule (SERVICE_ID, NAME, DESCRIPTION, STATUS, strategy)
@@ -62,7 +63,7 @@ impl RouteRuleDao {
             RouteStrategy::CloseByVisit => "close-by-visit",
         };
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             UPDATE service_route_rule
@@ -84,7 +85,7 @@ impl RouteRuleDao {
         // 先获取 route_rule_id
         let route_rule_id = self.get_route_rule_id(&rule.route_id).await?;
         if let Some(id) = route_rule_id {
-            let delete_stmt = Statement::from_sql_and_values(
+            let delete_stmt = statement(
                 self.conn.get_database_backend(),
                 "DELETE FROM service_route_rule_group WHERE ROUTE_RULE_ID = ?",
                 vec![Value::from(id)],
@@ -101,7 +102,7 @@ impl RouteRuleDao {
 
     /// 获取路由规则 ID
     async fn get_route_rule_id(&self, rule_name: &str) -> anyhow::Result<Option<i64>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT id FROM service_route_rule WHERE NAME = ?",
             vec![Value::from(rule_name)],
@@ -119,7 +120,7 @@ impl RouteRuleDao {
         // 先删除关联的分组
         let route_rule_id = self.get_route_rule_id(rule_id).await?;
         if let Some(id) = route_rule_id {
-            let delete_assoc_stmt = Statement::from_sql_and_values(
+            let delete_assoc_stmt = statement(
                 self.conn.get_database_backend(),
                 "DELETE FROM service_route_rule_group WHERE ROUTE_RULE_ID = ?",
                 vec![Value::from(id)],
@@ -127,7 +128,7 @@ impl RouteRuleDao {
             self.conn.execute(delete_assoc_stmt).await?;
         }
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM service_route_rule WHERE NAME = ?",
             vec![Value::from(rule_id)],
@@ -138,7 +139,7 @@ impl RouteRuleDao {
 
     /// 获取路由规则 (不加载groups,由Manager负责加载)
     pub async fn get_rule(&self, rule_id: &str) -> anyhow::Result<Option<RouteRule>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT id, SERVICE_ID, NAME, DESCRIPTION, STATUS, strategy
@@ -152,7 +153,7 @@ impl RouteRuleDao {
 
         match result {
             Some(row) => {
-                let status_str: String = row.try_get("", "STATUS")?;
+                let status_str: String = row.get_column("STATUS")?;
                 let status = match status_str.as_str() {
                     "active" => RouteRuleStatus::Active,
                     _ => RouteRuleStatus::Inactive,
@@ -168,10 +169,10 @@ impl RouteRuleDao {
 
                 Ok(Some(RouteRule {
                     route_rule_id: Some(route_rule_id),
-                    route_id: row.try_get("", "NAME")?,
-                    service_id: row.try_get("", "SERVICE_ID")?,
-                    name: row.try_get("", "NAME")?,
-                    description: row.try_get("", "DESCRIPTION")?,
+                    route_id: row.get_column("NAME")?,
+                    service_id: row.get_column("SERVICE_ID")?,
+                    name: row.get_column("NAME")?,
+                    description: row.get_column("DESCRIPTION")?,
                     status,
                     strategy,
                     groups: vec![], // Manager通过get_rule_group_ids加载
@@ -183,7 +184,7 @@ impl RouteRuleDao {
 
     /// 列出所有路由规则 (不加载groups)
     pub async fn list_rules(&self) -> anyhow::Result<Vec<RouteRule>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT id, SERVICE_ID, NAME, DESCRIPTION, STATUS, strategy
@@ -196,7 +197,7 @@ impl RouteRuleDao {
 
         let mut rules = Vec::new();
         for row in rows {
-            let status_str: String = row.try_get("", "STATUS")?;
+            let status_str: String = row.get_column("STATUS")?;
             let status = match status_str.as_str() {
                 "active" => RouteRuleStatus::Active,
                 _ => RouteRuleStatus::Inactive,
@@ -212,10 +213,10 @@ impl RouteRuleDao {
 
             rules.push(RouteRule {
                 route_rule_id: Some(route_rule_id),
-                route_id: row.try_get("", "NAME")?,
-                service_id: row.try_get("", "SERVICE_ID")?,
-                name: row.try_get("", "NAME")?,
-                description: row.try_get("", "DESCRIPTION")?,
+                route_id: row.get_column("NAME")?,
+                service_id: row.get_column("SERVICE_ID")?,
+                name: row.get_column("NAME")?,
+                description: row.get_column("DESCRIPTION")?,
                 status,
                 strategy,
                 groups: vec![], // Manager负责加载
@@ -235,17 +236,13 @@ impl RouteRuleDao {
         // 获取 group_id (需要通过 group_name 查询)
         let group_id = self.get_group_id_by_name(&group.group_key).await?;
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO service_route_rule_group (ROUTE_RULE_ID, GROUP_ID, WEIGHT)
             VALUES (?, ?, ?)
             "#,
-            vec![
-                Value::from(route_rule_id),
-                Value::from(group_id),
-                Value::from(weight as i32),
-            ],
+            vec![Value::from(route_rule_id), Value::from(group_id), Value::from(weight as i32)],
         );
 
         self.conn.execute(stmt).await?;
@@ -254,7 +251,7 @@ impl RouteRuleDao {
 
     /// 根据 group_name 获取 group_id
     async fn get_group_id_by_name(&self, group_name: &str) -> anyhow::Result<i64> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "SELECT id FROM service_group WHERE NAME = ?",
             vec![Value::from(group_name)],
@@ -275,7 +272,7 @@ impl RouteRuleDao {
             None => return Ok(vec![]),
         };
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT rg.GROUP_ID, rg.WEIGHT, g.NAME
@@ -291,10 +288,86 @@ impl RouteRuleDao {
         Ok(rows
             .into_iter()
             .map(|row| {
-                let weight: i32 = row.try_get("", "WEIGHT").unwrap_or(100);
-                let group_name: String = row.try_get("", "NAME").unwrap_or_default();
+                let weight: i32 = row.get_column("WEIGHT").unwrap_or(100);
+                let group_name: String = row.get_column("NAME").unwrap_or_default();
                 (group_name, weight as u32)
             })
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dao::GroupDao;
+    use crate::model::{GroupStatus, GroupType};
+
+    async fn insert_group(conn: &DatabaseConnection, name: &str) {
+        let group = crate::model::ServiceGroup {
+            group_id: None,
+            service_id: "svc-1".to_string(),
+            region_id: "us-east".to_string(),
+            zone_id: "zone-1".to_string(),
+            name: name.to_string(),
+            group_type: GroupType::Physical,
+            status: GroupStatus::Active,
+            description: None,
+            tags: None,
+            metadata: None,
+            created_at: None,
+            updated_at: None,
+        };
+        GroupDao::new(conn.clone()).insert_group(&group).await.unwrap();
+    }
+
+    fn rule_group(group_key: &str, weight: u32) -> ServiceGroup {
+        ServiceGroup {
+            group_key: group_key.to_string(),
+            weight: Some(weight),
+            instance_ids: None,
+            instances: None,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_rule_with_groups() {
+        let conn = crate::db::test_database().await.conn().clone();
+        insert_group(&conn, "g1").await;
+        insert_group(&conn, "g2").await;
+        let dao = RouteRuleDao::new(conn);
+
+        let mut rule = RouteRule {
+            route_rule_id: None,
+            route_id: "rule-1".to_string(),
+            service_id: "svc-1".to_string(),
+            name: "rule-1".to_string(),
+            description: Some("weighted".to_string()),
+            status: RouteRuleStatus::Active,
+            strategy: RouteStrategy::WeightedRoundRobin,
+            groups: vec![rule_group("g1", 80), rule_group("g2", 20)],
+        };
+        dao.insert_rule(&rule).await.unwrap();
+
+        let saved = dao.get_rule("rule-1").await.unwrap().unwrap();
+        assert_eq!(saved.status, RouteRuleStatus::Active);
+        let mut groups = dao.get_rule_group_ids("rule-1").await.unwrap();
+        groups.sort();
+        assert_eq!(groups, vec![("g1".to_string(), 80), ("g2".to_string(), 20)]);
+
+        // 更新时替换分组关联
+        rule.status = RouteRuleStatus::Inactive;
+        rule.groups = vec![rule_group("g2", 100)];
+        dao.update_rule(&rule).await.unwrap();
+        assert_eq!(
+            dao.get_rule("rule-1").await.unwrap().unwrap().status,
+            RouteRuleStatus::Inactive
+        );
+        assert_eq!(dao.get_rule_group_ids("rule-1").await.unwrap(), vec![("g2".to_string(), 100)]);
+        assert_eq!(dao.list_rules().await.unwrap().len(), 1);
+
+        dao.delete_rule("rule-1").await.unwrap();
+        assert!(dao.get_rule("rule-1").await.unwrap().is_none());
+        assert!(dao.get_rule_group_ids("rule-1").await.unwrap().is_empty());
+    }
+}
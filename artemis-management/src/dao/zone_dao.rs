@@ -1,13 +1,14 @@
+use crate::db::{RowExt, statement};
 use crate::model::{ZoneOperation, ZoneOperationRecord};
 use sea_orm::sea_query::Value;
-use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
 
-pub struct ZoneOperationDao {
-    conn: DatabaseConnection,
+pub struct ZoneOperationDao<C = DatabaseConnection> {
+    conn: C,
 }
 
-impl ZoneOperationDao {
-    pub fn new(conn: DatabaseConnection) -> Self {
+impl<C: ConnectionTrait> ZoneOperationDao<C> {
+    pub fn new(conn: C) -> Self {
         Self { conn }
     }
 
@@ -18,7 +19,7 @@ impl ZoneOperationDao {
             ZoneOperation::PullOut => "pullout",
         };
 
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             INSERT INTO service_zone (SERVICE_ID, ZONE_ID, REGION_ID, OPERATION)
@@ -39,7 +40,7 @@ impl ZoneOperationDao {
 
     /// 删除 Zone 操作
     pub async fn delete_operation(&self, zone_id: &str, region_id: &str) -> anyhow::Result<()> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             "DELETE FROM service_zone WHERE ZONE_ID = ? AND REGION_ID = ?",
             vec![Value::from(zone_id), Value::from(region_id)],
@@ -54,7 +55,7 @@ impl ZoneOperationDao {
         zone_id: &str,
         region_id: &str,
     ) -> anyhow::Result<Option<ZoneOperationRecord>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT ZONE_ID, REGION_ID, OPERATION
@@ -68,18 +69,18 @@ impl ZoneOperationDao {
 
         match result {
             Some(row) => {
-                let operation_str: String = row.try_get("", "OPERATION")?;
+                let operation_str: String = row.get_column("OPERATION")?;
                 let operation = match operation_str.as_str() {
                     "pullin" => ZoneOperation::PullIn,
                     _ => ZoneOperation::PullOut,
                 };
 
                 Ok(Some(ZoneOperationRecord {
-                    zone_id: row.try_get("", "ZONE_ID")?,
-                    region_id: row.try_get("", "REGION_ID")?,
+                    zone_id: row.get_column("ZONE_ID")?,
+                    region_id: row.get_column("REGION_ID")?,
                     operation,
                     operator_id: String::new(), // Java 表没有这个字段
-                    operation_time: 0, // Java 表没有这个字段
+                    operation_time: 0,          // Java 表没有这个字段
                 }))
             }
             None => Ok(None),
@@ -88,7 +89,7 @@ impl ZoneOperationDao {
 
     /// 列出所有 Zone 操作
     pub async fn list_operations(&self) -> anyhow::Result<Vec<ZoneOperationRecord>> {
-        let stmt = Statement::from_sql_and_values(
+        let stmt = statement(
             self.conn.get_database_backend(),
             r#"
             SELECT ZONE_ID, REGION_ID, OPERATION
@@ -102,15 +103,15 @@ impl ZoneOperationDao {
         Ok(rows
             .into_iter()
             .map(|row| {
-                let operation_str: String = row.try_get("", "OPERATION").unwrap_or_default();
+                let operation_str: String = row.get_column("OPERATION").unwrap_or_default();
                 let operation = match operation_str.as_str() {
                     "pullin" => ZoneOperation::PullIn,
                     _ => ZoneOperation::PullOut,
                 };
 
                 ZoneOperationRecord {
-                    zone_id: row.try_get("", "ZONE_ID").unwrap_or_default(),
-                    region_id: row.try_get("", "REGION_ID").unwrap_or_default(),
+                    zone_id: row.get_column("ZONE_ID").unwrap_or_default(),
+                    region_id: row.get_column("REGION_ID").unwrap_or_default(),
                     operation,
                     operator_id: String::new(),
                     operation_time: 0,
@@ -119,3 +120,35 @@ impl ZoneOperationDao {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_record(zone_id: &str, operation: ZoneOperation) -> ZoneOperationRecord {
+        ZoneOperationRecord {
+            zone_id: zone_id.to_string(),
+            region_id: "us-east".to_string(),
+            operation,
+            operator_id: "admin".to_string(),
+            operation_time: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_zone_operations() {
+        let dao = ZoneOperationDao::new(crate::db::test_database().await.conn().clone());
+        dao.insert_operation(&create_test_record("zone-1", ZoneOperation::PullOut)).await.unwrap();
+        // 重复插入同一操作不报错
+        dao.insert_operation(&create_test_record("zone-1", ZoneOperation::PullOut)).await.unwrap();
+        dao.insert_operation(&create_test_record("zone-2", ZoneOperation::PullIn)).await.unwrap();
+
+        let record = dao.get_operation("zone-1", "us-east").await.unwrap().unwrap();
+        assert_eq!(record.operation, ZoneOperation::PullOut);
+        assert_eq!(dao.list_operations().await.unwrap().len(), 2);
+
+        dao.delete_operation("zone-1", "us-east").await.unwrap();
+        assert!(dao.get_operation("zone-1", "us-east").await.unwrap().is_none());
+        assert_eq!(dao.list_operations().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,497 @@
+//! Export and import of the management database.
+//!
+//! [`Database::export_archive`] reads every management entity through the DAOs
+//! into one versioned [`ManagementArchive`]; users are exported without
+//! password hashes, MFA secrets or SSO links. [`Database::import_archive`]
+//! writes an archive back inside a single transaction, resolving objects that
+//! already exist according to a [`ConflictPolicy`]; any error rolls the whole
+//! import back.
+
+use super::{Database, Shared};
+use crate::auth::dao::{PasswordDao, RoleDao, TokenDao, UserDao};
+use crate::auth::{User, UserResponse};
+use crate::dao::{CanaryConfigDao, GroupDao, GroupInstanceDao, RouteRuleDao, ZoneOperationDao};
+use crate::model::{CanaryConfig, RouteRule, ServiceGroup, ZoneOperationRecord};
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// 当前归档格式版本
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// 管理数据归档
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagementArchive {
+    /// 归档格式版本
+    pub version: u32,
+    /// 导出时间 (Unix timestamp)
+    pub exported_at: i64,
+    /// 服务分组 (含标签)
+    #[serde(default)]
+    pub groups: Vec<ServiceGroup>,
+    /// 分组实例绑定
+    #[serde(default)]
+    pub group_instances: Vec<GroupBinding>,
+    /// 路由规则 (含规则分组及权重)
+    #[serde(default)]
+    pub route_rules: Vec<RouteRule>,
+    #[serde(default)]
+    pub canary_configs: Vec<CanaryConfig>,
+    #[serde(default)]
+    pub zone_operations: Vec<ZoneOperationRecord>,
+    /// 用户 (不含密码)
+    #[serde(default)]
+    pub users: Vec<UserResponse>,
+}
+
+/// 分组实例绑定, 分组以名称标识 (数据库主键在不同环境中不同)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupBinding {
+    pub group: String,
+    pub instance_id: String,
+}
+
+/// 导入时目标库已存在同一对象的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// 保留目标库中的对象
+    #[default]
+    Skip,
+    /// 用归档中的对象覆盖
+    Overwrite,
+    /// 中止导入并回滚
+    Fail,
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(ConflictPolicy::Skip),
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            "fail" => Ok(ConflictPolicy::Fail),
+            _ => Err(format!("Invalid conflict policy: {} (expected skip, overwrite or fail)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictPolicy::Skip => write!(f, "skip"),
+            ConflictPolicy::Overwrite => write!(f, "overwrite"),
+            ConflictPolicy::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub created: usize,
+    pub overwritten: usize,
+    /// 因冲突而跳过的对象, 如 `group order-stable`
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} created, {} overwritten, {} skipped",
+            self.created,
+            self.overwritten,
+            self.skipped.len()
+        )
+    }
+}
+
+impl Database {
+    /// 导出所有管理数据
+    pub async fn export_archive(&self) -> anyhow::Result<ManagementArchive> {
+        let conn = self.conn().clone();
+
+        let groups = GroupDao::new(conn.clone()).list_groups().await?;
+        let group_instances = GroupInstanceDao::new(conn.clone())
+            .list_bindings()
+            .await?
+            .into_iter()
+            .map(|(group, instance_id)| GroupBinding { group, instance_id })
+            .collect();
+
+        let route_dao = RouteRuleDao::new(conn.clone());
+        let mut route_rules = route_dao.list_rules().await?;
+        for rule in &mut route_rules {
+            rule.groups = route_dao
+                .get_rule_group_ids(&rule.route_id)
+                .await?
+                .into_iter()
+                .map(|(group_key, weight)| artemis_common::model::service::ServiceGroup {
+                    group_key,
+                    weight: Some(weight),
+                    instance_ids: None,
+                    instances: None,
+                    metadata: None,
+                })
+                .collect();
+        }
+
+        let canary_configs = CanaryConfigDao::new(conn.clone()).list_configs().await?;
+        let zone_operations = ZoneOperationDao::new(conn.clone()).list_operations().await?;
+
+        let mut roles: HashMap<String, Vec<String>> = HashMap::new();
+        for (user_id, role_id) in RoleDao::new(conn.clone()).list_user_roles().await? {
+            roles.entry(user_id).or_default().push(role_id);
+        }
+        let service_accounts: HashSet<String> =
+            TokenDao::new(conn.clone()).list_service_accounts().await?.into_iter().collect();
+        let change_required: HashSet<String> =
+            PasswordDao::new(conn.clone()).list_change_required().await?.into_iter().collect();
+        let users = UserDao::new(conn)
+            .list_users()
+            .await?
+            .into_iter()
+            .map(|mut user| {
+                user.roles = roles.remove(&user.user_id).unwrap_or_default();
+                user.service_account = service_accounts.contains(&user.user_id);
+                user.must_change_password = change_required.contains(&user.user_id);
+                user.to_response()
+            })
+            .collect();
+
+        Ok(ManagementArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            groups,
+            group_instances,
+            route_rules,
+            canary_configs,
+            zone_operations,
+            users,
+        })
+    }
+
+    /// 在一个事务中导入归档, 任一对象失败 (或 `Fail` 策略下遇到冲突) 时整体回滚
+    pub async fn import_archive(
+        &self,
+        archive: &ManagementArchive,
+        policy: ConflictPolicy,
+    ) -> anyhow::Result<ImportReport> {
+        if archive.version == 0 || archive.version > ARCHIVE_VERSION {
+            anyhow::bail!(
+                "Unsupported archive version {} (supported: 1-{})",
+                archive.version,
+                ARCHIVE_VERSION
+            );
+        }
+
+        let txn = self.conn().begin().await?;
+        match import_into(&txn, archive, policy).await {
+            Ok(report) => {
+                txn.commit().await?;
+                Ok(report)
+            }
+            Err(e) => {
+                txn.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// 导入的单个对象: 不存在时创建, 存在时按策略处理
+struct Importer {
+    policy: ConflictPolicy,
+    report: ImportReport,
+}
+
+impl Importer {
+    /// 返回 `Some(true)` 覆盖、`Some(false)` 创建、`None` 跳过
+    fn resolve(&mut self, kind: &str, key: &str, exists: bool) -> anyhow::Result<Option<bool>> {
+        if !exists {
+            self.report.created += 1;
+            return Ok(Some(false));
+        }
+        match self.policy {
+            ConflictPolicy::Skip => {
+                self.report.skipped.push(format!("{} {}", kind, key));
+                Ok(None)
+            }
+            ConflictPolicy::Overwrite => {
+                self.report.overwritten += 1;
+                Ok(Some(true))
+            }
+            ConflictPolicy::Fail => anyhow::bail!("Conflict: {} {} already exists", kind, key),
+        }
+    }
+}
+
+async fn import_into<C: ConnectionTrait>(
+    conn: &C,
+    archive: &ManagementArchive,
+    policy: ConflictPolicy,
+) -> anyhow::Result<ImportReport> {
+    let mut importer = Importer { policy, report: ImportReport::default() };
+
+    // 分组需先于绑定和路由规则导入
+    let group_dao = GroupDao::new(Shared(conn));
+    for group in &archive.groups {
+        let exists = group_dao.get_group(&group.name).await?.is_some();
+        match importer.resolve("group", &group.name, exists)? {
+            Some(true) => group_dao.update_group(group).await?,
+            Some(false) => group_dao.insert_group(group).await?,
+            None => {}
+        }
+    }
+
+    // 绑定没有可覆盖的内容, 已存在时直接跳过
+    let binding_dao = GroupInstanceDao::new(Shared(conn));
+    for binding in &archive.group_instances {
+        if binding_dao.insert_by_group_name(&binding.group, &binding.instance_id).await? {
+            importer.report.created += 1;
+        }
+    }
+
+    let route_dao = RouteRuleDao::new(Shared(conn));
+    for rule in &archive.route_rules {
+        let exists = route_dao.get_rule(&rule.route_id).await?.is_some();
+        match importer.resolve("route-rule", &rule.route_id, exists)? {
+            Some(true) => route_dao.update_rule(rule).await?,
+            Some(false) => route_dao.insert_rule(rule).await?,
+            None => {}
+        }
+    }
+
+    let canary_dao = CanaryConfigDao::new(Shared(conn));
+    for config in &archive.canary_configs {
+        let exists = canary_dao.get_config(&config.service_id).await?.is_some();
+        if importer.resolve("canary-config", &config.service_id, exists)?.is_some() {
+            canary_dao.upsert_config(config).await?;
+        }
+    }
+
+    let zone_dao = ZoneOperationDao::new(Shared(conn));
+    for record in &archive.zone_operations {
+        let key = format!("{}:{}", record.zone_id, record.region_id);
+        let exists = zone_dao.get_operation(&record.zone_id, &record.region_id).await?.is_some();
+        match importer.resolve("zone-operation", &key, exists)? {
+            Some(true) => {
+                zone_dao.delete_operation(&record.zone_id, &record.region_id).await?;
+                zone_dao.insert_operation(record).await?;
+            }
+            Some(false) => zone_dao.insert_operation(record).await?,
+            None => {}
+        }
+    }
+
+    let user_dao = UserDao::new(Shared(conn));
+    let role_dao = RoleDao::new(Shared(conn));
+    let token_dao = TokenDao::new(Shared(conn));
+    let password_dao = PasswordDao::new(Shared(conn));
+    for imported in &archive.users {
+        // 以用户名识别同一用户; 覆盖时保留目标库的用户 ID 和密码
+        let existing = user_dao.get_user_by_username(&imported.username).await?;
+        let user = User {
+            user_id: existing.as_ref().map_or(imported.user_id.clone(), |u| u.user_id.clone()),
+            username: imported.username.clone(),
+            email: imported.email.clone(),
+            description: imported.description.clone(),
+            // 导入的新用户没有密码, 需由管理员重置密码 (或通过单点登录) 后才能登录
+            password_hash: existing.as_ref().map(|u| u.password_hash.clone()).unwrap_or_default(),
+            role: imported.role.clone(),
+            roles: imported.roles.clone(),
+            status: imported.status.clone(),
+            service_account: imported.service_account,
+            must_change_password: imported.must_change_password,
+            mfa_enabled: false,
+            identity_provider: None,
+            created_at: imported.created_at,
+            updated_at: imported.updated_at,
+        };
+        match importer.resolve("user", &imported.username, existing.is_some())? {
+            Some(true) => user_dao.update_user(&user).await?,
+            Some(false) => user_dao.insert_user(&user).await?,
+            None => continue,
+        }
+
+        role_dao.set_user_roles(&user.user_id, &user.roles).await?;
+        token_dao.delete_service_account(&user.user_id).await?;
+        if user.service_account {
+            token_dao.insert_service_account(&user.user_id).await?;
+        }
+        if user.must_change_password {
+            password_dao.require_change(&user.user_id).await?;
+        }
+    }
+
+    Ok(importer.report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::UserRole;
+    use crate::model::{GroupStatus, GroupTag, GroupType, RouteRuleStatus, RouteStrategy};
+
+    fn group(name: &str, description: &str) -> ServiceGroup {
+        ServiceGroup {
+            group_id: None,
+            service_id: "order-service".to_string(),
+            region_id: "us-east".to_string(),
+            zone_id: "zone-1".to_string(),
+            name: name.to_string(),
+            group_type: GroupType::Physical,
+            status: GroupStatus::Active,
+            description: Some(description.to_string()),
+            tags: Some(vec![GroupTag { key: "env".to_string(), value: "prod".to_string() }]),
+            metadata: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    async fn seed(db: &Database) {
+        let conn = db.conn().clone();
+        GroupDao::new(conn.clone()).insert_group(&group("stable", "stable")).await.unwrap();
+        GroupInstanceDao::new(conn.clone()).insert_by_group_name("stable", "i-1").await.unwrap();
+        RouteRuleDao::new(conn.clone())
+            .insert_rule(&RouteRule {
+                route_rule_id: None,
+                route_id: "order-split".to_string(),
+                service_id: "order-service".to_string(),
+                name: "order-split".to_string(),
+                description: None,
+                status: RouteRuleStatus::Active,
+                strategy: RouteStrategy::WeightedRoundRobin,
+                groups: vec![artemis_common::model::service::ServiceGroup {
+                    group_key: "stable".to_string(),
+                    weight: Some(80),
+                    instance_ids: None,
+                    instances: None,
+                    metadata: None,
+                }],
+            })
+            .await
+            .unwrap();
+        CanaryConfigDao::new(conn.clone())
+            .upsert_config(&CanaryConfig {
+                service_id: "order-service".to_string(),
+                ip_whitelist: vec!["10.0.0.1".to_string()],
+                enabled: true,
+            })
+            .await
+            .unwrap();
+
+        ZoneOperationDao::new(conn.clone())
+            .insert_operation(&ZoneOperationRecord {
+                zone_id: "zone-2".to_string(),
+                region_id: "us-east".to_string(),
+                operation: crate::model::ZoneOperation::PullOut,
+                operator_id: "admin".to_string(),
+                operation_time: 0,
+            })
+            .await
+            .unwrap();
+
+        let mut user =
+            User::new("ops".to_string(), None, None, "secret-hash".to_string(), UserRole::Operator);
+        user.user_id = "user-ops".to_string();
+        UserDao::new(conn.clone()).insert_user(&user).await.unwrap();
+        RoleDao::new(conn.clone())
+            .set_user_roles("user-ops", &["auditor".to_string()])
+            .await
+            .unwrap();
+        TokenDao::new(conn).insert_service_account("user-ops").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_import_roundtrip() {
+        let source = crate::db::test_database().await;
+        seed(&source).await;
+
+        let archive = source.export_archive().await.unwrap();
+        assert_eq!(archive.version, ARCHIVE_VERSION);
+        assert_eq!(archive.groups[0].tags.as_ref().unwrap()[0].value, "prod");
+        assert_eq!(
+            archive.group_instances,
+            vec![GroupBinding { group: "stable".to_string(), instance_id: "i-1".to_string() }]
+        );
+        assert_eq!(archive.route_rules[0].groups[0].weight, Some(80));
+        assert_eq!(archive.users[0].roles, vec!["auditor"]);
+        assert!(archive.users[0].service_account);
+
+        // 导出内容不含密码
+        let json = serde_json::to_string(&archive).unwrap();
+        assert!(!json.contains("secret-hash"));
+        let archive: ManagementArchive = serde_json::from_str(&json).unwrap();
+
+        let target = crate::db::test_database().await;
+        let report = target.import_archive(&archive, ConflictPolicy::Fail).await.unwrap();
+        assert_eq!(report.created, 6);
+        assert!(report.skipped.is_empty());
+
+        let copied = target.export_archive().await.unwrap();
+        assert_eq!(copied.groups, archive.groups);
+        assert_eq!(copied.group_instances, archive.group_instances);
+        assert_eq!(copied.route_rules[0].groups[0].group_key, "stable");
+        assert_eq!(copied.canary_configs[0].ip_whitelist, vec!["10.0.0.1"]);
+        assert_eq!(copied.users[0].username, "ops");
+        assert_eq!(copied.users[0].roles, vec!["auditor"]);
+        let user = UserDao::new(target.conn().clone()).get_user("user-ops").await.unwrap().unwrap();
+        assert!(user.password_hash.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_conflict_policies() {
+        let db = crate::db::test_database().await;
+        seed(&db).await;
+
+        let mut archive = db.export_archive().await.unwrap();
+        archive.groups[0].description = Some("changed".to_string());
+        archive.groups.push(group("canary", "new"));
+
+        // fail: 整体回滚, 新分组也不会写入
+        let err = db.import_archive(&archive, ConflictPolicy::Fail).await.unwrap_err();
+        assert!(err.to_string().contains("group stable already exists"));
+        let group_dao = GroupDao::new(db.conn().clone());
+        assert!(group_dao.get_group("canary").await.unwrap().is_none());
+
+        // skip: 保留已有对象
+        let report = db.import_archive(&archive, ConflictPolicy::Skip).await.unwrap();
+        assert_eq!(report.created, 1);
+        assert_eq!(report.overwritten, 0);
+        assert_eq!(report.skipped.len(), 5);
+        assert!(report.skipped.contains(&"user ops".to_string()));
+        let stable = group_dao.get_group("stable").await.unwrap().unwrap();
+        assert_eq!(stable.description.as_deref(), Some("stable"));
+
+        // overwrite: 覆盖已有对象, 用户保留原密码
+        let report = db.import_archive(&archive, ConflictPolicy::Overwrite).await.unwrap();
+        assert_eq!(report.overwritten, 6);
+        let stable = group_dao.get_group("stable").await.unwrap().unwrap();
+        assert_eq!(stable.description.as_deref(), Some("changed"));
+        let user = UserDao::new(db.conn().clone()).get_user("user-ops").await.unwrap().unwrap();
+        assert_eq!(user.password_hash, "secret-hash");
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unknown_version() {
+        let db = crate::db::test_database().await;
+        let archive = ManagementArchive { version: ARCHIVE_VERSION + 1, ..Default::default() };
+        assert!(db.import_archive(&archive, ConflictPolicy::Skip).await.is_err());
+    }
+
+    #[test]
+    fn test_conflict_policy_from_str() {
+        assert_eq!("Overwrite".parse::<ConflictPolicy>().unwrap(), ConflictPolicy::Overwrite);
+        assert_eq!(ConflictPolicy::Fail.to_string(), "fail");
+        assert!("merge".parse::<ConflictPolicy>().is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Versioned schema migrations built on `sea-orm-migration`.
+//!
+//! Each migration is a set of SQL files: `migrations/NNN_name.sql` for SQLite,
+//! `migrations/mysql/NNN_name.sql` for MySQL and
+//! `migrations/postgres/NNN_name.sql` for PostgreSQL. Applied migrations are
+//! recorded in the `seaql_migrations` table, so every file runs exactly once
+//! per database. Rolling back runs the migration's `NNN_name.down.sql` files
+//! when it ships them, otherwise drops the tables the migration created;
+//! migrations that create no tables and ship no down files (data fixes) are
+//! irreversible and fail to roll back.
+
+use sea_orm::{ConnectionTrait, DbBackend, DbErr};
+use sea_orm_migration::{MigrationName, MigrationTrait, MigratorTrait, SchemaManager, async_trait};
+
+/// 迁移执行器
+pub struct Migrator;
+
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        sql_migrations().into_iter().map(|m| Box::new(m) as Box<dyn MigrationTrait>).collect()
+    }
+}
+
+/// 按名称引入 SQLite、MySQL 和 PostgreSQL 版本的迁移文件; 带 `down` 时同时引入回滚文件
+macro_rules! sql_migration {
+    ($name:literal) => {
+        SqlMigration {
+            name: $name,
+            sqlite: include_str!(concat!("../../migrations/", $name, ".sql")),
+            mysql: include_str!(concat!("../../migrations/mysql/", $name, ".sql")),
+            postgres: include_str!(concat!("../../migrations/postgres/", $name, ".sql")),
+            down: None,
+        }
+    };
+    ($name:literal, down) => {
+        SqlMigration {
+            down: Some(DownSql {
+                sqlite: include_str!(concat!("../../migrations/", $name, ".down.sql")),
+                mysql: include_str!(concat!("../../migrations/mysql/", $name, ".down.sql")),
+                postgres: include_str!(concat!("../../migrations/postgres/", $name, ".down.sql")),
+            }),
+            ..sql_migration!($name)
+        }
+    };
+}
+
+/// 全部迁移, 按执行顺序排列; 已发布的迁移不可修改, 结构变更只能追加新迁移
+fn sql_migrations() -> Vec<SqlMigration> {
+    vec![
+        // 数据库初始化: 所有表的创建 (业务表 + 认证表)
+        sql_migration!("001_initial_schema"),
+        // 细粒度 RBAC: 自定义角色和用户角色关联
+        sql_migration!("002_rbac"),
+        // API Token 和服务账号
+        sql_migration!("003_api_tokens"),
+        // 登录安全: 密码历史和强制改密
+        sql_migration!("004_login_security"),
+        // 两步验证: TOTP 密钥、恢复码和强制两步验证的角色
+        sql_migration!("005_mfa"),
+        // 单点登录: 外部身份绑定
+        sql_migration!("006_external_identities"),
+        // 访问令牌签名密钥
+        sql_migration!("007_signing_keys"),
+        // 会话集群同步: 会话失效记录
+        sql_migration!("008_session_revocations"),
+        // 分组标签关联改为分组主键 (数据修正, 不可回滚)
+        sql_migration!("009_group_tag_ids"),
+        // 高风险操作审批: 变更请求
+        sql_migration!("010_change_requests"),
+        // 两步验证挑战集群共享
+        sql_migration!("011_mfa_challenges"),
+        // 用户和角色变更集群同步: 失效记录增加类型
+        sql_migration!("012_auth_change_markers", down),
+    ]
+}
+
+/// 由 SQL 文件定义的迁移
+struct SqlMigration {
+    name: &'static str,
+    sqlite: &'static str,
+    mysql: &'static str,
+    postgres: &'static str,
+    /// 回滚文件, 为空时回滚删除本迁移创建的表
+    down: Option<DownSql>,
+}
+
+/// 迁移的回滚文件
+struct DownSql {
+    sqlite: &'static str,
+    mysql: &'static str,
+    postgres: &'static str,
+}
+
+impl SqlMigration {
+    fn sql(&self, backend: DbBackend) -> &'static str {
+        match backend {
+            DbBackend::MySql => self.mysql,
+            DbBackend::Postgres => self.postgres,
+            _ => self.sqlite,
+        }
+    }
+
+    fn down_sql(&self, backend: DbBackend) -> Option<&'static str> {
+        let down = self.down.as_ref()?;
+        Some(match backend {
+            DbBackend::MySql => down.mysql,
+            DbBackend::Postgres => down.postgres,
+            _ => down.sqlite,
+        })
+    }
+
+    /// 逐条执行 SQL 语句
+    async fn execute(&self, manager: &SchemaManager<'_>, sql: &str) -> Result<(), DbErr> {
+        let statements = split_statements(sql);
+        for (i, statement) in statements.iter().enumerate() {
+            tracing::debug!("Executing statement {} of {}", i + 1, statements.len());
+            manager.get_connection().execute_unprepared(statement).await.map_err(|e| {
+                DbErr::Migration(format!(
+                    "Failed to execute migration {} (statement {}): {}\nSQL: {}",
+                    self.name,
+                    i + 1,
+                    e,
+                    statement
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl MigrationName for SqlMigration {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for SqlMigration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        self.execute(manager, self.sql(manager.get_database_backend())).await
+    }
+
+    /// 执行回滚文件, 没有回滚文件时按创建的相反顺序删除本迁移创建的表 (索引随表一起删除);
+    /// 两者都没有的迁移 (数据修正) 不可回滚
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if let Some(sql) = self.down_sql(manager.get_database_backend()) {
+            return self.execute(manager, sql).await;
+        }
+
+        let tables = created_tables(self.sqlite);
+        if tables.is_empty() {
+            return Err(DbErr::Migration(format!("irreversible migration: {}", self.name)));
+        }
+        for table in tables.into_iter().rev() {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!("DROP TABLE IF EXISTS {}", table))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 去掉行注释后按 `;` 分割为单条语句
+fn split_statements(sql: &str) -> Vec<String> {
+    let without_comments = sql
+        .lines()
+        .map(|line| if let Some(pos) = line.find("--") { &line[..pos] } else { line })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    without_comments.split(';').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// 迁移文件中创建的表, 按出现顺序
+fn created_tables(sql: &str) -> Vec<String> {
+    split_statements(sql)
+        .iter()
+        .filter_map(|statement| {
+            statement
+                .strip_prefix("CREATE TABLE IF NOT EXISTS ")
+                .or_else(|| statement.strip_prefix("CREATE TABLE "))
+                .and_then(|rest| rest.split_whitespace().next())
+                .map(|table| table.trim_end_matches('(').to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements_ignores_comments() {
+        let sql = "-- header; with semicolon\nCREATE TABLE a (id INTEGER); -- trailing\n\n\
+                   CREATE INDEX idx_a ON a(id);\n";
+        assert_eq!(
+            split_statements(sql),
+            vec!["CREATE TABLE a (id INTEGER)", "CREATE INDEX idx_a ON a(id)"]
+        );
+    }
+
+    #[test]
+    fn test_created_tables() {
+        let tables = created_tables(include_str!("../../migrations/002_rbac.sql"));
+        assert_eq!(tables, vec!["auth_roles", "auth_user_roles"]);
+    }
+
+    #[test]
+    fn test_only_data_fixes_are_irreversible() {
+        let irreversible: Vec<&str> = sql_migrations()
+            .iter()
+            .filter(|m| m.down.is_none() && created_tables(m.sqlite).is_empty())
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(irreversible, vec!["009_group_tag_ids"]);
+    }
+
+    #[test]
+    fn test_dialects_create_same_tables() {
+        for migration in sql_migrations() {
+            let tables = created_tables(migration.sqlite);
+            assert_eq!(
+                tables,
+                created_tables(migration.mysql),
+                "migration {} differs between SQLite and MySQL",
+                migration.name
+            );
+            assert_eq!(
+                tables,
+                created_tables(migration.postgres),
+                "migration {} differs between SQLite and PostgreSQL",
+                migration.name
+            );
+        }
+    }
+}
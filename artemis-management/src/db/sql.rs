@@ -0,0 +1,114 @@
+//! Dialect helpers for the raw-SQL DAOs.
+//!
+//! DAOs write SQL once with `?` placeholders and unquoted identifiers; these
+//! helpers adapt it to the connected backend (PostgreSQL numbers its
+//! placeholders and folds unquoted column names to lower case).
+//! [`Shared`] lets several DAOs run against one transaction.
+
+use sea_orm::{
+    ConnectionTrait, DbBackend, DbErr, ExecResult, QueryResult, Statement, TryGetable, Value,
+};
+
+/// 创建带参数的 SQL 语句, PostgreSQL 下将 `?` 占位符转换为 `$1, $2, ...`
+pub fn statement(backend: DbBackend, sql: &str, values: Vec<Value>) -> Statement {
+    match backend {
+        DbBackend::Postgres => {
+            Statement::from_sql_and_values(backend, numbered_placeholders(sql), values)
+        }
+        _ => Statement::from_sql_and_values(backend, sql, values),
+    }
+}
+
+/// 将 `?` 替换为 `$n` (跳过字符串字面量中的问号)
+fn numbered_placeholders(sql: &str) -> String {
+    let mut result = String::with_capacity(sql.len() + 8);
+    let mut index = 0;
+    let mut in_literal = false;
+    for c in sql.chars() {
+        match c {
+            '\'' => {
+                in_literal = !in_literal;
+                result.push(c);
+            }
+            '?' if !in_literal => {
+                index += 1;
+                result.push('$');
+                result.push_str(&index.to_string());
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// 借用的连接, 使多个 DAO 共用同一事务: `GroupDao::new(Shared(&txn))`
+pub struct Shared<'a, C>(pub &'a C);
+
+#[async_trait::async_trait]
+impl<C: ConnectionTrait> ConnectionTrait for Shared<'_, C> {
+    fn get_database_backend(&self) -> DbBackend {
+        self.0.get_database_backend()
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.0.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.0.execute_unprepared(sql).await
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.0.query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.0.query_all(stmt).await
+    }
+}
+
+/// 按列名读取查询结果
+pub trait RowExt {
+    /// 读取列值; PostgreSQL 返回的未加引号列名为小写, 大写列名 (兼容 Java 表结构) 时按小写重试
+    fn get_column<T: TryGetable>(&self, column: &str) -> Result<T, DbErr>;
+}
+
+impl RowExt for QueryResult {
+    fn get_column<T: TryGetable>(&self, column: &str) -> Result<T, DbErr> {
+        // Option<T> 在列不存在时返回 None 而不是错误, 因此先按列名判断再读取
+        if column.chars().any(|c| c.is_ascii_uppercase()) {
+            let names = self.column_names();
+            let lowercase = column.to_ascii_lowercase();
+            if !names.iter().any(|name| name == column) && names.contains(&lowercase) {
+                return self.try_get("", &lowercase);
+            }
+        }
+        self.try_get("", column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbered_placeholders() {
+        assert_eq!(
+            numbered_placeholders("SELECT * FROM t WHERE a = ? AND b = '?' AND c IN (?, ?)"),
+            "SELECT * FROM t WHERE a = $1 AND b = '?' AND c IN ($2, $3)"
+        );
+        assert_eq!(numbered_placeholders("SELECT 1"), "SELECT 1");
+    }
+
+    #[test]
+    fn test_statement_by_backend() {
+        let sql = "DELETE FROM t WHERE a = ? AND b = ?";
+        let values = vec![Value::from("x"), Value::from(1i64)];
+        assert_eq!(statement(DbBackend::Sqlite, sql, values.clone()).sql, sql);
+        assert_eq!(statement(DbBackend::MySql, sql, values.clone()).sql, sql);
+        assert_eq!(
+            statement(DbBackend::Postgres, sql, values).sql,
+            "DELETE FROM t WHERE a = $1 AND b = $2"
+        );
+    }
+}
@@ -1,4 +1,6 @@
 pub mod api;
+pub mod apply;
+pub mod approval;
 pub mod audit;
 pub mod auth;
 pub mod canary;
@@ -14,6 +16,8 @@ pub mod routing;
 pub mod web;
 pub mod zone;
 
+pub use apply::ConfigApplier;
+pub use approval::ApprovalManager;
 pub use audit::AuditManager;
 pub use auth::AuthManager;
 pub use canary::CanaryManager;
@@ -24,5 +28,5 @@ pub use instance::InstanceManager;
 pub use loader::ConfigLoader;
 pub use route::RouteManager;
 pub use routing::RouteEngine;
-pub use web::{management_routes, ManagementState};
+pub use web::{ManagementState, management_routes};
 pub use zone::ZoneManager;
@@ -0,0 +1,269 @@
+//! Approval workflow data models
+
+use artemis_common::model::InstanceKey;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 可配置审批的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalOperation {
+    /// 拉出整个 Zone
+    ZonePullOut,
+    /// 拉出服务器 (该服务器上的全部实例)
+    ServerPullOut,
+    /// 拉出单个实例
+    InstancePullOut,
+}
+
+impl ApprovalOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalOperation::ZonePullOut => "zone-pull-out",
+            ApprovalOperation::ServerPullOut => "server-pull-out",
+            ApprovalOperation::InstancePullOut => "instance-pull-out",
+        }
+    }
+
+    /// 执行该操作所需的权限资源
+    pub fn permission_resource(&self) -> &'static str {
+        match self {
+            ApprovalOperation::ZonePullOut => "zones",
+            ApprovalOperation::ServerPullOut | ApprovalOperation::InstancePullOut => "instances",
+        }
+    }
+}
+
+impl fmt::Display for ApprovalOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApprovalOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zone-pull-out" => Ok(ApprovalOperation::ZonePullOut),
+            "server-pull-out" => Ok(ApprovalOperation::ServerPullOut),
+            "instance-pull-out" => Ok(ApprovalOperation::InstancePullOut),
+            _ => Err(format!("Unknown approval operation: {}", s)),
+        }
+    }
+}
+
+/// 审批策略: 命中的操作需要另一位有权限的用户批准后才执行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicy {
+    pub operation: ApprovalOperation,
+    /// 影响的实例数超过该值时才需要审批, None 表示总是需要审批
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_threshold: Option<usize>,
+}
+
+impl ApprovalPolicy {
+    /// 操作是否命中该策略
+    pub fn applies(&self, operation: &PendingOperation, affected_instances: usize) -> bool {
+        self.operation == operation.kind()
+            && self.instance_threshold.is_none_or(|threshold| affected_instances > threshold)
+    }
+}
+
+/// 等待审批的操作 (批准后按原请求执行)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "camelCase")]
+pub enum PendingOperation {
+    ZonePullOut {
+        zone_id: String,
+        region_id: String,
+        operator_id: String,
+    },
+    ServerPullOut {
+        server_id: String,
+        region_id: String,
+        operation_complete: bool,
+        operator_id: String,
+    },
+    InstancePullOut {
+        instance_key: InstanceKey,
+        operation_complete: bool,
+        operator_id: String,
+    },
+}
+
+impl PendingOperation {
+    pub fn kind(&self) -> ApprovalOperation {
+        match self {
+            PendingOperation::ZonePullOut { .. } => ApprovalOperation::ZonePullOut,
+            PendingOperation::ServerPullOut { .. } => ApprovalOperation::ServerPullOut,
+            PendingOperation::InstancePullOut { .. } => ApprovalOperation::InstancePullOut,
+        }
+    }
+
+    /// 操作对象标识, 与审计日志的 target_id 格式一致
+    pub fn target(&self) -> String {
+        match self {
+            PendingOperation::ZonePullOut { zone_id, region_id, .. } => {
+                format!("{}:{}", zone_id, region_id)
+            }
+            PendingOperation::ServerPullOut { server_id, region_id, .. } => {
+                format!("{}:{}", server_id, region_id)
+            }
+            PendingOperation::InstancePullOut { instance_key, .. } => format!(
+                "{}:{}:{}",
+                instance_key.service_id, instance_key.region_id, instance_key.instance_id
+            ),
+        }
+    }
+
+    /// 操作涉及的服务 (只有实例操作限定在单个服务内)
+    pub fn service_id(&self) -> Option<&str> {
+        match self {
+            PendingOperation::InstancePullOut { instance_key, .. } => {
+                Some(&instance_key.service_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 变更请求状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeRequestStatus {
+    /// 等待审批
+    Pending,
+    /// 已批准并执行
+    Approved,
+    /// 已拒绝
+    Rejected,
+    /// 申请人已撤回
+    Cancelled,
+    /// 超过有效期未审批
+    Expired,
+    /// 已批准但执行失败
+    Failed,
+}
+
+impl ChangeRequestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeRequestStatus::Pending => "pending",
+            ChangeRequestStatus::Approved => "approved",
+            ChangeRequestStatus::Rejected => "rejected",
+            ChangeRequestStatus::Cancelled => "cancelled",
+            ChangeRequestStatus::Expired => "expired",
+            ChangeRequestStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ChangeRequestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ChangeRequestStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ChangeRequestStatus::Pending),
+            "approved" => Ok(ChangeRequestStatus::Approved),
+            "rejected" => Ok(ChangeRequestStatus::Rejected),
+            "cancelled" => Ok(ChangeRequestStatus::Cancelled),
+            "expired" => Ok(ChangeRequestStatus::Expired),
+            "failed" => Ok(ChangeRequestStatus::Failed),
+            _ => Err(format!("Unknown change request status: {}", s)),
+        }
+    }
+}
+
+/// 变更请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeRequest {
+    pub request_id: String,
+    pub operation: PendingOperation,
+    /// 提交时统计的受影响实例数
+    pub affected_instances: usize,
+    pub status: ChangeRequestStatus,
+    pub requested_by: String,
+    pub requested_at: i64,
+    pub expires_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<i64>,
+    /// 审批意见
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// 执行失败原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 审批、拒绝或撤回变更请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewChangeRequest {
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone_pull_out() -> PendingOperation {
+        PendingOperation::ZonePullOut {
+            zone_id: "zone-1".to_string(),
+            region_id: "us-east".to_string(),
+            operator_id: "ops".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_policy_threshold() {
+        let always =
+            ApprovalPolicy { operation: ApprovalOperation::ZonePullOut, instance_threshold: None };
+        assert!(always.applies(&zone_pull_out(), 0));
+
+        let large = ApprovalPolicy {
+            operation: ApprovalOperation::ZonePullOut,
+            instance_threshold: Some(10),
+        };
+        assert!(!large.applies(&zone_pull_out(), 10));
+        assert!(large.applies(&zone_pull_out(), 11));
+
+        let server = ApprovalPolicy {
+            operation: ApprovalOperation::ServerPullOut,
+            instance_threshold: None,
+        };
+        assert!(!server.applies(&zone_pull_out(), 100));
+    }
+
+    #[test]
+    fn test_pending_operation_serde() {
+        let json = serde_json::to_value(zone_pull_out()).unwrap();
+        assert_eq!(json["type"], "zone-pull-out");
+        assert_eq!(json["zoneId"], "zone-1");
+        let parsed: PendingOperation = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, zone_pull_out());
+        assert_eq!(parsed.target(), "zone-1:us-east");
+        assert_eq!(parsed.kind().permission_resource(), "zones");
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!("server-pull-out".parse(), Ok(ApprovalOperation::ServerPullOut));
+        assert!("zone-pull-in".parse::<ApprovalOperation>().is_err());
+        for status in ["pending", "approved", "rejected", "cancelled", "expired", "failed"] {
+            assert_eq!(status.parse::<ChangeRequestStatus>().unwrap().as_str(), status);
+        }
+    }
+}
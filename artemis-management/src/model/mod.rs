@@ -1,11 +1,13 @@
 //! Management-specific data models
 
+pub mod approval;
 pub mod canary;
 pub mod group;
 pub mod management;
 pub mod route;
 pub mod zone;
 
+pub use approval::*;
 pub use canary::*;
 pub use group::*;
 pub use management::*;
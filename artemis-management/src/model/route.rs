@@ -87,7 +87,7 @@ impl RouteRuleGroup {
 }
 
 /// 路由策略
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum RouteStrategy {
     /// 加权轮询
@@ -0,0 +1,155 @@
+//! Declarative management configuration HTTP API
+
+use crate::apply::{
+    ApplyOptions, ApplyPlan, ChangeAction, ConfigApplier, DocumentFormat, ManagementDocument,
+    ResourceKind,
+};
+use crate::auth::Permission;
+use crate::model::{PendingOperation, ZoneOperation};
+use crate::web::middleware::TokenScopes;
+use crate::web::state::ManagementState;
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self { success: true, data: Some(data), message: None }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self { success: false, data: None, message: Some(message) }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// POST /api/management/apply?dryRun=&prune= - 应用声明式配置文档
+///
+/// 请求体为 YAML、TOML 或 JSON 文档 (按 Content-Type 解析, 默认 YAML)。
+/// 文档涉及的对象类型需要对应资源的不限服务范围的 write 权限, 删除对象还需要 delete 权限;
+/// 通过限定范围的 API Token 访问时令牌范围也必须授权
+pub async fn apply_document(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    scopes: Option<Extension<TokenScopes>>,
+    Query(query): Query<ApplyQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
+    // 与权限中间件一致: 必须先修改密码或绑定两步验证的用户一律拒绝
+    if state.auth_manager.password_change_required(&user_id)
+        || state.auth_manager.mfa_enrollment_required(&user_id)
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::error("Password change or 2FA enrollment required".to_string())),
+        );
+    }
+
+    let format = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(DocumentFormat::from_content_type)
+        .unwrap_or(DocumentFormat::Yaml);
+    let document = match ManagementDocument::parse(&body, format) {
+        Ok(document) => document,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::<ApplyPlan>::error(e))),
+    };
+
+    let applier = ConfigApplier::new(
+        state.group_manager.clone(),
+        state.route_manager.clone(),
+        state.canary_manager.clone(),
+        state.zone_manager.clone(),
+    );
+    let plan = match applier.plan(&document, query.prune) {
+        Ok(plan) => plan,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+    };
+
+    // 按计划中的变更检查权限 (dry-run 也检查, 以便提前发现权限不足)
+    let allowed = |resource: &str, action: &str| {
+        let granted = |permissions: &[Permission]| {
+            permissions.iter().any(|p| !p.is_scoped() && p.allows(resource, action))
+        };
+        granted(&state.auth_manager.get_effective_permissions(&user_id))
+            && scopes.as_ref().is_none_or(|Extension(TokenScopes(scopes))| granted(scopes))
+    };
+    for change in &plan.changes {
+        let resource = change.kind.permission_resource();
+        let action = if change.action == ChangeAction::Delete { "delete" } else { "write" };
+        if !allowed(resource, action) {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::error(format!(
+                    "Permission denied: {}:{} required for {}",
+                    resource, action, change
+                ))),
+            );
+        }
+    }
+
+    // 需要审批的 Zone 拉出不能通过声明式配置绕过审批
+    for spec in &document.zone_operations {
+        let pulls_out = spec.operation == ZoneOperation::PullOut
+            && plan.changes.iter().any(|change| {
+                change.kind == ResourceKind::ZoneOperation
+                    && change.action == ChangeAction::Create
+                    && change.key == spec.key()
+            });
+        let operation = PendingOperation::ZonePullOut {
+            zone_id: spec.zone_id.clone(),
+            region_id: spec.region_id.clone(),
+            operator_id: user_id.clone(),
+        };
+        if pulls_out && state.approval_manager.requires_approval(&operation) {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::error(format!(
+                    "Pulling out zone {} requires approval, submit it via /api/management/zone/pull-out",
+                    spec.key()
+                ))),
+            );
+        }
+    }
+    if query.dry_run {
+        return (StatusCode::OK, Json(ApiResponse::success(ApplyPlan { dry_run: true, ..plan })));
+    }
+
+    let options = ApplyOptions { dry_run: false, prune: query.prune, operator_id: user_id.clone() };
+    match applier.apply(&document, &options) {
+        Ok(plan) => {
+            for change in &plan.changes {
+                state.audit_manager.log_operation(
+                    change.kind.to_string(),
+                    change.key.clone(),
+                    format!("apply:{:?}", change.action).to_lowercase(),
+                    user_id.clone(),
+                );
+            }
+            (StatusCode::OK, Json(ApiResponse::success(plan)))
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e))),
+    }
+}
@@ -0,0 +1,256 @@
+//! Approval workflow HTTP API
+
+use crate::auth::Permission;
+use crate::model::{
+    ApprovalPolicy, ChangeRequest, ChangeRequestStatus, PendingOperation, ReviewChangeRequest,
+};
+use crate::web::middleware::TokenScopes;
+use crate::web::state::ManagementState;
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self { success: true, data: Some(data), message: None }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self { success: false, data: None, message: Some(message) }
+    }
+}
+
+/// 操作命中审批策略时的响应: 202 Accepted, data 为创建的变更请求
+pub fn pending_response(request: ChangeRequest) -> Response {
+    let message = format!(
+        "Approval required: change request {} must be approved by another user",
+        request.request_id
+    );
+    let body = ApiResponse { success: true, data: Some(request), message: Some(message) };
+    (StatusCode::ACCEPTED, Json(body)).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListChangeRequestsQuery {
+    pub status: Option<String>,
+}
+
+/// GET /api/management/approvals?status= - 列出变更请求
+pub async fn list_change_requests(
+    State(state): State<ManagementState>,
+    Query(query): Query<ListChangeRequestsQuery>,
+) -> impl IntoResponse {
+    let status = match query.status.as_deref().map(str::parse::<ChangeRequestStatus>) {
+        None => None,
+        Some(Ok(status)) => Some(status),
+        Some(Err(e)) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+    };
+    match state.approval_manager.list_requests(status).await {
+        Ok(requests) => (StatusCode::OK, Json(ApiResponse::success(requests))),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e))),
+    }
+}
+
+/// GET /api/management/approvals/policies - 查询审批策略
+pub async fn list_approval_policies(State(state): State<ManagementState>) -> impl IntoResponse {
+    let policies: Vec<ApprovalPolicy> = state.approval_manager.policies().to_vec();
+    (StatusCode::OK, Json(ApiResponse::success(policies)))
+}
+
+/// GET /api/management/approvals/:request_id - 查询变更请求
+pub async fn get_change_request(
+    State(state): State<ManagementState>,
+    Path(request_id): Path<String>,
+) -> impl IntoResponse {
+    match state.approval_manager.get_request(&request_id).await {
+        Ok(Some(request)) => (StatusCode::OK, Json(ApiResponse::success(request))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Change request not found: {}", request_id))),
+        ),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e))),
+    }
+}
+
+/// POST /api/management/approvals/:request_id/approve - 批准并执行变更请求
+///
+/// 审批人不能是申请人, 且必须有权执行该操作本身
+pub async fn approve_change_request(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(request_id): Path<String>,
+    body: Option<Json<ReviewChangeRequest>>,
+) -> impl IntoResponse {
+    if let Err((status, message)) =
+        check_reviewer(&state, &user_id, scopes.as_ref(), &request_id).await
+    {
+        return (status, Json(ApiResponse::error(message)));
+    }
+    let comment = body.and_then(|Json(body)| body.comment);
+
+    match state.approval_manager.approve(&request_id, &user_id, comment).await {
+        Ok(request) if request.status == ChangeRequestStatus::Failed => {
+            let message = format!(
+                "Change request approved but execution failed: {}",
+                request.error.as_deref().unwrap_or("unknown error")
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(message)))
+        }
+        Ok(request) => (StatusCode::OK, Json(ApiResponse::success(request))),
+        Err(e) => (StatusCode::CONFLICT, Json(ApiResponse::error(e))),
+    }
+}
+
+/// POST /api/management/approvals/:request_id/reject - 拒绝变更请求
+pub async fn reject_change_request(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(request_id): Path<String>,
+    body: Option<Json<ReviewChangeRequest>>,
+) -> impl IntoResponse {
+    if let Err((status, message)) =
+        check_reviewer(&state, &user_id, scopes.as_ref(), &request_id).await
+    {
+        return (status, Json(ApiResponse::error(message)));
+    }
+    let comment = body.and_then(|Json(body)| body.comment);
+
+    match state.approval_manager.reject(&request_id, &user_id, comment).await {
+        Ok(request) => (StatusCode::OK, Json(ApiResponse::success(request))),
+        Err(e) => (StatusCode::CONFLICT, Json(ApiResponse::error(e))),
+    }
+}
+
+/// POST /api/management/approvals/:request_id/cancel - 申请人撤回变更请求
+pub async fn cancel_change_request(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    Path(request_id): Path<String>,
+    body: Option<Json<ReviewChangeRequest>>,
+) -> impl IntoResponse {
+    match state.approval_manager.get_request(&request_id).await {
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e))),
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(format!("Change request not found: {}", request_id))),
+            );
+        }
+        Ok(Some(request)) if request.requested_by != user_id => {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::error(
+                    "Only the requester can cancel a change request".to_string(),
+                )),
+            );
+        }
+        Ok(Some(_)) => {}
+    }
+    let comment = body.and_then(|Json(body)| body.comment);
+
+    match state.approval_manager.cancel(&request_id, &user_id, comment).await {
+        Ok(request) => (StatusCode::OK, Json(ApiResponse::success(request))),
+        Err(e) => (StatusCode::CONFLICT, Json(ApiResponse::error(e))),
+    }
+}
+
+/// 检查审批人: 请求存在、不是申请人、有权执行该操作
+async fn check_reviewer(
+    state: &ManagementState,
+    user_id: &str,
+    scopes: Option<&Extension<TokenScopes>>,
+    request_id: &str,
+) -> Result<(), (StatusCode, String)> {
+    let Some(request) = state
+        .approval_manager
+        .get_request(request_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+    else {
+        return Err((StatusCode::NOT_FOUND, format!("Change request not found: {}", request_id)));
+    };
+    if request.requested_by == user_id {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Change requests must be reviewed by a different user".to_string(),
+        ));
+    }
+    if !may_execute(state, user_id, scopes, &request.operation) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!(
+                "Permission denied: {}:write required to review {}",
+                request.operation.kind().permission_resource(),
+                request.operation.kind()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 用户是否有权执行操作 (实例操作按服务范围检查), 通过 API Token 访问时令牌范围也必须授权
+fn may_execute(
+    state: &ManagementState,
+    user_id: &str,
+    scopes: Option<&Extension<TokenScopes>>,
+    operation: &PendingOperation,
+) -> bool {
+    let resource = operation.kind().permission_resource();
+    let granted = |permissions: &[Permission]| {
+        permissions.iter().any(|p| match operation.service_id() {
+            Some(service_id) => p.allows_service(resource, "write", service_id),
+            None => !p.is_scoped() && p.allows(resource, "write"),
+        })
+    };
+    granted(&state.auth_manager.get_effective_permissions(user_id))
+        && scopes.is_none_or(|Extension(TokenScopes(scopes))| granted(scopes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_query_status() {
+        let query: ListChangeRequestsQuery =
+            serde_json::from_value(serde_json::json!({ "status": "pending" })).unwrap();
+        assert_eq!(query.status.as_deref(), Some("pending"));
+    }
+
+    #[test]
+    fn test_pending_response_is_accepted() {
+        let request = ChangeRequest {
+            request_id: "cr-1".to_string(),
+            operation: PendingOperation::ZonePullOut {
+                zone_id: "zone-1".to_string(),
+                region_id: "us-east".to_string(),
+                operator_id: "ops".to_string(),
+            },
+            affected_instances: 0,
+            status: ChangeRequestStatus::Pending,
+            requested_by: "user-ops".to_string(),
+            requested_at: 0,
+            expires_at: 0,
+            reviewed_by: None,
+            reviewed_at: None,
+            comment: None,
+            error: None,
+        };
+        assert_eq!(pending_response(request).status(), StatusCode::ACCEPTED);
+    }
+}
@@ -1,12 +1,19 @@
-use crate::auth::{Session, UserResponse, UserRole, UserStatus};
+use crate::auth::keys::SigningKey;
+use crate::auth::oidc::{OIDC_LOGIN_COOKIE, OIDC_LOGIN_TTL_SECS};
+use crate::auth::{
+    ApiToken, LoginOutcome, Permission, Role, Session, UserResponse, UserRole, UserStatus,
+};
+use crate::web::middleware::TokenScopes;
 use crate::web::state::ManagementState;
+use artemis_service::config::OidcConfig;
 use axum::{
-    extract::{Path, Request, State},
-    http::StatusCode,
-    response::IntoResponse,
     Extension, Json,
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
 };
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::str::FromStr;
 
 // ===== 请求/响应模型 =====
@@ -23,6 +30,18 @@ pub struct LoginResponse {
     pub access_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    /// 必须先修改密码, 在此之前除个人接口外的请求都会被拒绝
+    pub must_change_password: bool,
+}
+
+/// OIDC 授权回调参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// 身份提供方拒绝授权时返回的错误
+    pub error: Option<String>,
+    pub error_description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -66,6 +85,9 @@ pub struct UpdateUserStatusRequest {
 pub struct CheckPermissionRequest {
     pub resource: String,
     pub action: String,
+    /// 指定服务时按服务范围检查, 否则只要拥有任意范围的授权即允许
+    #[serde(default)]
+    pub service_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,6 +95,119 @@ pub struct CheckPermissionResponse {
     pub allowed: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRoleRequest {
+    pub role_id: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub description: Option<String>,
+    pub permissions: Option<Vec<Permission>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetUserRolesRequest {
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    /// 有效天数, 不指定则永不过期
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+    /// 令牌权限范围, 为空则继承用户的全部权限
+    #[serde(default)]
+    pub scopes: Vec<Permission>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiTokenResponse {
+    /// 令牌明文, 只在创建时返回一次
+    pub token: String,
+    pub api_token: ApiToken,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateServiceAccountRequest {
+    pub username: String,
+    pub description: Option<String>,
+    pub role: String, // "admin", "operator", "viewer"
+}
+
+/// 启用两步验证的用户登录时返回的挑战
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    /// 提交验证码时使用的挑战令牌
+    pub challenge_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyMfaRequest {
+    pub challenge_token: String,
+    /// TOTP 验证码或恢复码
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    /// 所属角色要求两步验证
+    pub required: bool,
+    pub recovery_codes_remaining: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    /// 恢复码明文, 只返回一次
+    pub recovery_codes: Vec<String>,
+}
+
+/// 签名密钥信息 (不含私钥)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningKeyResponse {
+    pub kid: String,
+    pub algorithm: String,
+    /// 当前签名密钥; 否则为仅用于校验的旧密钥
+    pub active: bool,
+    pub created_at: i64,
+    pub retired_at: Option<i64>,
+}
+
+impl From<&SigningKey> for SigningKeyResponse {
+    fn from(key: &SigningKey) -> Self {
+        Self {
+            kid: key.kid.clone(),
+            algorithm: format!("{:?}", key.algorithm),
+            active: key.is_active(),
+            created_at: key.created_at,
+            retired_at: key.retired_at,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
@@ -104,27 +239,236 @@ fn extract_user_id(req: &Request) -> Result<String, (StatusCode, String)> {
 
 // ===== 认证 API =====
 
+/// 确定登录请求的客户端 IP
+///
+/// 只有配置信任反向代理时才使用 X-Forwarded-For / X-Real-IP, 否则使用连接地址
+fn client_ip(
+    headers: &HeaderMap,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    trust_forwarded_for: bool,
+) -> Option<String> {
+    if trust_forwarded_for {
+        let forwarded = headers
+            .get("X-Forwarded-For")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .or_else(|| headers.get("X-Real-IP").and_then(|h| h.to_str().ok()))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    connect_info.map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
 /// POST /api/auth/login - 用户登录
+///
+/// 账号或来源 IP 因连续失败被锁定时返回 429 和 Retry-After
 pub async fn login(
     State(state): State<ManagementState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
-) -> impl IntoResponse {
-    match state.auth_manager.authenticate(&req.username, &req.password, None, None) {
-        Ok(token) => {
-            let response = ApiResponse::success(LoginResponse {
-                access_token: token,
-                token_type: "Bearer".to_string(),
-                expires_in: 3600,
+) -> axum::response::Response {
+    let ip = client_ip(
+        &headers,
+        connect_info.as_ref().map(|Extension(info)| info),
+        state.auth_manager.trust_forwarded_for(),
+    );
+    let user_agent =
+        headers.get(header::USER_AGENT).and_then(|h| h.to_str().ok()).map(|v| v.to_string());
+
+    let locked_for = state.auth_manager.login_locked_for(&req.username, ip.as_deref());
+
+    match state.auth_manager.login(&req.username, &req.password, ip, user_agent) {
+        Ok(LoginOutcome::Authenticated(token)) => login_success(&state, &req.username, token),
+        Ok(LoginOutcome::MfaRequired(challenge)) => {
+            let response = ApiResponse::success(MfaChallengeResponse {
+                mfa_required: true,
+                challenge_token: challenge.challenge_token,
+                expires_in: challenge.expires_at - chrono::Utc::now().timestamp(),
             });
-            (StatusCode::OK, Json(response))
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => login_failure(e, locked_for),
+    }
+}
+
+/// POST /api/auth/login/mfa - 提交两步验证码完成登录
+pub async fn verify_mfa_login(
+    State(state): State<ManagementState>,
+    Json(req): Json<VerifyMfaRequest>,
+) -> axum::response::Response {
+    // 挑战可能由其他节点发起
+    if let Err(e) = state.auth_manager.load_mfa_challenge(&req.challenge_token).await {
+        tracing::error!("{}", e);
+    }
+    let Some(challenge) = state.auth_manager.get_mfa_challenge(&req.challenge_token) else {
+        return login_failure("Invalid or expired challenge".to_string(), None);
+    };
+    let locked_for =
+        state.auth_manager.login_locked_for(&challenge.username, challenge.ip_address.as_deref());
+
+    match state.auth_manager.verify_mfa_login(&req.challenge_token, &req.code) {
+        Ok(token) => login_success(&state, &challenge.username, token),
+        Err(e) => login_failure(e, locked_for),
+    }
+}
+
+/// GET /api/auth/oidc/login - 跳转到身份提供方登录
+///
+/// state、nonce 与 PKCE code_verifier 用集群签名密钥签名后放入 HttpOnly Cookie,
+/// 回调可落在任意节点, 且只有发起登录的浏览器能完成登录.
+pub async fn oidc_login(State(state): State<ManagementState>) -> axum::response::Response {
+    let Some(oidc) = state.auth_manager.oidc() else {
+        let response = ApiResponse::<()>::error("Single sign-on is not configured".to_string());
+        return (StatusCode::NOT_FOUND, Json(response)).into_response();
+    };
+
+    let started = oidc.authorization_url().await.and_then(|(url, login)| {
+        let sealed = state.auth_manager.seal_oidc_login(&login)?;
+        Ok((url, sealed))
+    });
+    match started {
+        Ok((url, sealed)) => {
+            let cookie = oidc_login_cookie(oidc.config(), &sealed, OIDC_LOGIN_TTL_SECS);
+            (StatusCode::FOUND, [(header::LOCATION, url), (header::SET_COOKIE, cookie)])
+                .into_response()
         }
         Err(e) => {
-            let response = ApiResponse::<LoginResponse>::error(e);
-            (StatusCode::UNAUTHORIZED, Json(response))
+            tracing::error!("Failed to start OIDC login: {}", e);
+            let response = ApiResponse::<()>::error(e);
+            (StatusCode::BAD_GATEWAY, Json(response)).into_response()
         }
     }
 }
 
+/// GET /api/auth/oidc/callback - 身份提供方授权回调
+///
+/// 校验 Cookie 中的登录状态和 ID Token 后跳转回控制台, 令牌放在 URL 片段中;
+/// 失败时片段携带 `error` 和 `error_description`.
+pub async fn oidc_callback(
+    State(state): State<ManagementState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> axum::response::Response {
+    let Some(oidc) = state.auth_manager.oidc() else {
+        let response = ApiResponse::<()>::error("Single sign-on is not configured".to_string());
+        return (StatusCode::NOT_FOUND, Json(response)).into_response();
+    };
+
+    let ip = client_ip(
+        &headers,
+        connect_info.as_ref().map(|Extension(info)| info),
+        state.auth_manager.trust_forwarded_for(),
+    );
+    let user_agent =
+        headers.get(header::USER_AGENT).and_then(|h| h.to_str().ok()).map(|v| v.to_string());
+
+    let result = async {
+        if let Some(error) = query.error {
+            let message = query.error_description.unwrap_or(error);
+            return Err(format!("Identity provider rejected login: {}", message));
+        }
+        let (Some(code), Some(oidc_state)) = (query.code, query.state) else {
+            return Err("Missing authorization code or state".to_string());
+        };
+        let sealed = cookie_value(&headers, OIDC_LOGIN_COOKIE)
+            .ok_or_else(|| "Login was not started from this browser".to_string())?;
+        let login = state.auth_manager.open_oidc_login(sealed).await?;
+        if login.state != oidc_state {
+            return Err("Login state mismatch".to_string());
+        }
+
+        let identity = oidc.exchange_code(&code, &login).await?;
+        let token = state.auth_manager.login_oidc(&identity, ip, user_agent).await?;
+        Ok((identity.username, token))
+    }
+    .await;
+
+    let fragment = match result {
+        Ok((username, token)) => {
+            let must_change_password = state
+                .auth_manager
+                .get_user_by_username(&username)
+                .is_some_and(|u| u.must_change_password);
+            encode_fragment(&[
+                ("access_token", token.as_str()),
+                ("token_type", "Bearer"),
+                ("expires_in", "3600"),
+                ("must_change_password", if must_change_password { "true" } else { "false" }),
+            ])
+        }
+        Err(e) => {
+            tracing::warn!("OIDC login failed: {}", e);
+            encode_fragment(&[("error", "login_failed"), ("error_description", e.as_str())])
+        }
+    };
+
+    let location = format!("{}#{}", oidc.config().console_url, fragment);
+    let cookie = oidc_login_cookie(oidc.config(), "", 0);
+    (StatusCode::SEE_OTHER, [(header::LOCATION, location), (header::SET_COOKIE, cookie)])
+        .into_response()
+}
+
+/// 登录状态 Cookie, 仅回调路径可见; max_age 为 0 时清除
+fn oidc_login_cookie(config: &OidcConfig, value: &str, max_age: i64) -> String {
+    let secure = if config.redirect_uri.starts_with("https://") { "; Secure" } else { "" };
+    format!(
+        "{}={}; Path=/api/auth/oidc; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        OIDC_LOGIN_COOKIE, value, max_age, secure
+    )
+}
+
+fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+/// 按 application/x-www-form-urlencoded 编码 URL 片段
+fn encode_fragment(pairs: &[(&str, &str)]) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").expect("static url");
+    url.query_pairs_mut().extend_pairs(pairs);
+    url.query().unwrap_or_default().to_string()
+}
+
+fn login_success(
+    state: &ManagementState,
+    username: &str,
+    token: String,
+) -> axum::response::Response {
+    let must_change_password =
+        state.auth_manager.get_user_by_username(username).is_some_and(|u| u.must_change_password);
+    let response = ApiResponse::success(LoginResponse {
+        access_token: token,
+        token_type: "Bearer".to_string(),
+        expires_in: 3600,
+        must_change_password,
+    });
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+fn login_failure(message: String, locked_for: Option<i64>) -> axum::response::Response {
+    let response = ApiResponse::<LoginResponse>::error(message);
+    match locked_for {
+        Some(seconds) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, seconds.to_string())],
+            Json(response),
+        )
+            .into_response(),
+        None => (StatusCode::UNAUTHORIZED, Json(response)).into_response(),
+    }
+}
+
 /// POST /api/auth/logout - 用户登出
 pub async fn logout(State(state): State<ManagementState>, req: Request) -> impl IntoResponse {
     // 从 header 中提取 token
@@ -151,12 +495,19 @@ pub async fn refresh_token(
     State(state): State<ManagementState>,
     Json(req): Json<RefreshTokenRequest>,
 ) -> impl IntoResponse {
+    // 令牌可能由其他节点签发
+    let _ = state.auth_manager.validate_token_shared(&req.token).await;
     match state.auth_manager.refresh_token(&req.token) {
         Ok(new_token) => {
+            let must_change_password = state
+                .auth_manager
+                .validate_token(&new_token)
+                .is_ok_and(|session| state.auth_manager.password_change_required(&session.user_id));
             let response = ApiResponse::success(LoginResponse {
                 access_token: new_token,
                 token_type: "Bearer".to_string(),
                 expires_in: 3600,
+                must_change_password,
             });
             (StatusCode::OK, Json(response))
         }
@@ -234,6 +585,10 @@ pub async fn list_sessions(
 ) -> impl IntoResponse {
     match extract_user_id(&req) {
         Ok(user_id) => {
+            // 包含在其他节点登录的会话
+            if let Err(e) = state.auth_manager.load_user_sessions(&user_id).await {
+                tracing::warn!("Failed to load sessions from database: {}", e);
+            }
             let sessions = state.auth_manager.list_user_sessions(&user_id);
             (StatusCode::OK, Json(ApiResponse::success(sessions)))
         }
@@ -242,10 +597,26 @@ pub async fn list_sessions(
 }
 
 /// DELETE /api/auth/sessions/:session_id - 撤销会话
+///
+/// 用户可以撤销自己的会话, 撤销他人会话需要 users:write 权限
 pub async fn revoke_session(
     State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
     Path(session_id): Path<String>,
 ) -> impl IntoResponse {
+    // 会话可能由其他节点创建
+    if let Err(e) = state.auth_manager.load_session(&session_id).await {
+        tracing::warn!("Failed to load session from database: {}", e);
+    }
+    let owned =
+        state.auth_manager.list_user_sessions(&user_id).iter().any(|s| s.session_id == session_id);
+    if !owned && !state.auth_manager.check_permission(&user_id, "users", "write") {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<String>::error("Permission denied".to_string())),
+        );
+    }
+
     match state.auth_manager.revoke_session(&session_id) {
         Ok(_) => {
             (StatusCode::OK, Json(ApiResponse::success("Session revoked successfully".to_string())))
@@ -254,22 +625,80 @@ pub async fn revoke_session(
     }
 }
 
-/// GET /api/auth/roles - 列出所有角色
-pub async fn list_roles() -> impl IntoResponse {
-    let roles = vec!["admin", "operator", "viewer"];
-    (StatusCode::OK, Json(ApiResponse::success(roles)))
-}
-
 /// POST /api/auth/check-permission - 检查权限
 pub async fn check_permission(
     State(state): State<ManagementState>,
     Extension(user_id): Extension<String>,
     Json(body): Json<CheckPermissionRequest>,
 ) -> impl IntoResponse {
-    let allowed = state.auth_manager.check_permission(&user_id, &body.resource, &body.action);
+    let allowed = match &body.service_id {
+        Some(service_id) => state.auth_manager.check_service_permission(
+            &user_id,
+            &body.resource,
+            &body.action,
+            service_id,
+        ),
+        None => state.auth_manager.check_permission(&user_id, &body.resource, &body.action),
+    };
     (StatusCode::OK, Json(ApiResponse::success(CheckPermissionResponse { allowed })))
 }
 
+// ===== 角色管理 API =====
+
+/// GET /api/auth/roles - 列出所有角色 (内置 + 自定义)
+pub async fn list_roles(State(state): State<ManagementState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.auth_manager.list_roles())))
+}
+
+/// POST /api/auth/roles - 创建自定义角色
+pub async fn create_role(
+    State(state): State<ManagementState>,
+    Json(req): Json<CreateRoleRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.create_role(&req.role_id, req.description, req.permissions) {
+        Ok(role) => (StatusCode::CREATED, Json(ApiResponse::success(role))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<Role>::error(e))),
+    }
+}
+
+/// GET /api/auth/roles/:role_id - 获取角色详情
+pub async fn get_role(
+    State(state): State<ManagementState>,
+    Path(role_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.get_role(&role_id) {
+        Some(role) => (StatusCode::OK, Json(ApiResponse::success(role))),
+        None => {
+            (StatusCode::NOT_FOUND, Json(ApiResponse::<Role>::error("Role not found".to_string())))
+        }
+    }
+}
+
+/// PUT /api/auth/roles/:role_id - 更新自定义角色
+pub async fn update_role(
+    State(state): State<ManagementState>,
+    Path(role_id): Path<String>,
+    Json(req): Json<UpdateRoleRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.update_role(&role_id, req.description, req.permissions) {
+        Ok(role) => (StatusCode::OK, Json(ApiResponse::success(role))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<Role>::error(e))),
+    }
+}
+
+/// DELETE /api/auth/roles/:role_id - 删除自定义角色
+pub async fn delete_role(
+    State(state): State<ManagementState>,
+    Path(role_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.delete_role(&role_id) {
+        Ok(_) => {
+            (StatusCode::OK, Json(ApiResponse::success("Role deleted successfully".to_string())))
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<String>::error(e))),
+    }
+}
+
 // ===== 用户管理 API =====
 
 /// GET /api/auth/users - 列出所有用户
@@ -372,6 +801,18 @@ pub async fn update_user_status(
     }
 }
 
+/// PUT /api/auth/users/:user_id/roles - 设置用户的附加角色
+pub async fn set_user_roles(
+    State(state): State<ManagementState>,
+    Path(user_id): Path<String>,
+    Json(req): Json<SetUserRolesRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.set_user_roles(&user_id, req.roles) {
+        Ok(user) => (StatusCode::OK, Json(ApiResponse::success(user.to_response()))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<UserResponse>::error(e))),
+    }
+}
+
 /// GET /api/auth/users/:user_id/login-history - 获取登录历史
 pub async fn get_login_history(
     State(state): State<ManagementState>,
@@ -380,3 +821,281 @@ pub async fn get_login_history(
     let history = state.auth_manager.get_login_history(&user_id, 50);
     (StatusCode::OK, Json(ApiResponse::success(history)))
 }
+
+// ===== API Token =====
+
+/// 签发 API Token
+fn issue_api_token(
+    state: &ManagementState,
+    user_id: &str,
+    req: CreateApiTokenRequest,
+) -> (StatusCode, Json<ApiResponse<CreateApiTokenResponse>>) {
+    match state.auth_manager.create_api_token(user_id, &req.name, req.scopes, req.expires_in_days) {
+        Ok((api_token, token)) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(CreateApiTokenResponse { token, api_token })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::error(e))),
+    }
+}
+
+/// GET /api/auth/tokens - 列出当前用户的 API Token
+pub async fn list_my_tokens(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.auth_manager.list_api_tokens(&user_id))))
+}
+
+/// POST /api/auth/tokens - 为当前用户签发 API Token
+///
+/// 限定范围的 API Token 不能再签发新令牌, 避免绕过范围限制
+pub async fn create_my_token(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    scopes: Option<Extension<TokenScopes>>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> impl IntoResponse {
+    if scopes.is_some() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::error("Scoped API tokens cannot issue new tokens".to_string())),
+        );
+    }
+    issue_api_token(&state, &user_id, req)
+}
+
+/// DELETE /api/auth/tokens/{token_id} - 撤销 API Token
+///
+/// 可撤销自己的令牌; 撤销他人令牌需要 users:write 权限
+pub async fn revoke_token(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    scopes: Option<Extension<TokenScopes>>,
+    Path(token_id): Path<String>,
+) -> impl IntoResponse {
+    let Some(token) = state.auth_manager.get_api_token(&token_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<String>::error("API token not found".to_string())),
+        );
+    };
+    let can_manage_users = state.auth_manager.check_permission(&user_id, "users", "write")
+        && scopes.is_none_or(|Extension(TokenScopes(scopes))| {
+            scopes.iter().any(|p| p.allows("users", "write"))
+        });
+    if token.user_id != user_id && !can_manage_users {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<String>::error("Permission denied".to_string())),
+        );
+    }
+
+    match state.auth_manager.revoke_api_token(&token_id) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success("API token revoked successfully".to_string())),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiResponse::<String>::error(e))),
+    }
+}
+
+/// GET /api/auth/users/{user_id}/tokens - 列出指定用户的 API Token
+pub async fn list_user_tokens(
+    State(state): State<ManagementState>,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.auth_manager.list_api_tokens(&user_id))))
+}
+
+/// POST /api/auth/users/{user_id}/tokens - 为指定用户或服务账号签发 API Token
+pub async fn create_user_token(
+    State(state): State<ManagementState>,
+    Path(user_id): Path<String>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> impl IntoResponse {
+    issue_api_token(&state, &user_id, req)
+}
+
+// ===== 服务账号 =====
+
+/// GET /api/auth/service-accounts - 列出服务账号
+pub async fn list_service_accounts(State(state): State<ManagementState>) -> impl IntoResponse {
+    let accounts: Vec<UserResponse> =
+        state.auth_manager.list_service_accounts().iter().map(|u| u.to_response()).collect();
+    (StatusCode::OK, Json(ApiResponse::success(accounts)))
+}
+
+/// POST /api/auth/service-accounts - 创建服务账号
+pub async fn create_service_account(
+    State(state): State<ManagementState>,
+    Json(req): Json<CreateServiceAccountRequest>,
+) -> impl IntoResponse {
+    let role = match UserRole::from_str(&req.role) {
+        Ok(r) => r,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<UserResponse>::error(e)));
+        }
+    };
+
+    match state.auth_manager.create_service_account(&req.username, req.description, role) {
+        Ok(user) => (StatusCode::CREATED, Json(ApiResponse::success(user.to_response()))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<UserResponse>::error(e))),
+    }
+}
+
+// ===== 两步验证 API =====
+
+/// GET /api/auth/mfa - 当前用户两步验证状态
+pub async fn get_mfa_status(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse {
+    let Some(user) = state.auth_manager.get_user(&user_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<MfaStatusResponse>::error("User not found".to_string())),
+        );
+    };
+
+    let status = MfaStatusResponse {
+        enabled: user.mfa_enabled,
+        required: user.mfa_enabled || state.auth_manager.mfa_enrollment_required(&user_id),
+        recovery_codes_remaining: state.auth_manager.recovery_codes_remaining(&user_id),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(status)))
+}
+
+/// POST /api/auth/mfa/totp/enroll - 发起 TOTP 绑定
+pub async fn begin_totp_enrollment(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.begin_totp_enrollment(&user_id) {
+        Ok((secret, otpauth_uri)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(TotpEnrollmentResponse { secret, otpauth_uri })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<TotpEnrollmentResponse>::error(e))),
+    }
+}
+
+/// POST /api/auth/mfa/totp/confirm - 使用验证码确认绑定, 返回恢复码
+pub async fn confirm_totp_enrollment(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    Json(req): Json<MfaCodeRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.confirm_totp_enrollment(&user_id, &req.code) {
+        Ok(recovery_codes) => {
+            (StatusCode::OK, Json(ApiResponse::success(RecoveryCodesResponse { recovery_codes })))
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<RecoveryCodesResponse>::error(e))),
+    }
+}
+
+/// POST /api/auth/mfa/totp/disable - 关闭两步验证
+pub async fn disable_totp(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    Json(req): Json<MfaCodeRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.disable_totp(&user_id, &req.code) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success("Two-factor authentication disabled".to_string())),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<String>::error(e))),
+    }
+}
+
+/// POST /api/auth/mfa/recovery-codes - 重新生成恢复码
+pub async fn regenerate_recovery_codes(
+    State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
+    Json(req): Json<MfaCodeRequest>,
+) -> impl IntoResponse {
+    match state.auth_manager.regenerate_recovery_codes(&user_id, &req.code) {
+        Ok(recovery_codes) => {
+            (StatusCode::OK, Json(ApiResponse::success(RecoveryCodesResponse { recovery_codes })))
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<RecoveryCodesResponse>::error(e))),
+    }
+}
+
+/// DELETE /api/auth/users/:user_id/mfa - 重置用户的两步验证 (管理员)
+pub async fn reset_user_mfa(
+    State(state): State<ManagementState>,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.reset_mfa(&user_id) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success("Two-factor authentication reset".to_string())),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiResponse::<String>::error(e))),
+    }
+}
+
+/// GET /api/auth/mfa/required-roles - 列出强制两步验证的角色
+pub async fn list_mfa_required_roles(State(state): State<ManagementState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.auth_manager.list_mfa_required_roles())))
+}
+
+/// PUT /api/auth/mfa/required-roles/:role_id - 要求角色启用两步验证
+pub async fn require_role_mfa(
+    State(state): State<ManagementState>,
+    Path(role_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.set_role_mfa_required(&role_id, true) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(state.auth_manager.list_mfa_required_roles())),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiResponse::<Vec<String>>::error(e))),
+    }
+}
+
+/// DELETE /api/auth/mfa/required-roles/:role_id - 取消角色的两步验证要求
+pub async fn unrequire_role_mfa(
+    State(state): State<ManagementState>,
+    Path(role_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_manager.set_role_mfa_required(&role_id, false) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(state.auth_manager.list_mfa_required_roles())),
+        ),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiResponse::<Vec<String>>::error(e))),
+    }
+}
+
+// ===== 签名密钥 API =====
+
+/// GET /api/auth/jwks - 访问令牌签名公钥 (JWKS), 供其他服务校验 Artemis 签发的令牌
+pub async fn jwks(State(state): State<ManagementState>) -> axum::response::Response {
+    match state.auth_manager.jwks() {
+        Ok(jwks) => (StatusCode::OK, [(header::CACHE_CONTROL, "public, max-age=300")], Json(jwks))
+            .into_response(),
+        Err(e) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<()>::error(e))).into_response()
+        }
+    }
+}
+
+/// GET /api/auth/keys - 列出签名密钥
+pub async fn list_signing_keys(State(state): State<ManagementState>) -> impl IntoResponse {
+    let keys: Vec<SigningKeyResponse> =
+        state.auth_manager.list_signing_keys().iter().map(SigningKeyResponse::from).collect();
+    (StatusCode::OK, Json(ApiResponse::success(keys)))
+}
+
+/// POST /api/auth/keys/rotate - 轮换签名密钥, 旧密钥在其令牌过期前仍可校验
+pub async fn rotate_signing_key(State(state): State<ManagementState>) -> impl IntoResponse {
+    match state.auth_manager.rotate_signing_key() {
+        Ok(key) => (StatusCode::OK, Json(ApiResponse::success(SigningKeyResponse::from(&key)))),
+        Err(e) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<SigningKeyResponse>::error(e)))
+        }
+    }
+}
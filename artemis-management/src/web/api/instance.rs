@@ -5,12 +5,14 @@ use crate::model::{
     GetAllServerOperationsRequest, GetAllServerOperationsResponse, GetInstanceOperationsRequest,
     GetInstanceOperationsResponse, InstanceOperation, IsInstanceDownRequest,
     IsInstanceDownResponse, IsServerDownRequest, IsServerDownResponse, OperateInstanceRequest,
-    OperateInstanceResponse, OperateServerRequest, OperateServerResponse, ServerOperationInfo,
+    OperateInstanceResponse, OperateServerRequest, OperateServerResponse, PendingOperation,
+    ServerOperationInfo,
 };
+use crate::web::api::approval;
 use crate::web::state::ManagementState;
 use artemis_common::model::ResponseStatus;
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
@@ -21,9 +23,10 @@ use tracing::{error, info};
 // ========== Instance Operations ==========
 
 /// POST /api/management/instance/operate-instance.json
-/// 操作实例 (拉入/拉出)
+/// 操作实例 (拉入/拉出), 拉出命中审批策略时返回 202 和待审批的变更请求
 pub async fn operate_instance(
     State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
     Json(req): Json<OperateInstanceRequest>,
 ) -> Response {
     info!(
@@ -31,6 +34,28 @@ pub async fn operate_instance(
         req.instance_key, req.operation, req.operation_complete
     );
 
+    if req.operation == InstanceOperation::PullOut {
+        let operation = PendingOperation::InstancePullOut {
+            instance_key: req.instance_key.clone(),
+            operation_complete: req.operation_complete,
+            operator_id: req.operator_id.clone(),
+        };
+        match state.approval_manager.submit(operation, &user_id).await {
+            Ok(Some(request)) => return approval::pending_response(request),
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to submit change request: {}", e);
+                let response = OperateInstanceResponse {
+                    status: ResponseStatus::error(
+                        artemis_common::model::ErrorCode::InternalError,
+                        format!("Operation failed: {}", e),
+                    ),
+                };
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            }
+        }
+    }
+
     let result = match req.operation {
         InstanceOperation::PullOut => state.instance_manager.pull_out_instance(
             &req.instance_key,
@@ -95,9 +120,10 @@ pub async fn is_instance_down(
 // ========== Server Operations ==========
 
 /// POST /api/management/server/operate-server.json
-/// 操作服务器 (批量拉入/拉出)
+/// 操作服务器 (批量拉入/拉出), 拉出命中审批策略时返回 202 和待审批的变更请求
 pub async fn operate_server(
     State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
     Json(req): Json<OperateServerRequest>,
 ) -> Response {
     info!(
@@ -105,23 +131,42 @@ pub async fn operate_server(
         req.server_id, req.region_id, req.operation, req.operation_complete
     );
 
-    let result = match req.operation {
-        crate::model::ServerOperation::PullOut => {
-            state.instance_manager.pull_out_server(
-                &req.server_id,
-                &req.region_id,
-                req.operator_id.clone(),
-                req.operation_complete,
-            )
-        }
-        crate::model::ServerOperation::PullIn => {
-            state.instance_manager.pull_in_server(
-                &req.server_id,
-                &req.region_id,
-                req.operator_id.clone(),
-                req.operation_complete,
-            )
+    if req.operation == crate::model::ServerOperation::PullOut {
+        let operation = PendingOperation::ServerPullOut {
+            server_id: req.server_id.clone(),
+            region_id: req.region_id.clone(),
+            operation_complete: req.operation_complete,
+            operator_id: req.operator_id.clone(),
+        };
+        match state.approval_manager.submit(operation, &user_id).await {
+            Ok(Some(request)) => return approval::pending_response(request),
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to submit change request: {}", e);
+                let response = OperateServerResponse {
+                    status: ResponseStatus::error(
+                        artemis_common::model::ErrorCode::InternalError,
+                        format!("Operation failed: {}", e),
+                    ),
+                };
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            }
         }
+    }
+
+    let result = match req.operation {
+        crate::model::ServerOperation::PullOut => state.instance_manager.pull_out_server(
+            &req.server_id,
+            &req.region_id,
+            req.operator_id.clone(),
+            req.operation_complete,
+        ),
+        crate::model::ServerOperation::PullIn => state.instance_manager.pull_in_server(
+            &req.server_id,
+            &req.region_id,
+            req.operator_id.clone(),
+            req.operation_complete,
+        ),
     };
 
     match result {
@@ -1,7 +1,9 @@
 //! Management API handlers
 
-pub mod auth;
+pub mod apply;
+pub mod approval;
 pub mod audit;
+pub mod auth;
 pub mod canary;
 pub mod instance;
 pub mod zone;
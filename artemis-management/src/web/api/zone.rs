@@ -1,9 +1,10 @@
 //! Zone management HTTP API
 
-use crate::model::OperateZoneRequest;
+use crate::model::{OperateZoneRequest, PendingOperation};
+use crate::web::api::approval;
 use crate::web::state::ManagementState;
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
@@ -30,16 +31,35 @@ impl<T> ApiResponse<T> {
 }
 
 /// POST /api/management/zone/pull-out - 拉出整个 Zone
+///
+/// 命中审批策略时不立即执行, 返回 202 和待审批的变更请求
 pub async fn pull_out_zone(
     State(state): State<ManagementState>,
+    Extension(user_id): Extension<String>,
     Json(req): Json<OperateZoneRequest>,
 ) -> impl IntoResponse {
+    let operation = PendingOperation::ZonePullOut {
+        zone_id: req.zone_id.clone(),
+        region_id: req.region_id.clone(),
+        operator_id: req.operator_id.clone(),
+    };
+    match state.approval_manager.submit(operation, &user_id).await {
+        Ok(Some(request)) => return approval::pending_response(request),
+        Ok(None) => {}
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<String>::error(e)))
+                .into_response();
+        }
+    }
+
     match state.zone_manager.pull_out_zone(&req.zone_id, &req.region_id, req.operator_id.clone()) {
         Ok(_) => {
             (StatusCode::OK, Json(ApiResponse::success("Zone pulled out successfully".to_string())))
+                .into_response()
         }
         Err(e) => {
             (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::<String>::error(e.to_string())))
+                .into_response()
         }
     }
 }
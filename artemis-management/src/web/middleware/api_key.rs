@@ -0,0 +1,113 @@
+use artemis_service::security::{API_KEY_HEADER, AppIdentity, SecurityManager};
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// 解析注册请求体时允许的最大请求体大小
+const MAX_REGISTRY_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// 注册接口 API Key 认证中间件
+///
+/// 认证应用身份后检查请求体中的 `instances[*].serviceId` / `instanceKeys[*].serviceId`,
+/// 应用只能注册、续约、注销自己拥有的服务; 未启用 API Key 时直接放行
+pub async fn registry_api_key_auth(
+    State(security): State<Arc<SecurityManager>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, &'static str)> {
+    if !security.registry_requires_api_key() {
+        return Ok(next.run(req).await);
+    }
+
+    let identity = authenticate(&security, req.headers())?;
+
+    let (mut parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_REGISTRY_BODY_BYTES)
+        .await
+        .map_err(|_| (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"))?;
+
+    if !service_ids_from_body(&bytes).iter().all(|service_id| identity.owns_service(service_id)) {
+        return Err((StatusCode::FORBIDDEN, "Service not owned by application"));
+    }
+
+    parts.extensions.insert(identity);
+    Ok(next.run(Request::from_parts(parts, Body::from(bytes))).await)
+}
+
+/// 发现接口 API Key 认证中间件
+///
+/// 只校验 API Key 有效, 不限制可查询的服务
+pub async fn discovery_api_key_auth(
+    State(security): State<Arc<SecurityManager>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, &'static str)> {
+    if !security.discovery_requires_api_key() {
+        return Ok(next.run(req).await);
+    }
+
+    let identity = authenticate(&security, req.headers())?;
+    req.extensions_mut().insert(identity);
+
+    Ok(next.run(req).await)
+}
+
+/// 从 `X-Artemis-Api-Key` 或 `Authorization: Bearer` 中提取 API Key 并认证
+fn authenticate(
+    security: &SecurityManager,
+    headers: &HeaderMap,
+) -> Result<AppIdentity, (StatusCode, &'static str)> {
+    let key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| {
+            headers
+                .get("Authorization")
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.strip_prefix("Bearer "))
+        })
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing API key"))?;
+
+    security.authenticate(key).ok_or((StatusCode::UNAUTHORIZED, "Invalid API key"))
+}
+
+/// 提取注册/心跳/注销请求体中的所有服务 ID
+fn service_ids_from_body(bytes: &[u8]) -> Vec<String> {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+        return Vec::new();
+    };
+
+    ["instances", "instanceKeys"]
+        .iter()
+        .filter_map(|field| value.get(*field).and_then(|v| v.as_array()))
+        .flatten()
+        .filter_map(|item| item.get("serviceId").and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_ids_from_body() {
+        assert_eq!(
+            service_ids_from_body(
+                br#"{"instances":[{"serviceId":"order-service"},{"serviceId":"order-api"}]}"#
+            ),
+            vec!["order-service".to_string(), "order-api".to_string()]
+        );
+        assert_eq!(
+            service_ids_from_body(br#"{"instanceKeys":[{"serviceId":"payment-service"}]}"#),
+            vec!["payment-service".to_string()]
+        );
+        assert!(service_ids_from_body(br#"{"instances":[]}"#).is_empty());
+        assert!(service_ids_from_body(b"not json").is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::auth::{ApiToken, Permission};
 use crate::web::state::ManagementState;
 use axum::{
     extract::{Request, State},
@@ -6,9 +7,14 @@ use axum::{
     response::Response,
 };
 
+/// API Token 的权限范围 (非空时与用户权限取交集)
+#[derive(Debug, Clone)]
+pub struct TokenScopes(pub Vec<Permission>);
+
 /// JWT 认证中间件
 ///
-/// 从 Authorization header 中提取 Bearer token,验证并将 user_id 注入到请求扩展中
+/// 从 Authorization header 中提取 Bearer token,验证并将 user_id 注入到请求扩展中;
+/// 同时接受 `art_` 前缀的 API Token, 限定范围的令牌额外注入 `TokenScopes`
 pub async fn jwt_auth(
     State(state): State<ManagementState>,
     headers: HeaderMap,
@@ -22,10 +28,26 @@ pub async fn jwt_auth(
         .and_then(|h| h.strip_prefix("Bearer "))
         .ok_or((StatusCode::UNAUTHORIZED, "Missing or invalid Authorization header"))?;
 
+    // API Token (个人访问令牌 / 服务账号令牌)
+    if ApiToken::is_api_token(token) {
+        let api_token = state
+            .auth_manager
+            .validate_api_token_shared(token)
+            .await
+            .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
+
+        req.extensions_mut().insert(api_token.user_id.clone());
+        if !api_token.scopes.is_empty() {
+            req.extensions_mut().insert(TokenScopes(api_token.scopes));
+        }
+        return Ok(next.run(req).await);
+    }
+
     // 验证 token
     let session = state
         .auth_manager
-        .validate_token(token)
+        .validate_token_shared(token)
+        .await
         .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
 
     // 将 user_id 注入到请求扩展中
@@ -1,5 +1,11 @@
-//! Management API middleware
+//! HTTP middleware: management API auth and core endpoint (registry/discovery/replication) auth
 
+pub mod api_key;
 pub mod jwt;
+pub mod permission;
+pub mod signature;
 
-pub use jwt::jwt_auth;
+pub use api_key::{discovery_api_key_auth, registry_api_key_auth};
+pub use jwt::{TokenScopes, jwt_auth};
+pub use permission::{PermissionGuard, require, require_permission};
+pub use signature::{federation_signature_auth, replication_signature_auth};
@@ -0,0 +1,198 @@
+use super::jwt::TokenScopes;
+use crate::auth::{AuthManager, Permission};
+use axum::{
+    RequestExt,
+    body::{Body, to_bytes},
+    extract::{RawPathParams, Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::Response,
+    routing::MethodRouter,
+};
+use std::sync::Arc;
+
+/// 解析请求体查找服务 ID 时允许的最大请求体大小
+const MAX_INSPECTED_BODY_BYTES: usize = 1024 * 1024;
+
+/// 路由所需的权限 (resource, action)
+#[derive(Clone)]
+pub struct PermissionGuard {
+    auth_manager: Arc<AuthManager>,
+    resource: &'static str,
+    action: &'static str,
+}
+
+impl PermissionGuard {
+    pub fn new(
+        auth_manager: Arc<AuthManager>,
+        resource: &'static str,
+        action: &'static str,
+    ) -> Self {
+        Self { auth_manager, resource, action }
+    }
+}
+
+/// 为路由附加权限要求
+///
+/// 必须位于 `jwt_auth` 之内 (即 jwt_auth 作为外层 route_layer), 以便获取当前用户
+pub fn require<S>(
+    method_router: MethodRouter<S>,
+    auth_manager: &Arc<AuthManager>,
+    resource: &'static str,
+    action: &'static str,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    method_router.route_layer(middleware::from_fn_with_state(
+        PermissionGuard::new(auth_manager.clone(), resource, action),
+        require_permission,
+    ))
+}
+
+/// 权限检查中间件
+///
+/// 用户拥有不限服务范围的授权时直接放行; 只拥有限定服务范围的授权时,
+/// 收集路径参数 `service_id`、查询参数 `serviceId` 和 JSON 请求体 (`serviceId` /
+/// `instanceKey.serviceId`) 中出现的所有目标服务, 任一不在服务范围内或无法确定时拒绝。
+/// 通过限定范围的 API Token 访问时, 用户权限和令牌范围必须同时授权;
+/// 用户被要求修改密码或绑定两步验证时一律拒绝
+pub async fn require_permission(
+    State(guard): State<PermissionGuard>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, &'static str)> {
+    let user_id = req
+        .extensions()
+        .get::<String>()
+        .cloned()
+        .ok_or((StatusCode::UNAUTHORIZED, "User ID not found in request"))?;
+
+    // 首次登录等场景下必须先修改密码, 此前只允许访问个人接口
+    if guard.auth_manager.password_change_required(&user_id) {
+        return Err((StatusCode::FORBIDDEN, "Password change required"));
+    }
+    if guard.auth_manager.mfa_enrollment_required(&user_id) {
+        return Err((StatusCode::FORBIDDEN, "Two-factor authentication enrollment required"));
+    }
+
+    let mut permission_sets = vec![guard.auth_manager.get_effective_permissions(&user_id)];
+    if let Some(TokenScopes(scopes)) = req.extensions().get::<TokenScopes>() {
+        permission_sets.push(scopes.clone());
+    }
+
+    // 每组权限 (用户权限、令牌范围) 都必须授权
+    let granted: Vec<Vec<Permission>> = permission_sets
+        .into_iter()
+        .map(|set| set.into_iter().filter(|p| p.allows(guard.resource, guard.action)).collect())
+        .collect();
+
+    if granted.iter().any(|set| set.is_empty()) {
+        return Err((StatusCode::FORBIDDEN, "Permission denied"));
+    }
+    if granted.iter().all(|set| set.iter().any(|p| !p.is_scoped())) {
+        return Ok(next.run(req).await);
+    }
+
+    // 仅有限定服务范围的授权, 收集路径、查询参数和请求体中的所有目标服务, 每个都必须在授权范围内
+    let mut service_ids: Vec<String> = req
+        .extract_parts::<RawPathParams>()
+        .await
+        .ok()
+        .map(|params| {
+            params
+                .iter()
+                .filter(|(key, _)| *key == "service_id")
+                .map(|(_, v)| v.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    service_ids.extend(service_ids_from_query(req.uri().query()));
+
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_INSPECTED_BODY_BYTES)
+        .await
+        .map_err(|_| (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"))?;
+    service_ids.extend(service_ids_from_body(&bytes));
+    let req = Request::from_parts(parts, Body::from(bytes));
+
+    let allowed = !service_ids.is_empty()
+        && service_ids.iter().all(|service_id| {
+            granted.iter().all(|set| {
+                set.iter().any(|p| p.allows_service(guard.resource, guard.action, service_id))
+            })
+        });
+    if allowed {
+        Ok(next.run(req).await)
+    } else {
+        Err((StatusCode::FORBIDDEN, "Permission denied"))
+    }
+}
+
+fn service_ids_from_query(query: Option<&str>) -> Vec<String> {
+    let Some(query) = query else {
+        return Vec::new();
+    };
+    query
+        .split('&')
+        .filter_map(|pair| match pair.split_once('=') {
+            Some(("serviceId", value)) | Some(("service_id", value)) if !value.is_empty() => {
+                Some(value.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn service_ids_from_body(bytes: &[u8]) -> Vec<String> {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+        return Vec::new();
+    };
+    [value.get("serviceId"), value.get("instanceKey").and_then(|key| key.get("serviceId"))]
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_ids_from_query() {
+        assert_eq!(
+            service_ids_from_query(Some("region=us&serviceId=order-service")),
+            vec!["order-service".to_string()]
+        );
+        assert_eq!(
+            service_ids_from_query(Some("service_id=a&serviceId=b")),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert!(service_ids_from_query(Some("serviceId=")).is_empty());
+        assert!(service_ids_from_query(None).is_empty());
+    }
+
+    #[test]
+    fn test_service_ids_from_body() {
+        assert_eq!(
+            service_ids_from_body(br#"{"serviceId":"order-service","enabled":true}"#),
+            vec!["order-service".to_string()]
+        );
+        assert_eq!(
+            service_ids_from_body(
+                br#"{"instanceKey":{"serviceId":"team-a-order","instanceId":"i-1"}}"#
+            ),
+            vec!["team-a-order".to_string()]
+        );
+        assert_eq!(
+            service_ids_from_body(
+                br#"{"serviceId":"team-a-x","instanceKey":{"serviceId":"team-b-y"}}"#
+            ),
+            vec!["team-a-x".to_string(), "team-b-y".to_string()]
+        );
+        assert!(service_ids_from_body(br#"{"zoneId":"zone-a"}"#).is_empty());
+        assert!(service_ids_from_body(b"not json").is_empty());
+    }
+}
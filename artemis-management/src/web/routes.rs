@@ -1,178 +1,288 @@
 //! Management API routes definition
 
-use crate::web::api::{audit, auth, canary, instance, zone};
-use crate::web::middleware;
+use crate::web::api::{apply, approval, audit, auth, canary, instance, zone};
+use crate::web::middleware::{self, require};
 use crate::web::state::ManagementState;
 use axum::{
-    middleware as axum_middleware,
+    Router, middleware as axum_middleware,
     routing::{delete, get, post, put},
-    Router,
 };
 use tower_http::cors::CorsLayer;
 
 /// Create management routes with authentication and authorization
+///
+/// 除登录和当前用户自助接口外, 每个路由都通过 `require` 标注所需的 (resource, action),
+/// 由权限中间件统一检查, 无权限时返回 403
 pub fn management_routes(state: ManagementState) -> Router {
+    let auth = state.auth_manager.clone();
+
     // 公开路由 (无需认证)
     let public_routes = Router::new()
         .route("/api/auth/login", post(auth::login))
-        .route("/api/auth/roles", get(auth::list_roles))
+        .route("/api/auth/login/mfa", post(auth::verify_mfa_login))
+        .route("/api/auth/oidc/login", get(auth::oidc_login))
+        .route("/api/auth/oidc/callback", get(auth::oidc_callback))
+        .route("/api/auth/jwks", get(auth::jwks))
         .with_state(state.clone());
 
     // 受保护路由 (需要 JWT 认证)
     let protected_routes = Router::new()
-        // ===== 认证相关 API =====
+        // ===== 认证相关 API (当前用户自助, 仅需登录) =====
         .route("/api/auth/logout", post(auth::logout))
         .route("/api/auth/refresh", post(auth::refresh_token))
         .route("/api/auth/user", get(auth::get_current_user))
         .route("/api/auth/permissions", get(auth::get_user_permissions))
         .route("/api/auth/password/change", post(auth::change_password))
+        .route("/api/auth/sessions", get(auth::list_sessions))
+        .route("/api/auth/sessions/{session_id}", delete(auth::revoke_session))
+        .route("/api/auth/check-permission", post(auth::check_permission))
+        .route("/api/auth/tokens", get(auth::list_my_tokens).post(auth::create_my_token))
+        .route("/api/auth/tokens/{token_id}", delete(auth::revoke_token))
+        .route("/api/auth/mfa", get(auth::get_mfa_status))
+        .route("/api/auth/mfa/totp/enroll", post(auth::begin_totp_enrollment))
+        .route("/api/auth/mfa/totp/confirm", post(auth::confirm_totp_enrollment))
+        .route("/api/auth/mfa/totp/disable", post(auth::disable_totp))
+        .route("/api/auth/mfa/recovery-codes", post(auth::regenerate_recovery_codes))
         .route(
             "/api/auth/password/reset/{user_id}",
-            post(auth::reset_password),
+            require(post(auth::reset_password), &auth, "users", "write"),
         )
-        .route("/api/auth/sessions", get(auth::list_sessions))
+        // 角色管理
+        .route("/api/auth/roles", require(get(auth::list_roles), &auth, "roles", "read"))
+        .route("/api/auth/roles", require(post(auth::create_role), &auth, "roles", "write"))
+        .route("/api/auth/roles/{role_id}", require(get(auth::get_role), &auth, "roles", "read"))
         .route(
-            "/api/auth/sessions/{session_id}",
-            delete(auth::revoke_session),
+            "/api/auth/roles/{role_id}",
+            require(put(auth::update_role), &auth, "roles", "write"),
+        )
+        .route(
+            "/api/auth/roles/{role_id}",
+            require(delete(auth::delete_role), &auth, "roles", "delete"),
+        )
+        // 强制两步验证的角色
+        .route(
+            "/api/auth/mfa/required-roles",
+            require(get(auth::list_mfa_required_roles), &auth, "roles", "read"),
+        )
+        .route(
+            "/api/auth/mfa/required-roles/{role_id}",
+            require(put(auth::require_role_mfa), &auth, "roles", "write"),
+        )
+        .route(
+            "/api/auth/mfa/required-roles/{role_id}",
+            require(delete(auth::unrequire_role_mfa), &auth, "roles", "write"),
+        )
+        // 访问令牌签名密钥
+        .route("/api/auth/keys", require(get(auth::list_signing_keys), &auth, "keys", "read"))
+        .route(
+            "/api/auth/keys/rotate",
+            require(post(auth::rotate_signing_key), &auth, "keys", "write"),
         )
-        .route("/api/auth/check-permission", post(auth::check_permission))
         // 用户管理
-        .route("/api/auth/users", get(auth::list_users))
-        .route("/api/auth/users", post(auth::create_user))
-        .route("/api/auth/users/{user_id}", get(auth::get_user))
-        .route("/api/auth/users/{user_id}", put(auth::update_user))
-        .route("/api/auth/users/{user_id}", delete(auth::delete_user))
+        .route("/api/auth/users", require(get(auth::list_users), &auth, "users", "read"))
+        .route("/api/auth/users", require(post(auth::create_user), &auth, "users", "write"))
+        .route("/api/auth/users/{user_id}", require(get(auth::get_user), &auth, "users", "read"))
+        .route(
+            "/api/auth/users/{user_id}",
+            require(put(auth::update_user), &auth, "users", "write"),
+        )
+        .route(
+            "/api/auth/users/{user_id}",
+            require(delete(auth::delete_user), &auth, "users", "delete"),
+        )
         .route(
             "/api/auth/users/{user_id}/status",
-            put(auth::update_user_status),
+            require(put(auth::update_user_status), &auth, "users", "write"),
+        )
+        .route(
+            "/api/auth/users/{user_id}/roles",
+            require(put(auth::set_user_roles), &auth, "users", "write"),
         )
         .route(
             "/api/auth/users/{user_id}/login-history",
-            get(auth::get_login_history),
+            require(get(auth::get_login_history), &auth, "users", "read"),
+        )
+        .route(
+            "/api/auth/users/{user_id}/tokens",
+            require(get(auth::list_user_tokens), &auth, "users", "read"),
+        )
+        .route(
+            "/api/auth/users/{user_id}/tokens",
+            require(post(auth::create_user_token), &auth, "users", "write"),
+        )
+        .route(
+            "/api/auth/users/{user_id}/mfa",
+            require(delete(auth::reset_user_mfa), &auth, "users", "write"),
+        )
+        // 服务账号
+        .route(
+            "/api/auth/service-accounts",
+            require(get(auth::list_service_accounts), &auth, "users", "read"),
+        )
+        .route(
+            "/api/auth/service-accounts",
+            require(post(auth::create_service_account), &auth, "users", "write"),
         )
         // ===== 实例操作 API =====
         .route(
             "/api/management/instance/operate-instance.json",
-            post(instance::operate_instance),
+            require(post(instance::operate_instance), &auth, "instances", "write"),
         )
         .route(
             "/api/management/instance/get-instance-operations.json",
-            post(instance::get_instance_operations),
+            require(post(instance::get_instance_operations), &auth, "instances", "read"),
         )
         .route(
             "/api/management/instance/is-instance-down.json",
-            post(instance::is_instance_down),
+            require(post(instance::is_instance_down), &auth, "instances", "read"),
         )
         .route(
             "/api/management/server/operate-server.json",
-            post(instance::operate_server),
+            require(post(instance::operate_server), &auth, "instances", "write"),
         )
         .route(
             "/api/management/server/is-server-down.json",
-            post(instance::is_server_down),
+            require(post(instance::is_server_down), &auth, "instances", "read"),
         )
         .route(
             "/api/management/all-instance-operations.json",
-            post(instance::get_all_instance_operations_post),
+            require(post(instance::get_all_instance_operations_post), &auth, "instances", "read"),
         )
         .route(
             "/api/management/all-instance-operations.json",
-            get(instance::get_all_instance_operations_get),
+            require(get(instance::get_all_instance_operations_get), &auth, "instances", "read"),
         )
         .route(
             "/api/management/all-server-operations.json",
-            post(instance::get_all_server_operations_post),
+            require(post(instance::get_all_server_operations_post), &auth, "instances", "read"),
         )
         .route(
             "/api/management/all-server-operations.json",
-            get(instance::get_all_server_operations_get),
+            require(get(instance::get_all_server_operations_get), &auth, "instances", "read"),
         )
         // ===== Zone 管理 API =====
-        .route("/api/management/zone/pull-out", post(zone::pull_out_zone))
-        .route("/api/management/zone/pull-in", post(zone::pull_in_zone))
+        .route(
+            "/api/management/zone/pull-out",
+            require(post(zone::pull_out_zone), &auth, "zones", "write"),
+        )
+        .route(
+            "/api/management/zone/pull-in",
+            require(post(zone::pull_in_zone), &auth, "zones", "write"),
+        )
         .route(
             "/api/management/zone/status/{zone_id}/{region_id}",
-            get(zone::get_zone_status),
+            require(get(zone::get_zone_status), &auth, "zones", "read"),
         )
         .route(
             "/api/management/zone/operations",
-            get(zone::list_zone_operations),
+            require(get(zone::list_zone_operations), &auth, "zones", "read"),
         )
         .route(
             "/api/management/zone/{zone_id}/{region_id}",
-            delete(zone::delete_zone_operation),
+            require(delete(zone::delete_zone_operation), &auth, "zones", "delete"),
         )
         // ===== 金丝雀发布 API =====
         .route(
             "/api/management/canary/config",
-            post(canary::set_canary_config),
+            require(post(canary::set_canary_config), &auth, "canary", "write"),
         )
         .route(
             "/api/management/canary/config/{service_id}",
-            get(canary::get_canary_config),
+            require(get(canary::get_canary_config), &auth, "canary", "read"),
+        )
+        .route(
+            "/api/management/canary/enable",
+            require(post(canary::enable_canary), &auth, "canary", "write"),
         )
-        .route("/api/management/canary/enable", post(canary::enable_canary))
         .route(
             "/api/management/canary/config/{service_id}",
-            delete(canary::delete_canary_config),
+            require(delete(canary::delete_canary_config), &auth, "canary", "delete"),
         )
         .route(
             "/api/management/canary/configs",
-            get(canary::list_canary_configs),
+            require(get(canary::list_canary_configs), &auth, "canary", "read"),
+        )
+        .route(
+            "/api/management/canary/disable",
+            require(post(canary::disable_canary), &auth, "canary", "write"),
         )
-        .route("/api/management/canary/disable", post(canary::disable_canary))
         .route(
             "/api/management/canary/{service_id}/whitelist/add",
-            post(canary::add_ip_to_whitelist),
+            require(post(canary::add_ip_to_whitelist), &auth, "canary", "write"),
         )
         .route(
             "/api/management/canary/{service_id}/whitelist/remove",
-            post(canary::remove_ip_from_whitelist),
+            require(post(canary::remove_ip_from_whitelist), &auth, "canary", "write"),
+        )
+        // ===== 声明式配置 API (按文档涉及的对象类型在处理函数中检查权限) =====
+        .route("/api/management/apply", post(apply::apply_document))
+        // ===== 审批 API (审批人还需要有权执行该操作本身, 在处理函数中检查) =====
+        .route(
+            "/api/management/approvals",
+            require(get(approval::list_change_requests), &auth, "approvals", "read"),
+        )
+        .route(
+            "/api/management/approvals/policies",
+            require(get(approval::list_approval_policies), &auth, "approvals", "read"),
+        )
+        .route(
+            "/api/management/approvals/{request_id}",
+            require(get(approval::get_change_request), &auth, "approvals", "read"),
+        )
+        .route(
+            "/api/management/approvals/{request_id}/approve",
+            require(post(approval::approve_change_request), &auth, "approvals", "write"),
+        )
+        .route(
+            "/api/management/approvals/{request_id}/reject",
+            require(post(approval::reject_change_request), &auth, "approvals", "write"),
+        )
+        // 撤回只允许申请人本人
+        .route(
+            "/api/management/approvals/{request_id}/cancel",
+            require(post(approval::cancel_change_request), &auth, "approvals", "read"),
         )
         // ===== 审计日志 API =====
-        .route("/api/management/audit/logs", get(audit::query_logs))
+        .route(
+            "/api/management/audit/logs",
+            require(get(audit::query_logs), &auth, "audit", "read"),
+        )
         .route(
             "/api/management/audit/instance-logs",
-            get(audit::query_instance_logs),
+            require(get(audit::query_instance_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/audit/server-logs",
-            get(audit::query_server_logs),
+            require(get(audit::query_server_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/group-logs.json",
-            post(audit::query_group_logs),
+            require(post(audit::query_group_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/route-rule-logs.json",
-            post(audit::query_route_rule_logs),
+            require(post(audit::query_route_rule_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/route-rule-group-logs.json",
-            post(audit::query_route_rule_group_logs),
+            require(post(audit::query_route_rule_group_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/zone-operation-logs.json",
-            post(audit::query_zone_operation_logs),
+            require(post(audit::query_zone_operation_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/group-instance-logs.json",
-            post(audit::query_group_instance_logs),
+            require(post(audit::query_group_instance_logs), &auth, "audit", "read"),
         )
         .route(
             "/api/management/log/service-instance-logs.json",
-            post(audit::query_service_instance_logs),
+            require(post(audit::query_service_instance_logs), &auth, "audit", "read"),
         )
         // 应用 JWT 认证中间件
-        .route_layer(axum_middleware::from_fn_with_state(
-            state.clone(),
-            middleware::jwt_auth,
-        ))
+        .route_layer(axum_middleware::from_fn_with_state(state.clone(), middleware::jwt_auth))
         .with_state(state);
 
     // 合并公开路由和受保护路由
-    Router::new()
-        .merge(public_routes)
-        .merge(protected_routes)
-        .layer(CorsLayer::permissive())
+    Router::new().merge(public_routes).merge(protected_routes).layer(CorsLayer::permissive())
 }
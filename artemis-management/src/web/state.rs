@@ -1,8 +1,8 @@
 //! Management state for HTTP API
 
 use crate::{
-    AuditManager, AuthManager, CanaryManager, GroupManager, InstanceManager, RouteManager,
-    ZoneManager,
+    ApprovalManager, AuditManager, AuthManager, CanaryManager, GroupManager, InstanceManager,
+    RouteManager, ZoneManager,
 };
 use std::sync::Arc;
 
@@ -16,6 +16,7 @@ pub struct ManagementState {
     pub zone_manager: Arc<ZoneManager>,
     pub canary_manager: Arc<CanaryManager>,
     pub audit_manager: Arc<AuditManager>,
+    pub approval_manager: Arc<ApprovalManager>,
 }
 
 impl ManagementState {
@@ -29,6 +30,12 @@ impl ManagementState {
         canary_manager: Arc<CanaryManager>,
         audit_manager: Arc<AuditManager>,
     ) -> Self {
+        // 默认不配置审批策略, 所有操作直接执行
+        let approval_manager = Arc::new(ApprovalManager::new(
+            instance_manager.clone(),
+            zone_manager.clone(),
+            audit_manager.clone(),
+        ));
         Self {
             auth_manager,
             instance_manager,
@@ -37,6 +44,13 @@ impl ManagementState {
             zone_manager,
             canary_manager,
             audit_manager,
+            approval_manager,
         }
     }
+
+    /// 使用已配置审批策略的审批管理器
+    pub fn with_approval_manager(mut self, approval_manager: Arc<ApprovalManager>) -> Self {
+        self.approval_manager = approval_manager;
+        self
+    }
 }
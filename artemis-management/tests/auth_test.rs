@@ -1,4 +1,15 @@
-use artemis_management::auth::{AuthManager, UserRole, UserStatus};
+use artemis_management::auth::totp::{TOTP_STEP_SECS, code_at};
+use artemis_management::auth::{AuthManager, LoginOutcome, Permission, UserRole, UserStatus};
+use artemis_management::{ApprovalManager, ManagementState, management_routes};
+use artemis_service::config::{
+    ApprovalConfig, ApprovalPolicyConfig, AuthConfig, JwtConfig, LockoutConfig,
+    PasswordPolicyConfig,
+};
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use std::sync::Arc;
+use tower::ServiceExt;
 
 // ========== 用户创建测试 ==========
 
@@ -27,8 +38,8 @@ fn test_create_user() {
 fn test_create_duplicate_user() {
     let manager = AuthManager::new();
 
-    manager.create_user("user1", None, None, "pass123", UserRole::Viewer).unwrap();
-    let result = manager.create_user("user1", None, None, "pass456", UserRole::Admin);
+    manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let result = manager.create_user("user1", None, None, "password456", UserRole::Admin);
 
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("already exists"));
@@ -38,9 +49,10 @@ fn test_create_duplicate_user() {
 fn test_create_users_with_different_roles() {
     let manager = AuthManager::new();
 
-    let admin = manager.create_user("admin", None, None, "pass", UserRole::Admin).unwrap();
-    let operator = manager.create_user("operator", None, None, "pass", UserRole::Operator).unwrap();
-    let viewer = manager.create_user("viewer", None, None, "pass", UserRole::Viewer).unwrap();
+    let admin = manager.create_user("admin", None, None, "password", UserRole::Admin).unwrap();
+    let operator =
+        manager.create_user("operator", None, None, "password", UserRole::Operator).unwrap();
+    let viewer = manager.create_user("viewer", None, None, "password", UserRole::Viewer).unwrap();
 
     assert_eq!(admin.role, UserRole::Admin);
     assert_eq!(operator.role, UserRole::Operator);
@@ -205,9 +217,9 @@ fn test_get_user_by_username() {
 fn test_list_users() {
     let manager = AuthManager::new();
 
-    manager.create_user("user1", None, None, "pass", UserRole::Admin).unwrap();
-    manager.create_user("user2", None, None, "pass", UserRole::Operator).unwrap();
-    manager.create_user("user3", None, None, "pass", UserRole::Viewer).unwrap();
+    manager.create_user("user1", None, None, "password", UserRole::Admin).unwrap();
+    manager.create_user("user2", None, None, "password", UserRole::Operator).unwrap();
+    manager.create_user("user3", None, None, "password", UserRole::Viewer).unwrap();
 
     let users = manager.list_users();
 
@@ -304,6 +316,10 @@ fn test_reset_password() {
     manager.reset_password(&user.user_id, "new_password").unwrap();
 
     assert!(manager.authenticate("user1", "new_password", None, None).is_ok());
+    // 管理员设置的密码只能用于登录后修改密码
+    assert!(manager.password_change_required(&user.user_id));
+    manager.change_password(&user.user_id, "new_password", "user_password").unwrap();
+    assert!(!manager.password_change_required(&user.user_id));
 }
 
 // ========== 用户状态测试 ==========
@@ -390,7 +406,7 @@ fn test_revoke_all_user_sessions() {
 #[test]
 fn test_admin_has_all_permissions() {
     let manager = AuthManager::new();
-    let admin = manager.create_user("admin", None, None, "pass", UserRole::Admin).unwrap();
+    let admin = manager.create_user("admin", None, None, "password", UserRole::Admin).unwrap();
 
     assert!(manager.check_permission(&admin.user_id, "services", "read"));
     assert!(manager.check_permission(&admin.user_id, "services", "write"));
@@ -401,7 +417,8 @@ fn test_admin_has_all_permissions() {
 #[test]
 fn test_operator_permissions() {
     let manager = AuthManager::new();
-    let operator = manager.create_user("operator", None, None, "pass", UserRole::Operator).unwrap();
+    let operator =
+        manager.create_user("operator", None, None, "password", UserRole::Operator).unwrap();
 
     // 有权限
     assert!(manager.check_permission(&operator.user_id, "services", "read"));
@@ -419,7 +436,7 @@ fn test_operator_permissions() {
 #[test]
 fn test_viewer_permissions() {
     let manager = AuthManager::new();
-    let viewer = manager.create_user("viewer", None, None, "pass", UserRole::Viewer).unwrap();
+    let viewer = manager.create_user("viewer", None, None, "password", UserRole::Viewer).unwrap();
 
     // 只有读权限
     assert!(manager.check_permission(&viewer.user_id, "services", "read"));
@@ -435,19 +452,136 @@ fn test_viewer_permissions() {
 #[test]
 fn test_get_user_permissions() {
     let manager = AuthManager::new();
-    let admin = manager.create_user("admin", None, None, "pass", UserRole::Admin).unwrap();
-    let operator = manager.create_user("operator", None, None, "pass", UserRole::Operator).unwrap();
-    let viewer = manager.create_user("viewer", None, None, "pass", UserRole::Viewer).unwrap();
+    let admin = manager.create_user("admin", None, None, "password", UserRole::Admin).unwrap();
+    let operator =
+        manager.create_user("operator", None, None, "password", UserRole::Operator).unwrap();
+    let viewer = manager.create_user("viewer", None, None, "password", UserRole::Viewer).unwrap();
 
     let admin_perms = manager.get_user_permissions(&admin.user_id);
     let operator_perms = manager.get_user_permissions(&operator.user_id);
     let viewer_perms = manager.get_user_permissions(&viewer.user_id);
 
     assert_eq!(admin_perms, vec!["*:*"]);
-    assert_eq!(operator_perms.len(), 6);
+    assert!(operator_perms.contains(&"instances:*".to_string()));
+    assert!(operator_perms.contains(&"zones:*".to_string()));
+    assert!(operator_perms.contains(&"audit:read".to_string()));
+    assert!(!operator_perms.iter().any(|p| p.starts_with("users:")));
     assert_eq!(viewer_perms, vec!["*:read"]);
 }
 
+// ========== 自定义角色测试 ==========
+
+#[test]
+fn test_builtin_roles_listed() {
+    let manager = AuthManager::new();
+    let roles = manager.list_roles();
+    let ids: Vec<&str> = roles.iter().map(|r| r.role_id.as_str()).collect();
+
+    assert_eq!(ids, vec!["admin", "operator", "viewer"]);
+    assert!(roles.iter().all(|r| r.builtin));
+}
+
+#[test]
+fn test_builtin_roles_are_immutable() {
+    let manager = AuthManager::new();
+
+    assert!(manager.update_role("admin", None, Some(vec![])).is_err());
+    assert!(manager.delete_role("viewer").is_err());
+    assert!(manager.create_role("operator", None, vec![]).is_err());
+}
+
+#[test]
+fn test_create_role_validation() {
+    let manager = AuthManager::new();
+
+    assert!(manager.create_role("Bad Role", None, vec![]).is_err());
+    assert!(
+        manager.create_role("ops", None, vec![Permission::new("nonexistent", "read")]).is_err()
+    );
+    assert!(manager.create_role("ops", None, vec![Permission::new("zones", "write")]).is_ok());
+    assert!(manager.create_role("ops", None, vec![]).is_err());
+}
+
+#[test]
+fn test_service_scoped_role() {
+    let manager = AuthManager::new();
+    manager
+        .create_role(
+            "team-a-ops",
+            Some("Team A instance operators".to_string()),
+            vec![Permission::scoped("instances", "write", "team-a-*")],
+        )
+        .unwrap();
+
+    let user = manager.create_user("alice", None, None, "password", UserRole::Viewer).unwrap();
+    manager.set_user_roles(&user.user_id, vec!["team-a-ops".to_string()]).unwrap();
+
+    // 可以操作 team A 的服务, 不能操作 team B 的服务
+    assert!(manager.check_service_permission(&user.user_id, "instances", "write", "team-a-order"));
+    assert!(!manager.check_service_permission(&user.user_id, "instances", "write", "team-b-order"));
+    // 不带服务时只要拥有任意范围的授权即允许
+    assert!(manager.check_permission(&user.user_id, "instances", "write"));
+    assert!(!manager.check_permission(&user.user_id, "zones", "write"));
+    // Viewer 主角色的读权限仍然有效
+    assert!(manager.check_service_permission(&user.user_id, "instances", "read", "team-b-order"));
+
+    let permissions = manager.get_user_permissions(&user.user_id);
+    assert_eq!(permissions, vec!["*:read", "instances:write:team-a-*"]);
+}
+
+#[test]
+fn test_user_with_multiple_roles() {
+    let manager = AuthManager::new();
+    manager
+        .create_role("team-a-ops", None, vec![Permission::scoped("instances", "*", "team-a-*")])
+        .unwrap();
+    manager
+        .create_role("team-b-ops", None, vec![Permission::scoped("instances", "*", "team-b-*")])
+        .unwrap();
+    manager.create_role("zone-ops", None, vec![Permission::new("zones", "write")]).unwrap();
+
+    let user = manager.create_user("bob", None, None, "password", UserRole::Viewer).unwrap();
+    let updated = manager
+        .set_user_roles(
+            &user.user_id,
+            vec![
+                "team-a-ops".to_string(),
+                "zone-ops".to_string(),
+                "team-a-ops".to_string(),
+                "viewer".to_string(),
+            ],
+        )
+        .unwrap();
+
+    // 去重, 且不重复记录主角色
+    assert_eq!(updated.roles, vec!["team-a-ops", "zone-ops"]);
+    assert_eq!(updated.to_response().roles, vec!["team-a-ops", "zone-ops"]);
+    assert!(manager.check_service_permission(&user.user_id, "instances", "delete", "team-a-x"));
+    assert!(!manager.check_service_permission(&user.user_id, "instances", "delete", "team-b-x"));
+    assert!(manager.check_permission(&user.user_id, "zones", "write"));
+
+    // 未知角色
+    assert!(manager.set_user_roles(&user.user_id, vec!["missing".to_string()]).is_err());
+}
+
+#[test]
+fn test_update_and_delete_custom_role() {
+    let manager = AuthManager::new();
+    manager.create_role("canary-ops", None, vec![Permission::new("canary", "read")]).unwrap();
+
+    let user = manager.create_user("carol", None, None, "password", UserRole::Viewer).unwrap();
+    manager.set_user_roles(&user.user_id, vec!["canary-ops".to_string()]).unwrap();
+    assert!(!manager.check_permission(&user.user_id, "canary", "write"));
+
+    manager.update_role("canary-ops", None, Some(vec![Permission::new("canary", "*")])).unwrap();
+    assert!(manager.check_permission(&user.user_id, "canary", "write"));
+
+    manager.delete_role("canary-ops").unwrap();
+    assert!(manager.get_role("canary-ops").is_none());
+    assert!(manager.get_user(&user.user_id).unwrap().roles.is_empty());
+    assert!(!manager.check_permission(&user.user_id, "canary", "write"));
+}
+
 // ========== 登录历史测试 ==========
 
 #[test]
@@ -525,3 +659,1174 @@ fn test_login_history_limit() {
 
     assert_eq!(history.len(), 5);
 }
+
+// ========== 路由权限检查测试 ==========
+
+struct TestApp {
+    app: Router,
+    manager: Arc<AuthManager>,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        let manager = Arc::new(AuthManager::new());
+        let state = ManagementState::new(
+            manager.clone(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        Self { app: management_routes(state), manager }
+    }
+
+    /// 指定操作需要审批
+    fn with_approval_policies(operations: &[&str]) -> Self {
+        let manager = Arc::new(AuthManager::new());
+        let state = ManagementState::new(
+            manager.clone(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let config = ApprovalConfig {
+            policies: operations
+                .iter()
+                .map(|operation| ApprovalPolicyConfig {
+                    operation: operation.to_string(),
+                    instance_threshold: None,
+                })
+                .collect(),
+            request_ttl_secs: 3600,
+        };
+        let approvals = ApprovalManager::new(
+            state.instance_manager.clone(),
+            state.zone_manager.clone(),
+            state.audit_manager.clone(),
+        )
+        .with_config(&config);
+        let state = state.with_approval_manager(Arc::new(approvals));
+        Self { app: management_routes(state), manager }
+    }
+
+    /// 创建用户并登录, 返回 token
+    fn login_as(&self, username: &str, role: UserRole) -> String {
+        self.manager.create_user(username, None, None, "password", role).unwrap();
+        self.manager.authenticate(username, "password", None, None).unwrap()
+    }
+
+    async fn call(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> StatusCode {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        };
+
+        self.app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    /// 发送 JSON 请求, 返回状态码和响应体
+    async fn call_json(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder =
+            Request::builder().method(method).uri(uri).header("Content-Type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = builder.body(Body::from(body.to_string())).unwrap();
+
+        let response = self.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+}
+
+fn new_user_body(username: &str) -> serde_json::Value {
+    serde_json::json!({
+        "username": username,
+        "password": "password",
+        "role": "viewer"
+    })
+}
+
+fn pull_out_zone_body() -> serde_json::Value {
+    serde_json::json!({
+        "zoneId": "zone-a",
+        "regionId": "region-1",
+        "operation": "pullout",
+        "operatorId": "tester"
+    })
+}
+
+fn operate_instance_body(service_id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "instanceKey": {
+            "regionId": "region-1",
+            "zoneId": "zone-a",
+            "serviceId": service_id,
+            "groupId": "",
+            "instanceId": "instance-1"
+        },
+        "operation": "pullout",
+        "operationComplete": true,
+        "operatorId": "tester"
+    })
+}
+
+#[tokio::test]
+async fn test_routes_require_authentication() {
+    let app = TestApp::new();
+
+    assert_eq!(
+        app.call(Method::GET, "/api/auth/users", None, None).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", None, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_viewer_route_permissions() {
+    let app = TestApp::new();
+    let token = app.login_as("viewer", UserRole::Viewer);
+    let token = Some(token.as_str());
+
+    // 读操作允许
+    assert_eq!(app.call(Method::GET, "/api/auth/users", token, None).await, StatusCode::OK);
+    assert_eq!(app.call(Method::GET, "/api/auth/roles", token, None).await, StatusCode::OK);
+    assert_eq!(
+        app.call(Method::GET, "/api/management/zone/operations", token, None).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(Method::GET, "/api/management/canary/configs", token, None).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(Method::GET, "/api/management/audit/logs", token, None).await,
+        StatusCode::OK
+    );
+
+    // 写操作拒绝
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/users", token, Some(new_user_body("mallory"))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/password/reset/some-user",
+            token,
+            Some(serde_json::json!({"new_password": "x"}))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", token, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(Method::DELETE, "/api/management/canary/config/order-service", token, None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("order-service"))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+
+    // 自助接口只需登录
+    assert_eq!(app.call(Method::GET, "/api/auth/user", token, None).await, StatusCode::OK);
+    assert_eq!(app.call(Method::GET, "/api/auth/permissions", token, None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_operator_route_permissions() {
+    let app = TestApp::new();
+    let token = app.login_as("operator", UserRole::Operator);
+    let token = Some(token.as_str());
+
+    // 运维操作允许
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", token, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("order-service"))
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_ne!(
+        app.call(Method::DELETE, "/api/management/canary/config/order-service", token, None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(Method::GET, "/api/management/audit/logs", token, None).await,
+        StatusCode::OK
+    );
+
+    // 用户和角色管理拒绝
+    assert_eq!(app.call(Method::GET, "/api/auth/users", token, None).await, StatusCode::FORBIDDEN);
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/users", token, Some(new_user_body("mallory"))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(app.call(Method::GET, "/api/auth/roles", token, None).await, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_admin_route_permissions() {
+    let app = TestApp::new();
+    let token = app.login_as("admin", UserRole::Admin);
+    let token = Some(token.as_str());
+
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/users", token, Some(new_user_body("dave"))).await,
+        StatusCode::CREATED
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/roles",
+            token,
+            Some(serde_json::json!({
+                "role_id": "team-a-ops",
+                "permissions": [{"resource": "instances", "action": "*", "servicePattern": "team-a-*"}]
+            }))
+        )
+        .await,
+        StatusCode::CREATED
+    );
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", token, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::OK
+    );
+    assert_eq!(app.call(Method::GET, "/api/auth/roles", token, None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_service_scoped_route_permissions() {
+    let app = TestApp::new();
+    app.manager
+        .create_role("team-a-ops", None, vec![Permission::scoped("instances", "write", "team-a-*")])
+        .unwrap();
+    let token = app.login_as("alice", UserRole::Viewer);
+    let user = app.manager.get_user_by_username("alice").unwrap();
+    app.manager.set_user_roles(&user.user_id, vec!["team-a-ops".to_string()]).unwrap();
+    let token = Some(token.as_str());
+
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("team-a-order"))
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("team-b-order"))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    // 查询参数和请求体中的服务都必须在授权范围内
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json?serviceId=team-a-order",
+            token,
+            Some(operate_instance_body("team-b-order"))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json?serviceId=team-a-order",
+            token,
+            Some(operate_instance_body("team-a-order"))
+        )
+        .await,
+        StatusCode::OK
+    );
+    // 无法确定目标服务的操作不授权
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/server/operate-server.json",
+            token,
+            Some(serde_json::json!({
+                "serverId": "10.0.0.1",
+                "regionId": "region-1",
+                "operation": "pullout",
+                "operatorId": "tester"
+            }))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_other_users_session_requires_permission() {
+    let app = TestApp::new();
+    let viewer_token = app.login_as("viewer", UserRole::Viewer);
+    let admin_token = app.login_as("admin", UserRole::Admin);
+    let admin = app.manager.get_user_by_username("admin").unwrap();
+    let viewer = app.manager.get_user_by_username("viewer").unwrap();
+
+    let admin_session = app.manager.list_user_sessions(&admin.user_id)[0].session_id.clone();
+    let viewer_session = app.manager.list_user_sessions(&viewer.user_id)[0].session_id.clone();
+
+    let uri = format!("/api/auth/sessions/{}", admin_session);
+    assert_eq!(
+        app.call(Method::DELETE, &uri, Some(viewer_token.as_str()), None).await,
+        StatusCode::FORBIDDEN
+    );
+
+    let uri = format!("/api/auth/sessions/{}", viewer_session);
+    assert_eq!(
+        app.call(Method::DELETE, &uri, Some(admin_token.as_str()), None).await,
+        StatusCode::OK
+    );
+}
+
+// ========== API Token 和服务账号测试 ==========
+
+#[test]
+fn test_api_token_lifecycle() {
+    let manager = AuthManager::new();
+    let user = manager.create_user("ci", None, None, "password", UserRole::Operator).unwrap();
+
+    let (token, plaintext) =
+        manager.create_api_token(&user.user_id, "pipeline", vec![], None).unwrap();
+    assert!(plaintext.starts_with("art_"));
+    assert!(plaintext.starts_with(&token.token_prefix));
+    assert_ne!(token.token_hash, plaintext);
+    assert_eq!(token.last_used_at, None);
+
+    let validated = manager.validate_api_token(&plaintext).unwrap();
+    assert_eq!(validated.user_id, user.user_id);
+    assert!(manager.get_api_token(&token.token_id).unwrap().last_used_at.is_some());
+    assert!(manager.validate_api_token("art_unknown").is_err());
+
+    // 序列化时不包含摘要
+    let json = serde_json::to_value(&validated).unwrap();
+    assert!(json.get("tokenHash").is_none());
+    assert_eq!(json["name"], "pipeline");
+
+    assert_eq!(manager.list_api_tokens(&user.user_id).len(), 1);
+    manager.revoke_api_token(&token.token_id).unwrap();
+    assert!(manager.validate_api_token(&plaintext).is_err());
+    assert!(manager.list_api_tokens(&user.user_id).is_empty());
+}
+
+#[test]
+fn test_api_token_validation_rules() {
+    let manager = AuthManager::new();
+    let user = manager.create_user("ci", None, None, "password", UserRole::Operator).unwrap();
+
+    assert!(manager.create_api_token("missing", "t", vec![], None).is_err());
+    assert!(manager.create_api_token(&user.user_id, "  ", vec![], None).is_err());
+    assert!(manager.create_api_token(&user.user_id, "t", vec![], Some(0)).is_err());
+    assert!(
+        manager
+            .create_api_token(&user.user_id, "t", vec![Permission::new("unknown", "read")], None)
+            .is_err()
+    );
+
+    // 停用用户后令牌失效
+    let (_, plaintext) = manager.create_api_token(&user.user_id, "t", vec![], Some(30)).unwrap();
+    assert!(manager.validate_api_token(&plaintext).is_ok());
+    manager.change_user_status(&user.user_id, UserStatus::Inactive).unwrap();
+    assert!(manager.validate_api_token(&plaintext).is_err());
+
+    // 删除用户时删除令牌
+    manager.delete_user(&user.user_id).unwrap();
+    assert!(manager.list_api_tokens(&user.user_id).is_empty());
+}
+
+#[test]
+fn test_service_account_cannot_login() {
+    let manager = AuthManager::new();
+    let account = manager
+        .create_service_account("ci-bot", Some("CI pipeline".to_string()), UserRole::Operator)
+        .unwrap();
+
+    assert!(account.service_account);
+    assert!(account.to_response().service_account);
+    assert!(manager.authenticate("ci-bot", "!", None, None).is_err());
+    assert!(manager.authenticate("ci-bot", "", None, None).is_err());
+    assert_eq!(manager.list_service_accounts().len(), 1);
+    assert!(manager.create_service_account("ci-bot", None, UserRole::Viewer).is_err());
+
+    let (_, plaintext) = manager.create_api_token(&account.user_id, "ci", vec![], None).unwrap();
+    assert_eq!(manager.validate_api_token(&plaintext).unwrap().user_id, account.user_id);
+}
+
+#[tokio::test]
+async fn test_api_token_accepted_by_jwt_auth() {
+    let app = TestApp::new();
+    let account = app.manager.create_service_account("ci-bot", None, UserRole::Operator).unwrap();
+    let (_, plaintext) =
+        app.manager.create_api_token(&account.user_id, "pipeline", vec![], None).unwrap();
+    let token = Some(plaintext.as_str());
+
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", token, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::OK
+    );
+    assert_eq!(app.call(Method::GET, "/api/auth/users", token, None).await, StatusCode::FORBIDDEN);
+    assert_eq!(app.call(Method::GET, "/api/auth/tokens", token, None).await, StatusCode::OK);
+    assert_eq!(
+        app.call(Method::GET, "/api/auth/user", Some("art_invalid"), None).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_scoped_api_token_restricts_permissions() {
+    let app = TestApp::new();
+    let user = app.manager.create_user("ops", None, None, "password", UserRole::Operator).unwrap();
+    let (_, plaintext) = app
+        .manager
+        .create_api_token(
+            &user.user_id,
+            "team-a",
+            vec![
+                Permission::scoped("instances", "write", "team-a-*"),
+                Permission::new("*", "read"),
+            ],
+            None,
+        )
+        .unwrap();
+    let token = Some(plaintext.as_str());
+
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("team-a-order"))
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/instance/operate-instance.json",
+            token,
+            Some(operate_instance_body("team-b-order"))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    // 用户有权限但令牌范围不包含
+    assert_eq!(
+        app.call(Method::POST, "/api/management/zone/pull-out", token, Some(pull_out_zone_body()))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+    // 令牌范围包含但用户无权限
+    assert_eq!(app.call(Method::GET, "/api/auth/users", token, None).await, StatusCode::FORBIDDEN);
+    // 限定范围的令牌不能签发新令牌
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/tokens", token, Some(serde_json::json!({"name": "x"})))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_token_management_routes() {
+    let app = TestApp::new();
+    let admin_token = app.login_as("admin", UserRole::Admin);
+    let viewer_token = app.login_as("viewer", UserRole::Viewer);
+    let admin = Some(admin_token.as_str());
+    let viewer = Some(viewer_token.as_str());
+
+    // 自助签发
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/tokens",
+            viewer,
+            Some(serde_json::json!({"name": "laptop", "expires_in_days": 30}))
+        )
+        .await,
+        StatusCode::CREATED
+    );
+
+    // 服务账号由管理员创建并签发令牌
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/service-accounts",
+            viewer,
+            Some(serde_json::json!({"username": "ci-bot", "role": "operator"}))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/service-accounts",
+            admin,
+            Some(serde_json::json!({"username": "ci-bot", "role": "operator"}))
+        )
+        .await,
+        StatusCode::CREATED
+    );
+    let account = app.manager.get_user_by_username("ci-bot").unwrap();
+    let uri = format!("/api/auth/users/{}/tokens", account.user_id);
+    assert_eq!(
+        app.call(Method::POST, &uri, viewer, Some(serde_json::json!({"name": "ci"}))).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(Method::POST, &uri, admin, Some(serde_json::json!({"name": "ci"}))).await,
+        StatusCode::CREATED
+    );
+    assert_eq!(app.call(Method::GET, &uri, admin, None).await, StatusCode::OK);
+
+    // 撤销他人令牌需要 users:write
+    let account_token = app.manager.list_api_tokens(&account.user_id)[0].token_id.clone();
+    let uri = format!("/api/auth/tokens/{}", account_token);
+    assert_eq!(app.call(Method::DELETE, &uri, viewer, None).await, StatusCode::FORBIDDEN);
+    assert_eq!(app.call(Method::DELETE, &uri, admin, None).await, StatusCode::OK);
+    assert_eq!(app.call(Method::DELETE, &uri, admin, None).await, StatusCode::NOT_FOUND);
+}
+
+// ========== 登录安全测试 ==========
+
+fn strict_auth_config() -> AuthConfig {
+    AuthConfig {
+        password_policy: PasswordPolicyConfig {
+            min_length: 10,
+            require_uppercase: true,
+            require_digit: true,
+            history_size: 2,
+            ..Default::default()
+        },
+        lockout: LockoutConfig {
+            max_failures_per_account: 3,
+            max_failures_per_ip: 5,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_password_policy_enforced() {
+    let manager = AuthManager::new().with_auth_config(&strict_auth_config());
+
+    assert!(manager.create_user("user1", None, None, "short", UserRole::Viewer).is_err());
+    assert!(manager.create_user("user1", None, None, "alllowercase1", UserRole::Viewer).is_err());
+    let user = manager.create_user("user1", None, None, "Password123", UserRole::Viewer).unwrap();
+
+    assert!(manager.change_password(&user.user_id, "Password123", "weak").is_err());
+    assert!(manager.reset_password(&user.user_id, "nodigitsHere").is_err());
+    assert!(manager.authenticate("user1", "Password123", None, None).is_ok());
+}
+
+#[test]
+fn test_password_reuse_rejected() {
+    let manager = AuthManager::new().with_auth_config(&strict_auth_config());
+    let user = manager.create_user("user1", None, None, "Password001", UserRole::Viewer).unwrap();
+
+    // 当前密码不能重复使用
+    assert!(manager.change_password(&user.user_id, "Password001", "Password001").is_err());
+
+    manager.change_password(&user.user_id, "Password001", "Password002").unwrap();
+    // 最近 2 个密码 (当前 + 上一个) 都不能使用
+    assert!(manager.change_password(&user.user_id, "Password002", "Password001").is_err());
+
+    manager.change_password(&user.user_id, "Password002", "Password003").unwrap();
+    // Password001 已超出历史范围
+    manager.change_password(&user.user_id, "Password003", "Password001").unwrap();
+}
+
+#[test]
+fn test_account_lockout_after_failed_logins() {
+    let manager = AuthManager::new().with_auth_config(&strict_auth_config());
+    let user = manager.create_user("user1", None, None, "Password123", UserRole::Viewer).unwrap();
+
+    for _ in 0..3 {
+        assert!(manager.authenticate("user1", "wrong", None, None).is_err());
+    }
+    assert!(manager.login_locked_for("user1", None).is_some());
+
+    // 锁定期内正确密码也被拒绝, 并记入登录历史
+    let result = manager.authenticate("user1", "Password123", None, None);
+    assert!(result.unwrap_err().contains("Too many failed login attempts"));
+    assert_eq!(manager.get_login_history(&user.user_id, 10).len(), 4);
+
+    // 管理员重置密码后解除锁定
+    manager.reset_password(&user.user_id, "Password456").unwrap();
+    assert!(manager.login_locked_for("user1", None).is_none());
+    assert!(manager.authenticate("user1", "Password456", None, None).is_ok());
+}
+
+#[test]
+fn test_ip_lockout_across_accounts() {
+    let manager = AuthManager::new().with_auth_config(&strict_auth_config());
+    let ip = Some("10.0.0.1".to_string());
+
+    for username in ["a", "b", "c", "d", "e"] {
+        assert!(manager.authenticate(username, "guess", ip.clone(), None).is_err());
+    }
+    assert!(manager.login_locked_for("f", Some("10.0.0.1")).is_some());
+    assert!(manager.login_locked_for("f", Some("10.0.0.2")).is_none());
+}
+
+#[tokio::test]
+async fn test_login_lockout_returns_too_many_requests() {
+    let app = TestApp::new();
+    app.manager.create_user("alice", None, None, "password", UserRole::Viewer).unwrap();
+    let login = |password: &str| serde_json::json!({"username": "alice", "password": password});
+
+    for _ in 0..5 {
+        assert_eq!(
+            app.call(Method::POST, "/api/auth/login", None, Some(login("wrong"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/login", None, Some(login("password"))).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_forced_password_change() {
+    let app = TestApp::new();
+    let admin = app
+        .manager
+        .create_user_requiring_password_change("admin", None, None, "admin123", UserRole::Admin)
+        .unwrap();
+    assert!(admin.must_change_password);
+
+    let token = app.manager.authenticate("admin", "admin123", None, None).unwrap();
+    let token = Some(token.as_str());
+
+    // 修改密码前只允许访问个人接口
+    assert_eq!(app.call(Method::GET, "/api/auth/users", token, None).await, StatusCode::FORBIDDEN);
+    assert_eq!(app.call(Method::GET, "/api/auth/user", token, None).await, StatusCode::OK);
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/password/change",
+            token,
+            Some(serde_json::json!({"old_password": "admin123", "new_password": "admin123"}))
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/auth/password/change",
+            token,
+            Some(serde_json::json!({"old_password": "admin123", "new_password": "n3w-secret"}))
+        )
+        .await,
+        StatusCode::OK
+    );
+
+    assert!(!app.manager.password_change_required(&admin.user_id));
+    let token = app.manager.authenticate("admin", "n3w-secret", None, None).unwrap();
+    assert_eq!(app.call(Method::GET, "/api/auth/users", Some(&token), None).await, StatusCode::OK);
+}
+
+// ========== 两步验证测试 ==========
+
+/// 当前时间步偏移 offset 后的验证码 (同一时间步的验证码只能使用一次)
+fn totp_code(secret: &str, offset: i64) -> String {
+    code_at(secret, chrono::Utc::now().timestamp() / TOTP_STEP_SECS + offset).unwrap()
+}
+
+/// 为用户绑定 TOTP, 返回 (密钥, 恢复码)
+fn enroll_totp(manager: &AuthManager, user_id: &str) -> (String, Vec<String>) {
+    let (secret, uri) = manager.begin_totp_enrollment(user_id).unwrap();
+    assert!(uri.starts_with("otpauth://totp/Artemis:"));
+    assert!(manager.confirm_totp_enrollment(user_id, "000000x").is_err());
+    let codes = manager.confirm_totp_enrollment(user_id, &totp_code(&secret, -1)).unwrap();
+    (secret, codes)
+}
+
+fn start_mfa_login(manager: &AuthManager, username: &str) -> String {
+    match manager.login(username, "password123", None, None).unwrap() {
+        LoginOutcome::MfaRequired(challenge) => challenge.challenge_token,
+        LoginOutcome::Authenticated(_) => panic!("expected MFA challenge"),
+    }
+}
+
+#[test]
+fn test_totp_two_step_login() {
+    let manager = AuthManager::new();
+    let user = manager.create_user("user1", None, None, "password123", UserRole::Admin).unwrap();
+    let (secret, codes) = enroll_totp(&manager, &user.user_id);
+    assert_eq!(codes.len(), 10);
+    assert!(manager.get_user(&user.user_id).unwrap().mfa_enabled);
+
+    // 仅密码无法完成登录
+    let result = manager.authenticate("user1", "password123", None, None);
+    assert_eq!(result.unwrap_err(), "Two-factor authentication required");
+
+    let challenge = start_mfa_login(&manager, "user1");
+    assert!(manager.verify_mfa_login(&challenge, "123456x").is_err());
+    let token = manager.verify_mfa_login(&challenge, &totp_code(&secret, 0)).unwrap();
+    assert!(manager.validate_token(&token).is_ok());
+
+    // 挑战只能使用一次, 同一验证码不能重放
+    assert!(manager.verify_mfa_login(&challenge, &totp_code(&secret, 0)).is_err());
+    let challenge = start_mfa_login(&manager, "user1");
+    assert!(manager.verify_mfa_login(&challenge, &totp_code(&secret, 0)).is_err());
+
+    // 恢复码只能使用一次
+    assert!(manager.verify_mfa_login(&challenge, &codes[0].to_uppercase()).is_ok());
+    assert_eq!(manager.recovery_codes_remaining(&user.user_id), 9);
+    let challenge = start_mfa_login(&manager, "user1");
+    assert!(manager.verify_mfa_login(&challenge, &codes[0]).is_err());
+}
+
+#[test]
+fn test_mfa_challenge_attempt_limit() {
+    let manager = AuthManager::new();
+    let user = manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let (secret, _) = enroll_totp(&manager, &user.user_id);
+
+    let challenge = start_mfa_login(&manager, "user1");
+    for _ in 0..5 {
+        assert!(manager.verify_mfa_login(&challenge, "not-a-code").is_err());
+    }
+    // 错误过多后挑战失效, 且账号被锁定
+    assert!(manager.get_mfa_challenge(&challenge).is_none());
+    assert!(manager.verify_mfa_login(&challenge, &totp_code(&secret, 0)).is_err());
+    assert!(manager.login_locked_for("user1", None).is_some());
+}
+
+#[test]
+fn test_disable_and_reset_mfa() {
+    let manager = AuthManager::new();
+    let user = manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let (secret, codes) = enroll_totp(&manager, &user.user_id);
+
+    let regenerated =
+        manager.regenerate_recovery_codes(&user.user_id, &totp_code(&secret, 0)).unwrap();
+    assert!(!regenerated.contains(&codes[0]));
+    assert!(manager.disable_totp(&user.user_id, &codes[0]).is_err());
+
+    // 角色要求两步验证时不能关闭
+    manager.set_role_mfa_required("viewer", true).unwrap();
+    assert!(manager.disable_totp(&user.user_id, &regenerated[0]).is_err());
+    manager.set_role_mfa_required("viewer", false).unwrap();
+
+    manager.disable_totp(&user.user_id, &regenerated[1]).unwrap();
+    assert!(!manager.get_user(&user.user_id).unwrap().mfa_enabled);
+    assert!(manager.authenticate("user1", "password123", None, None).is_ok());
+
+    // 管理员重置
+    enroll_totp(&manager, &user.user_id);
+    manager.reset_mfa(&user.user_id).unwrap();
+    assert_eq!(manager.recovery_codes_remaining(&user.user_id), 0);
+    assert!(manager.authenticate("user1", "password123", None, None).is_ok());
+}
+
+#[tokio::test]
+async fn test_mfa_login_routes() {
+    let app = TestApp::new();
+    let user =
+        app.manager.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    let (secret, _) = enroll_totp(&app.manager, &user.user_id);
+
+    let (status, body) = app
+        .call_json(
+            Method::POST,
+            "/api/auth/login",
+            None,
+            serde_json::json!({"username": "alice", "password": "password123"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["mfaRequired"], true);
+    assert!(body["data"].get("accessToken").is_none());
+    let challenge = body["data"]["challengeToken"].as_str().unwrap().to_string();
+
+    let verify = |code: String| serde_json::json!({"challenge_token": challenge, "code": code});
+    let (status, _) =
+        app.call_json(Method::POST, "/api/auth/login/mfa", None, verify("000000".into())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body) = app
+        .call_json(Method::POST, "/api/auth/login/mfa", None, verify(totp_code(&secret, 0)))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let token = body["data"]["accessToken"].as_str().unwrap().to_string();
+    assert_eq!(app.call(Method::GET, "/api/auth/users", Some(&token), None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_role_requires_mfa_enrollment() {
+    let app = TestApp::new();
+    let admin_token = app.login_as("admin", UserRole::Admin);
+    let admin = Some(admin_token.as_str());
+    let operator_token = app.login_as("operator", UserRole::Operator);
+    let operator = Some(operator_token.as_str());
+
+    assert_eq!(
+        app.call(Method::PUT, "/api/auth/mfa/required-roles/operator", operator, None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        app.call(Method::PUT, "/api/auth/mfa/required-roles/operator", admin, None).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(Method::PUT, "/api/auth/mfa/required-roles/missing", admin, None).await,
+        StatusCode::NOT_FOUND
+    );
+
+    // 未绑定前只能访问个人接口
+    assert_eq!(
+        app.call(Method::GET, "/api/management/zone/operations", operator, None).await,
+        StatusCode::FORBIDDEN
+    );
+    let (status, body) = app
+        .call_json(Method::POST, "/api/auth/mfa/totp/enroll", operator, serde_json::json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let secret = body["data"]["secret"].as_str().unwrap().to_string();
+    let (status, body) = app
+        .call_json(
+            Method::POST,
+            "/api/auth/mfa/totp/confirm",
+            operator,
+            serde_json::json!({"code": totp_code(&secret, 0)}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["recoveryCodes"].as_array().unwrap().len(), 10);
+
+    assert_eq!(
+        app.call(Method::GET, "/api/management/zone/operations", operator, None).await,
+        StatusCode::OK
+    );
+    let (_, body) =
+        app.call_json(Method::GET, "/api/auth/mfa", operator, serde_json::json!({})).await;
+    assert_eq!(body["data"]["enabled"], true);
+    assert_eq!(body["data"]["required"], true);
+
+    // 管理员重置后再次要求绑定
+    let user_id = app.manager.get_user_by_username("operator").unwrap().user_id;
+    assert_eq!(
+        app.call(Method::DELETE, &format!("/api/auth/users/{}/mfa", user_id), admin, None).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        app.call(Method::GET, "/api/management/zone/operations", operator, None).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+// ========== 签名密钥测试 ==========
+
+#[test]
+fn test_jwt_signed_with_asymmetric_key() {
+    let manager = AuthManager::new();
+    manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let token = manager.authenticate("user1", "password123", None, None).unwrap();
+
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::ES256);
+    let kid = header.kid.unwrap();
+
+    // 其他服务可以只凭 JWKS 校验令牌
+    let jwks = manager.jwks().unwrap();
+    let jwk = jwks.find(&kid).unwrap();
+    let claims = jsonwebtoken::decode::<serde_json::Value>(
+        &token,
+        &jsonwebtoken::DecodingKey::from_jwk(jwk).unwrap(),
+        &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::ES256),
+    )
+    .unwrap()
+    .claims;
+    assert_eq!(claims["username"], "user1");
+}
+
+#[test]
+fn test_rotate_signing_key_keeps_existing_tokens_valid() {
+    let manager = AuthManager::new();
+    manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let old_token = manager.authenticate("user1", "password123", None, None).unwrap();
+    let old_kid = jsonwebtoken::decode_header(&old_token).unwrap().kid.unwrap();
+
+    let new_key = manager.rotate_signing_key().unwrap();
+    assert_ne!(new_key.kid, old_kid);
+
+    // 旧令牌仍然有效, 新令牌使用新密钥
+    assert!(manager.validate_token(&old_token).is_ok());
+    let new_token = manager.refresh_token(&old_token).unwrap();
+    assert_eq!(jsonwebtoken::decode_header(&new_token).unwrap().kid, Some(new_key.kid.clone()));
+    assert!(manager.validate_token(&new_token).is_ok());
+
+    let keys = manager.list_signing_keys();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys.iter().filter(|k| k.is_active()).count(), 1);
+    assert_eq!(manager.jwks().unwrap().keys.len(), 2);
+}
+
+#[test]
+fn test_rs256_signing_key() {
+    let config = AuthConfig {
+        jwt: JwtConfig { algorithm: "RS256".to_string(), ..Default::default() },
+        ..Default::default()
+    };
+    let manager = AuthManager::new().with_auth_config(&config);
+    manager.create_user("user1", None, None, "password123", UserRole::Viewer).unwrap();
+    let token = manager.authenticate("user1", "password123", None, None).unwrap();
+
+    assert_eq!(jsonwebtoken::decode_header(&token).unwrap().alg, jsonwebtoken::Algorithm::RS256);
+    assert!(manager.validate_token(&token).is_ok());
+}
+
+#[tokio::test]
+async fn test_signing_key_api() {
+    let app = TestApp::new();
+    let admin = app.login_as("admin", UserRole::Admin);
+    let viewer = app.login_as("viewer", UserRole::Viewer);
+
+    // JWKS 无需登录
+    let (status, body) =
+        app.call_json(Method::GET, "/api/auth/jwks", None, serde_json::json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["keys"].as_array().unwrap().len(), 1);
+    assert_eq!(body["keys"][0]["kty"], "EC");
+    assert!(body["keys"][0].get("d").is_none());
+
+    let (status, body) =
+        app.call_json(Method::GET, "/api/auth/keys", Some(&viewer), serde_json::json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"][0]["active"], true);
+    assert_eq!(body["data"][0]["algorithm"], "ES256");
+
+    assert_eq!(
+        app.call(Method::POST, "/api/auth/keys/rotate", Some(&viewer), None).await,
+        StatusCode::FORBIDDEN
+    );
+    let (status, body) = app
+        .call_json(Method::POST, "/api/auth/keys/rotate", Some(&admin), serde_json::json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let new_kid = body["data"]["kid"].as_str().unwrap().to_string();
+
+    // 轮换前签发的令牌仍可使用
+    assert_eq!(app.call(Method::GET, "/api/auth/keys", Some(&viewer), None).await, StatusCode::OK);
+    let (_, body) = app.call_json(Method::GET, "/api/auth/jwks", None, serde_json::json!({})).await;
+    let kids: Vec<&str> =
+        body["keys"].as_array().unwrap().iter().map(|k| k["kid"].as_str().unwrap()).collect();
+    assert_eq!(kids.len(), 2);
+    assert!(kids.contains(&new_kid.as_str()));
+}
+
+#[tokio::test]
+async fn test_apply_checks_permissions_per_resource() {
+    let app = TestApp::new();
+    let admin = app.login_as("admin", UserRole::Admin);
+    let viewer = app.login_as("viewer", UserRole::Viewer);
+    let canary_ops = app.login_as("canary-ops", UserRole::Viewer);
+    let user = app.manager.get_user_by_username("canary-ops").unwrap();
+    app.manager.create_role("canary-ops", None, vec![Permission::new("canary", "write")]).unwrap();
+    app.manager.set_user_roles(&user.user_id, vec!["canary-ops".to_string()]).unwrap();
+
+    let canary_doc = serde_json::json!({
+        "canaryConfigs": [{ "serviceId": "order-service", "ipWhitelist": ["10.0.0.1"] }]
+    });
+    let zone_doc = serde_json::json!({
+        "zoneOperations": [{ "zoneId": "zone-a", "regionId": "us-east" }]
+    });
+
+    assert_eq!(
+        app.call(Method::POST, "/api/management/apply", None, Some(canary_doc.clone())).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        app.call(Method::POST, "/api/management/apply", Some(&viewer), Some(canary_doc.clone()))
+            .await,
+        StatusCode::FORBIDDEN
+    );
+
+    // dry-run 返回计划, 不修改配置
+    let (status, body) = app
+        .call_json(
+            Method::POST,
+            "/api/management/apply?dryRun=true",
+            Some(&admin),
+            zone_doc.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["dryRun"], true);
+    assert_eq!(body["data"]["changes"][0]["kind"], "zone-operation");
+    assert_eq!(body["data"]["changes"][0]["action"], "create");
+
+    // 只有 canary 权限的用户不能应用 Zone 操作
+    assert_eq!(
+        app.call(Method::POST, "/api/management/apply", Some(&canary_ops), Some(zone_doc)).await,
+        StatusCode::FORBIDDEN
+    );
+    let (status, body) =
+        app.call_json(Method::POST, "/api/management/apply", Some(&canary_ops), canary_doc).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["changes"][0]["kind"], "canary-config");
+
+    // 剪除需要 delete 权限
+    assert_eq!(
+        app.call(
+            Method::POST,
+            "/api/management/apply?prune=true",
+            Some(&canary_ops),
+            Some(serde_json::json!({}))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+
+    let (status, body) = app
+        .call_json(
+            Method::POST,
+            "/api/management/apply",
+            Some(&admin),
+            serde_json::json!({ "x": 1 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"].as_str().unwrap().contains("Invalid JSON document"));
+}
+
+#[tokio::test]
+async fn test_zone_pull_out_requires_approval() {
+    let app = TestApp::with_approval_policies(&["zone-pull-out"]);
+    let ops = app.login_as("ops", UserRole::Operator);
+    let lead = app.login_as("lead", UserRole::Operator);
+    let admin = app.login_as("admin", UserRole::Admin);
+    let zone_status = "/api/management/zone/status/zone-a/region-1";
+
+    // 命中策略的拉出只创建变更请求
+    let (status, body) = app
+        .call_json(Method::POST, "/api/management/zone/pull-out", Some(&ops), pull_out_zone_body())
+        .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["data"]["status"], "pending");
+    assert_eq!(body["data"]["operation"]["type"], "zone-pull-out");
+    let request_id = body["data"]["requestId"].as_str().unwrap().to_string();
+    let (_, body) =
+        app.call_json(Method::GET, zone_status, Some(&ops), serde_json::json!({})).await;
+    assert_eq!(body["data"]["is_down"], false);
+
+    // 申请人可以查看请求, 但没有审批权限的用户不能批准
+    let (status, body) = app
+        .call_json(Method::GET, "/api/management/approvals", Some(&ops), serde_json::json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    let approve = format!("/api/management/approvals/{}/approve", request_id);
+    assert_eq!(app.call(Method::POST, &approve, Some(&lead), None).await, StatusCode::FORBIDDEN);
+
+    // 不能批准自己的请求
+    let (status, body) = app
+        .call_json(Method::POST, "/api/management/zone/pull-out", Some(&admin), {
+            let mut body = pull_out_zone_body();
+            body["zoneId"] = "zone-b".into();
+            body
+        })
+        .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let own_request = body["data"]["requestId"].as_str().unwrap().to_string();
+    assert_eq!(
+        app.call(
+            Method::POST,
+            &format!("/api/management/approvals/{}/approve", own_request),
+            Some(&admin),
+            None
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+
+    // 另一位有权限的用户批准后执行
+    let (status, body) = app
+        .call_json(Method::POST, &approve, Some(&admin), serde_json::json!({ "comment": "ok" }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "approved");
+    assert_eq!(body["data"]["comment"], "ok");
+    let (_, body) =
+        app.call_json(Method::GET, zone_status, Some(&ops), serde_json::json!({})).await;
+    assert_eq!(body["data"]["is_down"], true);
+    assert_eq!(app.call(Method::POST, &approve, Some(&admin), None).await, StatusCode::CONFLICT);
+
+    // 只有申请人可以撤回
+    let cancel = format!("/api/management/approvals/{}/cancel", own_request);
+    assert_eq!(app.call(Method::POST, &cancel, Some(&ops), None).await, StatusCode::FORBIDDEN);
+    assert_eq!(app.call(Method::POST, &cancel, Some(&admin), None).await, StatusCode::OK);
+
+    // 声明式配置不能绕过审批
+    let zone_doc = serde_json::json!({
+        "zoneOperations": [{ "zoneId": "zone-c", "regionId": "region-1" }]
+    });
+    assert_eq!(
+        app.call(Method::POST, "/api/management/apply", Some(&admin), Some(zone_doc)).await,
+        StatusCode::CONFLICT
+    );
+}
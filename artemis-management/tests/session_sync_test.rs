@@ -0,0 +1,311 @@
+//! 集群会话共享测试: 两个 AuthManager 共享同一数据库, 模拟两个节点
+
+use artemis_management::auth::oidc::OidcIdentity;
+use artemis_management::auth::totp::{TOTP_STEP_SECS, code_at};
+use artemis_management::auth::{AuthManager, LoginOutcome, Permission, UserRole, UserStatus};
+use artemis_management::db::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn shared_database() -> Arc<Database> {
+    // 内存数据库按连接隔离, 只用一个连接
+    let db = Database::new("sqlite::memory:", 1).await.unwrap();
+    db.run_migrations().await.unwrap();
+    Arc::new(db)
+}
+
+async fn node(db: &Arc<Database>) -> AuthManager {
+    let manager = AuthManager::with_database(Some(db.clone()));
+    manager.load_from_database().await.unwrap();
+    manager
+}
+
+/// 等待异步持久化完成
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(300)).await;
+}
+
+#[tokio::test]
+async fn test_token_accepted_by_other_node() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    node_a.create_user("alice", None, None, "password123", UserRole::Operator).unwrap();
+    let token = node_a.authenticate("alice", "password123", None, None).unwrap();
+    settle().await;
+
+    // 节点 B 从数据库加载签名密钥、会话和用户
+    let session = node_b.validate_token_shared(&token).await.unwrap();
+    let user = node_b.get_user(&session.user_id).unwrap();
+    assert_eq!(user.username, "alice");
+    assert!(node_b.check_permission(&user.user_id, "zones", "write"));
+    assert_eq!(node_b.list_user_sessions(&user.user_id).len(), 1);
+
+    // 伪造的令牌不会被接受
+    let forged = AuthManager::new();
+    forged.create_user("alice", None, None, "password123", UserRole::Admin).unwrap();
+    let forged_token = forged.authenticate("alice", "password123", None, None).unwrap();
+    assert!(node_b.validate_token_shared(&forged_token).await.is_err());
+}
+
+#[tokio::test]
+async fn test_logout_propagates_to_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    let token = node_a.authenticate("alice", "password123", None, None).unwrap();
+    settle().await;
+    assert!(node_b.validate_token_shared(&token).await.is_ok());
+
+    // 在节点 B 登出, 节点 A 同步后清除缓存
+    node_b.logout(&token).unwrap();
+    settle().await;
+    assert!(node_a.validate_token_shared(&token).await.is_ok());
+    assert_eq!(node_a.sync_sessions().await.unwrap(), 1);
+    assert!(node_a.validate_token_shared(&token).await.is_err());
+    assert!(node_b.validate_token_shared(&token).await.is_err());
+}
+
+#[tokio::test]
+async fn test_revoke_all_sessions_and_refresh_propagate() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    settle().await;
+    let node_b = node(&db).await;
+
+    // 在两个节点分别登录
+    let first = node_a.authenticate("alice", "password123", None, None).unwrap();
+    let second = node_b.authenticate("alice", "password123", None, None).unwrap();
+    settle().await;
+
+    // 节点 B 看到两个节点上的会话
+    node_b.load_user_sessions(&user.user_id).await.unwrap();
+    assert_eq!(node_b.list_user_sessions(&user.user_id).len(), 2);
+
+    // 刷新后旧令牌在其他节点失效, 新令牌可用
+    assert!(node_a.validate_token_shared(&second).await.is_ok());
+    std::thread::sleep(Duration::from_secs(1));
+    let refreshed = node_b.refresh_token(&second).unwrap();
+    settle().await;
+    node_a.sync_sessions().await.unwrap();
+    assert!(node_a.validate_token_shared(&second).await.is_err());
+    assert!(node_a.validate_token_shared(&refreshed).await.is_ok());
+
+    // 撤销全部会话同样影响其他节点缓存的会话
+    node_b.revoke_all_user_sessions(&user.user_id).unwrap();
+    settle().await;
+    node_a.sync_sessions().await.unwrap();
+    assert!(node_a.validate_token_shared(&first).await.is_err());
+    assert!(node_a.validate_token_shared(&refreshed).await.is_err());
+}
+
+#[tokio::test]
+async fn test_rotated_key_synced_to_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+    settle().await;
+
+    let key = node_a.rotate_signing_key().unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+
+    // 各节点都使用最新的签名密钥
+    let active: Vec<String> =
+        node_b.list_signing_keys().into_iter().filter(|k| k.is_active()).map(|k| k.kid).collect();
+    assert_eq!(active, vec![key.kid.clone()]);
+    let jwks = node_b.jwks().unwrap();
+    assert!(jwks.keys.iter().any(|k| k.common.key_id.as_deref() == Some(&key.kid)));
+}
+
+#[tokio::test]
+async fn test_api_token_accepted_and_revoked_across_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    let (token, plaintext) = node_a.create_api_token(&user.user_id, "ci", vec![], None).unwrap();
+    settle().await;
+
+    // 节点 B 从数据库加载令牌和所属用户
+    assert_eq!(
+        node_b.validate_api_token_shared(&plaintext).await.unwrap().token_id,
+        token.token_id
+    );
+    assert_eq!(node_b.get_user(&user.user_id).unwrap().username, "alice");
+    assert!(node_b.validate_api_token_shared("art_unknown").await.is_err());
+
+    // 在节点 A 撤销, 节点 B 同步后清除缓存
+    node_a.revoke_api_token(&token.token_id).unwrap();
+    settle().await;
+    assert!(node_b.validate_api_token_shared(&plaintext).await.is_ok());
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.validate_api_token_shared(&plaintext).await.is_err());
+}
+
+#[tokio::test]
+async fn test_mfa_challenge_completed_on_other_node() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    let (secret, _) = node_a.begin_totp_enrollment(&user.user_id).unwrap();
+    settle().await;
+    let step = chrono::Utc::now().timestamp() / TOTP_STEP_SECS;
+    node_a.confirm_totp_enrollment(&user.user_id, &code_at(&secret, step - 1).unwrap()).unwrap();
+
+    let LoginOutcome::MfaRequired(challenge) =
+        node_a.login("alice", "password123", None, None).unwrap()
+    else {
+        panic!("expected MFA challenge");
+    };
+    settle().await;
+
+    // 验证码提交到节点 B
+    let token = &challenge.challenge_token;
+    assert!(node_b.get_mfa_challenge(token).is_none());
+    node_b.load_mfa_challenge(token).await.unwrap();
+    assert_eq!(node_b.get_mfa_challenge(token).unwrap().username, "alice");
+    assert!(node_b.verify_mfa_login(token, "000000x").is_err());
+    let session = node_b.verify_mfa_login(token, &code_at(&secret, step).unwrap()).unwrap();
+    assert!(node_b.validate_token(&session).is_ok());
+
+    // 挑战已在数据库中删除, 其他节点无法再使用
+    settle().await;
+    let fresh = node(&db).await;
+    fresh.load_mfa_challenge(token).await.unwrap();
+    assert!(fresh.get_mfa_challenge(token).is_none());
+}
+
+#[tokio::test]
+async fn test_reset_password_requires_change_on_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    settle().await;
+
+    node_a.reset_password(&user.user_id, "reset-password1").unwrap();
+    settle().await;
+    let node_b = node(&db).await;
+    assert!(node_b.password_change_required(&user.user_id));
+}
+
+#[tokio::test]
+async fn test_delete_user_propagates_to_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    let (_, plaintext) = node_a.create_api_token(&user.user_id, "ci", vec![], None).unwrap();
+    let token = node_a.authenticate("alice", "password123", None, None).unwrap();
+    settle().await;
+    assert!(node_b.validate_token_shared(&token).await.is_ok());
+    assert!(node_b.validate_api_token_shared(&plaintext).await.is_ok());
+
+    // 在节点 A 删除用户, 节点 B 同步后清除缓存的用户、会话和 API Token
+    node_a.delete_user(&user.user_id).unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.validate_token_shared(&token).await.is_err());
+    assert!(node_b.validate_api_token_shared(&plaintext).await.is_err());
+    assert!(node_b.get_user(&user.user_id).is_none());
+    assert!(node_b.authenticate("alice", "password123", None, None).is_err());
+}
+
+#[tokio::test]
+async fn test_password_change_propagates_to_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    settle().await;
+    let node_b = node(&db).await;
+
+    node_a.change_password(&user.user_id, "password123", "new-password1").unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.authenticate("alice", "password123", None, None).is_err());
+    assert!(node_b.authenticate("alice", "new-password1", None, None).is_ok());
+}
+
+#[tokio::test]
+async fn test_user_and_role_changes_propagate_to_other_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+
+    // 节点 A 创建的用户和角色, 节点 B 同步后可见
+    node_a
+        .create_role("team-a-ops", None, vec![Permission::scoped("instances", "write", "team-a-*")])
+        .unwrap();
+    let user = node_a.create_user("alice", None, None, "password123", UserRole::Viewer).unwrap();
+    settle().await;
+    node_a.set_user_roles(&user.user_id, vec!["team-a-ops".to_string()]).unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.authenticate("alice", "password123", None, None).is_ok());
+    assert!(node_b.check_service_permission(&user.user_id, "instances", "write", "team-a-order"));
+
+    // 修改角色权限
+    node_a
+        .update_role(
+            "team-a-ops",
+            None,
+            Some(vec![Permission::scoped("instances", "write", "team-a-payment")]),
+        )
+        .unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(!node_b.check_service_permission(&user.user_id, "instances", "write", "team-a-order"));
+
+    // 修改主角色, 删除角色
+    node_a.update_user(&user.user_id, None, None, Some(UserRole::Operator)).unwrap();
+    node_a.delete_role("team-a-ops").unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.get_role("team-a-ops").is_none());
+    let synced = node_b.get_user(&user.user_id).unwrap();
+    assert_eq!(synced.role, UserRole::Operator);
+    assert!(synced.roles.is_empty());
+
+    // 停用用户
+    node_a.change_user_status(&user.user_id, UserStatus::Inactive).unwrap();
+    settle().await;
+    node_b.sync_sessions().await.unwrap();
+    assert!(node_b.authenticate("alice", "password123", None, None).is_err());
+}
+
+#[tokio::test]
+async fn test_oidc_user_provisioned_once_across_nodes() {
+    let db = shared_database().await;
+    let node_a = node(&db).await;
+    let node_b = node(&db).await;
+    let identity = |subject: &str, username: &str| OidcIdentity {
+        issuer: "https://idp.example.com".to_string(),
+        subject: subject.to_string(),
+        username: username.to_string(),
+        email: None,
+        roles: vec!["viewer".to_string()],
+    };
+
+    let token = node_a.login_oidc(&identity("sub-1", "alice"), None, None).await.unwrap();
+    let user_id = node_a.validate_token(&token).unwrap().user_id;
+
+    // 回调落在未缓存该用户的节点 B: 复用数据库中的账号, 不重复开通
+    let token = node_b.login_oidc(&identity("sub-1", "alice"), None, None).await.unwrap();
+    assert_eq!(node_b.validate_token(&token).unwrap().user_id, user_id);
+    assert_eq!(node_b.list_users().len(), 1);
+
+    // 不接管其他节点创建的同名本地账号
+    node_a.create_user("bob", None, None, "password123", UserRole::Viewer).unwrap();
+    settle().await;
+    let err = node_b.login_oidc(&identity("sub-2", "bob"), None, None).await.unwrap_err();
+    assert!(err.contains("already exists"), "{}", err);
+    assert!(node_b.get_user_by_username("bob").is_none());
+}
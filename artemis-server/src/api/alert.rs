@@ -0,0 +1,101 @@
+//! 告警规则 HTTP API
+//!
+//! 提供告警规则管理和告警状态查询的 REST API
+
+use crate::state::AppState;
+use artemis_service::alert::{AlertRule, AlertState, AlertStatus};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self { success: true, data: Some(data), message: None }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self { success: false, data: None, message: Some(message) }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListAlertsQuery {
+    /// 按状态过滤: inactive, pending, firing
+    pub state: Option<AlertState>,
+}
+
+/// GET /api/alerts/rules - 列出告警规则
+pub async fn list_rules(State(state): State<AppState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(ApiResponse::success(state.alert_manager.list_rules())))
+}
+
+/// POST /api/alerts/rules - 创建或更新告警规则
+pub async fn upsert_rule(
+    State(state): State<AppState>,
+    Json(rule): Json<AlertRule>,
+) -> impl IntoResponse {
+    match state.alert_manager.upsert_rule(rule.clone()) {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::success(rule))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiResponse::<AlertRule>::error(e))),
+    }
+}
+
+/// GET /api/alerts/rules/{rule_id} - 获取告警规则
+pub async fn get_rule(
+    State(state): State<AppState>,
+    Path(rule_id): Path<String>,
+) -> impl IntoResponse {
+    match state.alert_manager.get_rule(&rule_id) {
+        Some(rule) => (StatusCode::OK, Json(ApiResponse::success(rule))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<AlertRule>::error(format!("Alert rule not found: {}", rule_id))),
+        ),
+    }
+}
+
+/// DELETE /api/alerts/rules/{rule_id} - 删除告警规则
+pub async fn delete_rule(
+    State(state): State<AppState>,
+    Path(rule_id): Path<String>,
+) -> impl IntoResponse {
+    match state.alert_manager.remove_rule(&rule_id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::success(()))),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(e))),
+    }
+}
+
+/// GET /api/alerts - 查询告警状态 (默认返回 pending 和 firing)
+pub async fn list_alerts(
+    State(state): State<AppState>,
+    Query(query): Query<ListAlertsQuery>,
+) -> impl IntoResponse {
+    let alerts: Vec<AlertStatus> = match query.state {
+        Some(filter) => {
+            state.alert_manager.list_statuses().into_iter().filter(|s| s.state == filter).collect()
+        }
+        None => state.alert_manager.active_alerts(),
+    };
+
+    (StatusCode::OK, Json(ApiResponse::success(alerts)))
+}
+
+/// POST /api/alerts/evaluate - 立即评估所有规则
+pub async fn evaluate_now(State(state): State<AppState>) -> impl IntoResponse {
+    let events = state.alert_manager.evaluate().await;
+    (StatusCode::OK, Json(ApiResponse::success(events)))
+}
@@ -0,0 +1,31 @@
+use crate::state::AppState;
+use artemis_common::model::{ErrorCode, FederationExportResponse, ResponseStatus};
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, header::IF_NONE_MATCH},
+};
+
+/// 联邦导出端点 - 其他区域拉取本区域的只读实例视图
+///
+/// 请求携带上次拉取到的摘要 (If-None-Match) 且视图没有变化时不返回实例
+pub async fn export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Json<FederationExportResponse> {
+    let Some(federation) = &state.federation else {
+        return Json(FederationExportResponse {
+            response_status: ResponseStatus::error(
+                ErrorCode::ServiceUnavailable,
+                "Federation is not enabled",
+            ),
+            region_id: String::new(),
+            instances: Vec::new(),
+            digest: String::new(),
+            unchanged: false,
+        });
+    };
+
+    let known_digest = headers.get(IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    Json(federation.export(known_digest))
+}
@@ -1,7 +1,13 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use crate::state::AppState;
+use artemis_service::alert::{AlertEvent, AlertEventType, AlertSink};
+use artemis_service::model::PeerReplicationStatus;
+use artemis_service::replication::{AntiEntropyListener, PeerReconciliation};
+use async_trait::async_trait;
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use lazy_static::lazy_static;
 use prometheus::{
-    Encoder, IntCounter, IntGauge, TextEncoder, register_int_counter, register_int_gauge,
+    Encoder, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder, register_int_counter,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
 };
 
 lazy_static! {
@@ -16,6 +22,168 @@ lazy_static! {
             .unwrap();
     pub static ref ACTIVE_INSTANCES: IntGauge =
         register_int_gauge!("artemis_active_instances", "Number of active instances").unwrap();
+    pub static ref ALERTS_FIRING: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_alert_firing",
+        "Whether an alert rule is currently firing (1) or not (0)",
+        &["rule_id"]
+    )
+    .unwrap();
+    pub static ref ALERT_EVENTS: IntCounterVec = register_int_counter_vec!(
+        "artemis_alert_events_total",
+        "Total alert state transitions by rule and event type",
+        &["rule_id", "event"]
+    )
+    .unwrap();
+    pub static ref ANTI_ENTROPY_DIVERGENT_SERVICES: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_anti_entropy_divergent_services",
+        "Services whose digest differed from the peer in the last anti-entropy round",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref ANTI_ENTROPY_DIVERGENCES: IntCounterVec = register_int_counter_vec!(
+        "artemis_anti_entropy_divergences_total",
+        "Total divergent services found by anti-entropy",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref ANTI_ENTROPY_REPAIRED_SERVICES: IntCounterVec = register_int_counter_vec!(
+        "artemis_anti_entropy_repaired_services_total",
+        "Total services repaired by anti-entropy",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref ANTI_ENTROPY_REPAIRED_INSTANCES: IntCounterVec = register_int_counter_vec!(
+        "artemis_anti_entropy_repaired_instances_total",
+        "Total instances repaired by anti-entropy",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_PENDING_EVENTS: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_pending_events",
+        "Events buffered for the next replication batch",
+        &["peer", "kind"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_RETRY_QUEUE_DEPTH: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_retry_queue_depth",
+        "Events waiting in the replication retry queue",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_LAST_SUCCESS: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_last_success_timestamp_seconds",
+        "Unix time of the last successful replication request",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_CONSECUTIVE_FAILURES: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_consecutive_failures",
+        "Replication requests failed in a row since the last success",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_DROPPED_EVENTS: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_dropped_events",
+        "Events dropped since startup (permanent errors, retries exhausted, peer left)",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_OUTBOX_DEPTH: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_outbox_depth",
+        "Events held in the outbox until the peer is healthy again",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_FULL_SYNC_PENDING: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_full_sync_pending",
+        "1 if the outbox overflowed and the peer will receive a full sync",
+        &["peer"]
+    )
+    .unwrap();
+    pub static ref REPLICATION_LAG: IntGaugeVec = register_int_gauge_vec!(
+        "artemis_replication_lag_milliseconds",
+        "Age of the oldest event not yet delivered to the peer",
+        &["peer"]
+    )
+    .unwrap();
+}
+
+/// 将告警状态跃迁同步到 Prometheus 指标
+pub struct MetricsAlertSink;
+
+#[async_trait]
+impl AlertSink for MetricsAlertSink {
+    async fn notify(&self, event: &AlertEvent) {
+        let (firing, label) = match event.event_type {
+            AlertEventType::Firing => (1, "firing"),
+            AlertEventType::Resolved => (0, "resolved"),
+        };
+        ALERTS_FIRING.with_label_values(&[event.rule_id.as_str()]).set(firing);
+        ALERT_EVENTS.with_label_values(&[event.rule_id.as_str(), label]).inc();
+    }
+}
+
+/// 将反熵结果同步到 Prometheus 指标
+pub struct MetricsAntiEntropyListener;
+
+impl AntiEntropyListener for MetricsAntiEntropyListener {
+    fn on_reconciled(&self, result: &PeerReconciliation) {
+        let peer = [result.peer.as_str()];
+        ANTI_ENTROPY_DIVERGENT_SERVICES
+            .with_label_values(&peer)
+            .set(result.divergent_services as i64);
+        ANTI_ENTROPY_DIVERGENCES.with_label_values(&peer).inc_by(result.divergent_services as u64);
+        ANTI_ENTROPY_REPAIRED_SERVICES
+            .with_label_values(&peer)
+            .inc_by(result.repaired_services as u64);
+        ANTI_ENTROPY_REPAIRED_INSTANCES
+            .with_label_values(&peer)
+            .inc_by(result.repaired_instances as u64);
+    }
+}
+
+/// 将复制状态快照同步到 Prometheus 指标 (替换旧快照, 已移除的节点不再导出)
+pub fn record_replication_status(peers: &[PeerReplicationStatus]) {
+    REPLICATION_PENDING_EVENTS.reset();
+    REPLICATION_RETRY_QUEUE_DEPTH.reset();
+    REPLICATION_OUTBOX_DEPTH.reset();
+    REPLICATION_FULL_SYNC_PENDING.reset();
+    REPLICATION_LAST_SUCCESS.reset();
+    REPLICATION_CONSECUTIVE_FAILURES.reset();
+    REPLICATION_DROPPED_EVENTS.reset();
+    REPLICATION_LAG.reset();
+
+    for status in peers {
+        let peer = status.node_id.as_str();
+        for (kind, pending) in [
+            ("register", status.pending_registers),
+            ("heartbeat", status.pending_heartbeats),
+            ("unregister", status.pending_unregisters),
+        ] {
+            REPLICATION_PENDING_EVENTS.with_label_values(&[peer, kind]).set(pending as i64);
+        }
+        REPLICATION_RETRY_QUEUE_DEPTH
+            .with_label_values(&[peer])
+            .set(status.retry_queue_depth as i64);
+        REPLICATION_OUTBOX_DEPTH.with_label_values(&[peer]).set(status.outbox_depth as i64);
+        REPLICATION_FULL_SYNC_PENDING
+            .with_label_values(&[peer])
+            .set(status.full_sync_pending as i64);
+        REPLICATION_LAST_SUCCESS
+            .with_label_values(&[peer])
+            .set(status.last_success_time.map_or(0, |time| time / 1000));
+        REPLICATION_CONSECUTIVE_FAILURES
+            .with_label_values(&[peer])
+            .set(status.consecutive_failures as i64);
+        REPLICATION_DROPPED_EVENTS.with_label_values(&[peer]).set(status.dropped_events as i64);
+        REPLICATION_LAG.with_label_values(&[peer]).set(status.lag_ms as i64);
+    }
+}
+
+/// 指标端点: 先刷新按需计算的复制指标再导出
+pub async fn scrape_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    record_replication_status(&crate::api::status::replication_status(&state).peers);
+    metrics().await
 }
 
 pub async fn metrics() -> impl IntoResponse {
@@ -65,6 +233,79 @@ mod tests {
         let _ = ACTIVE_INSTANCES.get();
     }
 
+    #[tokio::test]
+    async fn test_metrics_alert_sink_updates_gauge() {
+        let event = AlertEvent {
+            rule_id: "metrics-sink-test".to_string(),
+            rule_name: String::new(),
+            event_type: AlertEventType::Firing,
+            value: 0.0,
+            message: String::new(),
+            timestamp: 0,
+        };
+        MetricsAlertSink.notify(&event).await;
+        assert_eq!(ALERTS_FIRING.with_label_values(&["metrics-sink-test"]).get(), 1);
+
+        MetricsAlertSink
+            .notify(&AlertEvent { event_type: AlertEventType::Resolved, ..event })
+            .await;
+        assert_eq!(ALERTS_FIRING.with_label_values(&["metrics-sink-test"]).get(), 0);
+    }
+
+    #[test]
+    fn test_metrics_anti_entropy_listener_updates_counters() {
+        let result = PeerReconciliation {
+            peer: "metrics-listener-test".to_string(),
+            divergent_services: 3,
+            repaired_services: 2,
+            repaired_instances: 5,
+        };
+        MetricsAntiEntropyListener.on_reconciled(&result);
+        MetricsAntiEntropyListener.on_reconciled(&PeerReconciliation {
+            divergent_services: 0,
+            repaired_services: 0,
+            repaired_instances: 0,
+            ..result
+        });
+
+        let peer = ["metrics-listener-test"];
+        assert_eq!(ANTI_ENTROPY_DIVERGENT_SERVICES.with_label_values(&peer).get(), 0);
+        assert_eq!(ANTI_ENTROPY_DIVERGENCES.with_label_values(&peer).get(), 3);
+        assert_eq!(ANTI_ENTROPY_REPAIRED_SERVICES.with_label_values(&peer).get(), 2);
+        assert_eq!(ANTI_ENTROPY_REPAIRED_INSTANCES.with_label_values(&peer).get(), 5);
+    }
+
+    #[test]
+    fn test_record_replication_status() {
+        let status = PeerReplicationStatus {
+            node_id: "metrics-replication-test".to_string(),
+            url: "http://10.0.0.1:8080".to_string(),
+            healthy: true,
+            pending_registers: 1,
+            pending_heartbeats: 2,
+            pending_unregisters: 0,
+            retry_queue_depth: 4,
+            outbox_depth: 6,
+            full_sync_pending: true,
+            last_success_time: Some(1_700_000_000_123),
+            consecutive_failures: 3,
+            dropped_events: 5,
+            lag_ms: 1500,
+            protocol: None,
+        };
+        record_replication_status(&[status]);
+
+        let peer = ["metrics-replication-test"];
+        assert_eq!(REPLICATION_PENDING_EVENTS.with_label_values(&[peer[0], "heartbeat"]).get(), 2);
+        assert_eq!(REPLICATION_RETRY_QUEUE_DEPTH.with_label_values(&peer).get(), 4);
+        assert_eq!(REPLICATION_OUTBOX_DEPTH.with_label_values(&peer).get(), 6);
+        assert_eq!(REPLICATION_FULL_SYNC_PENDING.with_label_values(&peer).get(), 1);
+        assert_eq!(REPLICATION_LAST_SUCCESS.with_label_values(&peer).get(), 1_700_000_000);
+        assert_eq!(REPLICATION_CONSECUTIVE_FAILURES.with_label_values(&peer).get(), 3);
+        assert_eq!(REPLICATION_DROPPED_EVENTS.with_label_values(&peer).get(), 5);
+        assert_eq!(REPLICATION_LAG.with_label_values(&peer).get(), 1500);
+    }
+
     #[test]
     fn test_metrics_can_be_gathered() {
         // 确保至少一个指标被初始化
@@ -1,5 +1,7 @@
 // Core service APIs (kept in artemis-server)
+pub mod alert;
 pub mod discovery;
+pub mod federation;
 pub mod metrics;
 pub mod registry;
 pub mod replication;
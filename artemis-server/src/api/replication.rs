@@ -1,12 +1,25 @@
 use crate::state::AppState;
 use artemis_common::model::*;
+use artemis_service::cluster::{
+    ClusterManager,
+    gossip::{
+        self, GossipRequest, JoinClusterRequest, MembershipResponse, ProbeRequest, ProbeResponse,
+    },
+};
+use artemis_service::replication::{EventBatch, ReplicationClient, StreamFrame};
 use artemis_service::traits::RegistryService;
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
     http::{HeaderMap, StatusCode},
+    response::Response,
 };
 use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// 复制-注册端点
 ///
@@ -130,6 +143,77 @@ pub async fn batch_unregister(
     Ok(Json(response))
 }
 
+/// 复制流端点 - 升级为 WebSocket 长连接, 按顺序处理批次并逐个确认
+pub async fn replication_stream(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    if !headers.contains_key("x-artemis-replication") {
+        tracing::warn!("Replication stream request without X-Artemis-Replication header");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(ws.on_upgrade(move |socket| handle_replication_stream(socket, state)))
+}
+
+/// 批次处理完成后才读取下一帧, 发送方在未确认批次达到上限时停止发送
+async fn handle_replication_stream(mut socket: WebSocket, state: AppState) {
+    while let Some(message) = socket.recv().await {
+        let bytes = match message {
+            Ok(Message::Binary(bytes)) => bytes,
+            Ok(Message::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+
+        let seq = match StreamFrame::decode(&bytes) {
+            Ok(StreamFrame::Batch { seq, batch }) => {
+                apply_stream_batch(&state, batch).await;
+                seq
+            }
+            Ok(frame) => {
+                tracing::warn!("Unexpected replication stream frame: {:?}", frame);
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Closing replication stream: {}", e);
+                break;
+            }
+        };
+
+        let Ok(ack) = StreamFrame::Ack { seq }.encode() else {
+            break;
+        };
+        if socket.send(Message::Binary(ack.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// 与对应的批量复制端点相同的处理
+async fn apply_stream_batch(state: &AppState, batch: EventBatch) {
+    match batch {
+        EventBatch::Registers(instances, revisions) => {
+            state
+                .registry_service
+                .batch_register(BatchRegisterRequest { instances, revisions })
+                .await;
+        }
+        EventBatch::Heartbeats(instance_keys) => {
+            state
+                .registry_service
+                .heartbeat_from_replication(ReplicateHeartbeatRequest { instance_keys })
+                .await;
+        }
+        EventBatch::Unregisters(instance_keys, revisions) => {
+            state
+                .registry_service
+                .batch_unregister(BatchUnregisterRequest { instance_keys, revisions })
+                .await;
+        }
+    }
+}
+
 /// 增量同步端点 - 获取指定时间戳之后的服务变更
 pub async fn get_services_delta(
     State(state): State<AppState>,
@@ -140,10 +224,131 @@ pub async fn get_services_delta(
 }
 
 /// 全量同步端点 - 新节点加入时的完整数据同步
+///
+/// 本节点自身还在启动同步时数据不完整, 拒绝提供快照
 pub async fn sync_full_data(
     State(state): State<AppState>,
     Json(request): Json<SyncFullDataRequest>,
 ) -> Json<SyncFullDataResponse> {
+    if !state.status_service.readiness().is_ready() {
+        return Json(SyncFullDataResponse {
+            response_status: ResponseStatus::error(
+                ErrorCode::ServiceUnavailable,
+                "Node is still bootstrapping",
+            ),
+            services: Vec::new(),
+            sync_timestamp: 0,
+        });
+    }
+
     let response = state.registry_service.sync_full_data(request).await;
     Json(response)
 }
+
+/// 服务摘要端点 - 反熵比较
+pub async fn get_service_digests(State(state): State<AppState>) -> Json<GetServiceDigestsResponse> {
+    let response = state.registry_service.get_service_digests().await;
+    Json(response)
+}
+
+/// 拉取服务端点 - 反熵修复时拉取摘要不一致的服务
+pub async fn fetch_services(
+    State(state): State<AppState>,
+    Json(request): Json<FetchServicesRequest>,
+) -> Json<FetchServicesResponse> {
+    let response = state.registry_service.fetch_services(request).await;
+    Json(response)
+}
+
+/// gossip 模式下的集群管理器 (static 模式没有本节点的成员信息)
+fn gossip_cluster(state: &AppState) -> Option<&Arc<ClusterManager>> {
+    state.cluster_manager.as_ref().filter(|cluster| cluster.local_node().is_some())
+}
+
+fn membership_unavailable() -> Json<MembershipResponse> {
+    Json(MembershipResponse {
+        response_status: ResponseStatus::error(
+            ErrorCode::ServiceUnavailable,
+            "Gossip membership is not enabled",
+        ),
+        members: Vec::new(),
+    })
+}
+
+/// 加入集群端点 - 记录新成员并返回完整成员列表
+pub async fn join_cluster(
+    State(state): State<AppState>,
+    Json(request): Json<JoinClusterRequest>,
+) -> Json<MembershipResponse> {
+    let Some(cluster) = gossip_cluster(&state) else {
+        return membership_unavailable();
+    };
+    // 新成员会收到全部注册数据, 只接受签名的加入请求 (签名由复制认证中间件校验)
+    if state.security_manager.signer().is_none() {
+        tracing::warn!("Rejecting join request from {}: no cluster secret", request.node.node_id);
+        return Json(MembershipResponse {
+            response_status: ResponseStatus::error(
+                ErrorCode::ServiceUnavailable,
+                "Joining requires security.cluster_secret",
+            ),
+            members: Vec::new(),
+        });
+    }
+
+    tracing::info!("Join request from {} at {}", request.node.node_id, request.node.base_url());
+    cluster.merge_members(vec![request.node]);
+    Json(MembershipResponse {
+        response_status: ResponseStatus::success(),
+        members: cluster.members(),
+    })
+}
+
+/// Gossip 端点 - 合并对方的成员列表并返回本地成员列表
+pub async fn gossip(
+    State(state): State<AppState>,
+    Json(request): Json<GossipRequest>,
+) -> Json<MembershipResponse> {
+    let Some(cluster) = gossip_cluster(&state) else {
+        return membership_unavailable();
+    };
+
+    cluster.merge_members(request.members);
+    Json(MembershipResponse {
+        response_status: ResponseStatus::success(),
+        members: cluster.members(),
+    })
+}
+
+/// 间接探测端点 - 代替直接探测失败的成员探测目标节点
+pub async fn probe_member(
+    State(state): State<AppState>,
+    Json(request): Json<ProbeRequest>,
+) -> Json<ProbeResponse> {
+    let Some(cluster) = gossip_cluster(&state) else {
+        return Json(ProbeResponse {
+            response_status: ResponseStatus::error(
+                ErrorCode::ServiceUnavailable,
+                "Gossip membership is not enabled",
+            ),
+            alive: false,
+        });
+    };
+
+    // 只探测已知的成员, 不向调用方指定的任意地址发送请求
+    let Some(target) = cluster.get_peers().into_iter().find(|node| node.node_id == request.node_id)
+    else {
+        return Json(ProbeResponse {
+            response_status: ResponseStatus::error(
+                ErrorCode::BadRequest,
+                format!("Unknown member: {}", request.node_id),
+            ),
+            alive: false,
+        });
+    };
+
+    let timeout = Duration::from_millis(request.timeout_ms).min(gossip::MAX_PROBE_TIMEOUT);
+    let client =
+        ReplicationClient::new(timeout).with_signer(state.security_manager.signer().cloned());
+    let alive = gossip::exchange(cluster, &client, &target.base_url()).await;
+    Json(ProbeResponse { response_status: ResponseStatus::success(), alive })
+}
@@ -1,14 +1,32 @@
 use crate::state::AppState;
+use artemis_common::model::ResponseStatus;
+use artemis_service::cluster::{NodeStatus, PROTOCOL_HEADER, ProtocolInfo};
 use artemis_service::model::{
     GetClusterNodeStatusRequest, GetClusterStatusRequest, GetConfigStatusRequest,
-    GetDeploymentStatusRequest, GetLeasesStatusRequest,
+    GetDeploymentStatusRequest, GetFederationStatusResponse, GetLeasesStatusRequest,
+    GetReplicationStatusResponse,
 };
 use axum::{
     extract::{Json, Query, State},
+    http::StatusCode,
     response::IntoResponse,
 };
 use serde::Deserialize;
 
+// ==================== Health ====================
+
+/// 健康检查: 启动同步完成前返回 503
+///
+/// 响应头公布本节点的复制协议, static 模式的对等节点据此协商
+pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    let protocol = [(PROTOCOL_HEADER, ProtocolInfo::local().to_header())];
+    if state.status_service.readiness().is_ready() {
+        (StatusCode::OK, protocol, "OK")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, protocol, "STARTING")
+    }
+}
+
 // ==================== Node Status ====================
 
 pub async fn get_cluster_node_status_post(
@@ -41,6 +59,64 @@ pub async fn get_cluster_status_get(State(state): State<AppState>) -> impl IntoR
     Json(response)
 }
 
+// ==================== Replication Status ====================
+
+/// 各对等节点的复制状态 (已退出集群的节点不再列出)
+pub fn replication_status(state: &AppState) -> GetReplicationStatusResponse {
+    let peers = match (&state.cluster_manager, &state.replication_manager) {
+        (Some(cluster), Some(replication)) => {
+            let peers: Vec<_> = cluster
+                .get_peers()
+                .into_iter()
+                .filter(|peer| peer.status != NodeStatus::Left)
+                .collect();
+            Some(replication.stats().snapshot(&peers))
+        }
+        _ => None,
+    };
+
+    GetReplicationStatusResponse {
+        enabled: peers.is_some(),
+        peers: peers.unwrap_or_default(),
+        response_status: ResponseStatus::success(),
+    }
+}
+
+pub async fn get_replication_status_post(State(state): State<AppState>) -> impl IntoResponse {
+    Json(replication_status(&state))
+}
+
+pub async fn get_replication_status_get(State(state): State<AppState>) -> impl IntoResponse {
+    Json(replication_status(&state))
+}
+
+// ==================== Federation Status ====================
+
+/// 各远程区域的联邦同步状态
+pub fn federation_status(state: &AppState) -> GetFederationStatusResponse {
+    GetFederationStatusResponse {
+        enabled: state.federation.is_some(),
+        local_region: state
+            .federation
+            .as_ref()
+            .map(|federation| federation.local_region().to_string()),
+        regions: state
+            .federation
+            .as_ref()
+            .map(|federation| federation.status())
+            .unwrap_or_default(),
+        response_status: ResponseStatus::success(),
+    }
+}
+
+pub async fn get_federation_status_post(State(state): State<AppState>) -> impl IntoResponse {
+    Json(federation_status(&state))
+}
+
+pub async fn get_federation_status_get(State(state): State<AppState>) -> impl IntoResponse {
+    Json(federation_status(&state))
+}
+
 // ==================== Leases Status ====================
 
 #[derive(Debug, Deserialize)]
@@ -1,22 +1,42 @@
+use crate::api::{alert, routing};
 use crate::state::AppState;
-use artemis_management::{management_routes, ManagementState};
-use axum::{routing::delete, routing::get, routing::post, routing::put, Router};
+use artemis_management::web::middleware::{
+    discovery_api_key_auth, federation_signature_auth, jwt_auth, registry_api_key_auth,
+    replication_signature_auth, require,
+};
+use artemis_management::{ManagementState, management_routes};
+use axum::{Router, middleware, routing::delete, routing::get, routing::post, routing::put};
 use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
 
 pub async fn run_server(state: AppState, addr: SocketAddr) -> anyhow::Result<()> {
-    // 核心服务路由 (注册、发现、复制、状态、监控)
-    let core_routes = Router::new()
-        .route("/health", get(|| async { "OK" }))
-        .route("/metrics", get(crate::api::metrics::metrics))
-        // Registry endpoints
+    // 创建管理状态 (从 AppState 中提取管理相关的 managers)
+    let management_state = ManagementState::new(
+        state.auth_manager.clone(),
+        state.instance_manager.clone(),
+        state.group_manager.clone(),
+        state.route_manager.clone(),
+        state.zone_manager.clone(),
+        state.canary_manager.clone(),
+        state.audit_manager.clone(),
+    )
+    .with_approval_manager(state.approval_manager.clone());
+    let auth = state.auth_manager.clone();
+    let security = state.security_manager.clone();
+
+    // 注册路由 (启用后需要 API Key, 且只能操作应用拥有的服务)
+    let registry_routes = Router::new()
         .route("/api/registry/register", post(crate::api::registry::register))
         .route("/api/registry/register.json", post(crate::api::registry::register))
         .route("/api/registry/heartbeat", post(crate::api::registry::heartbeat))
         .route("/api/registry/heartbeat.json", post(crate::api::registry::heartbeat))
         .route("/api/registry/unregister", post(crate::api::registry::unregister))
         .route("/api/registry/unregister.json", post(crate::api::registry::unregister))
-        // Discovery endpoints
+        .route_layer(middleware::from_fn_with_state(security.clone(), registry_api_key_auth))
+        .with_state(state.clone());
+
+    // 发现路由 (启用后需要 API Key)
+    let discovery_routes = Router::new()
         .route(
             "/api/discovery/service",
             post(crate::api::discovery::get_service)
@@ -38,7 +58,11 @@ pub async fn run_server(state: AppState, addr: SocketAddr) -> anyhow::Result<()>
                 .get(crate::api::discovery::get_services_by_query),
         )
         .route("/api/discovery/lookup.json", post(crate::api::discovery::lookup_instance))
-        // Replication endpoints
+        .route_layer(middleware::from_fn_with_state(security.clone(), discovery_api_key_auth))
+        .with_state(state.clone());
+
+    // 复制路由 (配置集群共享密钥后需要请求签名)
+    let replication_routes = Router::new()
         .route(
             "/api/replication/registry/register.json",
             post(crate::api::replication::replicate_register),
@@ -77,121 +101,195 @@ pub async fn run_server(state: AppState, addr: SocketAddr) -> anyhow::Result<()>
             "/api/replication/registry/sync-full.json",
             post(crate::api::replication::sync_full_data),
         )
+        .route(
+            "/api/replication/registry/digests.json",
+            get(crate::api::replication::get_service_digests)
+                .post(crate::api::replication::get_service_digests),
+        )
+        .route(
+            "/api/replication/registry/fetch-services.json",
+            post(crate::api::replication::fetch_services),
+        )
+        .route(
+            artemis_service::replication::STREAM_PATH,
+            get(crate::api::replication::replication_stream),
+        )
+        .route("/api/replication/cluster/join.json", post(crate::api::replication::join_cluster))
+        .route("/api/replication/cluster/gossip.json", post(crate::api::replication::gossip))
+        .route("/api/replication/cluster/probe.json", post(crate::api::replication::probe_member))
+        .route_layer(middleware::from_fn_with_state(security.clone(), replication_signature_auth))
+        .with_state(state.clone());
+
+    // 跨区域联邦路由 (配置联邦共享密钥后需要请求签名)
+    let federation_routes = Router::new()
+        .route(artemis_service::federation::EXPORT_PATH, get(crate::api::federation::export))
+        .route_layer(middleware::from_fn_with_state(security, federation_signature_auth))
+        .with_state(state.clone());
+
+    // 核心服务路由 (状态、监控)
+    let core_routes = Router::new()
+        .route("/health", get(crate::api::status::health))
+        .route("/metrics", get(crate::api::metrics::scrape_metrics))
+        // Status endpoints
+        .route("/api/status/node.json", post(crate::api::status::get_cluster_node_status_post))
+        .route("/api/status/node.json", get(crate::api::status::get_cluster_node_status_get))
+        .route("/api/status/cluster.json", post(crate::api::status::get_cluster_status_post))
+        .route("/api/status/cluster.json", get(crate::api::status::get_cluster_status_get))
+        .route(
+            "/api/status/replication.json",
+            post(crate::api::status::get_replication_status_post),
+        )
+        .route("/api/status/replication.json", get(crate::api::status::get_replication_status_get))
+        .route("/api/status/federation.json", post(crate::api::status::get_federation_status_post))
+        .route("/api/status/federation.json", get(crate::api::status::get_federation_status_get))
+        .route("/api/status/leases.json", post(crate::api::status::get_leases_status_post))
+        .route("/api/status/leases.json", get(crate::api::status::get_leases_status_get))
+        .route(
+            "/api/status/legacy-leases.json",
+            post(crate::api::status::get_legacy_leases_status_post),
+        )
+        .route(
+            "/api/status/legacy-leases.json",
+            get(crate::api::status::get_legacy_leases_status_get),
+        )
+        .route("/api/status/config.json", post(crate::api::status::get_config_status_post))
+        .route("/api/status/config.json", get(crate::api::status::get_config_status_get))
+        .route("/api/status/deployment.json", post(crate::api::status::get_deployment_status_post))
+        .route("/api/status/deployment.json", get(crate::api::status::get_deployment_status_get))
+        // WebSocket endpoint
+        .route("/ws", get(crate::websocket::ws_handler))
+        .with_state(state.clone());
+
+    // 告警路由 (需要 JWT 认证, 每个路由标注所需权限)
+    let alert_routes = Router::new()
+        .route("/api/alerts", require(get(alert::list_alerts), &auth, "alerts", "read"))
+        .route("/api/alerts/evaluate", require(post(alert::evaluate_now), &auth, "alerts", "write"))
+        .route("/api/alerts/rules", require(get(alert::list_rules), &auth, "alerts", "read"))
+        .route("/api/alerts/rules", require(post(alert::upsert_rule), &auth, "alerts", "write"))
+        .route(
+            "/api/alerts/rules/{rule_id}",
+            require(get(alert::get_rule), &auth, "alerts", "read"),
+        )
+        .route(
+            "/api/alerts/rules/{rule_id}",
+            require(delete(alert::delete_rule), &auth, "alerts", "delete"),
+        )
+        .route_layer(middleware::from_fn_with_state(management_state.clone(), jwt_auth))
+        .with_state(state.clone());
+
+    // 路由管理路由 (需要 JWT 认证, 每个路由标注所需权限)
+    let routing_routes = Router::new()
         // Routing endpoints - Group management (保留在 artemis-server,因为需要访问 registry_service)
-        .route("/api/routing/groups", post(crate::api::routing::create_group))
-        .route("/api/routing/groups", get(crate::api::routing::list_groups))
-        .route("/api/routing/groups/by-id/{group_id}", get(crate::api::routing::get_group))
+        .route(
+            "/api/routing/groups",
+            require(post(routing::create_group), &auth, "groups", "write"),
+        )
+        .route("/api/routing/groups", require(get(routing::list_groups), &auth, "groups", "read"))
+        .route(
+            "/api/routing/groups/by-id/{group_id}",
+            require(get(routing::get_group), &auth, "groups", "read"),
+        )
         .route(
             "/api/routing/groups/{group_key}",
-            delete(crate::api::routing::delete_group),
+            require(delete(routing::delete_group), &auth, "groups", "delete"),
         )
         .route(
             "/api/routing/groups/{group_key}",
-            put(crate::api::routing::update_group),
+            require(put(routing::update_group), &auth, "groups", "write"),
+        )
+        .route(
+            "/api/routing/groups/{group_key}/tags",
+            require(post(routing::add_group_tags), &auth, "groups", "write"),
+        )
+        .route(
+            "/api/routing/groups/{group_key}/tags",
+            require(get(routing::get_group_tags), &auth, "groups", "read"),
         )
-        .route("/api/routing/groups/{group_key}/tags", post(crate::api::routing::add_group_tags))
-        .route("/api/routing/groups/{group_key}/tags", get(crate::api::routing::get_group_tags))
         .route(
             "/api/routing/groups/{group_key}/tags/{tag_key}",
-            delete(crate::api::routing::remove_group_tag),
+            require(delete(routing::remove_group_tag), &auth, "groups", "write"),
         )
         .route(
             "/api/routing/groups/{group_key}/instances",
-            get(crate::api::routing::get_group_instances),
+            require(get(routing::get_group_instances), &auth, "groups", "read"),
         )
         .route(
             "/api/routing/groups/{group_key}/instances",
-            post(crate::api::routing::add_instance_to_group),
+            require(post(routing::add_instance_to_group), &auth, "groups", "write"),
         )
         .route(
             "/api/routing/groups/{group_key}/instances/{instance_id}",
-            delete(crate::api::routing::remove_instance_from_group),
+            require(delete(routing::remove_instance_from_group), &auth, "groups", "write"),
         )
         .route(
             "/api/routing/services/{service_id}/instances",
-            post(crate::api::routing::batch_add_service_instances),
+            require(post(routing::batch_add_service_instances), &auth, "groups", "write"),
         )
         // Routing endpoints - Rule management
-        .route("/api/routing/rules", post(crate::api::routing::create_rule))
-        .route("/api/routing/rules", get(crate::api::routing::list_rules))
-        .route("/api/routing/rules/{rule_id}", get(crate::api::routing::get_rule))
+        .route("/api/routing/rules", require(post(routing::create_rule), &auth, "routing", "write"))
+        .route("/api/routing/rules", require(get(routing::list_rules), &auth, "routing", "read"))
         .route(
             "/api/routing/rules/{rule_id}",
-            delete(crate::api::routing::delete_rule),
+            require(get(routing::get_rule), &auth, "routing", "read"),
         )
         .route(
             "/api/routing/rules/{rule_id}",
-            put(crate::api::routing::update_rule),
+            require(delete(routing::delete_rule), &auth, "routing", "delete"),
         )
-        .route("/api/routing/rules/{rule_id}/publish", post(crate::api::routing::publish_rule))
         .route(
-            "/api/routing/rules/{rule_id}/unpublish",
-            post(crate::api::routing::unpublish_rule),
+            "/api/routing/rules/{rule_id}",
+            require(put(routing::update_rule), &auth, "routing", "write"),
         )
-        // Routing endpoints - Rule group association
-        .route("/api/routing/rules/{rule_id}/groups", post(crate::api::routing::add_rule_group))
-        .route("/api/routing/rules/{rule_id}/groups", get(crate::api::routing::get_rule_groups))
         .route(
-            "/api/routing/rules/{rule_id}/groups/{group_id}",
-            delete(crate::api::routing::remove_rule_group),
+            "/api/routing/rules/{rule_id}/publish",
+            require(post(routing::publish_rule), &auth, "routing", "write"),
         )
         .route(
-            "/api/routing/rules/{rule_id}/groups/{group_id}",
-            put(crate::api::routing::update_rule_group),
+            "/api/routing/rules/{rule_id}/unpublish",
+            require(post(routing::unpublish_rule), &auth, "routing", "write"),
         )
-        // Status endpoints
-        .route("/api/status/node.json", post(crate::api::status::get_cluster_node_status_post))
-        .route("/api/status/node.json", get(crate::api::status::get_cluster_node_status_get))
-        .route("/api/status/cluster.json", post(crate::api::status::get_cluster_status_post))
-        .route("/api/status/cluster.json", get(crate::api::status::get_cluster_status_get))
-        .route("/api/status/leases.json", post(crate::api::status::get_leases_status_post))
-        .route("/api/status/leases.json", get(crate::api::status::get_leases_status_get))
+        // Routing endpoints - Rule group association
         .route(
-            "/api/status/legacy-leases.json",
-            post(crate::api::status::get_legacy_leases_status_post),
+            "/api/routing/rules/{rule_id}/groups",
+            require(post(routing::add_rule_group), &auth, "routing", "write"),
         )
         .route(
-            "/api/status/legacy-leases.json",
-            get(crate::api::status::get_legacy_leases_status_get),
+            "/api/routing/rules/{rule_id}/groups",
+            require(get(routing::get_rule_groups), &auth, "routing", "read"),
         )
-        .route("/api/status/config.json", post(crate::api::status::get_config_status_post))
-        .route("/api/status/config.json", get(crate::api::status::get_config_status_get))
         .route(
-            "/api/status/deployment.json",
-            post(crate::api::status::get_deployment_status_post),
+            "/api/routing/rules/{rule_id}/groups/{group_id}",
+            require(delete(routing::remove_rule_group), &auth, "routing", "write"),
         )
         .route(
-            "/api/status/deployment.json",
-            get(crate::api::status::get_deployment_status_get),
+            "/api/routing/rules/{rule_id}/groups/{group_id}",
+            require(put(routing::update_rule_group), &auth, "routing", "write"),
         )
-        // WebSocket endpoint
-        .route("/ws", get(crate::websocket::ws_handler))
+        .route_layer(middleware::from_fn_with_state(management_state.clone(), jwt_auth))
         .with_state(state.clone());
 
-    // 创建管理状态 (从 AppState 中提取管理相关的 managers)
-    let management_state = ManagementState::new(
-        state.auth_manager.clone(),
-        state.instance_manager.clone(),
-        state.group_manager.clone(),
-        state.route_manager.clone(),
-        state.zone_manager.clone(),
-        state.canary_manager.clone(),
-        state.audit_manager.clone(),
-    );
-
     // 管理路由 (从 artemis-management crate)
     let mgmt_routes = management_routes(management_state);
 
     // 合并所有路由
     let app = Router::new()
         .merge(core_routes)
+        .merge(registry_routes)
+        .merge(discovery_routes)
+        .merge(replication_routes)
+        .merge(federation_routes)
+        .merge(alert_routes)
+        .merge(routing_routes)
         .merge(mgmt_routes)
         .layer(CorsLayer::permissive());
 
     tracing::info!("Starting Artemis Web Server on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
-    axum::serve(listener, app).with_graceful_shutdown(shutdown_signal()).await?;
+    // 携带连接地址, 供登录失败按来源 IP 限流
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
 
     Ok(())
 }
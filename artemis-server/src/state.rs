@@ -1,14 +1,17 @@
 use crate::websocket::SessionManager;
 use artemis_management::{
-    AuditManager, AuthManager, CanaryManager, GroupManager, InstanceManager, RouteManager,
-    ZoneManager,
+    ApprovalManager, AuditManager, AuthManager, CanaryManager, GroupManager, InstanceManager,
+    RouteManager, ZoneManager,
 };
 use artemis_service::{
     RegistryServiceImpl, StatusService,
+    alert::AlertManager,
     cache::VersionedCacheManager,
     cluster::ClusterManager,
     discovery::{DiscoveryServiceImpl, LoadBalancer},
+    federation::FederationManager,
     replication::ReplicationManager,
+    security::SecurityManager,
 };
 use std::sync::Arc;
 
@@ -26,7 +29,12 @@ pub struct AppState {
     pub zone_manager: Arc<ZoneManager>,
     pub canary_manager: Arc<CanaryManager>,
     pub audit_manager: Arc<AuditManager>,
+    pub approval_manager: Arc<ApprovalManager>,
     pub auth_manager: Arc<AuthManager>,
     pub load_balancer: Arc<LoadBalancer>,
     pub status_service: Arc<StatusService>,
+    pub alert_manager: Arc<AlertManager>,
+    pub security_manager: Arc<SecurityManager>,
+    /// 跨区域联邦 (未启用时为 None)
+    pub federation: Option<Arc<FederationManager>>,
 }
@@ -0,0 +1,205 @@
+//! 核心接口认证测试
+//!
+//! 测试覆盖:
+//! - registry_api_key_auth: API Key 认证和服务归属检查
+//! - discovery_api_key_auth: 发现接口 API Key 认证
+//! - replication_signature_auth: 复制请求签名校验
+
+use artemis_management::web::middleware::{
+    discovery_api_key_auth, registry_api_key_auth, replication_signature_auth,
+};
+use artemis_service::config::{ApiKeyConfig, SecurityConfig};
+use artemis_service::security::{
+    API_KEY_HEADER, NONCE_HEADER, RequestSigner, SIGNATURE_HEADER, SecurityManager,
+    TIMESTAMP_HEADER,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    routing::post,
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const REGISTER_PATH: &str = "/api/registry/register.json";
+const DISCOVERY_PATH: &str = "/api/discovery/service.json";
+const REPLICATION_PATH: &str = "/api/replication/registry/register.json";
+
+fn security(config: SecurityConfig) -> Arc<SecurityManager> {
+    Arc::new(SecurityManager::new(&config))
+}
+
+fn secured_config() -> SecurityConfig {
+    SecurityConfig {
+        require_api_key: true,
+        api_keys: vec![ApiKeyConfig {
+            app_id: "order-app".to_string(),
+            key: "order-secret".to_string(),
+            services: vec!["order-*".to_string()],
+        }],
+        cluster_secret: Some("cluster-secret".to_string()),
+        ..Default::default()
+    }
+}
+
+fn build_app(security: Arc<SecurityManager>) -> Router {
+    let registry = Router::new()
+        .route(REGISTER_PATH, post(|| async { "ok" }))
+        .route_layer(middleware::from_fn_with_state(security.clone(), registry_api_key_auth));
+    let discovery = Router::new()
+        .route(DISCOVERY_PATH, post(|| async { "ok" }))
+        .route_layer(middleware::from_fn_with_state(security.clone(), discovery_api_key_auth));
+    let replication = Router::new()
+        .route(REPLICATION_PATH, post(|body: String| async move { body }))
+        .route_layer(middleware::from_fn_with_state(security, replication_signature_auth));
+
+    Router::new().merge(registry).merge(discovery).merge(replication)
+}
+
+fn register_body(service_id: &str) -> String {
+    format!(r#"{{"instances":[{{"serviceId":"{}","instanceId":"i-1"}}]}}"#, service_id)
+}
+
+async fn send(app: &Router, request: Request<Body>) -> StatusCode {
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+fn post_request(path: &str, api_key: Option<&str>, body: String) -> Request<Body> {
+    let mut builder =
+        Request::builder().method("POST").uri(path).header("Content-Type", "application/json");
+    if let Some(key) = api_key {
+        builder = builder.header(API_KEY_HEADER, key);
+    }
+    builder.body(Body::from(body)).unwrap()
+}
+
+#[tokio::test]
+async fn test_security_disabled_by_default() {
+    let app = build_app(security(SecurityConfig::default()));
+
+    assert_eq!(
+        send(&app, post_request(REGISTER_PATH, None, register_body("any"))).await,
+        StatusCode::OK
+    );
+    assert_eq!(send(&app, post_request(DISCOVERY_PATH, None, "{}".into())).await, StatusCode::OK);
+    assert_eq!(send(&app, post_request(REPLICATION_PATH, None, "{}".into())).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_registry_requires_valid_api_key() {
+    let app = build_app(security(secured_config()));
+
+    let body = register_body("order-service");
+    assert_eq!(
+        send(&app, post_request(REGISTER_PATH, None, body.clone())).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send(&app, post_request(REGISTER_PATH, Some("wrong"), body.clone())).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send(&app, post_request(REGISTER_PATH, Some("order-secret"), body)).await,
+        StatusCode::OK
+    );
+
+    // 也接受 Bearer 形式
+    let request = Request::builder()
+        .method("POST")
+        .uri(REGISTER_PATH)
+        .header("Authorization", "Bearer order-secret")
+        .body(Body::from(register_body("order-api")))
+        .unwrap();
+    assert_eq!(send(&app, request).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_registry_rejects_unowned_service() {
+    let app = build_app(security(secured_config()));
+
+    assert_eq!(
+        send(
+            &app,
+            post_request(REGISTER_PATH, Some("order-secret"), register_body("user-service"))
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+
+    let heartbeat = r#"{"instanceKeys":[{"serviceId":"order-service"},{"serviceId":"payment"}]}"#;
+    assert_eq!(
+        send(&app, post_request(REGISTER_PATH, Some("order-secret"), heartbeat.into())).await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn test_discovery_api_key() {
+    let app = build_app(security(secured_config()));
+    assert_eq!(
+        send(&app, post_request(DISCOVERY_PATH, None, "{}".into())).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send(&app, post_request(DISCOVERY_PATH, Some("order-secret"), "{}".into())).await,
+        StatusCode::OK
+    );
+
+    // 关闭发现接口认证
+    let app = build_app(security(SecurityConfig {
+        discovery_requires_api_key: false,
+        ..secured_config()
+    }));
+    assert_eq!(send(&app, post_request(DISCOVERY_PATH, None, "{}".into())).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_replication_signature() {
+    let app = build_app(security(secured_config()));
+    let body = r#"{"instances":[]}"#;
+
+    assert_eq!(
+        send(&app, post_request(REPLICATION_PATH, None, body.into())).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let signed = |signer: &RequestSigner| {
+        let (timestamp, nonce, signature) =
+            signer.sign_now("POST", REPLICATION_PATH, body.as_bytes());
+        Request::builder()
+            .method("POST")
+            .uri(REPLICATION_PATH)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(NONCE_HEADER, nonce)
+            .header(SIGNATURE_HEADER, signature)
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response =
+        app.clone().oneshot(signed(&RequestSigner::new("cluster-secret", 300))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    // 签名校验后请求体应完整传递给处理器
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(bytes, body.as_bytes());
+
+    assert_eq!(
+        send(&app, signed(&RequestSigner::new("other-secret", 300))).await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // 重放窗口内重复使用同一 nonce 的请求被拒绝
+    let request = signed(&RequestSigner::new("cluster-secret", 300));
+    let replay = Request::builder()
+        .method("POST")
+        .uri(REPLICATION_PATH)
+        .header(TIMESTAMP_HEADER, request.headers()[TIMESTAMP_HEADER].clone())
+        .header(NONCE_HEADER, request.headers()[NONCE_HEADER].clone())
+        .header(SIGNATURE_HEADER, request.headers()[SIGNATURE_HEADER].clone())
+        .body(Body::from(body))
+        .unwrap();
+    assert_eq!(send(&app, request).await, StatusCode::OK);
+    assert_eq!(send(&app, replay).await, StatusCode::UNAUTHORIZED);
+}
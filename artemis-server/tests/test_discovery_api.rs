@@ -9,11 +9,11 @@ use artemis_common::model::{
     DiscoveryConfig, ErrorCode, GetServiceRequest, GetServicesRequest, Instance, InstanceStatus,
     RegisterRequest,
 };
+use artemis_server::{api::discovery, state::AppState};
 use artemis_service::{
     InstanceChangeManager, RegistryServiceImpl, cache::VersionedCacheManager, lease::LeaseManager,
     registry::RegistryRepository,
 };
-use artemis_server::{api::discovery, state::AppState};
 use axum::{Json, extract::State};
 use std::sync::Arc;
 use std::time::Duration;
@@ -33,6 +33,11 @@ fn create_test_app_state() -> AppState {
         None,
     ));
 
+    let alert_manager = Arc::new(artemis_service::alert::AlertManager::new(
+        repository.clone(),
+        lease_manager.clone(),
+    ));
+
     let discovery_service =
         Arc::new(artemis_service::discovery::DiscoveryServiceImpl::new(repository, cache.clone()));
 
@@ -43,6 +48,11 @@ fn create_test_app_state() -> AppState {
     let zone_manager = Arc::new(artemis_management::ZoneManager::new());
     let canary_manager = Arc::new(artemis_management::CanaryManager::new());
     let audit_manager = Arc::new(artemis_management::AuditManager::new());
+    let approval_manager = Arc::new(artemis_management::ApprovalManager::new(
+        instance_manager.clone(),
+        zone_manager.clone(),
+        audit_manager.clone(),
+    ));
     let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
     let status_service = Arc::new(artemis_service::StatusService::new(
         None, // cluster_manager
@@ -69,8 +79,12 @@ fn create_test_app_state() -> AppState {
         zone_manager,
         canary_manager,
         audit_manager,
+        approval_manager,
         load_balancer,
         status_service,
+        alert_manager,
+        security_manager: Arc::new(artemis_service::security::SecurityManager::default()),
+        federation: None,
         auth_manager,
     }
 }
@@ -17,7 +17,7 @@ use artemis_management::model::{
 };
 use artemis_management::web::api::instance as instance_api;
 use artemis_management::{InstanceManager, ManagementState};
-use axum::{Json, extract::State};
+use axum::{Extension, Json, extract::State};
 use std::sync::Arc;
 
 /// 创建测试用的 ManagementState
@@ -35,6 +35,11 @@ fn create_test_management_state() -> ManagementState {
     )
 }
 
+/// 当前登录用户 (由 JWT 中间件注入)
+fn operator() -> Extension<String> {
+    Extension("test-user".to_string())
+}
+
 /// 创建测试实例
 fn create_test_instance_key() -> InstanceKey {
     InstanceKey {
@@ -61,7 +66,8 @@ async fn test_operate_instance_pull_out() {
         token: None,
     };
 
-    let response = instance_api::operate_instance(State(state.clone()), Json(request)).await;
+    let response =
+        instance_api::operate_instance(State(state.clone()), operator(), Json(request)).await;
     let (parts, _body) = response.into_parts();
 
     assert_eq!(parts.status, 200); // StatusCode::OK
@@ -77,7 +83,8 @@ async fn test_operate_instance_pull_in() {
     let instance_key = create_test_instance_key();
 
     // 先拉出
-    let _ = state.instance_manager.pull_out_instance(&instance_key, "test-operator".to_string(), true);
+    let _ =
+        state.instance_manager.pull_out_instance(&instance_key, "test-operator".to_string(), true);
 
     // 然后拉入
     let request = OperateInstanceRequest {
@@ -88,7 +95,8 @@ async fn test_operate_instance_pull_in() {
         token: None,
     };
 
-    let response = instance_api::operate_instance(State(state.clone()), Json(request)).await;
+    let response =
+        instance_api::operate_instance(State(state.clone()), operator(), Json(request)).await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -124,7 +132,8 @@ async fn test_is_instance_down_true() {
     let instance_key = create_test_instance_key();
 
     // 拉出实例
-    let _ = state.instance_manager.pull_out_instance(&instance_key, "test-operator".to_string(), true);
+    let _ =
+        state.instance_manager.pull_out_instance(&instance_key, "test-operator".to_string(), true);
 
     let request = IsInstanceDownRequest { instance_key };
 
@@ -162,7 +171,8 @@ async fn test_operate_server_pull_out() {
         token: None,
     };
 
-    let response = instance_api::operate_server(State(state.clone()), Json(request)).await;
+    let response =
+        instance_api::operate_server(State(state.clone()), operator(), Json(request)).await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -177,7 +187,12 @@ async fn test_operate_server_pull_in() {
     let state = create_test_management_state();
 
     // 先拉出
-    let _ = state.instance_manager.pull_out_server("test-server", "test-region", "test-operator".to_string(), true);
+    let _ = state.instance_manager.pull_out_server(
+        "test-server",
+        "test-region",
+        "test-operator".to_string(),
+        true,
+    );
 
     // 然后拉入
     let request = OperateServerRequest {
@@ -189,7 +204,8 @@ async fn test_operate_server_pull_in() {
         token: None,
     };
 
-    let response = instance_api::operate_server(State(state.clone()), Json(request)).await;
+    let response =
+        instance_api::operate_server(State(state.clone()), operator(), Json(request)).await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -206,7 +222,12 @@ async fn test_is_server_down_true() {
     let state = create_test_management_state();
 
     // 拉出服务器
-    let _ = state.instance_manager.pull_out_server("test-server", "test-region", "test-operator".to_string(), true);
+    let _ = state.instance_manager.pull_out_server(
+        "test-server",
+        "test-region",
+        "test-operator".to_string(),
+        true,
+    );
 
     let request = IsServerDownRequest {
         server_id: "test-server".to_string(),
@@ -250,7 +271,8 @@ async fn test_get_all_instance_operations_post() {
 
     let request = GetAllInstanceOperationsRequest { region_id: None };
 
-    let response = instance_api::get_all_instance_operations_post(State(state), Json(request)).await;
+    let response =
+        instance_api::get_all_instance_operations_post(State(state), Json(request)).await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -267,7 +289,9 @@ async fn test_get_all_instance_operations_get() {
     use artemis_management::web::api::instance::AllInstanceOperationsQuery;
     let query = AllInstanceOperationsQuery { region_id: None };
 
-    let response = instance_api::get_all_instance_operations_get(State(state), axum::extract::Query(query)).await;
+    let response =
+        instance_api::get_all_instance_operations_get(State(state), axum::extract::Query(query))
+            .await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -280,8 +304,10 @@ async fn test_get_all_server_operations_post() {
     let state = create_test_management_state();
 
     // 执行一些操作
-    let _ = state.instance_manager.pull_out_server("server1", "region1", "operator1".to_string(), true);
-    let _ = state.instance_manager.pull_out_server("server2", "region1", "operator2".to_string(), true);
+    let _ =
+        state.instance_manager.pull_out_server("server1", "region1", "operator1".to_string(), true);
+    let _ =
+        state.instance_manager.pull_out_server("server2", "region1", "operator2".to_string(), true);
 
     let request = GetAllServerOperationsRequest { region_id: None };
 
@@ -296,12 +322,15 @@ async fn test_get_all_server_operations_get() {
     let state = create_test_management_state();
 
     // 执行一些操作
-    let _ = state.instance_manager.pull_out_server("server1", "region1", "operator1".to_string(), true);
+    let _ =
+        state.instance_manager.pull_out_server("server1", "region1", "operator1".to_string(), true);
 
     use artemis_management::web::api::instance::AllServerOperationsQuery;
     let query = AllServerOperationsQuery { region_id: None };
 
-    let response = instance_api::get_all_server_operations_get(State(state), axum::extract::Query(query)).await;
+    let response =
+        instance_api::get_all_server_operations_get(State(state), axum::extract::Query(query))
+            .await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -323,12 +352,15 @@ async fn test_operate_instance_idempotent() {
     };
 
     // 第一次
-    let response1 = instance_api::operate_instance(State(state.clone()), Json(request.clone())).await;
+    let response1 =
+        instance_api::operate_instance(State(state.clone()), operator(), Json(request.clone()))
+            .await;
     let (parts1, _) = response1.into_parts();
     assert_eq!(parts1.status, 200);
 
     // 第二次 (幂等性测试)
-    let response2 = instance_api::operate_instance(State(state.clone()), Json(request)).await;
+    let response2 =
+        instance_api::operate_instance(State(state.clone()), operator(), Json(request)).await;
     let (parts2, _) = response2.into_parts();
     assert_eq!(parts2.status, 200);
 
@@ -354,7 +386,8 @@ async fn test_get_all_operations_with_region_filter() {
     // 查询特定 region 的操作
     let request = GetAllInstanceOperationsRequest { region_id: Some("region1".to_string()) };
 
-    let response = instance_api::get_all_instance_operations_post(State(state), Json(request)).await;
+    let response =
+        instance_api::get_all_instance_operations_post(State(state), Json(request)).await;
     let (parts, _) = response.into_parts();
 
     assert_eq!(parts.status, 200);
@@ -8,11 +8,11 @@
 use artemis_common::model::{
     ErrorCode, HeartbeatRequest, Instance, InstanceStatus, RegisterRequest, UnregisterRequest,
 };
+use artemis_server::{api::registry, state::AppState};
 use artemis_service::{
     InstanceChangeManager, RegistryServiceImpl, cache::VersionedCacheManager, lease::LeaseManager,
     registry::RegistryRepository,
 };
-use artemis_server::{api::registry, state::AppState};
 use axum::{Json, extract::State};
 use std::sync::Arc;
 use std::time::Duration;
@@ -32,6 +32,11 @@ fn create_test_app_state() -> AppState {
         None,
     ));
 
+    let alert_manager = Arc::new(artemis_service::alert::AlertManager::new(
+        repository.clone(),
+        lease_manager.clone(),
+    ));
+
     let discovery_service =
         Arc::new(artemis_service::discovery::DiscoveryServiceImpl::new(repository, cache.clone()));
 
@@ -42,6 +47,11 @@ fn create_test_app_state() -> AppState {
     let zone_manager = Arc::new(artemis_management::ZoneManager::new());
     let canary_manager = Arc::new(artemis_management::CanaryManager::new());
     let audit_manager = Arc::new(artemis_management::AuditManager::new());
+    let approval_manager = Arc::new(artemis_management::ApprovalManager::new(
+        instance_manager.clone(),
+        zone_manager.clone(),
+        audit_manager.clone(),
+    ));
     let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
     let status_service = Arc::new(artemis_service::StatusService::new(
         None, // cluster_manager
@@ -68,8 +78,12 @@ fn create_test_app_state() -> AppState {
         zone_manager,
         canary_manager,
         audit_manager,
+        approval_manager,
         load_balancer,
         status_service,
+        alert_manager,
+        security_manager: Arc::new(artemis_service::security::SecurityManager::default()),
+        federation: None,
         auth_manager,
     }
 }
@@ -11,17 +11,46 @@
 //! - batch_unregister: 批量注销 API
 //! - get_services_delta: 增量同步 API
 //! - sync_full_data: 全量同步 API
+//! - BootstrapSync: 启动时从对等节点全量同步
+//! - AntiEntropyTask: 与对等节点比较摘要并修复差异
+//! - GossipMembership: 动态加入、gossip 收敛和主动退出, 加入要求集群密钥, 间接探测只探测已知成员
+//! - 复制发件箱: 对等节点恢复后按顺序重放, 溢出后改为全量同步
+//! - 修订号: 过期的复制写入被忽略, 并发写入在各节点收敛到同一结果
+//! - 并发复制: 慢节点不影响其他节点
+//! - 复制流: 通过长连接复制, 对等节点不支持时回退到批量接口
+//! - 协议协商: 按对等节点公布的协议选择端点, 旧版本节点回退到单事件端点
+//! - 跨区域联邦: 拉取远程区域的只读视图, 本地没有实例时故障转移到远程区域
 
 use artemis_common::model::{
-    BatchHeartbeatRequest, BatchRegisterRequest, BatchUnregisterRequest, ErrorCode, Instance,
-    InstanceKey, InstanceStatus, ReplicateHeartbeatRequest, ReplicateRegisterRequest,
-    ReplicateUnregisterRequest, ServicesDeltaRequest, SyncFullDataRequest,
+    BatchHeartbeatRequest, BatchRegisterRequest, BatchUnregisterRequest, DiscoveryConfig,
+    ErrorCode, GetServiceRequest, Instance, InstanceKey, InstanceStatus, ReplicateHeartbeatRequest,
+    ReplicateRegisterRequest, ReplicateUnregisterRequest, Revision, ServicesDeltaRequest,
+    SyncFullDataRequest,
+};
+use artemis_management::web::middleware::replication_signature_auth;
+use artemis_server::{
+    api::{discovery, federation, replication},
+    state::AppState,
 };
 use artemis_service::{
-    RegistryServiceImpl, cache::VersionedCacheManager, change::InstanceChangeManager,
-    lease::LeaseManager, registry::RegistryRepository,
+    RegistryServiceImpl, StatusService,
+    cache::VersionedCacheManager,
+    change::InstanceChangeManager,
+    cluster::{
+        ClusterManager, ClusterNode, GossipConfig, GossipMembership, NodeStatus, PROTOCOL_HEADER,
+        ProtocolInfo,
+        gossip::{JoinClusterRequest, ProbeRequest},
+    },
+    config::{FederationConfig, RemoteRegionConfig, ReplicationConfig, SecurityConfig},
+    federation::{FederationManager, FederationSync, REMOTE_REGION_METADATA},
+    lease::LeaseManager,
+    registry::RegistryRepository,
+    replication::{
+        AntiEntropyTask, BootstrapOutcome, BootstrapSync, PeerReconciliation, ReplicationManager,
+    },
+    security::SecurityManager,
+    status::Readiness,
 };
-use artemis_server::{api::replication, state::AppState};
 use axum::{
     Json,
     extract::State,
@@ -32,6 +61,11 @@ use std::time::Duration;
 
 /// 创建测试用的 AppState
 fn create_test_app_state() -> AppState {
+    create_app_state("test-region", None)
+}
+
+/// 创建指定区域的 AppState, 传入联邦配置时启用联邦
+fn create_app_state(region: &str, federation: Option<&FederationConfig>) -> AppState {
     let repository = RegistryRepository::new();
     let lease_manager = Arc::new(LeaseManager::new(Duration::from_secs(30)));
     let cache = Arc::new(VersionedCacheManager::new());
@@ -45,8 +79,18 @@ fn create_test_app_state() -> AppState {
         None,
     ));
 
-    let discovery_service =
-        Arc::new(artemis_service::discovery::DiscoveryServiceImpl::new(repository, cache.clone()));
+    let alert_manager = Arc::new(artemis_service::alert::AlertManager::new(
+        repository.clone(),
+        lease_manager.clone(),
+    ));
+
+    let federation = federation.map(|config| {
+        Arc::new(FederationManager::new(region.to_string(), repository.clone(), config))
+    });
+    let discovery_service = Arc::new(
+        artemis_service::discovery::DiscoveryServiceImpl::new(repository, cache.clone())
+            .with_federation(federation.clone()),
+    );
 
     let session_manager = Arc::new(artemis_server::websocket::SessionManager::new());
     let instance_manager = Arc::new(artemis_management::InstanceManager::new());
@@ -55,12 +99,17 @@ fn create_test_app_state() -> AppState {
     let zone_manager = Arc::new(artemis_management::ZoneManager::new());
     let canary_manager = Arc::new(artemis_management::CanaryManager::new());
     let audit_manager = Arc::new(artemis_management::AuditManager::new());
+    let approval_manager = Arc::new(artemis_management::ApprovalManager::new(
+        instance_manager.clone(),
+        zone_manager.clone(),
+        audit_manager.clone(),
+    ));
     let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
     let status_service = Arc::new(artemis_service::StatusService::new(
         None,
         lease_manager.clone(),
         "test-node".to_string(),
-        "test-region".to_string(),
+        region.to_string(),
         "test-zone".to_string(),
         "http://localhost:8080".to_string(),
         "test-app".to_string(),
@@ -81,8 +130,12 @@ fn create_test_app_state() -> AppState {
         zone_manager,
         canary_manager,
         audit_manager,
+        approval_manager,
         load_balancer,
         status_service,
+        alert_manager,
+        security_manager: Arc::new(artemis_service::security::SecurityManager::default()),
+        federation,
         auth_manager,
     }
 }
@@ -120,7 +173,7 @@ async fn test_replicate_register_success() {
     let state = create_test_app_state();
     let headers = create_replication_headers();
     let instances = vec![create_test_instance("inst-1")];
-    let request = ReplicateRegisterRequest { instances };
+    let request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
 
     let result = replication::replicate_register(State(state), headers, Json(request)).await;
 
@@ -134,7 +187,7 @@ async fn test_replicate_register_without_header() {
     let state = create_test_app_state();
     let headers = HeaderMap::new(); // 没有 replication header
     let instances = vec![create_test_instance("inst-1")];
-    let request = ReplicateRegisterRequest { instances };
+    let request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
 
     let result = replication::replicate_register(State(state), headers, Json(request)).await;
 
@@ -151,7 +204,7 @@ async fn test_replicate_register_multiple_instances() {
         create_test_instance("inst-2"),
         create_test_instance("inst-3"),
     ];
-    let request = ReplicateRegisterRequest { instances };
+    let request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
 
     let result = replication::replicate_register(State(state), headers, Json(request)).await;
 
@@ -169,7 +222,8 @@ async fn test_replicate_heartbeat_success() {
 
     // 先注册实例
     let instance = create_test_instance("inst-1");
-    let reg_request = ReplicateRegisterRequest { instances: vec![instance.clone()] };
+    let reg_request =
+        ReplicateRegisterRequest { instances: vec![instance.clone()], revisions: Vec::new() };
     let _ =
         replication::replicate_register(State(state.clone()), headers.clone(), Json(reg_request))
             .await;
@@ -205,13 +259,15 @@ async fn test_replicate_unregister_success() {
 
     // 先注册实例
     let instance = create_test_instance("inst-1");
-    let reg_request = ReplicateRegisterRequest { instances: vec![instance.clone()] };
+    let reg_request =
+        ReplicateRegisterRequest { instances: vec![instance.clone()], revisions: Vec::new() };
     let _ =
         replication::replicate_register(State(state.clone()), headers.clone(), Json(reg_request))
             .await;
 
     // 注销测试
-    let unreg_request = ReplicateUnregisterRequest { instance_keys: vec![instance.key()] };
+    let unreg_request =
+        ReplicateUnregisterRequest { instance_keys: vec![instance.key()], revisions: Vec::new() };
     let result =
         replication::replicate_unregister(State(state), headers, Json(unreg_request)).await;
 
@@ -225,7 +281,8 @@ async fn test_replicate_unregister_without_header() {
     let state = create_test_app_state();
     let headers = HeaderMap::new();
     let instance = create_test_instance("inst-1");
-    let request = ReplicateUnregisterRequest { instance_keys: vec![instance.key()] };
+    let request =
+        ReplicateUnregisterRequest { instance_keys: vec![instance.key()], revisions: Vec::new() };
 
     let result = replication::replicate_unregister(State(state), headers, Json(request)).await;
 
@@ -253,7 +310,7 @@ async fn test_get_all_services_with_instances() {
 
     // 注册一些实例
     let instances = vec![create_test_instance("inst-1"), create_test_instance("inst-2")];
-    let reg_request = ReplicateRegisterRequest { instances };
+    let reg_request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
     let _ = replication::replicate_register(State(state.clone()), headers, Json(reg_request)).await;
 
     // 获取所有服务
@@ -276,7 +333,7 @@ async fn test_batch_register_success() {
         create_test_instance("inst-2"),
         create_test_instance("inst-3"),
     ];
-    let request = BatchRegisterRequest { instances };
+    let request = BatchRegisterRequest { instances, revisions: Vec::new() };
 
     let result = replication::batch_register(State(state), headers, Json(request)).await;
 
@@ -294,7 +351,7 @@ async fn test_batch_register_without_header() {
     let state = create_test_app_state();
     let headers = HeaderMap::new();
     let instances = vec![create_test_instance("inst-1")];
-    let request = BatchRegisterRequest { instances };
+    let request = BatchRegisterRequest { instances, revisions: Vec::new() };
 
     let result = replication::batch_register(State(state), headers, Json(request)).await;
 
@@ -311,7 +368,7 @@ async fn test_batch_heartbeat_success() {
 
     // 先批量注册
     let instances = vec![create_test_instance("inst-1"), create_test_instance("inst-2")];
-    let reg_request = BatchRegisterRequest { instances: instances.clone() };
+    let reg_request = BatchRegisterRequest { instances: instances.clone(), revisions: Vec::new() };
     let _ =
         replication::batch_register(State(state.clone()), headers.clone(), Json(reg_request)).await;
 
@@ -351,13 +408,13 @@ async fn test_batch_unregister_success() {
 
     // 先批量注册
     let instances = vec![create_test_instance("inst-1"), create_test_instance("inst-2")];
-    let reg_request = BatchRegisterRequest { instances: instances.clone() };
+    let reg_request = BatchRegisterRequest { instances: instances.clone(), revisions: Vec::new() };
     let _ =
         replication::batch_register(State(state.clone()), headers.clone(), Json(reg_request)).await;
 
     // 批量注销
     let keys: Vec<InstanceKey> = instances.iter().map(|i| i.key()).collect();
-    let unreg_request = BatchUnregisterRequest { instance_keys: keys };
+    let unreg_request = BatchUnregisterRequest { instance_keys: keys, revisions: Vec::new() };
     let result = replication::batch_unregister(State(state), headers, Json(unreg_request)).await;
 
     assert!(result.is_ok());
@@ -374,7 +431,8 @@ async fn test_batch_unregister_without_header() {
     let state = create_test_app_state();
     let headers = HeaderMap::new();
     let instance = create_test_instance("inst-1");
-    let request = BatchUnregisterRequest { instance_keys: vec![instance.key()] };
+    let request =
+        BatchUnregisterRequest { instance_keys: vec![instance.key()], revisions: Vec::new() };
 
     let result = replication::batch_unregister(State(state), headers, Json(request)).await;
 
@@ -391,7 +449,7 @@ async fn test_get_services_delta() {
 
     // 先注册一些实例
     let instances = vec![create_test_instance("inst-1")];
-    let reg_request = ReplicateRegisterRequest { instances };
+    let reg_request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
     let _ = replication::replicate_register(State(state.clone()), headers, Json(reg_request)).await;
 
     // 获取增量数据 (since_timestamp = 0 应该返回所有数据)
@@ -417,7 +475,7 @@ async fn test_sync_full_data() {
 
     // 先注册一些实例
     let instances = vec![create_test_instance("inst-1"), create_test_instance("inst-2")];
-    let reg_request = ReplicateRegisterRequest { instances };
+    let reg_request = ReplicateRegisterRequest { instances, revisions: Vec::new() };
     let _ = replication::replicate_register(State(state.clone()), headers, Json(reg_request)).await;
 
     // 全量同步
@@ -433,6 +491,109 @@ async fn test_sync_full_data() {
     assert_eq!(response.services[0].instances.len(), 2);
 }
 
+/// 标记节点处于启动同步中
+fn starting(mut state: AppState) -> AppState {
+    let lease_manager = Arc::new(LeaseManager::new(Duration::from_secs(30)));
+    state.status_service = Arc::new(
+        StatusService::new(
+            None,
+            lease_manager,
+            "test-node".to_string(),
+            "test-region".to_string(),
+            "test-zone".to_string(),
+            "http://localhost:8080".to_string(),
+            "test-app".to_string(),
+        )
+        .with_readiness(Arc::new(Readiness::starting())),
+    );
+    state
+}
+
+/// 启动监听全量同步和反熵端点的对等节点, 返回地址
+async fn serve_peer(state: AppState) -> std::net::SocketAddr {
+    let app = axum::Router::new()
+        .route(
+            "/api/replication/registry/sync-full.json",
+            axum::routing::post(replication::sync_full_data),
+        )
+        .route(
+            "/api/replication/registry/digests.json",
+            axum::routing::get(replication::get_service_digests),
+        )
+        .route(
+            "/api/replication/registry/fetch-services.json",
+            axum::routing::post(replication::fetch_services),
+        )
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+#[tokio::test]
+async fn test_sync_full_data_refused_while_starting() {
+    let state = starting(create_test_app_state());
+    let sync_request = SyncFullDataRequest { region_id: "test-region".to_string(), zone_id: None };
+
+    let response = replication::sync_full_data(State(state), Json(sync_request)).await.0;
+    assert_eq!(response.response_status.error_code, ErrorCode::ServiceUnavailable);
+    assert!(response.services.is_empty());
+}
+
+#[tokio::test]
+async fn test_bootstrap_sync_from_peer() {
+    let peer = create_test_app_state();
+    let instances = vec![create_test_instance("inst-1"), create_test_instance("inst-2")];
+    peer.registry_service
+        .batch_register(BatchRegisterRequest { instances, revisions: Vec::new() })
+        .await;
+    let addr = serve_peer(peer).await;
+
+    let node = create_test_app_state();
+    let cluster =
+        Arc::new(ClusterManager::new("test-node".to_string(), vec![format!("http://{}", addr)]));
+    let bootstrap = BootstrapSync::new(
+        cluster,
+        "test-region".to_string(),
+        Duration::from_secs(5),
+        Duration::from_secs(1),
+    );
+
+    let outcome = bootstrap.run(&node.registry_service).await;
+    assert_eq!(
+        outcome,
+        BootstrapOutcome::Synced { peer: format!("http://{}", addr), instances: 2 }
+    );
+    assert_eq!(node.cache.get_service("test-service").unwrap().instances.len(), 2);
+}
+
+#[tokio::test]
+async fn test_bootstrap_sync_when_all_peers_starting() {
+    let peer = starting(create_test_app_state());
+    peer.registry_service
+        .batch_register(BatchRegisterRequest {
+            instances: vec![create_test_instance("inst-1")],
+            revisions: Vec::new(),
+        })
+        .await;
+    let addr = serve_peer(peer).await;
+
+    let node = create_test_app_state();
+    let cluster =
+        Arc::new(ClusterManager::new("test-node".to_string(), vec![format!("http://{}", addr)]));
+    let bootstrap = BootstrapSync::new(
+        cluster,
+        "test-region".to_string(),
+        Duration::from_secs(5),
+        Duration::from_secs(1),
+    );
+
+    // 对等节点自身的数据不完整, 不加载并立即就绪
+    assert_eq!(bootstrap.run(&node.registry_service).await, BootstrapOutcome::PeersStarting);
+    assert!(node.cache.get_service("test-service").is_none());
+}
+
 // ===== 边界条件和错误处理测试 =====
 
 #[tokio::test]
@@ -441,7 +602,7 @@ async fn test_batch_operations_empty_list() {
     let headers = create_replication_headers();
 
     // 空列表批量注册
-    let reg_request = BatchRegisterRequest { instances: vec![] };
+    let reg_request = BatchRegisterRequest { instances: vec![], revisions: Vec::new() };
     let result =
         replication::batch_register(State(state.clone()), headers.clone(), Json(reg_request)).await;
     assert!(result.is_ok());
@@ -453,7 +614,707 @@ async fn test_batch_operations_empty_list() {
     assert!(result.is_ok());
 
     // 空列表批量注销
-    let unreg_request = BatchUnregisterRequest { instance_keys: vec![] };
+    let unreg_request = BatchUnregisterRequest { instance_keys: vec![], revisions: Vec::new() };
     let result = replication::batch_unregister(State(state), headers, Json(unreg_request)).await;
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_anti_entropy_repairs_missing_instances() {
+    let mut other_service = create_test_instance("inst-3");
+    other_service.service_id = "other-service".to_string();
+    let peer = create_test_app_state();
+    let instances =
+        vec![create_test_instance("inst-1"), create_test_instance("inst-2"), other_service];
+    peer.registry_service
+        .batch_register(BatchRegisterRequest { instances, revisions: Vec::new() })
+        .await;
+    let addr = serve_peer(peer).await;
+
+    // 本节点错过了 inst-2 和 other-service, 且有一个只在本地存在的服务
+    let mut local_only = create_test_instance("inst-4");
+    local_only.service_id = "local-service".to_string();
+    let node = create_test_app_state();
+    let instances = vec![create_test_instance("inst-1"), local_only];
+    node.registry_service
+        .batch_register(BatchRegisterRequest { instances, revisions: Vec::new() })
+        .await;
+
+    let peer_url = format!("http://{}", addr);
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url.clone()]));
+    let task = AntiEntropyTask::new(
+        cluster,
+        node.registry_service.clone(),
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    );
+
+    let results = task.run_once().await;
+    assert_eq!(
+        results,
+        vec![PeerReconciliation {
+            peer: peer_url.clone(),
+            divergent_services: 3,
+            repaired_services: 2,
+            repaired_instances: 2,
+        }]
+    );
+    assert_eq!(node.cache.get_service("test-service").unwrap().instances.len(), 2);
+    assert_eq!(node.cache.get_service("other-service").unwrap().instances.len(), 1);
+
+    // 修复后只剩本地独有的服务不一致, 由对等节点在自己的轮次中拉取
+    let results = task.run_once().await;
+    assert_eq!(
+        results,
+        vec![PeerReconciliation { peer: peer_url, divergent_services: 1, ..Default::default() }]
+    );
+}
+
+#[tokio::test]
+async fn test_anti_entropy_converges_divergent_status() {
+    // 两边都有 inst-1, 分区期间对等节点上的下线 (较新的修订号) 没有复制过来
+    let peer = create_test_app_state();
+    let down = Instance { status: InstanceStatus::Down, ..create_test_instance("inst-1") };
+    peer.registry_service
+        .batch_register(BatchRegisterRequest {
+            instances: vec![down],
+            revisions: vec![revision(20, "node-2")],
+        })
+        .await;
+    let peer_url = format!("http://{}", serve_peer(peer).await);
+
+    let node = create_test_app_state();
+    node.registry_service
+        .batch_register(BatchRegisterRequest {
+            instances: vec![create_test_instance("inst-1")],
+            revisions: vec![revision(10, "node-1")],
+        })
+        .await;
+
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url.clone()]));
+    let task = AntiEntropyTask::new(
+        cluster,
+        node.registry_service.clone(),
+        Duration::from_secs(60),
+        Duration::from_secs(1),
+    );
+
+    let results = task.run_once().await;
+    assert_eq!(
+        results,
+        vec![PeerReconciliation {
+            peer: peer_url.clone(),
+            divergent_services: 1,
+            repaired_services: 1,
+            repaired_instances: 1,
+        }]
+    );
+    let service = node.cache.get_service("test-service").unwrap();
+    assert_eq!(service.instances[0].status, InstanceStatus::Down);
+
+    let results = task.run_once().await;
+    assert_eq!(results, vec![PeerReconciliation { peer: peer_url, ..Default::default() }]);
+}
+
+/// gossip 模式要求的集群共享密钥
+fn cluster_security() -> Arc<SecurityManager> {
+    Arc::new(SecurityManager::new(&SecurityConfig {
+        cluster_secret: Some("test-cluster-secret".to_string()),
+        ..Default::default()
+    }))
+}
+
+/// 启动开启 gossip 的节点, 返回成员管理器和集群管理器
+async fn serve_member(
+    node_id: &str,
+    seeds: Vec<String>,
+) -> (Arc<GossipMembership>, Arc<ClusterManager>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let cluster = Arc::new(
+        ClusterManager::new(node_id.to_string(), vec![])
+            .with_local_node(&format!("http://{}", addr)),
+    );
+
+    let mut state = create_test_app_state();
+    state.cluster_manager = Some(cluster.clone());
+    state.security_manager = cluster_security();
+    let signer = state.security_manager.signer().cloned();
+    let app = axum::Router::new()
+        .route("/api/replication/cluster/join.json", axum::routing::post(replication::join_cluster))
+        .route("/api/replication/cluster/gossip.json", axum::routing::post(replication::gossip))
+        .route(
+            "/api/replication/cluster/probe.json",
+            axum::routing::post(replication::probe_member),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.security_manager.clone(),
+            replication_signature_auth,
+        ))
+        .with_state(state);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let membership =
+        GossipMembership::new(cluster.clone(), seeds, GossipConfig::default()).with_signer(signer);
+    (Arc::new(membership), cluster)
+}
+
+fn healthy_peer_ids(cluster: &ClusterManager) -> Vec<String> {
+    let mut ids: Vec<String> =
+        cluster.get_healthy_peers().into_iter().map(|node| node.node_id).collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_gossip_membership_join_and_leave() {
+    let (_, cluster1) = serve_member("node-1", vec![]).await;
+    let seed = cluster1.local_node().unwrap().base_url();
+    let (membership2, cluster2) = serve_member("node-2", vec![seed.clone()]).await;
+    let (membership3, cluster3) = serve_member("node-3", vec![seed]).await;
+
+    // 通过种子节点加入, node-3 从种子节点得知 node-2
+    assert!(membership2.join().await);
+    assert!(membership3.join().await);
+    assert_eq!(healthy_peer_ids(&cluster1), vec!["node-2", "node-3"]);
+    assert_eq!(healthy_peer_ids(&cluster3), vec!["node-1", "node-2"]);
+
+    // node-2 加入时还不知道 node-3, gossip 一轮后收敛
+    assert_eq!(healthy_peer_ids(&cluster2), vec!["node-1"]);
+    membership2.run_round().await;
+    assert_eq!(healthy_peer_ids(&cluster2), vec!["node-1", "node-3"]);
+
+    // 主动退出后其他成员不再把它当作对等节点
+    membership3.leave().await;
+    assert_eq!(healthy_peer_ids(&cluster1), vec!["node-2"]);
+    assert_eq!(healthy_peer_ids(&cluster2), vec!["node-1"]);
+    let left = cluster1.members().into_iter().find(|node| node.node_id == "node-3").unwrap();
+    assert_eq!(left.status, NodeStatus::Left);
+}
+
+#[tokio::test]
+async fn test_probe_only_targets_known_members() {
+    let (_, cluster1) = serve_member("node-1", vec![]).await;
+    let seed = cluster1.local_node().unwrap().base_url();
+    let (membership2, _) = serve_member("node-2", vec![seed.clone()]).await;
+    assert!(membership2.join().await);
+
+    let mut state = create_test_app_state();
+    state.cluster_manager = Some(cluster1);
+    state.security_manager = cluster_security();
+    let probe = |node_id: &str| ProbeRequest { node_id: node_id.to_string(), timeout_ms: 60_000 };
+
+    let response = replication::probe_member(State(state.clone()), Json(probe("node-2"))).await;
+    assert_eq!(response.0.response_status.error_code, ErrorCode::Success);
+    assert!(response.0.alive);
+
+    // 不接受未知成员, 不会向调用方指定的地址发送请求
+    let response = replication::probe_member(State(state), Json(probe("unknown"))).await;
+    assert_eq!(response.0.response_status.error_code, ErrorCode::BadRequest);
+    assert!(!response.0.alive);
+}
+
+#[tokio::test]
+async fn test_join_requires_cluster_secret() {
+    let cluster = Arc::new(
+        ClusterManager::new("node-1".to_string(), vec![]).with_local_node("http://127.0.0.1:1"),
+    );
+    let mut state = create_test_app_state();
+    state.cluster_manager = Some(cluster.clone());
+    let node = ClusterNode::new("node-2".to_string(), "127.0.0.1".to_string(), 2);
+
+    let response = replication::join_cluster(State(state), Json(JoinClusterRequest { node })).await;
+    assert_eq!(response.0.response_status.error_code, ErrorCode::ServiceUnavailable);
+    assert!(cluster.get_peers().is_empty());
+}
+
+/// 在已绑定的端口上启动接收批量复制的对等节点
+fn serve_replica(state: AppState, listener: tokio::net::TcpListener) {
+    let app = axum::Router::new()
+        .route(
+            "/api/replication/registry/heartbeat.json",
+            axum::routing::post(replication::replicate_heartbeat),
+        )
+        .route(
+            "/api/replication/registry/batch-register.json",
+            axum::routing::post(replication::batch_register),
+        )
+        .route(
+            "/api/replication/registry/batch-unregister.json",
+            axum::routing::post(replication::batch_unregister),
+        )
+        .with_state(state);
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+}
+
+/// 启动复制工作器, 唯一的对等节点处于下线状态, 返回复制管理器、集群管理器和对等节点端口
+async fn start_worker_with_down_peer(
+    config: ReplicationConfig,
+    repository: RegistryRepository,
+) -> (ReplicationManager, Arc<ClusterManager>, tokio::net::TcpListener) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_url = format!("http://{}", listener.local_addr().unwrap());
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url]));
+    let peer_id = cluster.get_peers()[0].node_id.clone();
+    cluster.mark_node_down(&peer_id);
+
+    let (manager, event_rx) = ReplicationManager::new();
+    ReplicationManager::start_worker(
+        event_rx,
+        cluster.clone(),
+        ReplicationConfig { batch_interval_ms: 20, ..config },
+        None,
+        manager.stats().clone(),
+        repository,
+    );
+    (manager, cluster, listener)
+}
+
+#[tokio::test]
+async fn test_outbox_replays_after_peer_recovers() {
+    let config = ReplicationConfig::default();
+    let (manager, cluster, listener) =
+        start_worker_with_down_peer(config, RegistryRepository::new()).await;
+
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    manager.publish_register(create_test_instance("inst-2"), Revision::default());
+    manager.publish_unregister(create_test_instance("inst-1").key(), Revision::default());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let status = &manager.stats().snapshot(&cluster.get_peers())[0];
+    assert_eq!(status.outbox_depth, 3);
+    assert_eq!(status.dropped_events, 0);
+    assert!(status.lag_ms > 0);
+
+    // 对等节点恢复后按顺序重放: inst-1 先注册后注销
+    let peer = create_test_app_state();
+    serve_replica(peer.clone(), listener);
+    let peer_id = cluster.get_peers()[0].node_id.clone();
+    cluster.update_heartbeat(&peer_id);
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let instances = peer.cache.get_service("test-service").unwrap().instances;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].instance_id, "inst-2");
+    let status = &manager.stats().snapshot(&cluster.get_peers())[0];
+    assert_eq!(status.outbox_depth, 0);
+    assert!(status.last_success_time.is_some());
+}
+
+#[tokio::test]
+async fn test_outbox_overflow_falls_back_to_full_sync() {
+    let repository = RegistryRepository::new();
+    repository.register(create_test_instance("inst-1"));
+    repository.register(create_test_instance("inst-2"));
+    repository.register(create_test_instance("inst-3"));
+
+    let config = ReplicationConfig { outbox_capacity: 2, ..Default::default() };
+    let (manager, cluster, listener) = start_worker_with_down_peer(config, repository).await;
+
+    for id in ["inst-1", "inst-2", "inst-3"] {
+        manager.publish_register(create_test_instance(id), Revision::default());
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let status = &manager.stats().snapshot(&cluster.get_peers())[0];
+    assert!(status.full_sync_pending);
+    assert_eq!(status.outbox_depth, 0);
+
+    let peer = create_test_app_state();
+    serve_replica(peer.clone(), listener);
+    let peer_id = cluster.get_peers()[0].node_id.clone();
+    cluster.update_heartbeat(&peer_id);
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    assert_eq!(peer.cache.get_service("test-service").unwrap().instances.len(), 3);
+    assert!(!manager.stats().snapshot(&cluster.get_peers())[0].full_sync_pending);
+}
+
+// ===== 修订号冲突处理测试 =====
+
+fn revision(clock: u64, node_id: &str) -> Revision {
+    Revision { clock, node_id: node_id.to_string() }
+}
+
+fn instance_count(state: &AppState) -> usize {
+    state.cache.get_service("test-service").map_or(0, |service| service.instances.len())
+}
+
+#[tokio::test]
+async fn test_stale_replicated_register_does_not_resurrect() {
+    let state = create_test_app_state();
+    let headers = create_replication_headers();
+    let instance = create_test_instance("inst-1");
+
+    let register = |clock: u64, node_id: &str| ReplicateRegisterRequest {
+        instances: vec![instance.clone()],
+        revisions: vec![revision(clock, node_id)],
+    };
+    let _ = replication::replicate_register(
+        State(state.clone()),
+        headers.clone(),
+        Json(register(10, "node-2")),
+    )
+    .await;
+    let unregister = ReplicateUnregisterRequest {
+        instance_keys: vec![instance.key()],
+        revisions: vec![revision(20, "node-2")],
+    };
+    let _ =
+        replication::replicate_unregister(State(state.clone()), headers.clone(), Json(unregister))
+            .await;
+    assert_eq!(instance_count(&state), 0);
+
+    // 注销之前发出的注册迟到 (例如从发件箱重放), 不会让实例复活
+    let _ = replication::replicate_register(
+        State(state.clone()),
+        headers.clone(),
+        Json(register(15, "node-3")),
+    )
+    .await;
+    assert_eq!(instance_count(&state), 0);
+
+    // 反熵修复也不会让窗口内注销的实例复活
+    let repaired =
+        state.registry_service.repair_from_peer(vec![artemis_common::model::LeasedInstance {
+            instance: instance.clone(),
+            lease_age_ms: 0,
+            revision: Revision::default(),
+        }]);
+    assert!(repaired.is_empty());
+
+    // 注销之后的注册正常生效
+    let _ = replication::replicate_register(
+        State(state.clone()),
+        headers,
+        Json(register(21, "node-3")),
+    )
+    .await;
+    assert_eq!(instance_count(&state), 1);
+}
+
+#[tokio::test]
+async fn test_concurrent_writes_converge() {
+    let headers = create_replication_headers();
+    let mut from_node2 = create_test_instance("inst-1");
+    from_node2.port = 8081;
+    let mut from_node3 = create_test_instance("inst-1");
+    from_node3.port = 8082;
+
+    let writes = [
+        BatchRegisterRequest {
+            instances: vec![from_node2.clone()],
+            revisions: vec![revision(100, "node-2")],
+        },
+        BatchRegisterRequest {
+            instances: vec![from_node3.clone()],
+            revisions: vec![revision(100, "node-3")],
+        },
+    ];
+
+    // 两个节点以相反的顺序收到同一时钟的并发写入, 节点 ID 大的写入在两边都生效
+    let first = create_test_app_state();
+    let second = create_test_app_state();
+    for request in writes.iter().cloned() {
+        let _ =
+            replication::batch_register(State(first.clone()), headers.clone(), Json(request)).await;
+    }
+    for request in writes.iter().rev().cloned() {
+        let _ = replication::batch_register(State(second.clone()), headers.clone(), Json(request))
+            .await;
+    }
+
+    for state in [&first, &second] {
+        let service = state.cache.get_service("test-service").unwrap();
+        assert_eq!(service.instances.len(), 1);
+        assert_eq!(service.instances[0].port, 8082);
+    }
+}
+
+// ===== 并发复制测试 =====
+
+/// 接受连接但从不响应的对等节点, 返回其地址
+async fn serve_hanging_peer() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_slow_peer_does_not_delay_other_peers() {
+    let slow_peer = serve_hanging_peer().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let fast_peer = format!("http://{}", listener.local_addr().unwrap());
+    let peer = create_test_app_state();
+    serve_replica(peer.clone(), listener);
+
+    let cluster =
+        Arc::new(ClusterManager::new("test-node".to_string(), vec![slow_peer, fast_peer]));
+    let (manager, event_rx) = ReplicationManager::new();
+    ReplicationManager::start_worker(
+        event_rx,
+        cluster,
+        ReplicationConfig { batch_interval_ms: 20, timeout_secs: 30, ..Default::default() },
+        None,
+        manager.stats().clone(),
+        RegistryRepository::new(),
+    );
+
+    // 慢节点的请求一直挂起, 快节点仍然及时收到每一批
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 1);
+
+    manager.publish_register(create_test_instance("inst-2"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 2);
+}
+
+// ===== 复制流测试 =====
+
+/// 启动只提供复制流端点的对等节点, 返回地址
+async fn serve_stream_replica(state: AppState) -> String {
+    let app = axum::Router::new()
+        .route(
+            artemis_service::replication::STREAM_PATH,
+            axum::routing::get(replication::replication_stream),
+        )
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn start_stream_worker(peer_url: String) -> ReplicationManager {
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url]));
+    start_worker(cluster, ReplicationConfig { stream_enabled: true, ..Default::default() })
+}
+
+#[tokio::test]
+async fn test_stream_replication() {
+    // 对等节点没有批量接口, 只能通过复制流收到事件
+    let peer = create_test_app_state();
+    let manager = start_stream_worker(serve_stream_replica(peer.clone()).await);
+
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    manager.publish_register(create_test_instance("inst-2"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 2);
+
+    // 后续批次复用同一连接
+    manager.publish_unregister(create_test_instance("inst-1").key(), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 1);
+}
+
+#[tokio::test]
+async fn test_stream_falls_back_to_batch_endpoints() {
+    // 旧版本对等节点没有复制流端点
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_url = format!("http://{}", listener.local_addr().unwrap());
+    let peer = create_test_app_state();
+    serve_replica(peer.clone(), listener);
+    let manager = start_stream_worker(peer_url);
+
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 1);
+
+    manager.publish_register(create_test_instance("inst-2"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 2);
+}
+
+// ===== 协议协商测试 =====
+
+/// 启动只提供单事件复制端点的旧版本对等节点, 返回地址
+async fn serve_legacy_replica(state: AppState) -> String {
+    let app = axum::Router::new()
+        .route(
+            "/api/replication/registry/register.json",
+            axum::routing::post(replication::replicate_register),
+        )
+        .route(
+            "/api/replication/registry/heartbeat.json",
+            axum::routing::post(replication::replicate_heartbeat),
+        )
+        .route(
+            "/api/replication/registry/unregister.json",
+            axum::routing::post(replication::replicate_unregister),
+        )
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn start_worker(cluster: Arc<ClusterManager>, config: ReplicationConfig) -> ReplicationManager {
+    let (manager, event_rx) = ReplicationManager::new();
+    ReplicationManager::start_worker(
+        event_rx,
+        cluster,
+        ReplicationConfig { batch_interval_ms: 20, ..config },
+        None,
+        manager.stats().clone(),
+        RegistryRepository::new(),
+    );
+    manager
+}
+
+#[tokio::test]
+async fn test_legacy_peer_falls_back_to_per_event_endpoints() {
+    // 协议未知: 先尝试复制流和批量端点, 返回 404 后改用单事件端点
+    let peer = create_test_app_state();
+    let peer_url = serve_legacy_replica(peer.clone()).await;
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url]));
+    let config = ReplicationConfig { stream_enabled: true, ..Default::default() };
+    let manager = start_worker(cluster, config);
+
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    manager.publish_register(create_test_instance("inst-2"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 2);
+
+    manager.publish_unregister(create_test_instance("inst-1").key(), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 1);
+}
+
+#[tokio::test]
+async fn test_negotiated_protocol_selects_endpoints() {
+    let peer = create_test_app_state();
+    let peer_url = serve_legacy_replica(peer.clone()).await;
+    let cluster = Arc::new(ClusterManager::new("test-node".to_string(), vec![peer_url]));
+    let peer_id = cluster.get_peers()[0].node_id.clone();
+
+    // 健康检查得知对等节点是旧版本: 直接使用单事件端点
+    cluster.set_protocol(&peer_id, ProtocolInfo::legacy());
+    let manager = start_worker(cluster.clone(), ReplicationConfig::default());
+    manager.publish_register(create_test_instance("inst-1"), Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(instance_count(&peer), 1);
+
+    let status = &manager.stats().snapshot(&cluster.get_peers())[0];
+    let protocol = status.protocol.as_ref().unwrap();
+    assert_eq!(protocol.version, 1);
+    assert!(protocol.capabilities.is_empty());
+    assert_eq!(status.consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn test_health_advertises_protocol() {
+    let state = create_test_app_state();
+    let response = axum::response::IntoResponse::into_response(
+        artemis_server::api::status::health(State(state)).await,
+    );
+    let header = response.headers().get(PROTOCOL_HEADER).unwrap().to_str().unwrap();
+    assert_eq!(ProtocolInfo::from_header(header), Some(ProtocolInfo::local()));
+}
+
+// ===== 跨区域联邦测试 =====
+
+/// 启动提供联邦导出端点的远程区域, 返回地址
+async fn serve_remote_region(state: AppState) -> String {
+    let app = axum::Router::new()
+        .route(artemis_service::federation::EXPORT_PATH, axum::routing::get(federation::export))
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn replicate_instance(state: &AppState, instance: Instance) {
+    let request = ReplicateRegisterRequest { instances: vec![instance], revisions: Vec::new() };
+    let headers = create_replication_headers();
+    let response = replication::replicate_register(State(state.clone()), headers, Json(request))
+        .await
+        .unwrap();
+    assert_eq!(response.0.response_status.error_code, ErrorCode::Success);
+}
+
+async fn discover_test_service(state: &AppState) -> Vec<Instance> {
+    let request = GetServiceRequest {
+        discovery_config: DiscoveryConfig {
+            service_id: "test-service".to_string(),
+            region_id: "test-region".to_string(),
+            zone_id: "test-zone".to_string(),
+            discovery_data: None,
+        },
+    };
+    let response = discovery::get_service(State(state.clone()), Json(request)).await;
+    response.0.service.map(|service| service.instances).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_federation_failover_to_remote_region() {
+    let remote_config = FederationConfig { enabled: true, ..Default::default() };
+    let remote = create_app_state("us", Some(&remote_config));
+    replicate_instance(
+        &remote,
+        Instance { region_id: "us".to_string(), ..create_test_instance("us-1") },
+    )
+    .await;
+    let remote_url = serve_remote_region(remote).await;
+
+    let remotes = vec![RemoteRegionConfig { region: "us".to_string(), urls: vec![remote_url] }];
+    let config = FederationConfig { enabled: true, remotes: remotes.clone(), ..Default::default() };
+    let local = create_app_state("test-region", Some(&config));
+    let manager = local.federation.clone().unwrap();
+    let sync = FederationSync::new(
+        manager.clone(),
+        remotes,
+        Duration::from_secs(30),
+        Duration::from_secs(5),
+    );
+    sync.run_once().await;
+
+    // 本地没有实例: 返回标记了来源区域的远程实例
+    let instances = discover_test_service(&local).await;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].instance_id, "us-1");
+    let metadata = instances[0].metadata.as_ref().unwrap();
+    assert_eq!(metadata.get(REMOTE_REGION_METADATA).map(String::as_str), Some("us"));
+
+    // 远程视图没有变化时只交换摘要
+    let digest = manager.digest("us");
+    sync.run_once().await;
+    assert_eq!(manager.digest("us"), digest);
+    let status = artemis_server::api::status::federation_status(&local);
+    assert_eq!(status.local_region.as_deref(), Some("test-region"));
+    assert!(status.regions[0].fresh);
+    assert_eq!(status.regions[0].instance_count, 1);
+
+    // 本地有实例时优先使用本地实例
+    replicate_instance(&local, create_test_instance("inst-1")).await;
+    let instances = discover_test_service(&local).await;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].instance_id, "inst-1");
+}
+
+#[tokio::test]
+async fn test_federation_sync_failure_is_recorded() {
+    // 远程区域没有启用联邦
+    let remote_url = serve_remote_region(create_test_app_state()).await;
+    let remotes = vec![RemoteRegionConfig { region: "us".to_string(), urls: vec![remote_url] }];
+    let config = FederationConfig { enabled: true, remotes: remotes.clone(), ..Default::default() };
+    let local = create_app_state("test-region", Some(&config));
+    let manager = local.federation.clone().unwrap();
+    FederationSync::new(manager, remotes, Duration::from_secs(30), Duration::from_secs(5))
+        .run_once()
+        .await;
+
+    let status = artemis_server::api::status::federation_status(&local);
+    assert!(!status.regions[0].fresh);
+    assert_eq!(status.regions[0].consecutive_failures, 1);
+    assert!(status.regions[0].last_error.is_some());
+    assert!(discover_test_service(&local).await.is_empty());
+}
@@ -7,7 +7,10 @@
 //! - get_legacy_leases_status: 获取传统租约状态 (POST/GET)
 //! - get_config_status: 获取配置状态 (POST/GET)
 //! - get_deployment_status: 获取部署状态 (POST/GET)
+//! - health: 健康检查 (启动同步完成前不就绪)
+//! - replication_status: 各对等节点的复制状态
 
+use artemis_server::{api::status, state::AppState};
 use artemis_service::model::{
     GetClusterNodeStatusRequest, GetClusterStatusRequest, GetConfigStatusRequest,
     GetDeploymentStatusRequest, GetLeasesStatusRequest,
@@ -16,7 +19,6 @@ use artemis_service::{
     RegistryServiceImpl, cache::VersionedCacheManager, change::InstanceChangeManager,
     lease::LeaseManager, registry::RegistryRepository,
 };
-use artemis_server::{api::status, state::AppState};
 use axum::{
     Json,
     extract::{Query, State},
@@ -40,6 +42,11 @@ fn create_test_app_state() -> AppState {
         None,
     ));
 
+    let alert_manager = Arc::new(artemis_service::alert::AlertManager::new(
+        repository.clone(),
+        lease_manager.clone(),
+    ));
+
     let discovery_service =
         Arc::new(artemis_service::discovery::DiscoveryServiceImpl::new(repository, cache.clone()));
 
@@ -50,6 +57,11 @@ fn create_test_app_state() -> AppState {
     let zone_manager = Arc::new(artemis_management::ZoneManager::new());
     let canary_manager = Arc::new(artemis_management::CanaryManager::new());
     let audit_manager = Arc::new(artemis_management::AuditManager::new());
+    let approval_manager = Arc::new(artemis_management::ApprovalManager::new(
+        instance_manager.clone(),
+        zone_manager.clone(),
+        audit_manager.clone(),
+    ));
     let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
     let status_service = Arc::new(artemis_service::StatusService::new(
         None,
@@ -76,8 +88,12 @@ fn create_test_app_state() -> AppState {
         zone_manager,
         canary_manager,
         audit_manager,
+        approval_manager,
         load_balancer,
         status_service,
+        alert_manager,
+        security_manager: Arc::new(artemis_service::security::SecurityManager::default()),
+        federation: None,
         auth_manager,
     }
 }
@@ -343,3 +359,96 @@ async fn test_deployment_status_post_get_consistency() {
     assert_eq!(post_response.status(), 200);
     assert_eq!(get_response.status(), 200);
 }
+
+// ===== Health 测试 =====
+
+#[tokio::test]
+async fn test_health_reports_readiness() {
+    let mut state = create_test_app_state();
+    let response = status::health(State(state.clone())).await.into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let readiness = Arc::new(artemis_service::status::Readiness::starting());
+    state.status_service = Arc::new(
+        artemis_service::StatusService::new(
+            None,
+            Arc::new(LeaseManager::new(Duration::from_secs(30))),
+            "test-node".to_string(),
+            "test-region".to_string(),
+            "test-zone".to_string(),
+            "http://localhost:8080".to_string(),
+            "test-app".to_string(),
+        )
+        .with_readiness(readiness.clone()),
+    );
+    let response = status::health(State(state.clone())).await.into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+    readiness.mark_ready();
+    let response = status::health(State(state)).await.into_response();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+}
+
+// ===== Replication Status 测试 =====
+
+#[tokio::test]
+async fn test_replication_status_disabled_without_cluster() {
+    let response = status::replication_status(&create_test_app_state());
+    assert!(!response.enabled);
+    assert!(response.peers.is_empty());
+}
+
+#[tokio::test]
+async fn test_replication_status_reports_failing_peer() {
+    use artemis_common::model::{Instance, InstanceStatus, Revision};
+    use artemis_service::cluster::ClusterManager;
+    use artemis_service::config::ReplicationConfig;
+    use artemis_service::replication::ReplicationManager;
+
+    // 端口 1 上没有服务, 连接失败后事件进入重试队列
+    let cluster =
+        Arc::new(ClusterManager::new("test-node".to_string(), vec!["127.0.0.1:1".to_string()]));
+    let (replication, event_rx) = ReplicationManager::new();
+    let config = ReplicationConfig { batch_interval_ms: 20, ..Default::default() };
+    ReplicationManager::start_worker(
+        event_rx,
+        cluster.clone(),
+        config,
+        None,
+        replication.stats().clone(),
+        artemis_service::registry::RegistryRepository::new(),
+    );
+
+    let instance = Instance {
+        region_id: "test-region".to_string(),
+        zone_id: "test-zone".to_string(),
+        group_id: None,
+        service_id: "test-service".to_string(),
+        instance_id: "inst-1".to_string(),
+        machine_name: None,
+        ip: "127.0.0.1".to_string(),
+        port: 8080,
+        protocol: None,
+        url: "http://127.0.0.1:8080".to_string(),
+        health_check_url: None,
+        status: InstanceStatus::Up,
+        metadata: None,
+    };
+    replication.publish_register(instance, Revision::default());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let mut state = create_test_app_state();
+    state.cluster_manager = Some(cluster);
+    state.replication_manager = Some(Arc::new(replication));
+    let response = status::replication_status(&state);
+
+    assert!(response.enabled);
+    assert_eq!(response.peers.len(), 1);
+    let peer = &response.peers[0];
+    assert_eq!(peer.node_id, "127.0.0.1:1");
+    assert_eq!(peer.consecutive_failures, 1);
+    assert_eq!(peer.retry_queue_depth, 1);
+    assert_eq!(peer.pending_registers, 0);
+    assert!(peer.last_success_time.is_none());
+    assert!(peer.lag_ms > 0);
+}
@@ -0,0 +1,131 @@
+//! Replication fan-out benchmarks
+//!
+//! Local stub peers add artificial latency; one slow peer must not delay
+//! replication to the fast ones.
+//!
+//! Run with: cargo bench --bench replication
+
+use artemis_common::model::{
+    BatchRegisterRequest, BatchRegisterResponse, Instance, InstanceStatus, ResponseStatus, Revision,
+};
+use artemis_service::cluster::ClusterManager;
+use artemis_service::config::ReplicationConfig;
+use artemis_service::registry::RegistryRepository;
+use artemis_service::replication::ReplicationManager;
+use axum::{Json, Router, routing::post};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// 每次迭代发布的注册事件数
+const EVENTS: usize = 500;
+
+fn create_test_instance(id: usize) -> Instance {
+    Instance {
+        region_id: "test-region".to_string(),
+        zone_id: "test-zone".to_string(),
+        service_id: "benchmark-service".to_string(),
+        group_id: None,
+        instance_id: format!("inst-{}", id),
+        machine_name: None,
+        ip: format!("192.168.1.{}", id % 255),
+        port: 8080,
+        protocol: None,
+        url: format!("http://192.168.1.{}:8080", id % 255),
+        health_check_url: None,
+        status: InstanceStatus::Up,
+        metadata: None,
+    }
+}
+
+/// 启动接收批量注册的桩节点, 每个请求延迟 latency 后返回, 返回地址和收到的实例数
+async fn serve_stub_peer(latency: Duration) -> (String, Arc<AtomicUsize>) {
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    let app = Router::new().route(
+        "/api/replication/registry/batch-register.json",
+        post(move |Json(request): Json<BatchRegisterRequest>| {
+            let counter = counter.clone();
+            async move {
+                tokio::time::sleep(latency).await;
+                counter.fetch_add(request.instances.len(), Ordering::SeqCst);
+                Json(BatchRegisterResponse {
+                    response_status: ResponseStatus::success(),
+                    failed_instances: None,
+                })
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}", addr), received)
+}
+
+/// 两个快节点和一个慢节点, 测量快节点收到全部事件的时间
+fn bench_replication_fanout(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("replication_fanout");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(EVENTS as u64));
+
+    for slow_latency_ms in [1u64, 100, 1000] {
+        let (manager, fast_peers) = rt.block_on(async {
+            let fast = Duration::from_millis(1);
+            let (fast1, received1) = serve_stub_peer(fast).await;
+            let (fast2, received2) = serve_stub_peer(fast).await;
+            let (slow, _) = serve_stub_peer(Duration::from_millis(slow_latency_ms)).await;
+
+            let cluster =
+                Arc::new(ClusterManager::new("bench-node".to_string(), vec![fast1, fast2, slow]));
+            let config = ReplicationConfig {
+                batch_size: 100,
+                batch_interval_ms: 5,
+                timeout_secs: 30,
+                ..Default::default()
+            };
+            let (manager, event_rx) = ReplicationManager::new();
+            ReplicationManager::start_worker(
+                event_rx,
+                cluster,
+                config,
+                None,
+                manager.stats().clone(),
+                RegistryRepository::new(),
+            );
+            (manager, vec![received1, received2])
+        });
+
+        let published = AtomicUsize::new(0);
+        group.bench_with_input(
+            BenchmarkId::new("slow_peer_latency_ms", slow_latency_ms),
+            &slow_latency_ms,
+            |b, _| {
+                b.iter(|| {
+                    rt.block_on(async {
+                        let base = published.fetch_add(EVENTS, Ordering::SeqCst);
+                        for id in base..base + EVENTS {
+                            manager.publish_register(create_test_instance(id), Revision::default());
+                        }
+
+                        let target = base + EVENTS;
+                        while fast_peers
+                            .iter()
+                            .any(|received| received.load(Ordering::SeqCst) < target)
+                        {
+                            tokio::time::sleep(Duration::from_millis(1)).await;
+                        }
+                    });
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_replication_fanout);
+criterion_main!(benches);
@@ -0,0 +1,495 @@
+use super::model::{
+    AlertCondition, AlertEvent, AlertEventType, AlertRule, AlertState, AlertStatus,
+};
+use super::sink::AlertSink;
+use crate::lease::LeaseManager;
+use crate::registry::RegistryRepository;
+use artemis_common::model::InstanceStatus;
+use chrono::Utc;
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 单条规则的评估结果
+struct Evaluation {
+    breached: bool,
+    value: f64,
+    message: String,
+}
+
+/// 告警管理器
+///
+/// 周期性地基于 RegistryRepository 和租约数据评估告警规则,
+/// 维护每条规则的 Inactive/Pending/Firing 状态, 状态变为 Firing 或
+/// Resolved 时通知所有 AlertSink
+#[derive(Clone)]
+pub struct AlertManager {
+    repository: RegistryRepository,
+    lease_manager: Arc<LeaseManager>,
+    /// 告警规则: rule_id -> AlertRule
+    rules: Arc<DashMap<String, AlertRule>>,
+    /// 规则状态: rule_id -> AlertStatus
+    statuses: Arc<DashMap<String, AlertStatus>>,
+    /// 条件开始持续满足的时间: rule_id -> Unix 秒
+    pending_since: Arc<DashMap<String, i64>>,
+    sinks: Arc<RwLock<Vec<Arc<dyn AlertSink>>>>,
+}
+
+impl AlertManager {
+    pub fn new(repository: RegistryRepository, lease_manager: Arc<LeaseManager>) -> Self {
+        Self {
+            repository,
+            lease_manager,
+            rules: Arc::new(DashMap::new()),
+            statuses: Arc::new(DashMap::new()),
+            pending_since: Arc::new(DashMap::new()),
+            sinks: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// 注册告警事件接收器
+    pub fn add_sink(&self, sink: Arc<dyn AlertSink>) {
+        self.sinks.write().push(sink);
+    }
+
+    /// 创建或更新告警规则
+    pub fn upsert_rule(&self, rule: AlertRule) -> Result<(), String> {
+        Self::validate_rule(&rule)?;
+
+        // 规则变更后重新开始计时
+        self.pending_since.remove(&rule.rule_id);
+        info!("Upserting alert rule: {}", rule.rule_id);
+        self.rules.insert(rule.rule_id.clone(), rule);
+        Ok(())
+    }
+
+    /// 删除告警规则, 规则正在告警时发送 Resolved 事件
+    pub async fn remove_rule(&self, rule_id: &str) -> Result<(), String> {
+        let (_, rule) = self
+            .rules
+            .remove(rule_id)
+            .ok_or_else(|| format!("Alert rule not found: {}", rule_id))?;
+        self.pending_since.remove(rule_id);
+
+        if let Some((_, status)) = self.statuses.remove(rule_id)
+            && status.state == AlertState::Firing
+        {
+            let event = AlertEvent {
+                rule_id: rule.rule_id,
+                rule_name: rule.name,
+                event_type: AlertEventType::Resolved,
+                value: status.value,
+                message: "rule removed".to_string(),
+                timestamp: Utc::now().timestamp(),
+            };
+            self.notify(std::slice::from_ref(&event)).await;
+        }
+        Ok(())
+    }
+
+    /// 获取告警规则
+    pub fn get_rule(&self, rule_id: &str) -> Option<AlertRule> {
+        self.rules.get(rule_id).map(|r| r.clone())
+    }
+
+    /// 列出所有告警规则
+    pub fn list_rules(&self) -> Vec<AlertRule> {
+        let mut rules: Vec<AlertRule> = self.rules.iter().map(|r| r.value().clone()).collect();
+        rules.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+        rules
+    }
+
+    /// 列出所有规则的当前状态
+    pub fn list_statuses(&self) -> Vec<AlertStatus> {
+        let mut statuses: Vec<AlertStatus> =
+            self.statuses.iter().map(|s| s.value().clone()).collect();
+        statuses.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+        statuses
+    }
+
+    /// 列出 Pending 或 Firing 的告警
+    pub fn active_alerts(&self) -> Vec<AlertStatus> {
+        self.list_statuses().into_iter().filter(|s| s.state != AlertState::Inactive).collect()
+    }
+
+    /// 评估所有规则, 返回本轮产生的告警事件 (已发送给所有 sink)
+    pub async fn evaluate(&self) -> Vec<AlertEvent> {
+        let now = Utc::now().timestamp();
+        let rules = self.list_rules();
+        let mut events = Vec::new();
+
+        for rule in rules {
+            let evaluation = if rule.enabled {
+                self.evaluate_condition(&rule.condition)
+            } else {
+                Evaluation { breached: false, value: 0.0, message: "rule disabled".to_string() }
+            };
+
+            if let Some(event) = self.apply_evaluation(&rule, evaluation, now) {
+                events.push(event);
+            }
+        }
+
+        self.notify(&events).await;
+        events
+    }
+
+    /// 记录告警事件并发送给所有 sink
+    async fn notify(&self, events: &[AlertEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let sinks = self.sinks.read().clone();
+        for event in events {
+            match event.event_type {
+                AlertEventType::Firing => {
+                    warn!("Alert firing: {} - {}", event.rule_id, event.message)
+                }
+                AlertEventType::Resolved => {
+                    info!("Alert resolved: {} - {}", event.rule_id, event.message)
+                }
+            }
+            for sink in &sinks {
+                sink.notify(event).await;
+            }
+        }
+    }
+
+    /// 启动后台评估任务
+    pub fn start_evaluation_task(self: Arc<Self>, evaluation_interval: Duration) {
+        tokio::spawn(async move {
+            info!("Alert evaluation task started (interval: {:?})", evaluation_interval);
+            let mut interval = tokio::time::interval(evaluation_interval);
+            loop {
+                interval.tick().await;
+                self.evaluate().await;
+            }
+        });
+    }
+
+    /// 更新规则状态, 状态跃迁到 Firing 或从 Firing 恢复时返回事件
+    fn apply_evaluation(
+        &self,
+        rule: &AlertRule,
+        evaluation: Evaluation,
+        now: i64,
+    ) -> Option<AlertEvent> {
+        let previous_state =
+            self.statuses.get(&rule.rule_id).map(|s| s.state).unwrap_or(AlertState::Inactive);
+
+        let (state, active_since) = if evaluation.breached {
+            let since = *self.pending_since.entry(rule.rule_id.clone()).or_insert(now);
+            if now - since >= rule.for_secs as i64 {
+                (AlertState::Firing, Some(since))
+            } else {
+                (AlertState::Pending, Some(since))
+            }
+        } else {
+            self.pending_since.remove(&rule.rule_id);
+            (AlertState::Inactive, None)
+        };
+
+        self.statuses.insert(
+            rule.rule_id.clone(),
+            AlertStatus {
+                rule_id: rule.rule_id.clone(),
+                rule_name: rule.name.clone(),
+                state,
+                value: evaluation.value,
+                message: evaluation.message.clone(),
+                active_since,
+                last_evaluated: now,
+            },
+        );
+
+        let event_type = match (previous_state, state) {
+            (AlertState::Firing, AlertState::Firing) => return None,
+            (_, AlertState::Firing) => AlertEventType::Firing,
+            (AlertState::Firing, _) => AlertEventType::Resolved,
+            _ => return None,
+        };
+
+        Some(AlertEvent {
+            rule_id: rule.rule_id.clone(),
+            rule_name: rule.name.clone(),
+            event_type,
+            value: evaluation.value,
+            message: evaluation.message,
+            timestamp: now,
+        })
+    }
+
+    fn evaluate_condition(&self, condition: &AlertCondition) -> Evaluation {
+        match condition {
+            AlertCondition::MinUpInstances { service_id, region_id, zone_id, min_instances } => {
+                let up_count = self
+                    .repository
+                    .get_instances_by_service(service_id)
+                    .into_iter()
+                    .filter(|inst| region_id.as_ref().is_none_or(|r| &inst.region_id == r))
+                    .filter(|inst| zone_id.as_ref().is_none_or(|z| &inst.zone_id == z))
+                    .filter(|inst| inst.status == InstanceStatus::Up)
+                    .filter(|inst| self.lease_manager.is_valid(&inst.key()))
+                    .count();
+
+                Evaluation {
+                    breached: up_count < *min_instances,
+                    value: up_count as f64,
+                    message: format!(
+                        "service {} has {} up instances (min {}){}",
+                        service_id,
+                        up_count,
+                        min_instances,
+                        zone_id.as_ref().map(|z| format!(" in zone {}", z)).unwrap_or_default()
+                    ),
+                }
+            }
+            AlertCondition::EvictionRatio { service_id, max_percent, window_secs } => {
+                let service_id = service_id.as_ref().map(|s| s.to_lowercase());
+                let evicted = self
+                    .lease_manager
+                    .evictions_within(Duration::from_secs(*window_secs))
+                    .into_iter()
+                    .filter(|key| service_id.as_ref().is_none_or(|s| &key.service_id == s))
+                    .count();
+                let current = match &service_id {
+                    Some(s) => self.repository.get_instances_by_service(s).len(),
+                    None => self.repository.count(),
+                };
+
+                let total = current + evicted;
+                let percent = if total == 0 { 0.0 } else { evicted as f64 * 100.0 / total as f64 };
+
+                Evaluation {
+                    breached: percent > *max_percent,
+                    value: percent,
+                    message: format!(
+                        "{} of {} instances ({:.1}%) evicted in the last {}s for {}",
+                        evicted,
+                        total,
+                        percent,
+                        window_secs,
+                        service_id.as_deref().unwrap_or("all services")
+                    ),
+                }
+            }
+        }
+    }
+
+    fn validate_rule(rule: &AlertRule) -> Result<(), String> {
+        if rule.rule_id.trim().is_empty() {
+            return Err("Alert rule id must not be empty".to_string());
+        }
+
+        match &rule.condition {
+            AlertCondition::MinUpInstances { service_id, .. } if service_id.trim().is_empty() => {
+                Err("service_id must not be empty".to_string())
+            }
+            AlertCondition::EvictionRatio { max_percent, .. }
+                if !(0.0..=100.0).contains(max_percent) =>
+            {
+                Err("max_percent must be between 0 and 100".to_string())
+            }
+            AlertCondition::EvictionRatio { window_secs: 0, .. } => {
+                Err("window_secs must be greater than 0".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use artemis_common::model::Instance;
+    use parking_lot::Mutex;
+
+    struct RecordingSink {
+        events: Mutex<Vec<AlertEvent>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AlertSink for RecordingSink {
+        async fn notify(&self, event: &AlertEvent) {
+            self.events.lock().push(event.clone());
+        }
+    }
+
+    fn create_test_instance(instance_id: &str, zone_id: &str) -> Instance {
+        Instance {
+            region_id: "test-region".to_string(),
+            zone_id: zone_id.to_string(),
+            group_id: None,
+            service_id: "order-service".to_string(),
+            instance_id: instance_id.to_string(),
+            machine_name: None,
+            ip: "127.0.0.1".to_string(),
+            port: 8080,
+            protocol: None,
+            url: "http://127.0.0.1:8080".to_string(),
+            health_check_url: None,
+            status: InstanceStatus::Up,
+            metadata: None,
+        }
+    }
+
+    fn min_up_rule(min_instances: usize, for_secs: u64) -> AlertRule {
+        AlertRule {
+            rule_id: "order-zone-a".to_string(),
+            name: "order capacity".to_string(),
+            condition: AlertCondition::MinUpInstances {
+                service_id: "order-service".to_string(),
+                region_id: None,
+                zone_id: Some("zone-a".to_string()),
+                min_instances,
+            },
+            for_secs,
+            enabled: true,
+        }
+    }
+
+    fn setup() -> (AlertManager, RegistryRepository, Arc<LeaseManager>) {
+        let repository = RegistryRepository::new();
+        let lease_manager = Arc::new(LeaseManager::new(Duration::from_secs(30)));
+        let manager = AlertManager::new(repository.clone(), lease_manager.clone());
+        (manager, repository, lease_manager)
+    }
+
+    fn register(repository: &RegistryRepository, leases: &LeaseManager, instance: Instance) {
+        leases.create_lease(instance.key());
+        repository.register(instance);
+    }
+
+    #[tokio::test]
+    async fn test_min_up_instances_fires_and_resolves() {
+        let (manager, repository, leases) = setup();
+        let sink = Arc::new(RecordingSink { events: Mutex::new(Vec::new()) });
+        manager.add_sink(sink.clone());
+
+        register(&repository, &leases, create_test_instance("inst-1", "zone-a"));
+        register(&repository, &leases, create_test_instance("inst-2", "zone-b"));
+        manager.upsert_rule(min_up_rule(2, 0)).unwrap();
+
+        let events = manager.evaluate().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AlertEventType::Firing);
+        assert_eq!(events[0].value, 1.0);
+        assert_eq!(manager.active_alerts().len(), 1);
+
+        // 持续触发不会重复发送事件
+        assert!(manager.evaluate().await.is_empty());
+
+        register(&repository, &leases, create_test_instance("inst-3", "zone-a"));
+        let events = manager.evaluate().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AlertEventType::Resolved);
+        assert!(manager.active_alerts().is_empty());
+
+        assert_eq!(sink.events.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pending_until_for_duration_elapses() {
+        let (manager, repository, leases) = setup();
+        register(&repository, &leases, create_test_instance("inst-1", "zone-a"));
+        manager.upsert_rule(min_up_rule(3, 120)).unwrap();
+
+        assert!(manager.evaluate().await.is_empty());
+        let statuses = manager.list_statuses();
+        assert_eq!(statuses[0].state, AlertState::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_instances_without_valid_lease_are_not_counted() {
+        let (manager, repository, _leases) = setup();
+        repository.register(create_test_instance("inst-1", "zone-a"));
+        manager.upsert_rule(min_up_rule(1, 0)).unwrap();
+
+        let events = manager.evaluate().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].value, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_eviction_ratio() {
+        let (manager, repository, leases) = setup();
+        register(&repository, &leases, create_test_instance("inst-1", "zone-a"));
+        leases.record_eviction(create_test_instance("inst-2", "zone-a").key());
+
+        manager
+            .upsert_rule(AlertRule {
+                rule_id: "eviction".to_string(),
+                name: String::new(),
+                condition: AlertCondition::EvictionRatio {
+                    service_id: Some("order-service".to_string()),
+                    max_percent: 40.0,
+                    window_secs: 300,
+                },
+                for_secs: 0,
+                enabled: true,
+            })
+            .unwrap();
+
+        let events = manager.evaluate().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].value, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_rule_resolves() {
+        let (manager, _repository, _leases) = setup();
+        manager.upsert_rule(min_up_rule(1, 0)).unwrap();
+        assert_eq!(manager.evaluate().await.len(), 1);
+
+        let mut rule = min_up_rule(1, 0);
+        rule.enabled = false;
+        manager.upsert_rule(rule).unwrap();
+
+        let events = manager.evaluate().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, AlertEventType::Resolved);
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        let (manager, _, _) = setup();
+
+        let mut rule = min_up_rule(1, 0);
+        rule.rule_id = " ".to_string();
+        assert!(manager.upsert_rule(rule).is_err());
+
+        let rule = AlertRule {
+            rule_id: "bad".to_string(),
+            name: String::new(),
+            condition: AlertCondition::EvictionRatio {
+                service_id: None,
+                max_percent: 150.0,
+                window_secs: 60,
+            },
+            for_secs: 0,
+            enabled: true,
+        };
+        assert!(manager.upsert_rule(rule).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remove_firing_rule_resolves() {
+        let (manager, _repository, _leases) = setup();
+        let sink = Arc::new(RecordingSink { events: Mutex::new(Vec::new()) });
+        manager.add_sink(sink.clone());
+
+        manager.upsert_rule(min_up_rule(1, 0)).unwrap();
+        assert_eq!(manager.evaluate().await.len(), 1);
+
+        manager.remove_rule("order-zone-a").await.unwrap();
+        let events = sink.events.lock().clone();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event_type, AlertEventType::Resolved);
+        assert!(manager.active_alerts().is_empty());
+
+        assert!(manager.remove_rule("missing").await.is_err());
+    }
+}
@@ -0,0 +1,15 @@
+//! Threshold alert rules on service health
+//!
+//! This module evaluates alert rules against registry and lease data:
+//! - Minimum Up instances per service/region/zone
+//! - Eviction ratio within a time window
+//! - Pending/Firing/Resolved state tracking with `for` durations
+//! - Pluggable sinks (webhook, metrics) notified on state transitions
+
+pub mod manager;
+pub mod model;
+pub mod sink;
+
+pub use manager::AlertManager;
+pub use model::{AlertCondition, AlertEvent, AlertEventType, AlertRule, AlertState, AlertStatus};
+pub use sink::{AlertSink, WebhookSink};
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+/// 告警规则
+///
+/// API 使用 camelCase 字段, TOML 配置文件中同时接受 snake_case 写法
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    #[serde(alias = "rule_id")]
+    pub rule_id: String,
+    #[serde(default)]
+    pub name: String,
+    pub condition: AlertCondition,
+    /// 条件需要持续满足的时长(秒), 之后才进入 Firing 状态
+    #[serde(default, alias = "for_secs")]
+    pub for_secs: u64,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+/// 告警条件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AlertCondition {
+    /// 服务的 Up 实例数(租约有效)低于阈值
+    #[serde(rename_all = "camelCase")]
+    MinUpInstances {
+        #[serde(alias = "service_id")]
+        service_id: String,
+        #[serde(default, alias = "region_id", skip_serializing_if = "Option::is_none")]
+        region_id: Option<String>,
+        #[serde(default, alias = "zone_id", skip_serializing_if = "Option::is_none")]
+        zone_id: Option<String>,
+        #[serde(alias = "min_instances")]
+        min_instances: usize,
+    },
+    /// 时间窗口内被驱逐的实例比例超过阈值
+    #[serde(rename_all = "camelCase")]
+    EvictionRatio {
+        /// 为空时统计所有服务
+        #[serde(default, alias = "service_id", skip_serializing_if = "Option::is_none")]
+        service_id: Option<String>,
+        /// 百分比阈值 (0-100)
+        #[serde(alias = "max_percent")]
+        max_percent: f64,
+        #[serde(alias = "window_secs")]
+        window_secs: u64,
+    },
+}
+
+/// 告警状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    /// 条件未满足
+    Inactive,
+    /// 条件已满足, 等待 for_secs 到期
+    Pending,
+    /// 告警触发中
+    Firing,
+}
+
+impl AlertState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlertState::Inactive => "inactive",
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+        }
+    }
+}
+
+/// 规则当前的告警状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertStatus {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub state: AlertState,
+    /// 最近一次评估的观测值 (实例数或驱逐百分比)
+    pub value: f64,
+    pub message: String,
+    /// 进入当前状态的时间 (Unix 秒)
+    pub active_since: Option<i64>,
+    pub last_evaluated: i64,
+}
+
+/// 告警事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertEventType {
+    Firing,
+    Resolved,
+}
+
+/// 告警事件 (Firing / Resolved), 发送给所有 AlertSink
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub event_type: AlertEventType,
+    pub value: f64,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_deserialize_from_toml() {
+        let rule: AlertRule = toml::from_str(
+            r#"
+            rule_id = "order-zone-a"
+            name = "order service low capacity"
+            for_secs = 120
+
+            [condition]
+            type = "min-up-instances"
+            service_id = "order-service"
+            zone_id = "zone-a"
+            min_instances = 3
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rule.rule_id, "order-zone-a");
+        assert_eq!(rule.for_secs, 120);
+        assert!(rule.enabled);
+        assert_eq!(
+            rule.condition,
+            AlertCondition::MinUpInstances {
+                service_id: "order-service".to_string(),
+                region_id: None,
+                zone_id: Some("zone-a".to_string()),
+                min_instances: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_condition_json_roundtrip() {
+        let condition =
+            AlertCondition::EvictionRatio { service_id: None, max_percent: 20.0, window_secs: 300 };
+        let json = serde_json::to_value(&condition).unwrap();
+        assert_eq!(json["type"], "eviction-ratio");
+        assert_eq!(json["maxPercent"], 20.0);
+
+        let parsed: AlertCondition = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, condition);
+    }
+}
@@ -0,0 +1,55 @@
+use super::model::AlertEvent;
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// 告警事件接收器
+///
+/// AlertManager 在规则状态变为 Firing 或 Resolved 时调用所有已注册的 sink
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn notify(&self, event: &AlertEvent);
+}
+
+/// Webhook 告警接收器 - 以 JSON POST 推送告警事件
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        Self { client, url }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    async fn notify(&self, event: &AlertEvent) {
+        match self.client.post(&self.url).json(event).send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Delivered alert {} to webhook {}", event.rule_id, self.url);
+            }
+            Ok(response) => {
+                warn!(
+                    "Webhook {} rejected alert {}: HTTP {}",
+                    self.url,
+                    event.rule_id,
+                    response.status()
+                );
+            }
+            Err(e) => {
+                warn!("Failed to deliver alert {} to webhook {}: {}", event.rule_id, self.url, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use super::manager::ClusterManager;
+use super::node::ClusterNode;
+use crate::replication::ReplicationClient;
+use crate::security::RequestSigner;
+use artemis_common::model::ResponseStatus;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, info, warn};
+
+/// 每隔多少轮尝试联系一个下线的成员, 网络分区恢复后两边重新合并
+const DOWN_PEER_PROBE_ROUNDS: u64 = 10;
+
+/// 加入集群请求 - 新节点向任意种子节点发送自己的成员信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinClusterRequest {
+    pub node: ClusterNode,
+}
+
+/// Gossip 请求 - 发送方的完整成员列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GossipRequest {
+    pub members: Vec<ClusterNode>,
+}
+
+/// 成员列表响应 (加入和 gossip 共用)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipResponse {
+    pub response_status: ResponseStatus,
+    pub members: Vec<ClusterNode>,
+}
+
+/// 间接探测请求 - 直接探测失败后请其他成员代为探测
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeRequest {
+    /// 被探测的成员, 代为探测的节点只探测自己已知的成员
+    pub node_id: String,
+    /// 探测超时, 代为探测的节点最多使用 MAX_PROBE_TIMEOUT
+    pub timeout_ms: u64,
+}
+
+/// 间接探测允许的最长超时时间
+pub const MAX_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResponse {
+    pub response_status: ResponseStatus,
+    pub alive: bool,
+}
+
+/// Gossip 参数
+#[derive(Debug, Clone)]
+pub struct GossipConfig {
+    /// 每轮探测一个成员的间隔
+    pub interval: Duration,
+    /// 直接探测的超时时间
+    pub probe_timeout: Duration,
+    /// 直接探测失败后请求代为探测的成员数
+    pub indirect_probes: usize,
+    /// 被怀疑的成员在这段时间内没有反驳则判定下线
+    pub suspect_timeout: Duration,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            probe_timeout: Duration::from_millis(500),
+            indirect_probes: 3,
+            suspect_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+/// 与对等节点交换成员列表并合并响应, 成功即说明对方存活
+pub async fn exchange(
+    cluster: &ClusterManager,
+    client: &ReplicationClient,
+    peer_url: &str,
+) -> bool {
+    let request = GossipRequest { members: cluster.members() };
+    match client.gossip(peer_url, &request).await {
+        Ok(response) => {
+            cluster.merge_members(response.members);
+            true
+        }
+        Err(e) => {
+            debug!("Gossip with {} failed: {}", peer_url, e);
+            false
+        }
+    }
+}
+
+/// Gossip 成员管理 (SWIM 风格)
+///
+/// - 启动时向任意种子节点发送加入请求, 获得完整成员列表
+/// - 每轮随机选择一个成员交换成员列表, 交换成功即视为存活
+/// - 直接探测失败后请其他成员代为探测, 都失败则怀疑该成员
+/// - 被怀疑的成员收到怀疑信息后递增 incarnation 反驳, 超时未反驳则判定下线
+/// - 关闭时把自己标记为已退出并通知所有成员
+pub struct GossipMembership {
+    cluster: Arc<ClusterManager>,
+    client: ReplicationClient,
+    seeds: Vec<String>,
+    config: GossipConfig,
+    rounds: AtomicU64,
+}
+
+impl GossipMembership {
+    pub fn new(cluster: Arc<ClusterManager>, seeds: Vec<String>, config: GossipConfig) -> Self {
+        Self {
+            cluster,
+            client: ReplicationClient::new(config.probe_timeout),
+            seeds,
+            config,
+            rounds: AtomicU64::new(0),
+        }
+    }
+
+    /// 使用集群共享密钥对成员协议请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
+    /// 依次尝试种子节点, 任意一个接受即加入成功
+    pub async fn join(&self) -> bool {
+        let Some(local) = self.cluster.local_node() else {
+            return false;
+        };
+        let local_url = local.base_url();
+        let request = JoinClusterRequest { node: local };
+
+        for seed in &self.seeds {
+            let seed_url = ClusterNode::new_from_url(seed.clone()).base_url();
+            if seed_url == local_url {
+                continue;
+            }
+            match self.client.join_cluster(&seed_url, &request).await {
+                Ok(response) => {
+                    let members = response.members.len();
+                    self.cluster.merge_members(response.members);
+                    info!("Joined cluster via {} ({} members)", seed_url, members);
+                    return true;
+                }
+                Err(e) => debug!("Join via {} failed: {}", seed_url, e),
+            }
+        }
+
+        if !self.seeds.is_empty() {
+            warn!("Failed to join cluster: no seed reachable");
+        }
+        false
+    }
+
+    /// 后台执行 gossip 轮次
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("Gossip membership started (interval: {:?})", self.config.interval);
+            let mut interval = tokio::time::interval(self.config.interval);
+
+            loop {
+                interval.tick().await;
+                self.run_round().await;
+            }
+        })
+    }
+
+    /// 执行一轮探测
+    pub async fn run_round(&self) {
+        let round = self.rounds.fetch_add(1, Ordering::Relaxed);
+        let peers = self.cluster.get_healthy_peers();
+
+        // 还没有发现任何成员 (种子节点比本节点晚启动), 重新尝试加入
+        if peers.is_empty() {
+            self.join().await;
+        } else {
+            let (target, helpers) = {
+                let mut rng = rand::thread_rng();
+                let target = peers.choose(&mut rng).cloned();
+                let helpers: Vec<ClusterNode> = target
+                    .as_ref()
+                    .map(|target| {
+                        let others: Vec<&ClusterNode> =
+                            peers.iter().filter(|peer| peer.node_id != target.node_id).collect();
+                        others
+                            .choose_multiple(&mut rng, self.config.indirect_probes)
+                            .map(|peer| (*peer).clone())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (target, helpers)
+            };
+
+            if let Some(target) = target {
+                self.probe(&target, helpers).await;
+            }
+        }
+
+        if round % DOWN_PEER_PROBE_ROUNDS == DOWN_PEER_PROBE_ROUNDS - 1 {
+            let down = self.cluster.get_down_peers();
+            let target = down.choose(&mut rand::thread_rng()).map(|peer| peer.base_url());
+            if let Some(target_url) = target {
+                exchange(&self.cluster, &self.client, &target_url).await;
+            }
+        }
+
+        self.cluster.expire_suspects(self.config.suspect_timeout);
+    }
+
+    /// 探测成员: 先直接交换成员列表, 失败后请其他成员间接探测
+    async fn probe(&self, target: &ClusterNode, helpers: Vec<ClusterNode>) {
+        let target_url = target.base_url();
+        if exchange(&self.cluster, &self.client, &target_url).await {
+            return;
+        }
+
+        let request = ProbeRequest {
+            node_id: target.node_id.clone(),
+            timeout_ms: self.config.probe_timeout.as_millis() as u64,
+        };
+        let mut probes = JoinSet::new();
+        for helper in helpers {
+            let client = self.client.clone();
+            let request = request.clone();
+            probes.spawn(async move {
+                client
+                    .probe_member(&helper.base_url(), &request)
+                    .await
+                    .map(|response| response.alive)
+                    .unwrap_or(false)
+            });
+        }
+
+        while let Some(result) = probes.join_next().await {
+            if result.unwrap_or(false) {
+                debug!("Indirect probe of {} succeeded", target_url);
+                return;
+            }
+        }
+
+        self.cluster.mark_suspect(&target.node_id);
+    }
+
+    /// 标记本节点退出集群并通知所有健康的成员
+    pub async fn leave(&self) {
+        if self.cluster.leave().is_none() {
+            return;
+        }
+
+        let request = GossipRequest { members: self.cluster.members() };
+        let mut notifications = JoinSet::new();
+        for peer in self.cluster.get_healthy_peers() {
+            let client = self.client.clone();
+            let request = request.clone();
+            notifications.spawn(async move {
+                let peer_url = peer.base_url();
+                if let Err(e) = client.gossip(&peer_url, &request).await {
+                    warn!("Failed to notify {} of leave: {}", peer_url, e);
+                }
+            });
+        }
+        while notifications.join_next().await.is_some() {}
+        info!("Left cluster");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_join_without_seeds() {
+        let cluster = Arc::new(
+            ClusterManager::new("node-1".to_string(), vec![])
+                .with_local_node("http://127.0.0.1:8081"),
+        );
+        let membership = GossipMembership::new(cluster, vec![], GossipConfig::default());
+        assert!(!membership.join().await);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_member_is_suspected() {
+        let cluster = Arc::new(
+            ClusterManager::new("node-1".to_string(), vec![])
+                .with_local_node("http://127.0.0.1:8081"),
+        );
+        // 端口 1 上没有服务, 连接立即失败
+        let mut peer = ClusterNode::new_from_url("127.0.0.1:1".to_string());
+        peer.node_id = "node-2".to_string();
+        cluster.merge_members(vec![peer]);
+
+        let config =
+            GossipConfig { suspect_timeout: Duration::from_secs(60), ..Default::default() };
+        let membership = GossipMembership::new(cluster.clone(), vec![], config);
+        membership.run_round().await;
+
+        assert_eq!(cluster.get_healthy_peers()[0].status, crate::cluster::NodeStatus::Suspect);
+    }
+}
@@ -1,5 +1,8 @@
 use super::node::{ClusterNode, NodeStatus};
+use super::protocol::{PROTOCOL_HEADER, ProtocolInfo};
+use chrono::Utc;
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{info, warn};
@@ -8,7 +11,8 @@ use tracing::{info, warn};
 ///
 /// 负责管理集群节点，包括：
 /// - 节点注册和发现
-/// - 主动健康检查
+/// - 主动健康检查 (static 模式)
+/// - 合并 gossip 成员信息 (gossip 模式)
 /// - 节点状态管理
 #[derive(Clone)]
 pub struct ClusterManager {
@@ -34,6 +38,135 @@ impl ClusterManager {
         Self { node_id, nodes, heartbeat_timeout: Duration::from_secs(30) }
     }
 
+    /// 注册当前节点 (gossip 模式)
+    ///
+    /// incarnation 取启动时间, 重启后的节点信息总是覆盖其他成员记录的重启前状态
+    pub fn with_local_node(self, advertise_url: &str) -> Self {
+        let mut node = ClusterNode::new_from_url(advertise_url.to_string());
+        node.node_id = self.node_id.clone();
+        node.incarnation = Utc::now().timestamp_millis() as u64;
+        node.protocol = Some(ProtocolInfo::local());
+        info!("Local cluster node {} at {}", node.node_id, node.base_url());
+        self.nodes.insert(node.node_id.clone(), node);
+        self
+    }
+
+    /// 当前节点ID
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// 当前节点的成员信息 (仅 gossip 模式)
+    pub fn local_node(&self) -> Option<ClusterNode> {
+        self.nodes.get(&self.node_id).map(|node| node.clone())
+    }
+
+    /// 所有成员, 包括当前节点和已下线、已退出的节点
+    pub fn members(&self) -> Vec<ClusterNode> {
+        self.nodes.iter().map(|entry| entry.value().clone()).collect()
+    }
+
+    /// 合并其他节点传播的成员信息, 返回发生变化的成员数
+    pub fn merge_members(&self, members: Vec<ClusterNode>) -> usize {
+        members.into_iter().filter(|member| self.merge_member(member.clone())).count()
+    }
+
+    fn merge_member(&self, mut remote: ClusterNode) -> bool {
+        if remote.node_id == self.node_id {
+            return self.refute(&remote);
+        }
+
+        // 本地记录的是状态变化的时间, 用于怀疑超时
+        remote.last_heartbeat = Utc::now();
+        match self.nodes.entry(remote.node_id.clone()) {
+            Entry::Vacant(entry) => {
+                info!(
+                    "Cluster member {} at {} discovered ({:?})",
+                    remote.node_id,
+                    remote.base_url(),
+                    remote.status
+                );
+                entry.insert(remote);
+                true
+            }
+            Entry::Occupied(mut entry) => {
+                if !entry.get().is_superseded_by(&remote) {
+                    return false;
+                }
+                // 经旧版本节点转发的成员信息不带协议, 保留已知的协议
+                if remote.protocol.is_none() {
+                    remote.protocol = entry.get().protocol.clone();
+                }
+                if entry.get().status != remote.status {
+                    info!(
+                        "Cluster member {} is now {:?} (incarnation {})",
+                        remote.node_id, remote.status, remote.incarnation
+                    );
+                }
+                entry.insert(remote);
+                true
+            }
+        }
+    }
+
+    /// 其他节点认为当前节点被怀疑或下线时, 递增 incarnation 反驳
+    fn refute(&self, remote: &ClusterNode) -> bool {
+        let Some(mut local) = self.nodes.get_mut(&self.node_id) else {
+            return false;
+        };
+        if local.status != NodeStatus::Up
+            || remote.status == NodeStatus::Up
+            || remote.incarnation < local.incarnation
+        {
+            return false;
+        }
+
+        local.incarnation = remote.incarnation + 1;
+        info!(
+            "Refuted {:?} status of local node (incarnation {})",
+            remote.status, local.incarnation
+        );
+        true
+    }
+
+    /// 直接和间接探测都失败时怀疑节点
+    pub fn mark_suspect(&self, node_id: &str) -> bool {
+        match self.nodes.get_mut(node_id) {
+            Some(mut node) if node.status == NodeStatus::Up => {
+                node.status = NodeStatus::Suspect;
+                node.last_heartbeat = Utc::now();
+                warn!("Cluster member {} is suspected", node_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 将怀疑超时的成员判定为下线, 返回下线的节点
+    pub fn expire_suspects(&self, suspect_timeout: Duration) -> Vec<String> {
+        let now = Utc::now();
+        let mut expired = Vec::new();
+        for mut entry in self.nodes.iter_mut() {
+            let node = entry.value_mut();
+            let elapsed =
+                now.signed_duration_since(node.last_heartbeat).to_std().unwrap_or_default();
+            if node.status == NodeStatus::Suspect && elapsed >= suspect_timeout {
+                node.status = NodeStatus::Down;
+                node.last_heartbeat = now;
+                warn!("Cluster member {} is now DOWN (suspect timeout)", node.node_id);
+                expired.push(node.node_id.clone());
+            }
+        }
+        expired
+    }
+
+    /// 标记当前节点退出集群, 返回需要传播的成员信息
+    pub fn leave(&self) -> Option<ClusterNode> {
+        let mut local = self.nodes.get_mut(&self.node_id)?;
+        local.status = NodeStatus::Left;
+        Some(local.clone())
+    }
+
     /// 注册新节点
     pub fn register_node(&self, node: ClusterNode) {
         info!("Registering cluster node: {}", node.node_id);
@@ -51,6 +184,16 @@ impl ClusterManager {
         }
     }
 
+    /// 记录节点公布的复制协议
+    pub fn set_protocol(&self, node_id: &str, protocol: ProtocolInfo) {
+        if let Some(mut node) = self.nodes.get_mut(node_id)
+            && node.protocol.as_ref() != Some(&protocol)
+        {
+            info!("Node {} speaks replication protocol {}", node_id, protocol.to_header());
+            node.protocol = Some(protocol);
+        }
+    }
+
     /// 获取所有健康节点
     pub fn get_healthy_nodes(&self) -> Vec<ClusterNode> {
         self.nodes
@@ -60,6 +203,26 @@ impl ClusterManager {
             .collect()
     }
 
+    /// 获取所有对等节点 (排除自己, 包括不健康的节点)
+    pub fn get_peers(&self) -> Vec<ClusterNode> {
+        self.nodes
+            .iter()
+            .filter(|entry| entry.key() != &self.node_id)
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
+    /// 获取下线的对等节点 (gossip 模式下定期尝试联系, 网络分区恢复后重新合并)
+    pub fn get_down_peers(&self) -> Vec<ClusterNode> {
+        self.nodes
+            .iter()
+            .filter(|entry| {
+                entry.key() != &self.node_id && entry.value().status == NodeStatus::Down
+            })
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
     /// 获取健康的对等节点(排除自己)
     pub fn get_healthy_peers(&self) -> Vec<ClusterNode> {
         self.nodes
@@ -117,8 +280,8 @@ impl ClusterManager {
 
                     // 为每个节点启动独立的健康检查任务
                     let task = tokio::spawn(async move {
-                        let is_healthy = check_node_health(&base_url).await;
-                        (node_id, is_healthy)
+                        let (is_healthy, protocol) = check_node_health(&base_url).await;
+                        (node_id, is_healthy, protocol)
                     });
 
                     tasks.push(task);
@@ -126,7 +289,10 @@ impl ClusterManager {
 
                 // 等待所有健康检查完成
                 for task in tasks {
-                    if let Ok((node_id, is_healthy)) = task.await {
+                    if let Ok((node_id, is_healthy, protocol)) = task.await {
+                        if let Some(protocol) = protocol {
+                            self.set_protocol(&node_id, protocol);
+                        }
                         // 更新节点状态
                         if let Some(mut node) = self.nodes.get_mut(&node_id) {
                             let was_healthy = node.is_healthy();
@@ -148,8 +314,10 @@ impl ClusterManager {
     }
 }
 
-/// 检查节点健康状态
-async fn check_node_health(base_url: &str) -> bool {
+/// 检查节点健康状态, 同时交换复制协议
+///
+/// 健康的节点响应中没有协议头时为旧版本节点
+async fn check_node_health(base_url: &str) -> (bool, Option<ProtocolInfo>) {
     let health_url = format!("{}/health", base_url);
 
     // 创建带超时的客户端 (避免长时间阻塞)
@@ -158,11 +326,22 @@ async fn check_node_health(base_url: &str) -> bool {
         .build()
         .unwrap_or_else(|_| reqwest::Client::new());
 
-    match client.get(&health_url).send().await {
-        Ok(response) => response.status().is_success(),
+    let request =
+        client.get(&health_url).header(PROTOCOL_HEADER, ProtocolInfo::local().to_header());
+    match request.send().await {
+        Ok(response) if response.status().is_success() => {
+            let protocol = response
+                .headers()
+                .get(PROTOCOL_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(ProtocolInfo::from_header)
+                .unwrap_or_else(ProtocolInfo::legacy);
+            (true, Some(protocol))
+        }
+        Ok(_) => (false, None),
         Err(e) => {
             tracing::debug!("Health check failed for {}: {}", base_url, e);
-            false
+            (false, None)
         }
     }
 }
@@ -362,9 +541,100 @@ mod tests {
         assert_eq!(healthy[0].node_id, "node-2");
     }
 
+    fn gossip_manager(node_id: &str, port: u16) -> ClusterManager {
+        ClusterManager::new(node_id.to_string(), vec![])
+            .with_local_node(&format!("http://127.0.0.1:{}", port))
+    }
+
+    #[test]
+    fn test_merge_members() {
+        let node1 = gossip_manager("node-1", 8081);
+        let node2 = gossip_manager("node-2", 8082);
+
+        assert_eq!(node2.merge_members(node1.members()), 1);
+        assert_eq!(node2.get_healthy_peers()[0].node_id, "node-1");
+        assert_eq!(node2.get_healthy_peers()[0].base_url(), "http://127.0.0.1:8081");
+
+        // 相同信息再次合并不产生变化
+        assert_eq!(node2.merge_members(node1.members()), 0);
+    }
+
+    #[test]
+    fn test_suspect_refuted_by_member() {
+        let node1 = gossip_manager("node-1", 8081);
+        let node2 = gossip_manager("node-2", 8082);
+        node2.merge_members(node1.members());
+        node1.merge_members(node2.members());
+
+        assert!(node2.mark_suspect("node-1"));
+        assert!(!node2.mark_suspect("node-1"));
+        assert_eq!(node2.get_healthy_peers().len(), 1, "被怀疑的节点仍然健康");
+
+        // node-1 收到怀疑后递增 incarnation 反驳, node-2 合并后恢复 Up
+        let incarnation = node1.local_node().unwrap().incarnation;
+        assert_eq!(node1.merge_members(node2.members()), 1);
+        assert_eq!(node1.local_node().unwrap().incarnation, incarnation + 1);
+        node2.merge_members(node1.members());
+        assert_eq!(node2.get_healthy_peers()[0].status, NodeStatus::Up);
+    }
+
+    #[test]
+    fn test_expire_suspects() {
+        let node1 = gossip_manager("node-1", 8081);
+        let node2 = gossip_manager("node-2", 8082);
+        node2.merge_members(node1.members());
+        node2.mark_suspect("node-1");
+
+        assert!(node2.expire_suspects(Duration::from_secs(60)).is_empty());
+        assert_eq!(node2.expire_suspects(Duration::ZERO), vec!["node-1".to_string()]);
+        assert!(node2.get_healthy_peers().is_empty());
+        assert_eq!(node2.get_down_peers().len(), 1);
+    }
+
+    #[test]
+    fn test_leave_and_rejoin() {
+        let node1 = gossip_manager("node-1", 8081);
+        let node2 = gossip_manager("node-2", 8082);
+        node2.merge_members(node1.members());
+        node1.merge_members(node2.members());
+
+        let left = node1.leave().unwrap();
+        assert_eq!(left.status, NodeStatus::Left);
+        node2.merge_members(vec![left.clone()]);
+        assert!(node2.get_healthy_peers().is_empty());
+
+        // 退出的节点不反驳自己的 Left 状态
+        assert_eq!(node1.merge_members(node2.members()), 0);
+
+        // 重启后 incarnation 更大, 覆盖退出前的状态
+        let mut restarted = left;
+        restarted.status = NodeStatus::Up;
+        restarted.incarnation += 1;
+        node2.merge_members(vec![restarted]);
+        assert_eq!(node2.get_healthy_peers().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_check_node_health_invalid_url() {
-        let result = check_node_health("http://invalid-host-that-does-not-exist:9999").await;
-        assert!(!result);
+        let (healthy, protocol) =
+            check_node_health("http://invalid-host-that-does-not-exist:9999").await;
+        assert!(!healthy);
+        assert!(protocol.is_none());
+    }
+
+    #[test]
+    fn test_merge_keeps_known_protocol() {
+        let node1 = gossip_manager("node-1", 8081);
+        let node2 = gossip_manager("node-2", 8082);
+        node1.merge_members(node2.members());
+        let peer = &node1.get_peers()[0];
+        assert_eq!(peer.protocol, Some(ProtocolInfo::local()));
+
+        // 旧版本节点转发的更新信息不带协议
+        let mut forwarded = peer.clone();
+        forwarded.protocol = None;
+        forwarded.incarnation += 1;
+        assert_eq!(node1.merge_members(vec![forwarded]), 1);
+        assert_eq!(node1.get_peers()[0].protocol, Some(ProtocolInfo::local()));
     }
 }
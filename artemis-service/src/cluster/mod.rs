@@ -4,11 +4,15 @@
 //! - Node discovery and registration
 //! - Health checking
 //! - Cluster state management
-//!
-//! Status: Framework only, full implementation pending
+//! - Dynamic membership with join/leave and SWIM-style gossip
+//! - Replication protocol version and capability negotiation
 
+pub mod gossip;
 pub mod manager;
 pub mod node;
+pub mod protocol;
 
+pub use gossip::{GossipConfig, GossipMembership};
 pub use manager::ClusterManager;
 pub use node::{ClusterNode, NodeStatus};
+pub use protocol::{PROTOCOL_HEADER, PROTOCOL_VERSION, ProtocolInfo};
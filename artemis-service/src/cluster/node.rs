@@ -1,3 +1,4 @@
+use super::protocol::ProtocolInfo;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,23 @@ pub enum NodeStatus {
     Down,
     /// 节点状态未知
     Unknown,
+    /// 直接和间接探测都失败, 等待节点反驳或超时后判定下线
+    Suspect,
+    /// 节点已主动退出集群
+    Left,
+}
+
+impl NodeStatus {
+    /// 同一 incarnation 下状态的优先级, 高优先级覆盖低优先级
+    pub fn precedence(&self) -> u8 {
+        match self {
+            NodeStatus::Unknown => 0,
+            NodeStatus::Up => 1,
+            NodeStatus::Suspect => 2,
+            NodeStatus::Down => 3,
+            NodeStatus::Left => 4,
+        }
+    }
 }
 
 /// 集群节点信息
@@ -25,10 +43,16 @@ pub struct ClusterNode {
     pub port: u16,
     /// 节点状态
     pub status: NodeStatus,
-    /// 最后心跳时间
+    /// 最后心跳时间 (gossip 模式下为状态最后变化的时间)
     pub last_heartbeat: DateTime<Utc>,
     /// 元数据
     pub metadata: Option<std::collections::HashMap<String, String>>,
+    /// 节点自己维护的版本号, 只有节点本身可以递增, 用于反驳怀疑和区分重启前后的状态
+    #[serde(default)]
+    pub incarnation: u64,
+    /// 节点公布的复制协议, None 表示尚未得知 (还没有健康检查或成员信息来自旧版本节点)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<ProtocolInfo>,
 }
 
 impl ClusterNode {
@@ -40,6 +64,8 @@ impl ClusterNode {
             status: NodeStatus::Up,
             last_heartbeat: Utc::now(),
             metadata: None,
+            incarnation: 0,
+            protocol: None,
         }
     }
 
@@ -65,8 +91,18 @@ impl ClusterNode {
         format!("http://{}:{}", self.address, self.port)
     }
 
+    /// 被怀疑的节点在判定下线前仍然视为健康, 避免短暂抖动丢弃复制事件
     pub fn is_healthy(&self) -> bool {
-        matches!(self.status, NodeStatus::Up)
+        matches!(self.status, NodeStatus::Up | NodeStatus::Suspect)
+    }
+
+    /// 按 SWIM 规则判断远端的成员信息是否比本地的新
+    ///
+    /// incarnation 更大的信息更新; incarnation 相同时按状态优先级比较
+    pub fn is_superseded_by(&self, other: &ClusterNode) -> bool {
+        other.incarnation > self.incarnation
+            || (other.incarnation == self.incarnation
+                && other.status.precedence() > self.status.precedence())
     }
 
     pub fn update_heartbeat(&mut self) {
@@ -210,6 +246,39 @@ mod tests {
         assert!(!node.is_healthy());
     }
 
+    #[test]
+    fn test_is_healthy_with_suspect_and_left_status() {
+        let mut node = ClusterNode::new("node-1".to_string(), "localhost".to_string(), 8080);
+        node.status = NodeStatus::Suspect;
+        assert!(node.is_healthy(), "被怀疑的节点在判定下线前仍然健康");
+
+        node.status = NodeStatus::Left;
+        assert!(!node.is_healthy());
+    }
+
+    #[test]
+    fn test_is_superseded_by() {
+        let mut local = ClusterNode::new("node-1".to_string(), "localhost".to_string(), 8080);
+        local.incarnation = 5;
+
+        // 同一 incarnation 下 Suspect 覆盖 Up, Up 不能覆盖 Suspect
+        let mut remote = local.clone();
+        remote.status = NodeStatus::Suspect;
+        assert!(local.is_superseded_by(&remote));
+        assert!(!remote.is_superseded_by(&local));
+
+        // 节点反驳怀疑时递增 incarnation
+        let mut refuted = local.clone();
+        refuted.incarnation = 6;
+        assert!(remote.is_superseded_by(&refuted));
+
+        // 旧 incarnation 的信息被忽略
+        let mut stale = local.clone();
+        stale.incarnation = 4;
+        stale.status = NodeStatus::Down;
+        assert!(!local.is_superseded_by(&stale));
+    }
+
     // ===== 心跳更新测试 =====
 
     #[test]
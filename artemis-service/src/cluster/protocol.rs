@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// 当前节点的复制协议版本
+///
+/// - 1: 单事件复制端点 (`/api/replication/registry/{register,heartbeat,unregister}.json`)
+/// - 2: 增加批量复制端点、修订号和复制流 (由能力标记区分)
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// 开始在复制请求中携带修订号的协议版本
+pub const REVISIONS_SINCE_VERSION: u32 = 2;
+
+/// 健康检查响应中携带协议版本和能力的头, 格式为 `2;batch,stream`
+pub const PROTOCOL_HEADER: &str = "X-Artemis-Protocol";
+
+/// 批量复制端点 (`batch-register.json` / `batch-unregister.json`)
+pub const CAPABILITY_BATCH: &str = "batch";
+
+/// 复制流端点 (`/api/replication/stream`)
+pub const CAPABILITY_STREAM: &str = "stream";
+
+/// 节点支持的复制协议版本和能力
+///
+/// 节点在 gossip 成员信息 (加入集群时交换) 和健康检查响应头中公布自己的协议, 复制时与对等节点
+/// 协商: 取双方都支持的最高版本和共同的能力。没有公布协议的旧版本节点视为版本 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolInfo {
+    pub version: u32,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+impl ProtocolInfo {
+    /// 当前节点的协议
+    pub fn local() -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            capabilities: vec![CAPABILITY_BATCH.to_string(), CAPABILITY_STREAM.to_string()],
+        }
+    }
+
+    /// 没有公布协议的旧版本节点, 只使用单事件端点
+    pub fn legacy() -> Self {
+        Self { version: 1, capabilities: Vec::new() }
+    }
+
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    /// 复制请求是否携带修订号, 版本 1 的节点不认识修订号字段
+    pub fn supports_revisions(&self) -> bool {
+        self.version >= REVISIONS_SINCE_VERSION
+    }
+
+    /// 与对等节点协商: 双方都支持的最高版本和共同的能力
+    pub fn negotiate(&self, remote: &ProtocolInfo) -> ProtocolInfo {
+        Self {
+            version: self.version.min(remote.version),
+            capabilities: self
+                .capabilities
+                .iter()
+                .filter(|capability| remote.supports(capability))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// 编码为协议头的值
+    pub fn to_header(&self) -> String {
+        format!("{};{}", self.version, self.capabilities.join(","))
+    }
+
+    /// 解析协议头, 格式错误时返回 None
+    pub fn from_header(value: &str) -> Option<Self> {
+        let (version, capabilities) = value.split_once(';').unwrap_or((value, ""));
+        Some(Self {
+            version: version.trim().parse().ok()?,
+            capabilities: capabilities
+                .split(',')
+                .map(str::trim)
+                .filter(|capability| !capability.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        let local = ProtocolInfo::local();
+        assert_eq!(local.negotiate(&local), local);
+
+        let negotiated = local.negotiate(&ProtocolInfo::legacy());
+        assert_eq!(negotiated.version, 1);
+        assert!(!negotiated.supports(CAPABILITY_BATCH));
+        assert!(!negotiated.supports_revisions());
+
+        // 更新的节点: 使用本节点的版本, 忽略本节点不认识的能力
+        let newer = ProtocolInfo {
+            version: 3,
+            capabilities: vec![CAPABILITY_BATCH.to_string(), "compression".to_string()],
+        };
+        let negotiated = local.negotiate(&newer);
+        assert_eq!(negotiated.version, PROTOCOL_VERSION);
+        assert!(negotiated.supports_revisions());
+        assert_eq!(negotiated.capabilities, vec![CAPABILITY_BATCH.to_string()]);
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let local = ProtocolInfo::local();
+        assert_eq!(local.to_header(), "2;batch,stream");
+        assert_eq!(ProtocolInfo::from_header(&local.to_header()), Some(local));
+        assert_eq!(ProtocolInfo::from_header("1"), Some(ProtocolInfo::legacy()));
+        assert_eq!(ProtocolInfo::from_header("1;"), Some(ProtocolInfo::legacy()));
+        assert!(ProtocolInfo::from_header("abc;batch").is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use super::filter::{DiscoveryFilterChain, StatusFilter};
 use crate::cache::VersionedCacheManager;
+use crate::federation::FederationManager;
 use crate::registry::RegistryRepository;
 use crate::traits::DiscoveryService;
 use artemis_common::model::{
@@ -14,6 +15,7 @@ pub struct DiscoveryServiceImpl {
     repository: RegistryRepository,
     cache: Arc<VersionedCacheManager>,
     filter_chain: DiscoveryFilterChain,
+    federation: Option<Arc<FederationManager>>,
 }
 
 impl DiscoveryServiceImpl {
@@ -21,7 +23,13 @@ impl DiscoveryServiceImpl {
         let mut filter_chain = DiscoveryFilterChain::new();
         filter_chain.add_filter(Arc::new(StatusFilter));
 
-        Self { repository, cache, filter_chain }
+        Self { repository, cache, filter_chain, federation: None }
+    }
+
+    /// 本地没有可用实例时故障转移到远程区域
+    pub fn with_federation(mut self, federation: Option<Arc<FederationManager>>) -> Self {
+        self.federation = federation;
+        self
     }
 
     /// 添加过滤器到过滤链
@@ -43,6 +51,25 @@ impl DiscoveryServiceImpl {
         }
     }
 
+    /// 远程区域的实例 (只读, 元数据中标记了所属区域)
+    fn failover(&self, service_id: &str) -> Option<Service> {
+        let instances = self.federation.as_ref()?.failover_instances(service_id);
+        if instances.is_empty() {
+            return None;
+        }
+        tracing::debug!(
+            "No local instances of {}, failing over to {} remote instances",
+            service_id,
+            instances.len()
+        );
+        Some(Service {
+            service_id: service_id.to_string(),
+            metadata: None,
+            instances,
+            logic_instances: None,
+        })
+    }
+
     pub fn refresh_cache(&self) {
         let all_instances = self.repository.get_all_instances();
 
@@ -63,31 +90,42 @@ impl DiscoveryService for DiscoveryServiceImpl {
     async fn get_service(&self, request: GetServiceRequest) -> GetServiceResponse {
         let service_id = request.discovery_config.service_id.to_lowercase();
 
-        if let Some(mut service) = self.cache.get_service(&service_id) {
-            if let Err(e) = self.filter_chain.apply(&mut service, &request.discovery_config).await {
-                tracing::warn!("Filter failed: {}", e);
-            }
-            return GetServiceResponse {
-                response_status: ResponseStatus::success(),
-                service: Some(service),
-            };
-        }
-
-        match self.build_service(&service_id) {
+        let local = match self.cache.get_service(&service_id) {
             Some(mut service) => {
                 if let Err(e) =
                     self.filter_chain.apply(&mut service, &request.discovery_config).await
                 {
                     tracing::warn!("Filter failed: {}", e);
                 }
-                // 先更新缓存，避免在响应中克隆
-                let service_for_cache = service.clone();
-                self.cache.update_service(service_for_cache);
-                GetServiceResponse {
-                    response_status: ResponseStatus::success(),
-                    service: Some(service),
-                }
+                Some(service)
             }
+            None => match self.build_service(&service_id) {
+                Some(mut service) => {
+                    if let Err(e) =
+                        self.filter_chain.apply(&mut service, &request.discovery_config).await
+                    {
+                        tracing::warn!("Filter failed: {}", e);
+                    }
+                    // 先更新缓存，避免在响应中克隆
+                    let service_for_cache = service.clone();
+                    self.cache.update_service(service_for_cache);
+                    Some(service)
+                }
+                None => None,
+            },
+        };
+
+        // 本地没有可用实例时故障转移到远程区域
+        let service = match local {
+            Some(service) if !service.instances.is_empty() => Some(service),
+            local => self.failover(&service_id).or(local),
+        };
+
+        match service {
+            Some(service) => GetServiceResponse {
+                response_status: ResponseStatus::success(),
+                service: Some(service),
+            },
             None => GetServiceResponse {
                 response_status: ResponseStatus::error(
                     ErrorCode::BadRequest,
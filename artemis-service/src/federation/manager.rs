@@ -0,0 +1,272 @@
+use super::REMOTE_REGION_METADATA;
+use crate::config::FederationConfig;
+use crate::model::RemoteRegionStatus;
+use crate::registry::RegistryRepository;
+use crate::replication::anti_entropy::service_digest;
+use crate::security::wildcard_match;
+use artemis_common::model::{FederationExportResponse, Instance, InstanceStatus, ResponseStatus};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 从单个远程区域同步到的实例视图
+#[derive(Debug, Default)]
+struct RemoteRegion {
+    /// 服务 ID (小写) -> 实例, 已标记为远程实例
+    services: HashMap<String, Vec<Instance>>,
+    instance_count: usize,
+    /// 最近一次拉取到的视图摘要
+    digest: Option<String>,
+    last_success: Option<Instant>,
+    last_success_time: Option<i64>,
+    consecutive_failures: u64,
+    last_error: Option<String>,
+}
+
+/// 跨区域联邦管理器
+///
+/// 本区域只导出自己注册的实例, 远程区域的实例单独保存, 不进入本地注册表, 因此不会被复制、
+/// 修改或再次导出。超过 `stale_after_secs` 没有同步成功的区域不参与故障转移
+pub struct FederationManager {
+    local_region: String,
+    repository: RegistryRepository,
+    export_services: Vec<String>,
+    stale_after: Duration,
+    /// 按配置顺序排列的远程区域, 故障转移时优先使用靠前的区域
+    remotes: Vec<String>,
+    regions: RwLock<HashMap<String, RemoteRegion>>,
+}
+
+impl FederationManager {
+    pub fn new(
+        local_region: String,
+        repository: RegistryRepository,
+        config: &FederationConfig,
+    ) -> Self {
+        Self {
+            local_region,
+            repository,
+            export_services: config
+                .export_services
+                .iter()
+                .map(|pattern| pattern.to_lowercase())
+                .collect(),
+            stale_after: Duration::from_secs(config.stale_after_secs),
+            remotes: config.remotes.iter().map(|remote| remote.region.clone()).collect(),
+            regions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn local_region(&self) -> &str {
+        &self.local_region
+    }
+
+    fn is_exported(&self, service_id: &str) -> bool {
+        let service_id = service_id.to_lowercase();
+        self.export_services.is_empty()
+            || self.export_services.iter().any(|pattern| wildcard_match(pattern, &service_id))
+    }
+
+    /// 导出给其他区域的实例视图, 与拉取方已有的摘要相同时不返回实例
+    pub fn export(&self, known_digest: Option<&str>) -> FederationExportResponse {
+        let instances: Vec<Instance> = self
+            .repository
+            .get_all_instances()
+            .into_iter()
+            .filter(|instance| instance.status == InstanceStatus::Up)
+            .filter(|instance| self.is_exported(&instance.service_id))
+            .collect();
+        let digest = service_digest(&instances);
+        let unchanged = known_digest == Some(digest.as_str());
+
+        FederationExportResponse {
+            response_status: ResponseStatus::success(),
+            region_id: self.local_region.clone(),
+            instances: if unchanged { Vec::new() } else { instances },
+            digest,
+            unchanged,
+        }
+    }
+
+    /// 最近一次从远程区域拉取到的视图摘要
+    pub fn digest(&self, region: &str) -> Option<String> {
+        self.regions.read().get(region).and_then(|remote| remote.digest.clone())
+    }
+
+    /// 应用从远程区域拉取到的视图
+    pub fn apply_export(&self, region: &str, response: FederationExportResponse) {
+        let mut regions = self.regions.write();
+        let remote = regions.entry(region.to_string()).or_default();
+        remote.last_success = Some(Instant::now());
+        remote.last_success_time = Some(chrono::Utc::now().timestamp_millis());
+        remote.consecutive_failures = 0;
+        remote.last_error = None;
+        if response.unchanged {
+            return;
+        }
+
+        let mut services: HashMap<String, Vec<Instance>> = HashMap::new();
+        for mut instance in response.instances {
+            instance
+                .metadata
+                .get_or_insert_with(HashMap::new)
+                .insert(REMOTE_REGION_METADATA.to_string(), region.to_string());
+            services.entry(instance.service_id.to_lowercase()).or_default().push(instance);
+        }
+        remote.instance_count = services.values().map(Vec::len).sum();
+        remote.services = services;
+        remote.digest = Some(response.digest);
+    }
+
+    /// 记录拉取失败, 保留上一次的视图直到过期
+    pub fn record_failure(&self, region: &str, error: String) {
+        let mut regions = self.regions.write();
+        let remote = regions.entry(region.to_string()).or_default();
+        remote.consecutive_failures += 1;
+        remote.last_error = Some(error);
+    }
+
+    fn is_fresh(&self, remote: &RemoteRegion) -> bool {
+        remote.last_success.is_some_and(|last| last.elapsed() < self.stale_after)
+    }
+
+    /// 故障转移: 按配置顺序返回第一个有该服务实例且未过期的远程区域的实例
+    pub fn failover_instances(&self, service_id: &str) -> Vec<Instance> {
+        let service_id = service_id.to_lowercase();
+        let regions = self.regions.read();
+        self.remotes
+            .iter()
+            .filter_map(|region| regions.get(region))
+            .filter(|remote| self.is_fresh(remote))
+            .find_map(|remote| remote.services.get(&service_id).filter(|i| !i.is_empty()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 各远程区域的同步状态 (按配置顺序)
+    pub fn status(&self) -> Vec<RemoteRegionStatus> {
+        let regions = self.regions.read();
+        self.remotes
+            .iter()
+            .map(|region| {
+                let remote = regions.get(region);
+                RemoteRegionStatus {
+                    region: region.clone(),
+                    fresh: remote.is_some_and(|remote| self.is_fresh(remote)),
+                    service_count: remote.map_or(0, |remote| remote.services.len()),
+                    instance_count: remote.map_or(0, |remote| remote.instance_count),
+                    last_success_time: remote.and_then(|remote| remote.last_success_time),
+                    consecutive_failures: remote.map_or(0, |remote| remote.consecutive_failures),
+                    last_error: remote.and_then(|remote| remote.last_error.clone()),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RemoteRegionConfig;
+
+    fn create_instance(region: &str, service_id: &str, instance_id: &str) -> Instance {
+        Instance {
+            region_id: region.to_string(),
+            zone_id: "zone".to_string(),
+            group_id: None,
+            service_id: service_id.to_string(),
+            instance_id: instance_id.to_string(),
+            machine_name: None,
+            ip: "127.0.0.1".to_string(),
+            port: 8080,
+            protocol: None,
+            url: "http://127.0.0.1:8080".to_string(),
+            health_check_url: None,
+            status: InstanceStatus::Up,
+            metadata: None,
+        }
+    }
+
+    fn config(export_services: Vec<String>) -> FederationConfig {
+        FederationConfig {
+            enabled: true,
+            export_services,
+            remotes: ["eu", "us"]
+                .iter()
+                .map(|region| RemoteRegionConfig { region: region.to_string(), urls: vec![] })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_export_filters_services_and_status() {
+        let repository = RegistryRepository::new();
+        repository.register(create_instance("ap", "order-service", "inst-1"));
+        repository.register(create_instance("ap", "user-service", "inst-2"));
+        let mut down = create_instance("ap", "order-service", "inst-3");
+        down.status = InstanceStatus::Down;
+        repository.register(down);
+
+        let manager =
+            FederationManager::new("ap".to_string(), repository, &config(vec!["Order-*".into()]));
+        let export = manager.export(None);
+        assert_eq!(export.region_id, "ap");
+        assert_eq!(export.instances.len(), 1);
+        assert_eq!(export.instances[0].instance_id, "inst-1");
+
+        // 摘要未变化时不返回实例
+        let unchanged = manager.export(Some(&export.digest));
+        assert!(unchanged.unchanged);
+        assert!(unchanged.instances.is_empty());
+    }
+
+    #[test]
+    fn test_failover_prefers_configured_order() {
+        let manager =
+            FederationManager::new("ap".to_string(), RegistryRepository::new(), &config(vec![]));
+        let export = |region: &str, instance_id: &str| FederationExportResponse {
+            response_status: ResponseStatus::success(),
+            region_id: region.to_string(),
+            instances: vec![create_instance(region, "order-service", instance_id)],
+            digest: instance_id.to_string(),
+            unchanged: false,
+        };
+
+        manager.apply_export("us", export("us", "us-1"));
+        let instances = manager.failover_instances("ORDER-SERVICE");
+        assert_eq!(instances[0].instance_id, "us-1");
+        let metadata = instances[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata.get(REMOTE_REGION_METADATA).unwrap(), "us");
+
+        manager.apply_export("eu", export("eu", "eu-1"));
+        assert_eq!(manager.failover_instances("order-service")[0].instance_id, "eu-1");
+        assert!(manager.failover_instances("user-service").is_empty());
+
+        // 拉取失败时保留上一次的视图
+        manager.record_failure("eu", "timeout".to_string());
+        assert_eq!(manager.failover_instances("order-service")[0].instance_id, "eu-1");
+        let status = manager.status();
+        assert_eq!(status[0].consecutive_failures, 1);
+        assert_eq!(status[0].instance_count, 1);
+        assert!(status[0].fresh);
+    }
+
+    #[test]
+    fn test_stale_region_not_used() {
+        let config = FederationConfig { stale_after_secs: 0, ..config(vec![]) };
+        let manager = FederationManager::new("ap".to_string(), RegistryRepository::new(), &config);
+        manager.apply_export(
+            "eu",
+            FederationExportResponse {
+                response_status: ResponseStatus::success(),
+                region_id: "eu".to_string(),
+                instances: vec![create_instance("eu", "order-service", "eu-1")],
+                digest: String::new(),
+                unchanged: false,
+            },
+        );
+        assert!(manager.failover_instances("order-service").is_empty());
+        assert!(!manager.status()[0].fresh);
+    }
+}
@@ -0,0 +1,18 @@
+//! Multi-region federation
+//!
+//! This module provides:
+//! - Export of a filtered, read-only view of the local region's instances
+//! - Periodic pull of other regions' views over a WAN-tolerant link
+//! - Cross-region discovery failover when a service has no local instances
+
+pub mod manager;
+pub mod sync;
+
+pub use manager::FederationManager;
+pub use sync::FederationSync;
+
+/// 远程实例的元数据标记, 值为实例所属的远程区域
+pub const REMOTE_REGION_METADATA: &str = "artemis.remoteRegion";
+
+/// 联邦导出端点
+pub const EXPORT_PATH: &str = "/api/federation/export.json";
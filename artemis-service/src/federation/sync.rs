@@ -0,0 +1,101 @@
+use super::manager::FederationManager;
+use crate::config::RemoteRegionConfig;
+use crate::replication::{ReplicationClient, ReplicationError, ReplicationErrorKind};
+use crate::security::RequestSigner;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 跨区域联邦同步任务
+///
+/// 定期从每个远程区域拉取导出的实例视图。跨区域链路不可靠: 每次拉取依次尝试远程区域配置的
+/// 各个地址, 全部失败时保留上一次的视图直到过期; 视图摘要未变化时远程区域不返回实例
+pub struct FederationSync {
+    manager: Arc<FederationManager>,
+    remotes: Vec<RemoteRegionConfig>,
+    client: ReplicationClient,
+    interval: Duration,
+}
+
+impl FederationSync {
+    pub fn new(
+        manager: Arc<FederationManager>,
+        remotes: Vec<RemoteRegionConfig>,
+        interval: Duration,
+        request_timeout: Duration,
+    ) -> Self {
+        Self { manager, remotes, client: ReplicationClient::new(request_timeout), interval }
+    }
+
+    /// 使用联邦共享密钥对拉取请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
+    /// 启动后台任务, 启动时立即同步一次
+    pub fn start(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                "Federation sync started with {} remote regions (interval: {:?})",
+                self.remotes.len(),
+                self.interval
+            );
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                self.run_once().await;
+            }
+        })
+    }
+
+    /// 从所有远程区域各拉取一次
+    pub async fn run_once(&self) {
+        for remote in &self.remotes {
+            if let Err(e) = self.sync_region(remote).await {
+                warn!("Federation sync with region {} failed: {}", remote.region, e);
+                self.manager.record_failure(&remote.region, e.to_string());
+            }
+        }
+    }
+
+    /// 依次尝试远程区域的各个地址, 第一个成功的结果生效
+    async fn sync_region(&self, remote: &RemoteRegionConfig) -> Result<(), ReplicationError> {
+        let digest = self.manager.digest(&remote.region);
+        let mut last_error = None;
+
+        for url in &remote.urls {
+            match self.client.fetch_federation_export(url, digest.as_deref()).await {
+                Ok(response) if response.region_id != remote.region => {
+                    warn!(
+                        "Federation endpoint {} belongs to region {}, expected {}",
+                        url, response.region_id, remote.region
+                    );
+                }
+                Ok(response) => {
+                    debug!(
+                        "Federation sync with region {} via {}: {} instances{}",
+                        remote.region,
+                        url,
+                        response.instances.len(),
+                        if response.unchanged { " (unchanged)" } else { "" }
+                    );
+                    self.manager.apply_export(&remote.region, response);
+                    return Ok(());
+                }
+                Err(e) => {
+                    debug!("Federation endpoint {} unavailable: {}", url, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ReplicationError::new(
+                ReplicationErrorKind::ServiceUnavailable,
+                format!("No reachable endpoint for region {}", remote.region),
+            )
+        }))
+    }
+}
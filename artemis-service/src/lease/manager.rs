@@ -5,8 +5,10 @@ use crate::registry::repository::RegistryRepository;
 use crate::replication::ReplicationManager;
 use artemis_common::model::{InstanceKey, Service};
 use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time;
 use tracing::info;
 
@@ -14,12 +16,21 @@ use tracing::info;
 #[derive(Clone)]
 pub struct LeaseManager {
     leases: Arc<DashMap<InstanceKey, Arc<Lease>>>,
+    /// 最近的驱逐记录 (按时间排序), 供告警规则统计驱逐比例
+    evictions: Arc<Mutex<VecDeque<(Instant, InstanceKey)>>>,
     ttl: Duration,
 }
 
+/// 驱逐记录保留时长
+const EVICTION_HISTORY_RETENTION: Duration = Duration::from_secs(3600);
+
 impl LeaseManager {
     pub fn new(ttl: Duration) -> Self {
-        Self { leases: Arc::new(DashMap::new()), ttl }
+        Self {
+            leases: Arc::new(DashMap::new()),
+            evictions: Arc::new(Mutex::new(VecDeque::new())),
+            ttl,
+        }
     }
 
     /// 创建租约
@@ -29,6 +40,18 @@ impl LeaseManager {
         lease
     }
 
+    /// 创建最近一次续约发生在 age 之前的租约
+    pub fn create_lease_renewed_before(&self, key: InstanceKey, age: Duration) -> Arc<Lease> {
+        let lease = Arc::new(Lease::renewed_before(key.clone(), self.ttl, age));
+        self.leases.insert(key, lease.clone());
+        lease
+    }
+
+    /// 距最近一次续约的时长
+    pub fn lease_age(&self, key: &InstanceKey) -> Option<Duration> {
+        self.leases.get(key).map(|lease| lease.renewal_time().elapsed())
+    }
+
     /// 续约
     pub fn renew(&self, key: &InstanceKey) -> bool {
         if let Some(lease) = self.leases.get(key) {
@@ -58,6 +81,31 @@ impl LeaseManager {
             .collect()
     }
 
+    /// 记录一次租约驱逐
+    pub fn record_eviction(&self, key: InstanceKey) {
+        let now = Instant::now();
+        let mut evictions = self.evictions.lock();
+        evictions.push_back((now, key));
+        while let Some((time, _)) = evictions.front() {
+            if now.duration_since(*time) > EVICTION_HISTORY_RETENTION {
+                evictions.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 获取指定时间窗口内被驱逐的实例 (最多保留 1 小时)
+    pub fn evictions_within(&self, window: Duration) -> Vec<InstanceKey> {
+        let now = Instant::now();
+        self.evictions
+            .lock()
+            .iter()
+            .filter(|(time, _)| now.duration_since(*time) <= window)
+            .map(|(_, key)| key.clone())
+            .collect()
+    }
+
     /// 启动后台清理任务
     pub fn start_eviction_task(
         self: Arc<Self>,
@@ -71,13 +119,17 @@ impl LeaseManager {
             let mut interval = time::interval(cleanup_interval);
             loop {
                 interval.tick().await;
+                repository.revisions().purge_expired_tombstones();
                 let expired_keys = self.get_expired_keys();
 
                 if !expired_keys.is_empty() {
                     info!("Evicting {} expired leases", expired_keys.len());
                     for key in expired_keys {
                         // 从租约管理器移除
-                        self.remove_lease(&key);
+                        if let Some(lease) = self.remove_lease(&key) {
+                            lease.mark_evicted();
+                        }
+                        self.record_eviction(key.clone());
 
                         let service_id = key.service_id.clone();
 
@@ -100,9 +152,10 @@ impl LeaseManager {
                             // 发布变更事件
                             change_manager.publish_unregister(&key, &instance);
 
-                            // 触发复制
+                            // 留下删除标记并触发复制
+                            let revision = repository.revisions().record_local(&key, true);
                             if let Some(ref repl_mgr) = replication_manager {
-                                repl_mgr.publish_unregister(key.clone());
+                                repl_mgr.publish_unregister(key.clone(), revision);
                             }
                         }
                     }
@@ -147,6 +200,20 @@ mod tests {
         assert!(manager.renew(&key));
     }
 
+    #[test]
+    fn test_create_lease_renewed_before() {
+        let manager = LeaseManager::new(Duration::from_secs(30));
+        let fresh = create_test_key("inst-1");
+        let stale = create_test_key("inst-2");
+
+        manager.create_lease_renewed_before(fresh.clone(), Duration::from_secs(10));
+        manager.create_lease_renewed_before(stale.clone(), Duration::from_secs(40));
+        assert!(manager.is_valid(&fresh));
+        assert!(manager.lease_age(&fresh).unwrap() >= Duration::from_secs(10));
+        assert!(!manager.is_valid(&stale));
+        assert!(manager.lease_age(&create_test_key("inst-3")).is_none());
+    }
+
     #[test]
     fn test_remove_lease() {
         let manager = LeaseManager::new(Duration::from_secs(30));
@@ -157,6 +224,22 @@ mod tests {
         assert!(!manager.is_valid(&key));
     }
 
+    #[test]
+    fn test_evictions_within_window() {
+        let manager = LeaseManager::new(Duration::from_secs(30));
+
+        manager.record_eviction(create_test_key("inst-1"));
+        manager.record_eviction(create_test_key("inst-2"));
+        std::thread::sleep(Duration::from_millis(50));
+        manager.record_eviction(create_test_key("inst-3"));
+
+        assert_eq!(manager.evictions_within(Duration::from_secs(60)).len(), 3);
+        assert_eq!(
+            manager.evictions_within(Duration::from_millis(25)),
+            vec![create_test_key("inst-3")]
+        );
+    }
+
     #[tokio::test]
     async fn test_lease_expiration() {
         let manager = LeaseManager::new(Duration::from_millis(100));
@@ -1,15 +1,18 @@
 //! Artemis Server - 业务逻辑实现
 
+pub mod alert;
 pub mod cache;
 pub mod change;
 pub mod cluster;
 pub mod config;
 pub mod discovery;
+pub mod federation;
 pub mod lease;
 pub mod model;
 pub mod ratelimiter;
 pub mod registry;
 pub mod replication;
+pub mod security;
 pub mod status;
 pub mod storage;
 pub mod telemetry;
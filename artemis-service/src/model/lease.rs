@@ -25,6 +25,15 @@ impl Lease {
         }
     }
 
+    /// 创建最近一次续约发生在 age 之前的租约 (从对等节点修复实例时保留其剩余有效期)
+    pub fn renewed_before(key: InstanceKey, ttl: Duration, age: Duration) -> Self {
+        let lease = Self::new(key, ttl);
+        if let Some(renewal_time) = lease.creation_time.checked_sub(age) {
+            *lease.renewal_time.lock() = renewal_time;
+        }
+        lease
+    }
+
     pub fn renew(&self) {
         *self.renewal_time.lock() = Instant::now();
     }
@@ -1,3 +1,4 @@
+use crate::cluster::ProtocolInfo;
 use artemis_common::model::ResponseStatus;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -49,6 +50,72 @@ pub struct GetClusterStatusResponse {
     pub response_status: ResponseStatus,
 }
 
+// ==================== Replication Status ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplicationStatusResponse {
+    pub enabled: bool,
+    pub peers: Vec<PeerReplicationStatus>,
+    pub response_status: ResponseStatus,
+}
+
+/// 单个对等节点的复制状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerReplicationStatus {
+    pub node_id: String,
+    pub url: String,
+    pub healthy: bool,
+    /// 批处理缓冲区中等待发送的事件数 (发送给所有健康节点)
+    pub pending_registers: usize,
+    pub pending_heartbeats: usize,
+    pub pending_unregisters: usize,
+    /// 等待重试的事件数
+    pub retry_queue_depth: usize,
+    /// 发件箱中等待节点恢复后重放的事件数
+    pub outbox_depth: usize,
+    /// 发件箱溢出, 节点恢复后改为全量同步
+    pub full_sync_pending: bool,
+    /// 最近一次复制成功的时间 (毫秒时间戳)
+    pub last_success_time: Option<i64>,
+    /// 连续失败次数, 成功后清零
+    pub consecutive_failures: u64,
+    /// 因永久错误、重试耗尽或节点退出而丢弃的事件数
+    pub dropped_events: u64,
+    /// 估算的复制延迟: 最早一个尚未送达该节点的事件已等待的时间
+    pub lag_ms: u64,
+    /// 与该节点协商的复制协议, 尚未得知节点的协议时为空
+    pub protocol: Option<ProtocolInfo>,
+}
+
+// ==================== Federation Status ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFederationStatusResponse {
+    pub enabled: bool,
+    pub local_region: Option<String>,
+    pub regions: Vec<RemoteRegionStatus>,
+    pub response_status: ResponseStatus,
+}
+
+/// 单个远程区域的同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteRegionStatus {
+    pub region: String,
+    /// 最近一次同步在 stale_after_secs 之内, 可用于故障转移
+    pub fresh: bool,
+    pub service_count: usize,
+    pub instance_count: usize,
+    /// 最近一次同步成功的时间 (毫秒时间戳)
+    pub last_success_time: Option<i64>,
+    /// 连续失败次数, 成功后清零
+    pub consecutive_failures: u64,
+    pub last_error: Option<String>,
+}
+
 // ==================== Leases Status ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,5 +1,7 @@
 pub mod repository;
+pub mod revision;
 pub mod service_impl;
 
 pub use repository::RegistryRepository;
+pub use revision::RevisionTracker;
 pub use service_impl::RegistryServiceImpl;
@@ -1,18 +1,40 @@
+use super::revision::{DEFAULT_TOMBSTONE_WINDOW, RevisionTracker};
 use artemis_common::model::{Instance, InstanceKey, Service};
 use dashmap::DashMap;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// 内存中的注册表存储（高性能无锁）
 #[derive(Clone)]
 pub struct RegistryRepository {
     /// Instance存储: InstanceKey -> Instance
     instances: Arc<DashMap<InstanceKey, Instance>>,
+    /// 实例修订号和删除标记 (复制冲突处理)
+    revisions: Arc<RevisionTracker>,
 }
 
 impl RegistryRepository {
     pub fn new() -> Self {
-        Self { instances: Arc::new(DashMap::new()) }
+        Self {
+            instances: Arc::new(DashMap::new()),
+            revisions: Arc::new(RevisionTracker::new(String::new(), DEFAULT_TOMBSTONE_WINDOW)),
+        }
+    }
+
+    /// 设置分配修订号的节点 ID 和删除标记保留时间
+    pub fn with_revisions(
+        mut self,
+        node_id: impl Into<String>,
+        tombstone_window: Duration,
+    ) -> Self {
+        self.revisions = Arc::new(RevisionTracker::new(node_id, tombstone_window));
+        self
+    }
+
+    /// 修订号跟踪
+    pub fn revisions(&self) -> &RevisionTracker {
+        &self.revisions
     }
 
     /// 注册实例
@@ -0,0 +1,207 @@
+use artemis_common::model::{InstanceKey, Revision};
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 默认的删除标记保留时间
+pub const DEFAULT_TOMBSTONE_WINDOW: Duration = Duration::from_secs(300);
+
+/// 实例的最新修订号
+#[derive(Debug, Clone)]
+struct Tracked {
+    revision: Revision,
+    /// 注销时间, 不为空表示这是删除标记
+    deleted_at: Option<Instant>,
+}
+
+/// 修订号跟踪
+///
+/// 本地变更使用混合逻辑时钟分配修订号: 不小于当前毫秒时间戳, 也不小于见过的任何修订号,
+/// 节点重启后分配的修订号仍然比重启前的大。复制来的变更只有比本地修订号新才应用,
+/// 注销留下删除标记, 窗口内迟到的旧注册不会让实例复活
+#[derive(Debug)]
+pub struct RevisionTracker {
+    node_id: String,
+    clock: AtomicU64,
+    revisions: DashMap<InstanceKey, Tracked>,
+    tombstone_window: Duration,
+}
+
+impl RevisionTracker {
+    pub fn new(node_id: impl Into<String>, tombstone_window: Duration) -> Self {
+        Self {
+            node_id: node_id.into(),
+            clock: AtomicU64::new(0),
+            revisions: DashMap::new(),
+            tombstone_window,
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// 分配本地修订号
+    fn next(&self) -> Revision {
+        let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        let previous = self
+            .clock
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |clock| Some((clock + 1).max(now)))
+            .unwrap_or_default();
+        Revision { clock: (previous + 1).max(now), node_id: self.node_id.clone() }
+    }
+
+    fn is_live(&self, tracked: &Tracked) -> bool {
+        tracked.deleted_at.is_none_or(|deleted_at| deleted_at.elapsed() < self.tombstone_window)
+    }
+
+    /// 记录本地变更, 返回分配的修订号
+    pub fn record_local(&self, key: &InstanceKey, deleted: bool) -> Revision {
+        let revision = self.next();
+        let deleted_at = deleted.then(Instant::now);
+        self.revisions.insert(key.clone(), Tracked { revision: revision.clone(), deleted_at });
+        revision
+    }
+
+    /// 复制来的变更比本地修订号新时执行并记录 (注销时留下删除标记), 过期则返回 None
+    ///
+    /// 判断和执行期间持有该实例的锁, 同一实例的并发复制请求按修订号顺序生效。
+    /// 来源未知的变更 (默认修订号) 无法比较先后, 直接执行且不记录, 但注册不会覆盖删除标记
+    pub fn apply_if_newer<T>(
+        &self,
+        key: &InstanceKey,
+        revision: &Revision,
+        deleted: bool,
+        apply: impl FnOnce() -> T,
+    ) -> Option<T> {
+        if *revision == Revision::default() {
+            return (deleted || !self.is_deleted(key)).then(apply);
+        }
+
+        // 推进本地时钟, 之后的本地变更排在已见过的变更之后
+        self.clock.fetch_max(revision.clock, Ordering::SeqCst);
+
+        let deleted_at = deleted.then(Instant::now);
+        let tracked = Tracked { revision: revision.clone(), deleted_at };
+        match self.revisions.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                if self.is_live(entry.get()) && *revision <= entry.get().revision {
+                    return None;
+                }
+                let result = apply();
+                entry.insert(tracked);
+                Some(result)
+            }
+            Entry::Vacant(entry) => {
+                let result = apply();
+                entry.insert(tracked);
+                Some(result)
+            }
+        }
+    }
+
+    /// 实例当前的修订号 (不包括删除标记)
+    pub fn current(&self, key: &InstanceKey) -> Option<Revision> {
+        self.revisions
+            .get(key)
+            .filter(|tracked| tracked.deleted_at.is_none())
+            .map(|tracked| tracked.revision.clone())
+    }
+
+    /// 实例是否在窗口内被注销 (启动同步和反熵修复不应让它复活)
+    pub fn is_deleted(&self, key: &InstanceKey) -> bool {
+        self.revisions
+            .get(key)
+            .is_some_and(|tracked| tracked.deleted_at.is_some() && self.is_live(&tracked))
+    }
+
+    /// 清理超过保留时间的删除标记, 返回清理的数量
+    pub fn purge_expired_tombstones(&self) -> usize {
+        let before = self.revisions.len();
+        self.revisions.retain(|_, tracked| self.is_live(tracked));
+        before - self.revisions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> InstanceKey {
+        InstanceKey {
+            region_id: "test".to_string(),
+            zone_id: "zone".to_string(),
+            service_id: "service".to_string(),
+            group_id: String::new(),
+            instance_id: "inst-1".to_string(),
+        }
+    }
+
+    fn revision(clock: u64, node_id: &str) -> Revision {
+        Revision { clock, node_id: node_id.to_string() }
+    }
+
+    #[test]
+    fn test_local_revisions_are_monotonic() {
+        let tracker = RevisionTracker::new("node-1", DEFAULT_TOMBSTONE_WINDOW);
+        let first = tracker.record_local(&key(), false);
+        let second = tracker.record_local(&key(), true);
+        assert!(second > first);
+        assert_eq!(second.node_id, "node-1");
+
+        // 见过更大的修订号后, 本地修订号排在它之后
+        let remote = revision(second.clock + 1000, "node-2");
+        assert!(tracker.apply_if_newer(&key(), &remote, false, || ()).is_some());
+        assert!(tracker.record_local(&key(), false) > remote);
+    }
+
+    #[test]
+    fn test_apply_only_newer() {
+        let tracker = RevisionTracker::new("node-1", DEFAULT_TOMBSTONE_WINDOW);
+        assert!(tracker.apply_if_newer(&key(), &revision(10, "node-2"), false, || ()).is_some());
+        assert!(tracker.apply_if_newer(&key(), &revision(9, "node-3"), false, || ()).is_none());
+        assert!(tracker.apply_if_newer(&key(), &revision(10, "node-2"), false, || ()).is_none());
+        // 时钟相同时按节点 ID 决定先后
+        assert!(tracker.apply_if_newer(&key(), &revision(10, "node-3"), false, || ()).is_some());
+        assert_eq!(tracker.current(&key()), Some(revision(10, "node-3")));
+    }
+
+    #[test]
+    fn test_tombstone_blocks_late_register() {
+        let tracker = RevisionTracker::new("node-1", DEFAULT_TOMBSTONE_WINDOW);
+        assert!(tracker.apply_if_newer(&key(), &revision(20, "node-2"), true, || ()).is_some());
+        assert!(tracker.is_deleted(&key()));
+        assert!(tracker.current(&key()).is_none());
+
+        // 注销之前的注册迟到, 不会让实例复活
+        assert!(tracker.apply_if_newer(&key(), &revision(15, "node-3"), false, || ()).is_none());
+        assert!(tracker.apply_if_newer(&key(), &revision(21, "node-3"), false, || ()).is_some());
+        assert!(!tracker.is_deleted(&key()));
+    }
+
+    #[test]
+    fn test_expired_tombstones() {
+        let tracker = RevisionTracker::new("node-1", Duration::ZERO);
+        tracker.apply_if_newer(&key(), &revision(20, "node-2"), true, || ());
+        assert!(!tracker.is_deleted(&key()));
+
+        // 删除标记过期后不再阻止旧的注册
+        assert!(tracker.apply_if_newer(&key(), &revision(15, "node-3"), false, || ()).is_some());
+        tracker.apply_if_newer(&key(), &revision(30, "node-2"), true, || ());
+        assert_eq!(tracker.purge_expired_tombstones(), 1);
+    }
+
+    #[test]
+    fn test_unknown_revision() {
+        let tracker = RevisionTracker::new("node-1", DEFAULT_TOMBSTONE_WINDOW);
+        let unknown = Revision::default();
+        assert!(tracker.apply_if_newer(&key(), &unknown, false, || ()).is_some());
+        assert!(tracker.apply_if_newer(&key(), &unknown, true, || ()).is_some());
+        assert!(tracker.current(&key()).is_none());
+
+        // 来源未知的注册不会覆盖删除标记
+        tracker.record_local(&key(), true);
+        assert!(tracker.apply_if_newer(&key(), &unknown, false, || ()).is_none());
+    }
+}
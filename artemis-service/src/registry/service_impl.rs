@@ -3,6 +3,7 @@ use crate::cache::VersionedCacheManager;
 use crate::change::InstanceChangeManager;
 use crate::lease::LeaseManager;
 use crate::replication::ReplicationManager;
+use crate::replication::anti_entropy::versioned_service_digest;
 use crate::traits::RegistryService;
 use artemis_common::model::{
     BatchHeartbeatRequest,
@@ -13,9 +14,15 @@ use artemis_common::model::{
     BatchUnregisterRequest,
     BatchUnregisterResponse,
     ErrorCode,
+    FetchServicesRequest,
+    FetchServicesResponse,
     GetAllServicesResponse,
+    GetServiceDigestsResponse,
     HeartbeatRequest,
     HeartbeatResponse,
+    Instance,
+    InstanceKey,
+    LeasedInstance,
     RegisterRequest,
     RegisterResponse,
     ReplicateHeartbeatRequest,
@@ -25,6 +32,8 @@ use artemis_common::model::{
     ReplicateUnregisterRequest,
     ReplicateUnregisterResponse,
     ResponseStatus,
+    Revision,
+    ServiceDigest,
     ServicesDeltaRequest,
     ServicesDeltaResponse,
     SyncFullDataRequest,
@@ -34,7 +43,7 @@ use artemis_common::model::{
 };
 use async_trait::async_trait;
 use std::sync::Arc;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 #[derive(Clone)]
 pub struct RegistryServiceImpl {
@@ -76,13 +85,10 @@ impl RegistryServiceImpl {
         let failed = Vec::new();
         let mut affected_services = std::collections::HashSet::new();
 
-        for instance in request.instances {
-            let service_id = instance.service_id.clone();
-            let key = instance.key();
-
-            self.repository.register(instance.clone());
-            self.lease_manager.create_lease(key.clone());
-            affected_services.insert(service_id);
+        for (index, instance) in request.instances.iter().enumerate() {
+            if self.apply_replicated_register(instance, request.revisions.get(index)) {
+                affected_services.insert(instance.service_id.clone());
+            }
         }
 
         // 批量更新缓存
@@ -133,16 +139,19 @@ impl RegistryServiceImpl {
         let mut affected_services = std::collections::HashSet::new();
         let mut failed = Vec::new();
 
-        for key in request.instance_keys {
+        for (index, key) in request.instance_keys.into_iter().enumerate() {
             let service_id = key.service_id.clone();
 
-            if let Some(instance) = self.repository.remove(&key) {
-                self.lease_manager.remove_lease(&key);
-                affected_services.insert(service_id);
-                self.change_manager.publish_unregister(&key, &instance);
-            } else {
-                warn!("Batch unregister failed for non-existent instance: {:?}", key);
-                failed.push(key);
+            match self.apply_replicated_unregister(&key, request.revisions.get(index)) {
+                Some(Some(instance)) => {
+                    affected_services.insert(service_id);
+                    self.change_manager.publish_unregister(&key, &instance);
+                }
+                Some(None) => {
+                    warn!("Batch unregister failed for non-existent instance: {:?}", key);
+                    failed.push(key);
+                }
+                None => {}
             }
         }
 
@@ -199,6 +208,155 @@ impl RegistryServiceImpl {
         }
     }
 
+    /// 加载对等节点的全量快照 (启动同步)
+    ///
+    /// 为快照中的实例创建租约并重建缓存, 不触发复制。同步期间本地已注册的实例更新, 保留本地数据;
+    /// 同步期间本地已注销的实例不会复活。
+    /// 返回加载的实例数
+    pub fn load_snapshot(&self, services: Vec<artemis_common::model::Service>) -> usize {
+        let mut loaded = 0;
+        let mut affected_services = std::collections::HashSet::new();
+
+        for instance in services.into_iter().flat_map(|service| service.instances) {
+            let key = instance.key();
+            if self.repository.get_instance(&key).is_some()
+                || self.repository.revisions().is_deleted(&key)
+            {
+                continue;
+            }
+
+            affected_services.insert(instance.service_id.clone());
+            self.repository.register(instance);
+            self.lease_manager.create_lease(key);
+            loaded += 1;
+        }
+
+        for service_id in affected_services {
+            self.rebuild_and_cache_service(&service_id);
+        }
+
+        loaded
+    }
+
+    /// 所有服务的摘要 (反熵比较)
+    pub fn service_digests(&self) -> Vec<ServiceDigest> {
+        let mut services: std::collections::HashMap<String, Vec<(Instance, Revision)>> =
+            std::collections::HashMap::new();
+        for instance in self.repository.get_all_instances() {
+            let key = instance.key();
+            let revision = self.repository.revisions().current(&key).unwrap_or_default();
+            services.entry(key.service_id).or_default().push((instance, revision));
+        }
+
+        services
+            .into_iter()
+            .map(|(service_id, instances)| ServiceDigest {
+                service_id,
+                instance_count: instances.len(),
+                digest: versioned_service_digest(&instances),
+            })
+            .collect()
+    }
+
+    /// 服务摘要端点
+    pub async fn get_service_digests(&self) -> GetServiceDigestsResponse {
+        GetServiceDigestsResponse {
+            response_status: ResponseStatus::success(),
+            digests: self.service_digests(),
+        }
+    }
+
+    /// 拉取指定服务的实例及租约年龄 (反熵修复)
+    pub async fn fetch_services(&self, request: FetchServicesRequest) -> FetchServicesResponse {
+        let instances = request
+            .service_ids
+            .iter()
+            .flat_map(|service_id| self.repository.get_instances_by_service(service_id))
+            .filter_map(|instance| {
+                let key = instance.key();
+                let age = self.lease_manager.lease_age(&key)?;
+                Some(LeasedInstance {
+                    revision: self.repository.revisions().current(&key).unwrap_or_default(),
+                    instance,
+                    lease_age_ms: age.as_millis() as u64,
+                })
+            })
+            .collect();
+
+        FetchServicesResponse { response_status: ResponseStatus::success(), instances }
+    }
+
+    /// 反熵修复: 补齐本地缺失的实例, 并用修订号较新的实例覆盖本地数据, 不触发复制
+    ///
+    /// 租约按对等节点最近一次续约的时间创建, 已停止心跳的实例不会因为互相修复而一直存活。
+    /// 删除标记窗口内的实例只有对等节点的修订号比注销新时才会恢复; 来源未知 (默认修订号) 的实例
+    /// 只用于补齐缺失的实例, 不覆盖本地数据。返回修复的实例
+    pub fn repair_from_peer(&self, instances: Vec<LeasedInstance>) -> Vec<InstanceKey> {
+        let mut repaired = Vec::new();
+        let mut affected_services = std::collections::HashSet::new();
+
+        for LeasedInstance { instance, lease_age_ms, revision } in instances {
+            let key = instance.key();
+            if revision == Revision::default() && self.repository.get_instance(&key).is_some() {
+                continue;
+            }
+
+            let lease_age = std::time::Duration::from_millis(lease_age_ms);
+            let service_id = instance.service_id.clone();
+            let applied =
+                self.repository.revisions().apply_if_newer(&key, &revision, false, || {
+                    self.repository.register(instance);
+                    self.lease_manager.create_lease_renewed_before(key.clone(), lease_age);
+                });
+            if applied.is_some() {
+                affected_services.insert(service_id);
+                repaired.push(key);
+            }
+        }
+
+        for service_id in affected_services {
+            self.rebuild_and_cache_service(&service_id);
+        }
+        repaired
+    }
+
+    /// 应用复制来的注册, 只有比本地修订号新才写入 (不带修订号的旧版本请求视为来源未知)。
+    /// 返回是否写入
+    fn apply_replicated_register(&self, instance: &Instance, revision: Option<&Revision>) -> bool {
+        let key = instance.key();
+        let revision = revision.cloned().unwrap_or_default();
+        let applied = self.repository.revisions().apply_if_newer(&key, &revision, false, || {
+            self.repository.register(instance.clone());
+            self.lease_manager.create_lease(key.clone());
+        });
+        if applied.is_none() {
+            debug!("Ignoring stale replicated register {:?} ({:?})", key, revision);
+        }
+        applied.is_some()
+    }
+
+    /// 应用复制来的注销, 只有比本地修订号新才删除并留下删除标记
+    ///
+    /// 返回 `Some(删除的实例)`, 修订号过期时返回 None
+    fn apply_replicated_unregister(
+        &self,
+        key: &InstanceKey,
+        revision: Option<&Revision>,
+    ) -> Option<Option<Instance>> {
+        let revision = revision.cloned().unwrap_or_default();
+        let removed = self.repository.revisions().apply_if_newer(key, &revision, true, || {
+            let removed = self.repository.remove(key);
+            if removed.is_some() {
+                self.lease_manager.remove_lease(key);
+            }
+            removed
+        });
+        if removed.is_none() {
+            debug!("Ignoring stale replicated unregister {:?} ({:?})", key, revision);
+        }
+        removed
+    }
+
     /// 重建服务并更新缓存
     fn rebuild_and_cache_service(&self, service_id: &str) {
         let instances = self.repository.get_instances_by_service(service_id);
@@ -240,8 +398,9 @@ impl RegistryService for RegistryServiceImpl {
 
             let service_id = instance.service_id.clone();
 
-            // 注册实例
+            // 注册实例并分配修订号
             self.repository.register(instance.clone());
+            let revision = self.repository.revisions().record_local(&key, false);
 
             // 创建租约
             self.lease_manager.create_lease(key);
@@ -254,7 +413,7 @@ impl RegistryService for RegistryServiceImpl {
 
             // 触发复制
             if let Some(ref repl_mgr) = self.replication_manager {
-                repl_mgr.publish_register(instance.clone());
+                repl_mgr.publish_register(instance.clone(), revision);
             }
         }
 
@@ -303,6 +462,8 @@ impl RegistryService for RegistryServiceImpl {
             // 获取实例信息用于发布事件
             if let Some(instance) = self.repository.remove(&key) {
                 self.lease_manager.remove_lease(&key);
+                // 留下删除标记, 迟到的旧注册不会让实例复活
+                let revision = self.repository.revisions().record_local(&key, true);
 
                 // 更新缓存
                 self.rebuild_and_cache_service(&service_id);
@@ -312,7 +473,7 @@ impl RegistryService for RegistryServiceImpl {
 
                 // 触发复制
                 if let Some(ref repl_mgr) = self.replication_manager {
-                    repl_mgr.publish_unregister(key.clone());
+                    repl_mgr.publish_unregister(key.clone(), revision);
                 }
             }
         }
@@ -328,18 +489,17 @@ impl RegistryService for RegistryServiceImpl {
     ) -> ReplicateRegisterResponse {
         let failed = Vec::new();
 
-        for instance in request.instances {
+        for (index, instance) in request.instances.into_iter().enumerate() {
             let key = instance.key();
             info!("Registering instance from replication: {:?}", key);
 
-            let service_id = instance.service_id.clone();
-
             // 只做本地处理,不触发复制
-            self.repository.register(instance.clone());
-            self.lease_manager.create_lease(key);
+            if !self.apply_replicated_register(&instance, request.revisions.get(index)) {
+                continue;
+            }
 
             // 更新缓存
-            self.rebuild_and_cache_service(&service_id);
+            self.rebuild_and_cache_service(&instance.service_id);
 
             // 发布变更事件(用于 WebSocket 推送等)
             self.change_manager.publish_register(&instance);
@@ -382,14 +542,14 @@ impl RegistryService for RegistryServiceImpl {
         &self,
         request: ReplicateUnregisterRequest,
     ) -> ReplicateUnregisterResponse {
-        for key in request.instance_keys {
+        for (index, key) in request.instance_keys.into_iter().enumerate() {
             info!("Unregistering instance from replication: {:?}", key);
 
             let service_id = key.service_id.clone();
 
-            if let Some(instance) = self.repository.remove(&key) {
-                self.lease_manager.remove_lease(&key);
-
+            if let Some(Some(instance)) =
+                self.apply_replicated_unregister(&key, request.revisions.get(index))
+            {
                 // 更新缓存
                 self.rebuild_and_cache_service(&service_id);
 
@@ -488,4 +648,116 @@ mod tests {
         assert_eq!(response.response_status.error_code, ErrorCode::Success);
         assert_eq!(repo.count(), 0);
     }
+
+    #[tokio::test]
+    async fn test_load_snapshot() {
+        let repo = RegistryRepository::new();
+        let lease_mgr = Arc::new(LeaseManager::new(Duration::from_secs(30)));
+        let cache = Arc::new(crate::cache::VersionedCacheManager::new());
+        let change_mgr = Arc::new(InstanceChangeManager::new());
+        let service = RegistryServiceImpl::new(
+            repo.clone(),
+            lease_mgr.clone(),
+            cache.clone(),
+            change_mgr,
+            None,
+        );
+
+        // 本地已注册的实例保留本地数据
+        let mut local = create_test_instance();
+        local.port = 9090;
+        service.register(RegisterRequest { instances: vec![local] }).await;
+
+        let mut other = create_test_instance();
+        other.instance_id = "inst-2".to_string();
+        let snapshot = artemis_common::model::Service {
+            service_id: "my-service".to_string(),
+            metadata: None,
+            instances: vec![create_test_instance(), other.clone()],
+            logic_instances: None,
+        };
+
+        assert_eq!(service.load_snapshot(vec![snapshot]), 1);
+        assert_eq!(repo.count(), 2);
+        assert_eq!(repo.get_instance(&create_test_instance().key()).unwrap().port, 9090);
+        assert!(lease_mgr.is_valid(&other.key()));
+        assert_eq!(cache.get_service("my-service").unwrap().instances.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_repair_from_peer() {
+        let repo = RegistryRepository::new();
+        let lease_mgr = Arc::new(LeaseManager::new(Duration::from_secs(30)));
+        let cache = Arc::new(crate::cache::VersionedCacheManager::new());
+        let change_mgr = Arc::new(InstanceChangeManager::new());
+        let peer = RegistryServiceImpl::new(
+            RegistryRepository::new(),
+            Arc::new(LeaseManager::new(Duration::from_secs(30))),
+            Arc::new(crate::cache::VersionedCacheManager::new()),
+            Arc::new(InstanceChangeManager::new()),
+            None,
+        );
+        let service = RegistryServiceImpl::new(
+            repo.clone(),
+            lease_mgr.clone(),
+            cache.clone(),
+            change_mgr,
+            None,
+        );
+
+        let mut other = create_test_instance();
+        other.instance_id = "inst-2".to_string();
+        peer.register(RegisterRequest { instances: vec![create_test_instance(), other.clone()] })
+            .await;
+        assert!(service.service_digests().is_empty());
+
+        let response = peer
+            .fetch_services(FetchServicesRequest { service_ids: vec!["my-service".to_string()] })
+            .await;
+        assert_eq!(response.instances.len(), 2);
+
+        let mut repaired = service.repair_from_peer(response.instances);
+        repaired.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+        assert_eq!(repaired, vec![create_test_instance().key(), other.key()]);
+        assert!(lease_mgr.is_valid(&other.key()));
+        assert_eq!(cache.get_service("my-service").unwrap().instances.len(), 2);
+        assert_eq!(service.service_digests(), peer.service_digests());
+    }
+
+    #[tokio::test]
+    async fn test_repair_overwrites_older_revision() {
+        let create_service = |node_id: &str| {
+            RegistryServiceImpl::new(
+                RegistryRepository::new().with_revisions(node_id, Duration::from_secs(300)),
+                Arc::new(LeaseManager::new(Duration::from_secs(30))),
+                Arc::new(crate::cache::VersionedCacheManager::new()),
+                Arc::new(InstanceChangeManager::new()),
+                None,
+            )
+        };
+        async fn fetch(service: &RegistryServiceImpl) -> Vec<LeasedInstance> {
+            let request = FetchServicesRequest { service_ids: vec!["my-service".to_string()] };
+            service.fetch_services(request).await.instances
+        }
+        let node1 = create_service("node-1");
+        let node2 = create_service("node-2");
+
+        // 两边都有同一实例但状态不同 (如分区期间丢失的状态变更), node-2 的变更较新
+        node1.register(RegisterRequest { instances: vec![create_test_instance()] }).await;
+        let down = Instance { status: InstanceStatus::Down, ..create_test_instance() };
+        node2.register(RegisterRequest { instances: vec![down] }).await;
+        assert_ne!(node1.service_digests(), node2.service_digests());
+
+        // 较旧的一方不会覆盖较新的一方
+        assert!(node2.repair_from_peer(fetch(&node1).await).is_empty());
+
+        let repaired = node1.repair_from_peer(fetch(&node2).await);
+        assert_eq!(repaired, vec![create_test_instance().key()]);
+        let instance = node1.repository.get_instance(&create_test_instance().key()).unwrap();
+        assert_eq!(instance.status, InstanceStatus::Down);
+        assert_eq!(node1.service_digests(), node2.service_digests());
+
+        // 收敛后不再修复
+        assert!(node1.repair_from_peer(fetch(&node2).await).is_empty());
+    }
 }
@@ -0,0 +1,258 @@
+use super::client::ReplicationClient;
+use super::error::ReplicationError;
+use crate::cluster::ClusterManager;
+use crate::registry::RegistryServiceImpl;
+use crate::security::RequestSigner;
+use artemis_common::model::{FetchServicesRequest, Instance, Revision};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 服务摘要: 按实例 key 排序后对 key 和实例内容做 SHA-256
+///
+/// 元数据按 key 排序后参与计算, 保证相同内容在不同节点上得到相同摘要
+pub fn service_digest(instances: &[Instance]) -> String {
+    digest_entries(instances.iter().map(|instance| (instance, None)))
+}
+
+/// 带修订号的服务摘要: 按实例 key 排序后对 key、修订号和实例内容做 SHA-256
+///
+/// 反熵使用, 内容相同但修订号不同的实例也视为不一致, 修复时采用修订号较新的一方
+pub fn versioned_service_digest(instances: &[(Instance, Revision)]) -> String {
+    digest_entries(instances.iter().map(|(instance, revision)| (instance, Some(revision))))
+}
+
+fn digest_entries<'a>(
+    instances: impl Iterator<Item = (&'a Instance, Option<&'a Revision>)>,
+) -> String {
+    let mut entries: Vec<(String, String)> = instances
+        .map(|(instance, revision)| {
+            let key = instance.key();
+            let key = format!(
+                "{}:{}:{}:{}:{}",
+                key.region_id, key.zone_id, key.service_id, key.group_id, key.instance_id
+            );
+            let mut content = instance.clone();
+            let metadata: Option<BTreeMap<String, String>> =
+                content.metadata.take().map(|metadata| metadata.into_iter().collect());
+            let content = match revision {
+                Some(revision) => serde_json::to_string(&(content, metadata, revision)),
+                None => serde_json::to_string(&(content, metadata)),
+            };
+            (key, content.unwrap_or_default())
+        })
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for (key, content) in entries {
+        hasher.update(key.as_bytes());
+        hasher.update([0]);
+        hasher.update(content.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 与单个对等节点的一轮反熵结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerReconciliation {
+    pub peer: String,
+    /// 摘要不一致的服务数 (包括只在一方存在的服务)
+    pub divergent_services: usize,
+    /// 本轮修复的服务数
+    pub repaired_services: usize,
+    /// 本轮修复的实例数
+    pub repaired_instances: usize,
+}
+
+/// 反熵结果监听器 (如导出 Prometheus 指标)
+pub trait AntiEntropyListener: Send + Sync {
+    fn on_reconciled(&self, result: &PeerReconciliation);
+}
+
+/// 反熵任务
+///
+/// 复制是尽力而为的: 对等节点不健康时事件被丢弃, 重试耗尽后放弃。反熵任务定期与每个健康的
+/// 对等节点交换服务摘要 (覆盖实例 key、修订号和内容), 只拉取摘要不一致的服务, 补齐本地缺失的实例
+/// 并用修订号较新的实例覆盖本地数据。只在本地存在或本地较新的实例由对等节点在它自己的反熵轮次中拉取
+pub struct AntiEntropyTask {
+    cluster_manager: Arc<ClusterManager>,
+    registry: Arc<RegistryServiceImpl>,
+    client: ReplicationClient,
+    interval: Duration,
+    listeners: Vec<Arc<dyn AntiEntropyListener>>,
+}
+
+impl AntiEntropyTask {
+    pub fn new(
+        cluster_manager: Arc<ClusterManager>,
+        registry: Arc<RegistryServiceImpl>,
+        interval: Duration,
+        request_timeout: Duration,
+    ) -> Self {
+        Self {
+            cluster_manager,
+            registry,
+            client: ReplicationClient::new(request_timeout),
+            interval,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// 使用集群共享密钥对反熵请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
+    pub fn with_listener(mut self, listener: Arc<dyn AntiEntropyListener>) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// 启动后台任务, 每个间隔与所有健康的对等节点比较一次
+    pub fn start(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("Anti-entropy task started (interval: {:?})", self.interval);
+            let mut interval = tokio::time::interval(self.interval);
+            // 第一次 tick 立即返回, 跳过以免与启动同步重叠
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                self.run_once().await;
+            }
+        })
+    }
+
+    /// 与所有健康的对等节点各比较一次
+    pub async fn run_once(&self) -> Vec<PeerReconciliation> {
+        let mut results = Vec::new();
+
+        for peer in self.cluster_manager.get_healthy_peers() {
+            let peer_url = peer.base_url();
+            match self.reconcile(&peer_url).await {
+                Ok(result) => {
+                    for listener in &self.listeners {
+                        listener.on_reconciled(&result);
+                    }
+                    results.push(result);
+                }
+                Err(e) => warn!("Anti-entropy with {} failed: {}", peer_url, e),
+            }
+        }
+
+        results
+    }
+
+    /// 与单个对等节点比较并修复
+    pub async fn reconcile(&self, peer_url: &str) -> Result<PeerReconciliation, ReplicationError> {
+        let local: HashMap<String, String> = self
+            .registry
+            .service_digests()
+            .into_iter()
+            .map(|digest| (digest.service_id, digest.digest))
+            .collect();
+        let remote = self.client.get_service_digests(peer_url).await?.digests;
+        let remote_ids: HashSet<&str> = remote.iter().map(|d| d.service_id.as_str()).collect();
+
+        // 对等节点上有且与本地不同的服务需要拉取
+        let to_fetch: Vec<String> = remote
+            .iter()
+            .filter(|d| local.get(&d.service_id) != Some(&d.digest))
+            .map(|d| d.service_id.clone())
+            .collect();
+        let local_only = local.keys().filter(|id| !remote_ids.contains(id.as_str())).count();
+
+        let mut result = PeerReconciliation {
+            peer: peer_url.to_string(),
+            divergent_services: to_fetch.len() + local_only,
+            ..Default::default()
+        };
+        if to_fetch.is_empty() {
+            debug!("Anti-entropy with {}: {} local-only services", peer_url, local_only);
+            return Ok(result);
+        }
+
+        let response = self
+            .client
+            .fetch_services(peer_url, FetchServicesRequest { service_ids: to_fetch })
+            .await?;
+        let repaired = self.registry.repair_from_peer(response.instances);
+        result.repaired_instances = repaired.len();
+        result.repaired_services =
+            repaired.iter().map(|key| key.service_id.as_str()).collect::<HashSet<_>>().len();
+
+        info!(
+            "Anti-entropy with {}: {} divergent services, repaired {} instances in {} services",
+            peer_url,
+            result.divergent_services,
+            result.repaired_instances,
+            result.repaired_services
+        );
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use artemis_common::model::InstanceStatus;
+
+    fn create_instance(id: &str) -> Instance {
+        Instance {
+            region_id: "us-east".to_string(),
+            zone_id: "zone-1".to_string(),
+            group_id: None,
+            service_id: "order-service".to_string(),
+            instance_id: id.to_string(),
+            machine_name: None,
+            ip: "10.0.0.1".to_string(),
+            port: 8080,
+            protocol: None,
+            url: "http://10.0.0.1:8080".to_string(),
+            health_check_url: None,
+            status: InstanceStatus::Up,
+            metadata: Some(
+                [("version", "1.0"), ("weight", "100"), ("zone", "a")]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_service_digest_is_order_independent() {
+        let a = create_instance("inst-1");
+        let b = create_instance("inst-2");
+        assert_eq!(
+            service_digest(&[a.clone(), b.clone()]),
+            service_digest(&[b.clone(), a.clone()])
+        );
+
+        // 实例内容变化时摘要变化
+        let mut changed = b.clone();
+        changed.status = InstanceStatus::Down;
+        assert_ne!(service_digest(&[a.clone(), b]), service_digest(&[a.clone(), changed]));
+        assert_ne!(service_digest(&[a]), service_digest(&[]));
+    }
+
+    #[test]
+    fn test_versioned_digest_covers_revisions() {
+        let a = create_instance("inst-1");
+        let revision = |clock| Revision { clock, node_id: "node-1".to_string() };
+        assert_eq!(
+            versioned_service_digest(&[(a.clone(), revision(1))]),
+            versioned_service_digest(&[(a.clone(), revision(1))])
+        );
+        assert_ne!(
+            versioned_service_digest(&[(a.clone(), revision(1))]),
+            versioned_service_digest(&[(a, revision(2))])
+        );
+    }
+}
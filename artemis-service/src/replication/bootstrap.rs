@@ -0,0 +1,197 @@
+use super::client::ReplicationClient;
+use crate::cluster::ClusterManager;
+use crate::registry::RegistryServiceImpl;
+use crate::security::RequestSigner;
+use crate::status::Readiness;
+use artemis_common::model::{ErrorCode, SyncFullDataRequest};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// 启动同步结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootstrapOutcome {
+    /// 已从对等节点加载全量快照
+    Synced { peer: String, instances: usize },
+    /// 没有可用的对等节点
+    NoPeers,
+    /// 所有对等节点都在启动同步 (整个集群同时启动, 没有可同步的数据)
+    PeersStarting,
+    /// 超时前没有任何对等节点返回快照
+    TimedOut,
+}
+
+/// 启动同步
+///
+/// 节点加入集群或重启后, 从健康的对等节点拉取全量快照并加载到注册表、租约和缓存,
+/// 完成或超时后标记节点就绪
+pub struct BootstrapSync {
+    cluster_manager: Arc<ClusterManager>,
+    client: ReplicationClient,
+    region_id: String,
+    timeout: Duration,
+    retry_interval: Duration,
+}
+
+impl BootstrapSync {
+    pub fn new(
+        cluster_manager: Arc<ClusterManager>,
+        region_id: String,
+        timeout: Duration,
+        request_timeout: Duration,
+    ) -> Self {
+        Self {
+            cluster_manager,
+            client: ReplicationClient::new(request_timeout),
+            region_id,
+            timeout,
+            retry_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// 使用集群共享密钥对同步请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
+    /// 所有对等节点都失败后的重试间隔
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// 后台执行同步, 结束后标记就绪
+    pub fn start(
+        self,
+        registry: Arc<RegistryServiceImpl>,
+        readiness: Arc<Readiness>,
+    ) -> JoinHandle<BootstrapOutcome> {
+        tokio::spawn(async move {
+            let outcome = self.run(&registry).await;
+            readiness.mark_ready();
+            outcome
+        })
+    }
+
+    /// 依次尝试健康的对等节点, 直到加载成功或超时
+    pub async fn run(&self, registry: &RegistryServiceImpl) -> BootstrapOutcome {
+        let deadline = Instant::now() + self.timeout;
+        info!("Bootstrap sync started (timeout: {:?})", self.timeout);
+
+        loop {
+            let peers = self.cluster_manager.get_healthy_peers();
+            if peers.is_empty() {
+                info!("Bootstrap sync skipped: no healthy peers");
+                return BootstrapOutcome::NoPeers;
+            }
+
+            let mut all_starting = true;
+            for peer in peers {
+                let peer_url = peer.base_url();
+                let request =
+                    SyncFullDataRequest { region_id: self.region_id.clone(), zone_id: None };
+                let response = tokio::time::timeout_at(
+                    deadline,
+                    self.client.sync_full_data(&peer_url, request),
+                )
+                .await;
+
+                let response = match response {
+                    Err(_) => {
+                        all_starting = false;
+                        break;
+                    }
+                    Ok(Err(e)) => {
+                        warn!("Bootstrap sync from {} failed: {}", peer_url, e);
+                        all_starting = false;
+                        continue;
+                    }
+                    Ok(Ok(response)) => response,
+                };
+
+                // 对等节点自身还在启动同步时拒绝提供快照
+                match response.response_status.error_code {
+                    ErrorCode::Success => {
+                        let instances = registry.load_snapshot(response.services);
+                        info!("Bootstrap sync loaded {} instances from {}", instances, peer_url);
+                        return BootstrapOutcome::Synced { peer: peer_url, instances };
+                    }
+                    ErrorCode::ServiceUnavailable => {
+                        info!("Bootstrap sync skipped {}: peer is still starting", peer_url);
+                    }
+                    _ => {
+                        warn!(
+                            "Bootstrap sync from {} refused: {}",
+                            peer_url,
+                            response.response_status.error_message.unwrap_or_default()
+                        );
+                        all_starting = false;
+                    }
+                }
+            }
+
+            if all_starting {
+                info!("Bootstrap sync skipped: all peers are still starting");
+                return BootstrapOutcome::PeersStarting;
+            }
+
+            if Instant::now() + self.retry_interval >= deadline {
+                warn!("Bootstrap sync timed out, serving with local data");
+                return BootstrapOutcome::TimedOut;
+            }
+            tokio::time::sleep(self.retry_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::VersionedCacheManager;
+    use crate::change::InstanceChangeManager;
+    use crate::lease::LeaseManager;
+    use crate::registry::RegistryRepository;
+
+    fn create_registry() -> Arc<RegistryServiceImpl> {
+        Arc::new(RegistryServiceImpl::new(
+            RegistryRepository::new(),
+            Arc::new(LeaseManager::new(Duration::from_secs(30))),
+            Arc::new(VersionedCacheManager::new()),
+            Arc::new(InstanceChangeManager::new()),
+            None,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_no_peers() {
+        let bootstrap = BootstrapSync::new(
+            Arc::new(ClusterManager::default()),
+            "us-east".to_string(),
+            Duration::from_secs(5),
+            Duration::from_secs(1),
+        );
+        assert_eq!(bootstrap.run(&create_registry()).await, BootstrapOutcome::NoPeers);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_peer_times_out_and_marks_ready() {
+        // 端口 1 上没有服务, 连接立即失败
+        let cluster =
+            Arc::new(ClusterManager::new("node-1".to_string(), vec!["127.0.0.1:1".to_string()]));
+        let bootstrap = BootstrapSync::new(
+            cluster,
+            "us-east".to_string(),
+            Duration::from_millis(300),
+            Duration::from_millis(100),
+        )
+        .with_retry_interval(Duration::from_millis(50));
+
+        let readiness = Arc::new(Readiness::starting());
+        let outcome = bootstrap.start(create_registry(), readiness.clone()).await.unwrap();
+        assert_eq!(outcome, BootstrapOutcome::TimedOut);
+        assert!(readiness.is_ready());
+    }
+}
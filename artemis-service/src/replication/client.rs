@@ -1,20 +1,36 @@
 use super::error::{ReplicationError, ReplicationErrorKind};
+use super::stream::STREAM_PATH;
+use crate::cluster::gossip::{
+    GossipRequest, JoinClusterRequest, MembershipResponse, ProbeRequest, ProbeResponse,
+};
+use crate::federation::EXPORT_PATH;
+use crate::security::{NONCE_HEADER, RequestSigner, SIGNATURE_HEADER, TIMESTAMP_HEADER};
 use artemis_common::model::{
     BatchRegisterRequest, BatchRegisterResponse, BatchUnregisterRequest, BatchUnregisterResponse,
-    GetAllServicesResponse, ReplicateHeartbeatRequest, ReplicateHeartbeatResponse,
+    FederationExportResponse, FetchServicesRequest, FetchServicesResponse, GetAllServicesResponse,
+    GetServiceDigestsResponse, ReplicateHeartbeatRequest, ReplicateHeartbeatResponse,
     ReplicateRegisterRequest, ReplicateRegisterResponse, ReplicateUnregisterRequest,
-    ReplicateUnregisterResponse,
+    ReplicateUnregisterResponse, SyncFullDataRequest, SyncFullDataResponse,
 };
+use serde::Serialize;
 use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use tracing::debug;
 
+/// 到对等节点的复制流连接
+pub type ReplicationStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
 /// 复制客户端
 ///
 /// 负责向对等节点发送复制请求
+#[derive(Clone)]
 pub struct ReplicationClient {
     client: reqwest::Client,
-    #[allow(dead_code)]
     timeout: Duration,
+    /// 集群共享密钥签名器, 配置后每个请求都携带签名头
+    signer: Option<RequestSigner>,
 }
 
 impl ReplicationClient {
@@ -25,7 +41,100 @@ impl ReplicationClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, timeout }
+        Self { client, timeout, signer: None }
+    }
+
+    /// 使用集群共享密钥对复制请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// 构建 JSON POST 请求 (带防循环标记和签名)
+    fn post_json<T: Serialize>(
+        &self,
+        peer_url: &str,
+        path: &str,
+        request: &T,
+    ) -> Result<reqwest::RequestBuilder, ReplicationError> {
+        let body = serde_json::to_vec(request).map_err(|e| {
+            ReplicationError::new(
+                ReplicationErrorKind::PermanentFailure,
+                format!("Failed to serialize request: {}", e),
+            )
+        })?;
+
+        let builder = self
+            .client
+            .post(format!("{}{}", peer_url, path))
+            .header("X-Artemis-Replication", "true") // 防止复制循环
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+
+        Ok(self.sign(builder, "POST", path, &body).body(body))
+    }
+
+    fn sign(
+        &self,
+        builder: reqwest::RequestBuilder,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> reqwest::RequestBuilder {
+        match &self.signer {
+            Some(signer) => {
+                let (timestamp, nonce, signature) = signer.sign_now(method, path, body);
+                builder
+                    .header(TIMESTAMP_HEADER, timestamp.to_string())
+                    .header(NONCE_HEADER, nonce)
+                    .header(SIGNATURE_HEADER, signature)
+            }
+            None => builder,
+        }
+    }
+
+    /// 请求超时
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// 打开复制流 (WebSocket 长连接), 对等节点不支持时返回 Unsupported 错误
+    pub async fn open_stream(&self, peer_url: &str) -> Result<ReplicationStream, ReplicationError> {
+        let url = format!("{}{}", peer_url.replacen("http", "ws", 1), STREAM_PATH);
+        let mut request = url.into_client_request().map_err(|e| {
+            ReplicationError::new(
+                ReplicationErrorKind::PermanentFailure,
+                format!("Invalid stream url: {}", e),
+            )
+        })?;
+
+        let headers = request.headers_mut();
+        headers.insert("X-Artemis-Replication", "true".parse().expect("valid header value"));
+        if let Some(signer) = &self.signer {
+            let (timestamp, nonce, signature) = signer.sign_now("GET", STREAM_PATH, &[]);
+            headers.insert(TIMESTAMP_HEADER, timestamp.into());
+            if let Ok(nonce) = nonce.parse() {
+                headers.insert(NONCE_HEADER, nonce);
+            }
+            if let Ok(signature) = signature.parse() {
+                headers.insert(SIGNATURE_HEADER, signature);
+            }
+        }
+
+        debug!("Opening replication stream to {}", peer_url);
+        match tokio::time::timeout(self.timeout, tokio_tungstenite::connect_async(request)).await {
+            Ok(Ok((stream, _))) => Ok(stream),
+            Ok(Err(tungstenite::Error::Http(response))) => {
+                Err(ReplicationError::from_status(response.status()))
+            }
+            Ok(Err(e)) => Err(ReplicationError::new(
+                ReplicationErrorKind::ServiceUnavailable,
+                format!("Stream connection failed: {}", e),
+            )),
+            Err(_) => Err(ReplicationError::new(
+                ReplicationErrorKind::NetworkTimeout,
+                "Stream connection timeout",
+            )),
+        }
     }
 
     /// 复制注册请求
@@ -34,15 +143,10 @@ impl ReplicationClient {
         peer_url: &str,
         request: ReplicateRegisterRequest,
     ) -> Result<ReplicateRegisterResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/register.json", peer_url);
-
         debug!("Replicating register to {}", peer_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("X-Artemis-Replication", "true") // 防止复制循环
-            .json(&request)
+            .post_json(peer_url, "/api/replication/registry/register.json", &request)?
             .send()
             .await
             .map_err(ReplicationError::from_reqwest)?;
@@ -65,15 +169,10 @@ impl ReplicationClient {
         peer_url: &str,
         request: ReplicateHeartbeatRequest,
     ) -> Result<ReplicateHeartbeatResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/heartbeat.json", peer_url);
-
         debug!("Replicating {} heartbeats to {}", request.instance_keys.len(), peer_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("X-Artemis-Replication", "true")
-            .json(&request)
+            .post_json(peer_url, "/api/replication/registry/heartbeat.json", &request)?
             .send()
             .await
             .map_err(ReplicationError::from_reqwest)?;
@@ -96,15 +195,10 @@ impl ReplicationClient {
         peer_url: &str,
         request: ReplicateUnregisterRequest,
     ) -> Result<ReplicateUnregisterResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/unregister.json", peer_url);
-
         debug!("Replicating unregister to {}", peer_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("X-Artemis-Replication", "true")
-            .json(&request)
+            .post_json(peer_url, "/api/replication/registry/unregister.json", &request)?
             .send()
             .await
             .map_err(ReplicationError::from_reqwest)?;
@@ -126,12 +220,180 @@ impl ReplicationClient {
         &self,
         peer_url: &str,
     ) -> Result<GetAllServicesResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/services.json", peer_url);
+        let path = "/api/replication/registry/services.json";
 
         debug!("Fetching all services from {}", peer_url);
 
-        let response =
-            self.client.get(&url).send().await.map_err(ReplicationError::from_reqwest)?;
+        let request = self.sign(self.client.get(format!("{}{}", peer_url, path)), "GET", path, &[]);
+        let response = request.send().await.map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    /// 拉取全量数据 (节点启动同步)
+    pub async fn sync_full_data(
+        &self,
+        peer_url: &str,
+        request: SyncFullDataRequest,
+    ) -> Result<SyncFullDataResponse, ReplicationError> {
+        debug!("Fetching full snapshot from {}", peer_url);
+
+        let response = self
+            .post_json(peer_url, "/api/replication/registry/sync-full.json", &request)?
+            .send()
+            .await
+            .map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    /// 获取服务摘要 (反熵比较)
+    pub async fn get_service_digests(
+        &self,
+        peer_url: &str,
+    ) -> Result<GetServiceDigestsResponse, ReplicationError> {
+        let path = "/api/replication/registry/digests.json";
+
+        let request = self.sign(self.client.get(format!("{}{}", peer_url, path)), "GET", path, &[]);
+        let response = request.send().await.map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    /// 拉取指定服务的实例 (反熵修复)
+    pub async fn fetch_services(
+        &self,
+        peer_url: &str,
+        request: FetchServicesRequest,
+    ) -> Result<FetchServicesResponse, ReplicationError> {
+        debug!("Fetching {} services from {}", request.service_ids.len(), peer_url);
+
+        let response = self
+            .post_json(peer_url, "/api/replication/registry/fetch-services.json", &request)?
+            .send()
+            .await
+            .map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    /// 拉取远程区域导出的实例视图 (跨区域联邦), 携带上次的摘要, 未变化时不返回实例
+    pub async fn fetch_federation_export(
+        &self,
+        remote_url: &str,
+        known_digest: Option<&str>,
+    ) -> Result<FederationExportResponse, ReplicationError> {
+        let path = EXPORT_PATH;
+        let mut request = self.client.get(format!("{}{}", remote_url, path));
+        if let Some(digest) = known_digest {
+            request = request.header(reqwest::header::IF_NONE_MATCH, digest);
+        }
+
+        let response = self
+            .sign(request, "GET", path, &[])
+            .send()
+            .await
+            .map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    /// 加入集群 (向种子节点发送本节点信息)
+    pub async fn join_cluster(
+        &self,
+        seed_url: &str,
+        request: &JoinClusterRequest,
+    ) -> Result<MembershipResponse, ReplicationError> {
+        debug!("Joining cluster via {}", seed_url);
+        let request = self.post_json(seed_url, "/api/replication/cluster/join.json", request)?;
+        self.send_membership(request).await
+    }
+
+    /// 交换成员列表
+    pub async fn gossip(
+        &self,
+        peer_url: &str,
+        request: &GossipRequest,
+    ) -> Result<MembershipResponse, ReplicationError> {
+        let request = self.post_json(peer_url, "/api/replication/cluster/gossip.json", request)?;
+        self.send_membership(request).await
+    }
+
+    /// 请求其他成员代为探测 (超时时间需要覆盖对方的探测时间)
+    pub async fn probe_member(
+        &self,
+        helper_url: &str,
+        request: &ProbeRequest,
+    ) -> Result<ProbeResponse, ReplicationError> {
+        let response = self
+            .post_json(helper_url, "/api/replication/cluster/probe.json", request)?
+            .timeout(Duration::from_millis(request.timeout_ms * 2))
+            .send()
+            .await
+            .map_err(ReplicationError::from_reqwest)?;
+
+        if response.status().is_success() {
+            response.json().await.map_err(|e| {
+                ReplicationError::new(
+                    ReplicationErrorKind::PermanentFailure,
+                    format!("Failed to parse response: {}", e),
+                )
+            })
+        } else {
+            Err(ReplicationError::from_status(response.status()))
+        }
+    }
+
+    async fn send_membership(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<MembershipResponse, ReplicationError> {
+        let response = request.send().await.map_err(ReplicationError::from_reqwest)?;
 
         if response.status().is_success() {
             response.json().await.map_err(|e| {
@@ -151,15 +413,10 @@ impl ReplicationClient {
         peer_url: &str,
         request: BatchRegisterRequest,
     ) -> Result<BatchRegisterResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/batch-register.json", peer_url);
-
         debug!("Batch replicating {} instances to {}", request.instances.len(), peer_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("X-Artemis-Replication", "true") // 防止复制循环
-            .json(&request)
+            .post_json(peer_url, "/api/replication/registry/batch-register.json", &request)?
             .send()
             .await
             .map_err(ReplicationError::from_reqwest)?;
@@ -182,15 +439,10 @@ impl ReplicationClient {
         peer_url: &str,
         request: BatchUnregisterRequest,
     ) -> Result<BatchUnregisterResponse, ReplicationError> {
-        let url = format!("{}/api/replication/registry/batch-unregister.json", peer_url);
-
         debug!("Batch unregistering {} instances to {}", request.instance_keys.len(), peer_url);
 
         let response = self
-            .client
-            .post(&url)
-            .header("X-Artemis-Replication", "true") // 防止复制循环
-            .json(&request)
+            .post_json(peer_url, "/api/replication/registry/batch-unregister.json", &request)?
             .send()
             .await
             .map_err(ReplicationError::from_reqwest)?;
@@ -440,4 +692,43 @@ mod tests {
 
         // 验证可以创建多个默认客户端
     }
+
+    // ===== 请求签名测试 =====
+
+    #[test]
+    fn test_unsigned_request_has_no_signature_headers() {
+        let client = ReplicationClient::default();
+        let request = client
+            .post_json("http://peer:8080", "/api/replication/registry/register.json", &"{}")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["X-Artemis-Replication"], "true");
+        assert!(request.headers().get(SIGNATURE_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_signed_request_verifies() {
+        let path = "/api/replication/registry/batch-register.json";
+        let signer = RequestSigner::new("cluster-secret", 300);
+        let client = ReplicationClient::default().with_signer(Some(signer.clone()));
+        let request =
+            client.post_json("http://peer:8080", path, &vec!["a", "b"]).unwrap().build().unwrap();
+
+        let header = |name| request.headers().get(name).and_then(|v| v.to_str().ok());
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        assert!(
+            signer
+                .verify(
+                    header(TIMESTAMP_HEADER),
+                    header(NONCE_HEADER),
+                    header(SIGNATURE_HEADER),
+                    "POST",
+                    path,
+                    body
+                )
+                .is_ok()
+        );
+    }
 }
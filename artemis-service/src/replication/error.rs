@@ -18,6 +18,8 @@ pub enum ReplicationErrorKind {
     ServiceUnavailable,
     /// 400 Bad Request - 不可重试
     BadRequest,
+    /// 404/405 对等节点不支持该接口 (旧版本节点) - 不可重试
+    Unsupported,
     /// 其他永久失败 - 不可重试
     PermanentFailure,
 }
@@ -43,6 +45,7 @@ impl ReplicationError {
             429 => ReplicationErrorKind::RateLimited,
             503 => ReplicationErrorKind::ServiceUnavailable,
             400 => ReplicationErrorKind::BadRequest,
+            404 | 405 => ReplicationErrorKind::Unsupported,
             _ => ReplicationErrorKind::PermanentFailure,
         };
 
@@ -106,5 +109,9 @@ mod tests {
         let err = ReplicationError::from_status(reqwest::StatusCode::BAD_REQUEST);
         assert_eq!(err.kind, ReplicationErrorKind::BadRequest);
         assert!(!err.is_retryable());
+
+        let err = ReplicationError::from_status(reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(err.kind, ReplicationErrorKind::Unsupported);
+        assert!(!err.is_retryable());
     }
 }
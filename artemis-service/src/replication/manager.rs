@@ -1,19 +1,24 @@
+use super::stats::ReplicationStats;
 use super::worker::ReplicationWorker;
 use crate::cluster::ClusterManager;
 use crate::config::ReplicationConfig;
-use artemis_common::model::{Instance, InstanceKey};
+use crate::registry::RegistryRepository;
+use crate::security::RequestSigner;
+use artemis_common::model::{Instance, InstanceKey, Revision};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tracing::info;
 
 /// 复制事件类型
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ReplicationEvent {
-    /// 注册事件
-    Register(Instance),
-    /// 注销事件
-    Unregister(InstanceKey),
+    /// 注册事件 (携带本地分配的修订号)
+    Register(Instance, Revision),
+    /// 注销事件 (携带本地分配的修订号)
+    Unregister(InstanceKey, Revision),
     /// 心跳事件
     Heartbeat(InstanceKey),
 }
@@ -29,25 +34,31 @@ pub enum ReplicationEvent {
 #[derive(Clone)]
 pub struct ReplicationManager {
     event_tx: Arc<mpsc::UnboundedSender<ReplicationEvent>>,
+    stats: Arc<ReplicationStats>,
 }
 
 impl ReplicationManager {
     pub fn new() -> (Self, mpsc::UnboundedReceiver<ReplicationEvent>) {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
-        (Self { event_tx: Arc::new(event_tx) }, event_rx)
+        (Self { event_tx: Arc::new(event_tx), stats: Arc::new(ReplicationStats::new()) }, event_rx)
+    }
+
+    /// 复制统计 (由工作器更新)
+    pub fn stats(&self) -> &Arc<ReplicationStats> {
+        &self.stats
     }
 
     /// 发布注册事件
-    pub fn publish_register(&self, instance: Instance) {
-        if let Err(e) = self.event_tx.send(ReplicationEvent::Register(instance)) {
+    pub fn publish_register(&self, instance: Instance, revision: Revision) {
+        if let Err(e) = self.event_tx.send(ReplicationEvent::Register(instance, revision)) {
             tracing::error!("Failed to publish register event: {}", e);
         }
     }
 
     /// 发布注销事件
-    pub fn publish_unregister(&self, key: InstanceKey) {
-        if let Err(e) = self.event_tx.send(ReplicationEvent::Unregister(key)) {
+    pub fn publish_unregister(&self, key: InstanceKey, revision: Revision) {
+        if let Err(e) = self.event_tx.send(ReplicationEvent::Unregister(key, revision)) {
             tracing::error!("Failed to publish unregister event: {}", e);
         }
     }
@@ -64,9 +75,15 @@ impl ReplicationManager {
         event_rx: mpsc::UnboundedReceiver<ReplicationEvent>,
         cluster_manager: Arc<ClusterManager>,
         config: ReplicationConfig,
+        signer: Option<RequestSigner>,
+        stats: Arc<ReplicationStats>,
+        repository: RegistryRepository,
     ) -> JoinHandle<()> {
         info!("Starting replication worker");
-        let worker = ReplicationWorker::new(event_rx, cluster_manager, config);
+        let worker = ReplicationWorker::new(event_rx, cluster_manager, config)
+            .with_signer(signer)
+            .with_stats(stats)
+            .with_repository(repository);
         worker.start()
     }
 
@@ -78,10 +95,10 @@ impl ReplicationManager {
 
             while let Some(event) = event_rx.recv().await {
                 match event {
-                    ReplicationEvent::Register(instance) => {
+                    ReplicationEvent::Register(instance, _) => {
                         info!("Would replicate register: {}", instance.instance_id);
                     }
-                    ReplicationEvent::Unregister(key) => {
+                    ReplicationEvent::Unregister(key, _) => {
                         info!("Would replicate unregister: {}", key.instance_id);
                     }
                     ReplicationEvent::Heartbeat(key) => {
@@ -128,11 +145,11 @@ mod tests {
         let (manager, mut rx) = ReplicationManager::new();
 
         let instance = create_test_instance();
-        manager.publish_register(instance.clone());
+        manager.publish_register(instance.clone(), Revision::default());
 
         let event = rx.recv().await.unwrap();
         match event {
-            ReplicationEvent::Register(inst) => {
+            ReplicationEvent::Register(inst, _) => {
                 assert_eq!(inst.instance_id, "inst-1");
             }
             _ => panic!("Unexpected event type"),
@@ -150,13 +167,15 @@ mod tests {
             service_id: "service".to_string(),
             instance_id: "inst-1".to_string(),
         };
-        manager.publish_unregister(key.clone());
+        let revision = Revision { clock: 42, node_id: "node-1".to_string() };
+        manager.publish_unregister(key.clone(), revision.clone());
 
         let event = rx.recv().await.unwrap();
         match event {
-            ReplicationEvent::Unregister(k) => {
+            ReplicationEvent::Unregister(k, r) => {
                 assert_eq!(k.instance_id, "inst-1");
                 assert_eq!(k.service_id, "service");
+                assert_eq!(r, revision);
             }
             _ => panic!("Expected Unregister event"),
         }
@@ -198,13 +217,13 @@ mod tests {
             instance_id: "inst-2".to_string(),
         };
 
-        manager.publish_register(instance.clone());
+        manager.publish_register(instance.clone(), Revision::default());
         manager.publish_heartbeat(key.clone());
-        manager.publish_unregister(key.clone());
+        manager.publish_unregister(key.clone(), Revision::default());
 
         // 第一个事件: Register
         let event1 = rx.recv().await.unwrap();
-        assert!(matches!(event1, ReplicationEvent::Register(_)));
+        assert!(matches!(event1, ReplicationEvent::Register(..)));
 
         // 第二个事件: Heartbeat
         let event2 = rx.recv().await.unwrap();
@@ -212,7 +231,7 @@ mod tests {
 
         // 第三个事件: Unregister
         let event3 = rx.recv().await.unwrap();
-        assert!(matches!(event3, ReplicationEvent::Unregister(_)));
+        assert!(matches!(event3, ReplicationEvent::Unregister(..)));
     }
 
     #[tokio::test]
@@ -220,7 +239,7 @@ mod tests {
         let manager = ReplicationManager::default();
 
         let instance = create_test_instance();
-        manager.publish_register(instance);
+        manager.publish_register(instance, Revision::default());
 
         // Default manager 丢弃了接收器,事件会被发送但不会被接收
         // 这个测试确保 default() 不会 panic
@@ -229,11 +248,11 @@ mod tests {
     #[test]
     fn test_replication_event_clone() {
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance.clone());
+        let event = ReplicationEvent::Register(instance.clone(), Revision::default());
         let cloned = event.clone();
 
         match (event, cloned) {
-            (ReplicationEvent::Register(i1), ReplicationEvent::Register(i2)) => {
+            (ReplicationEvent::Register(i1, _), ReplicationEvent::Register(i2, _)) => {
                 assert_eq!(i1.instance_id, i2.instance_id);
             }
             _ => panic!("Clone failed"),
@@ -243,7 +262,7 @@ mod tests {
     #[test]
     fn test_replication_event_debug() {
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let debug_str = format!("{:?}", event);
         assert!(debug_str.contains("Register"));
     }
@@ -254,11 +273,11 @@ mod tests {
         let manager2 = manager1.clone();
 
         let instance = create_test_instance();
-        manager2.publish_register(instance.clone());
+        manager2.publish_register(instance.clone(), Revision::default());
 
         let event = rx.recv().await.unwrap();
         match event {
-            ReplicationEvent::Register(inst) => {
+            ReplicationEvent::Register(inst, _) => {
                 assert_eq!(inst.instance_id, "inst-1");
             }
             _ => panic!("Unexpected event type"),
@@ -285,7 +304,7 @@ mod tests {
 
         let instance = create_test_instance();
         // 发送应该失败,但不会 panic
-        manager.publish_register(instance);
+        manager.publish_register(instance, Revision::default());
     }
 
     #[tokio::test]
@@ -298,26 +317,26 @@ mod tests {
         let mut instance3 = create_test_instance();
         instance3.instance_id = "inst-3".to_string();
 
-        manager.publish_register(instance1);
-        manager.publish_register(instance2);
-        manager.publish_register(instance3);
+        manager.publish_register(instance1, Revision::default());
+        manager.publish_register(instance2, Revision::default());
+        manager.publish_register(instance3, Revision::default());
 
         // 验证事件顺序
         let event1 = rx.recv().await.unwrap();
         match event1 {
-            ReplicationEvent::Register(i) => assert_eq!(i.instance_id, "inst-1"),
+            ReplicationEvent::Register(i, _) => assert_eq!(i.instance_id, "inst-1"),
             _ => panic!("Expected Register event"),
         }
 
         let event2 = rx.recv().await.unwrap();
         match event2 {
-            ReplicationEvent::Register(i) => assert_eq!(i.instance_id, "inst-2"),
+            ReplicationEvent::Register(i, _) => assert_eq!(i.instance_id, "inst-2"),
             _ => panic!("Expected Register event"),
         }
 
         let event3 = rx.recv().await.unwrap();
         match event3 {
-            ReplicationEvent::Register(i) => assert_eq!(i.instance_id, "inst-3"),
+            ReplicationEvent::Register(i, _) => assert_eq!(i.instance_id, "inst-3"),
             _ => panic!("Expected Register event"),
         }
     }
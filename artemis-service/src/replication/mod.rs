@@ -5,13 +5,31 @@
 //! - Batch replication optimization
 //! - HTTP-based replication client
 //! - Error handling and retry logic
+//! - Bootstrap full sync from peers on startup
+//! - Periodic anti-entropy reconciliation between peers
+//! - Per-peer replication statistics (queue depth, failures, lag)
+//! - Durable per-peer outbox for events to unavailable peers
+//! - Per-peer sender tasks with bounded queues and concurrency limits
+//! - Optional persistent streaming channel (WebSocket + CBOR) with batch fallback
 
+pub mod anti_entropy;
+pub mod bootstrap;
 pub mod client;
 pub mod error;
 pub mod manager;
+pub mod outbox;
+pub mod peer;
+pub mod stats;
+pub mod stream;
 pub mod worker;
 
+pub use anti_entropy::{AntiEntropyListener, AntiEntropyTask, PeerReconciliation};
+pub use bootstrap::{BootstrapOutcome, BootstrapSync};
 pub use client::ReplicationClient;
 pub use error::{ReplicationError, ReplicationErrorKind};
 pub use manager::{ReplicationEvent, ReplicationManager};
+pub use outbox::{EventBatch, Outbox, PeerOutbox};
+pub use peer::{PeerJob, PeerOutcome, PeerSender};
+pub use stats::{OutboxSummary, ReplicationStats};
+pub use stream::{STREAM_PATH, StreamFrame, StreamTransport};
 pub use worker::ReplicationWorker;
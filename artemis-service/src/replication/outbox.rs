@@ -0,0 +1,444 @@
+use super::manager::ReplicationEvent;
+use artemis_common::model::{Instance, InstanceKey, Revision};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// 发件箱中的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    pub event: ReplicationEvent,
+    /// 入队时间 (毫秒时间戳)
+    pub queued_at: i64,
+}
+
+/// 同类事件批次 (批量发送或发件箱重放), 修订号与实例一一对应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventBatch {
+    Registers(Vec<Instance>, Vec<Revision>),
+    Heartbeats(Vec<InstanceKey>),
+    Unregisters(Vec<InstanceKey>, Vec<Revision>),
+}
+
+impl EventBatch {
+    pub fn len(&self) -> usize {
+        match self {
+            EventBatch::Registers(instances, _) => instances.len(),
+            EventBatch::Heartbeats(keys) | EventBatch::Unregisters(keys, _) => keys.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 事件类型 (日志)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EventBatch::Registers(..) => "registers",
+            EventBatch::Heartbeats(_) => "heartbeats",
+            EventBatch::Unregisters(..) => "unregisters",
+        }
+    }
+
+    /// 拆分为单个事件 (加入重试队列或发件箱)
+    pub fn into_events(self) -> Vec<ReplicationEvent> {
+        match self {
+            EventBatch::Registers(instances, revisions) => instances
+                .into_iter()
+                .zip(revisions)
+                .map(|(instance, revision)| ReplicationEvent::Register(instance, revision))
+                .collect(),
+            EventBatch::Heartbeats(keys) => {
+                keys.into_iter().map(ReplicationEvent::Heartbeat).collect()
+            }
+            EventBatch::Unregisters(keys, revisions) => keys
+                .into_iter()
+                .zip(revisions)
+                .map(|(key, revision)| ReplicationEvent::Unregister(key, revision))
+                .collect(),
+        }
+    }
+}
+
+/// 发件箱文件内容
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OutboxFile {
+    node_id: String,
+    /// 溢出时间 (毫秒时间戳), 不为空表示需要全量同步
+    full_sync_since: Option<i64>,
+    entries: VecDeque<OutboxEntry>,
+}
+
+/// 单个对等节点的发件箱
+///
+/// 按顺序保存对等节点不可用期间的事件。同一实例的心跳只保留一条 (重放时续约一次即可),
+/// 事件数超过容量时丢弃全部事件并标记需要全量同步
+#[derive(Debug)]
+pub struct PeerOutbox {
+    node_id: String,
+    entries: VecDeque<OutboxEntry>,
+    full_sync_since: Option<i64>,
+    heartbeats: HashSet<InstanceKey>,
+    capacity: usize,
+    dirty: bool,
+}
+
+impl PeerOutbox {
+    fn new(node_id: String, capacity: usize) -> Self {
+        Self {
+            node_id,
+            entries: VecDeque::new(),
+            full_sync_since: None,
+            heartbeats: HashSet::new(),
+            capacity,
+            dirty: false,
+        }
+    }
+
+    fn from_file(file: OutboxFile, capacity: usize) -> Self {
+        let heartbeats = file
+            .entries
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                ReplicationEvent::Heartbeat(key) => Some(key.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            node_id: file.node_id,
+            entries: file.entries,
+            full_sync_since: file.full_sync_since,
+            heartbeats,
+            capacity,
+            dirty: false,
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 是否因为溢出需要全量同步
+    pub fn full_sync_required(&self) -> bool {
+        self.full_sync_since.is_some()
+    }
+
+    /// 是否有待重放的事件或全量同步
+    pub fn has_pending(&self) -> bool {
+        !self.entries.is_empty() || self.full_sync_required()
+    }
+
+    /// 最早一个未送达事件的入队时间 (需要全量同步时为溢出时间)
+    pub fn oldest_queued_at(&self) -> Option<i64> {
+        self.full_sync_since.or_else(|| self.entries.front().map(|entry| entry.queued_at))
+    }
+
+    /// 追加事件, 返回因溢出丢弃的事件数
+    pub fn push(&mut self, event: ReplicationEvent) -> usize {
+        // 全量同步会覆盖之后的所有事件
+        if self.full_sync_required() {
+            return 0;
+        }
+        if let ReplicationEvent::Heartbeat(key) = &event
+            && !self.heartbeats.insert(key.clone())
+        {
+            return 0;
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        self.dirty = true;
+        if self.entries.len() >= self.capacity {
+            let discarded = self.entries.len() + 1;
+            self.entries.clear();
+            self.heartbeats.clear();
+            self.full_sync_since = Some(now);
+            return discarded;
+        }
+
+        self.entries.push_back(OutboxEntry { event, queued_at: now });
+        0
+    }
+
+    /// 头部连续的同类事件, 最多 max 个
+    pub fn next_batch(&self, max: usize) -> Option<EventBatch> {
+        let mut batch = match &self.entries.front()?.event {
+            ReplicationEvent::Register(..) => EventBatch::Registers(Vec::new(), Vec::new()),
+            ReplicationEvent::Heartbeat(_) => EventBatch::Heartbeats(Vec::new()),
+            ReplicationEvent::Unregister(..) => EventBatch::Unregisters(Vec::new(), Vec::new()),
+        };
+
+        for entry in self.entries.iter().take(max.max(1)) {
+            match (&mut batch, &entry.event) {
+                (
+                    EventBatch::Registers(instances, revisions),
+                    ReplicationEvent::Register(instance, revision),
+                ) => {
+                    instances.push(instance.clone());
+                    revisions.push(revision.clone());
+                }
+                (EventBatch::Heartbeats(keys), ReplicationEvent::Heartbeat(key)) => {
+                    keys.push(key.clone())
+                }
+                (
+                    EventBatch::Unregisters(keys, revisions),
+                    ReplicationEvent::Unregister(key, revision),
+                ) => {
+                    keys.push(key.clone());
+                    revisions.push(revision.clone());
+                }
+                _ => break,
+            }
+        }
+        Some(batch)
+    }
+
+    /// 确认头部 count 个事件已处理
+    pub fn ack(&mut self, count: usize) {
+        for _ in 0..count {
+            let Some(entry) = self.entries.pop_front() else {
+                break;
+            };
+            if let ReplicationEvent::Heartbeat(key) = entry.event {
+                self.heartbeats.remove(&key);
+            }
+            self.dirty = true;
+        }
+    }
+
+    /// 全量同步完成
+    pub fn complete_full_sync(&mut self) {
+        self.full_sync_since = None;
+        self.dirty = true;
+    }
+
+    fn to_file(&self) -> OutboxFile {
+        OutboxFile {
+            node_id: self.node_id.clone(),
+            full_sync_since: self.full_sync_since,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+/// 所有对等节点的发件箱
+///
+/// 配置了目录时每个对等节点保存为一个 JSON 文件, 发送方重启后继续重放未送达的事件
+#[derive(Debug)]
+pub struct Outbox {
+    peers: HashMap<String, PeerOutbox>,
+    capacity: usize,
+    dir: Option<PathBuf>,
+}
+
+impl Outbox {
+    /// 只保存在内存中的发件箱
+    pub fn in_memory(capacity: usize) -> Self {
+        Self { peers: HashMap::new(), capacity, dir: None }
+    }
+
+    /// 持久化到目录, 并加载上次退出时未送达的事件
+    pub fn open(capacity: usize, dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut peers = HashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match std::fs::read(&path).map(|data| serde_json::from_slice::<OutboxFile>(&data)) {
+                Ok(Ok(file)) => {
+                    let outbox = PeerOutbox::from_file(file, capacity);
+                    peers.insert(outbox.node_id.clone(), outbox);
+                }
+                Ok(Err(e)) => warn!("Ignoring corrupt replication outbox {:?}: {}", path, e),
+                Err(e) => warn!("Failed to read replication outbox {:?}: {}", path, e),
+            }
+        }
+
+        let pending: usize = peers.values().map(PeerOutbox::len).sum();
+        if !peers.is_empty() {
+            info!("Loaded {} pending replication events for {} peers", pending, peers.len());
+        }
+        Ok(Self { peers, capacity, dir: Some(dir) })
+    }
+
+    pub fn get(&self, node_id: &str) -> Option<&PeerOutbox> {
+        self.peers.get(node_id)
+    }
+
+    pub fn get_mut(&mut self, node_id: &str) -> Option<&mut PeerOutbox> {
+        self.peers.get_mut(node_id)
+    }
+
+    /// 获取或创建对等节点的发件箱
+    pub fn peer_mut(&mut self, node_id: &str) -> &mut PeerOutbox {
+        let capacity = self.capacity;
+        self.peers
+            .entry(node_id.to_string())
+            .or_insert_with(|| PeerOutbox::new(node_id.to_string(), capacity))
+    }
+
+    /// 对等节点是否有待重放的事件或全量同步
+    pub fn has_pending(&self, node_id: &str) -> bool {
+        self.peers.get(node_id).is_some_and(PeerOutbox::has_pending)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PeerOutbox> {
+        self.peers.values()
+    }
+
+    pub fn node_ids(&self) -> Vec<String> {
+        self.peers.keys().cloned().collect()
+    }
+
+    /// 删除对等节点的发件箱 (节点已退出集群)
+    pub fn remove(&mut self, node_id: &str) -> Option<PeerOutbox> {
+        let outbox = self.peers.remove(node_id)?;
+        if let Some(dir) = &self.dir {
+            remove_file(&dir.join(file_name(node_id)));
+        }
+        Some(outbox)
+    }
+
+    /// 把有变化的发件箱写入磁盘 (先写临时文件再重命名, 避免写到一半时退出)
+    pub fn persist(&mut self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        for outbox in self.peers.values_mut().filter(|outbox| outbox.dirty) {
+            let path = dir.join(file_name(&outbox.node_id));
+            if !outbox.has_pending() {
+                remove_file(&path);
+                outbox.dirty = false;
+                continue;
+            }
+
+            let tmp = path.with_extension("json.tmp");
+            let result = serde_json::to_vec(&outbox.to_file())
+                .map_err(std::io::Error::other)
+                .and_then(|data| std::fs::write(&tmp, data))
+                .and_then(|_| std::fs::rename(&tmp, &path));
+            match result {
+                Ok(()) => outbox.dirty = false,
+                Err(e) => {
+                    warn!("Failed to persist replication outbox for {}: {}", outbox.node_id, e)
+                }
+            }
+        }
+    }
+}
+
+/// 节点 ID 可能包含 `:` 等字符, 文件名使用十六进制编码
+fn file_name(node_id: &str) -> String {
+    format!("{}.json", hex::encode(node_id))
+}
+
+fn remove_file(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("Failed to remove replication outbox {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: &str) -> InstanceKey {
+        InstanceKey {
+            region_id: "test".to_string(),
+            zone_id: "zone".to_string(),
+            service_id: "service".to_string(),
+            group_id: String::new(),
+            instance_id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_batches_preserve_order() {
+        let mut outbox = Outbox::in_memory(100);
+        let peer = outbox.peer_mut("node-2");
+        peer.push(ReplicationEvent::Heartbeat(key("inst-1")));
+        peer.push(ReplicationEvent::Heartbeat(key("inst-2")));
+        // 重复的心跳只保留一条
+        peer.push(ReplicationEvent::Heartbeat(key("inst-1")));
+        peer.push(ReplicationEvent::Unregister(key("inst-1"), Revision::default()));
+        assert_eq!(peer.len(), 3);
+
+        let batch = peer.next_batch(10).unwrap();
+        assert!(matches!(&batch, EventBatch::Heartbeats(keys) if keys.len() == 2));
+        peer.ack(batch.len());
+
+        let batch = peer.next_batch(10).unwrap();
+        assert!(matches!(&batch, EventBatch::Unregisters(keys, _) if keys[0] == key("inst-1")));
+        peer.ack(batch.len());
+        assert!(peer.next_batch(10).is_none());
+
+        // 心跳送达后可以再次入队
+        peer.push(ReplicationEvent::Heartbeat(key("inst-1")));
+        assert_eq!(peer.len(), 1);
+    }
+
+    #[test]
+    fn test_overflow_requires_full_sync() {
+        let mut outbox = Outbox::in_memory(2);
+        let peer = outbox.peer_mut("node-2");
+        assert_eq!(peer.push(ReplicationEvent::Unregister(key("inst-1"), Revision::default())), 0);
+        assert_eq!(peer.push(ReplicationEvent::Unregister(key("inst-2"), Revision::default())), 0);
+        assert_eq!(peer.push(ReplicationEvent::Unregister(key("inst-3"), Revision::default())), 3);
+
+        assert!(peer.is_empty());
+        assert!(peer.full_sync_required());
+        // 全量同步完成前不再保存事件
+        assert_eq!(peer.push(ReplicationEvent::Unregister(key("inst-4"), Revision::default())), 0);
+        assert!(peer.is_empty());
+
+        peer.complete_full_sync();
+        assert!(!outbox.has_pending("node-2"));
+    }
+
+    #[test]
+    fn test_persist_and_reload() {
+        let dir = std::env::temp_dir().join(format!("artemis-outbox-{}", uuid::Uuid::new_v4()));
+
+        let mut outbox = Outbox::open(100, &dir).unwrap();
+        outbox
+            .peer_mut("10.0.0.2:8080")
+            .push(ReplicationEvent::Unregister(key("inst-1"), Revision::default()));
+        outbox.peer_mut("10.0.0.2:8080").push(ReplicationEvent::Heartbeat(key("inst-2")));
+        outbox
+            .peer_mut("10.0.0.3:8080")
+            .push(ReplicationEvent::Unregister(key("inst-1"), Revision::default()));
+        outbox.persist();
+
+        let mut reloaded = Outbox::open(100, &dir).unwrap();
+        let peer = reloaded.get("10.0.0.2:8080").unwrap();
+        assert_eq!(peer.len(), 2);
+        assert!(matches!(peer.next_batch(10), Some(EventBatch::Unregisters(..))));
+
+        // 重放完的发件箱删除文件, 已退出的节点删除发件箱
+        reloaded.get_mut("10.0.0.2:8080").unwrap().ack(2);
+        reloaded.remove("10.0.0.3:8080");
+        reloaded.persist();
+        assert!(Outbox::open(100, &dir).unwrap().iter().next().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
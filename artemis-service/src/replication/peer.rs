@@ -0,0 +1,390 @@
+use super::client::ReplicationClient;
+use super::error::{ReplicationError, ReplicationErrorKind};
+use super::manager::ReplicationEvent;
+use super::outbox::EventBatch;
+use super::stream::StreamTransport;
+use crate::cluster::protocol::{CAPABILITY_BATCH, CAPABILITY_STREAM, ProtocolInfo};
+use crate::config::ReplicationConfig;
+use crate::registry::RegistryRepository;
+use artemis_common::model::{
+    BatchRegisterRequest, BatchUnregisterRequest, ReplicateHeartbeatRequest,
+    ReplicateRegisterRequest, ReplicateUnregisterRequest,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Sender, UnboundedSender};
+use tracing::{debug, info};
+
+/// 发往单个对等节点的复制任务
+#[derive(Debug)]
+pub enum PeerJob {
+    /// 新事件批次
+    Send(EventBatch),
+    /// 重试单个事件
+    Retry { event: Box<ReplicationEvent>, retry_count: u32, first_queued: Instant },
+    /// 发件箱重放批次 (同一节点同时只有一个, 保证重放顺序)
+    Replay(EventBatch),
+    /// 发件箱溢出后推送本地全部实例
+    FullSync,
+}
+
+impl PeerJob {
+    /// 任务包含的事件 (发送失败时加入重试队列或发件箱)
+    pub fn into_events(self) -> Vec<ReplicationEvent> {
+        match self {
+            PeerJob::Send(batch) | PeerJob::Replay(batch) => batch.into_events(),
+            PeerJob::Retry { event, .. } => vec![*event],
+            PeerJob::FullSync => Vec::new(),
+        }
+    }
+}
+
+/// 复制任务的执行结果, 由复制工作器处理
+#[derive(Debug)]
+pub struct PeerOutcome {
+    pub node_id: String,
+    pub job: PeerJob,
+    pub result: Result<(), ReplicationError>,
+}
+
+/// 单个对等节点的复制发送器
+///
+/// 每个节点一个后台任务, 有自己的有界任务队列和并发上限, 慢节点或宕机节点只会占满自己的队列,
+/// 不影响其他节点和复制工作器的事件循环。同一节点的多个请求可能乱序送达, 由修订号保证结果正确。
+/// 启用复制流时批次优先通过长连接发送, 未确认的批次数同样受并发上限约束。
+/// 使用哪些接口由与对等节点协商的协议决定, 旧版本节点回退到单事件端点
+pub struct PeerSender {
+    jobs: Sender<PeerJob>,
+    protocol: Arc<PeerProtocol>,
+}
+
+impl PeerSender {
+    /// 启动节点的发送任务, 执行结果发送到 outcomes
+    pub fn spawn(
+        node_id: String,
+        peer_url: String,
+        client: ReplicationClient,
+        repository: Option<RegistryRepository>,
+        config: &ReplicationConfig,
+        outcomes: UnboundedSender<PeerOutcome>,
+    ) -> Self {
+        let (jobs, mut job_rx) = mpsc::channel(config.peer_queue_capacity.max(1));
+        let semaphore = Arc::new(Semaphore::new(config.peer_concurrency.max(1)));
+        let protocol = Arc::new(PeerProtocol::default());
+        let link = Arc::new(PeerLink {
+            stream: config
+                .stream_enabled
+                .then(|| StreamTransport::new(client.clone(), peer_url.clone())),
+            client,
+            peer_url,
+            protocol: protocol.clone(),
+            repository,
+            batch_size: config.batch_size.max(1),
+        });
+
+        tokio::spawn(async move {
+            // 先取得并发名额再取任务, 等待中的任务留在队列里计入队列长度
+            while let Ok(permit) = semaphore.clone().acquire_owned().await {
+                let Some(job) = job_rx.recv().await else {
+                    break;
+                };
+                let node_id = node_id.clone();
+                let link = link.clone();
+                let outcomes = outcomes.clone();
+                tokio::spawn(async move {
+                    let result = link.execute(&job).await;
+                    drop(permit);
+                    let _ = outcomes.send(PeerOutcome { node_id, job, result });
+                });
+            }
+            debug!("Replication sender for {} stopped", node_id);
+        });
+
+        Self { jobs, protocol }
+    }
+
+    /// 提交任务, 队列已满时返回该任务
+    pub fn submit(&self, job: PeerJob) -> Result<(), PeerJob> {
+        self.jobs.try_send(job).map_err(|e| match e {
+            TrySendError::Full(job) | TrySendError::Closed(job) => job,
+        })
+    }
+
+    /// 队列是否已满
+    pub fn is_full(&self) -> bool {
+        self.jobs.capacity() == 0
+    }
+
+    /// 更新对等节点公布的协议 (来自成员信息或健康检查)
+    pub fn set_protocol(&self, remote: Option<ProtocolInfo>) {
+        self.protocol.set_remote(remote);
+    }
+}
+
+/// 与对等节点协商的复制协议
+#[derive(Default)]
+struct PeerProtocol {
+    /// 对等节点公布的协议, None 表示尚未得知, 先尝试新接口, 不支持时回退
+    remote: Mutex<Option<ProtocolInfo>>,
+    /// 批量端点返回 404/405, 对等节点公布新的协议之前使用单事件端点
+    batch_unsupported: AtomicBool,
+}
+
+impl PeerProtocol {
+    fn set_remote(&self, remote: Option<ProtocolInfo>) {
+        let mut current = self.remote.lock();
+        if *current != remote {
+            *current = remote;
+            self.batch_unsupported.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn supports(&self, capability: &str) -> bool {
+        match &*self.remote.lock() {
+            Some(remote) => ProtocolInfo::local().negotiate(remote).supports(capability),
+            None => true,
+        }
+    }
+
+    /// 协商的版本是否携带修订号, 尚未得知对等节点协议时携带 (旧版本节点会忽略未知字段)
+    fn supports_revisions(&self) -> bool {
+        match &*self.remote.lock() {
+            Some(remote) => ProtocolInfo::local().negotiate(remote).supports_revisions(),
+            None => true,
+        }
+    }
+
+    fn supports_batch(&self) -> bool {
+        self.supports(CAPABILITY_BATCH) && !self.batch_unsupported.load(Ordering::Relaxed)
+    }
+}
+
+/// 发送任务共享的节点连接信息
+struct PeerLink {
+    client: ReplicationClient,
+    peer_url: String,
+    stream: Option<StreamTransport>,
+    protocol: Arc<PeerProtocol>,
+    repository: Option<RegistryRepository>,
+    batch_size: usize,
+}
+
+impl PeerLink {
+    /// 执行复制任务
+    async fn execute(&self, job: &PeerJob) -> Result<(), ReplicationError> {
+        match job {
+            PeerJob::Send(batch) | PeerJob::Replay(batch) => self.send_batch(batch).await,
+            PeerJob::Retry { event, .. } => {
+                let revisions = self.protocol.supports_revisions();
+                send_event(&self.client, &self.peer_url, event, revisions).await
+            }
+            PeerJob::FullSync => match &self.repository {
+                Some(repository) => self.full_sync(repository).await,
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// 发送批次: 优先使用复制流, 其次批量端点, 对等节点都不支持时使用单事件端点
+    async fn send_batch(&self, batch: &EventBatch) -> Result<(), ReplicationError> {
+        if let Some(stream) = &self.stream
+            && self.protocol.supports(CAPABILITY_STREAM)
+            && let Some(result) = stream.send(batch).await
+        {
+            return result;
+        }
+
+        if self.protocol.supports_batch() {
+            match send_batch(&self.client, &self.peer_url, batch).await {
+                Err(e) if e.kind == ReplicationErrorKind::Unsupported => {
+                    info!(
+                        "Peer {} does not support batch replication, using per-event endpoints",
+                        self.peer_url
+                    );
+                    self.protocol.batch_unsupported.store(true, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+        let revisions = self.protocol.supports_revisions();
+        send_events(&self.client, &self.peer_url, batch, revisions).await
+    }
+
+    /// 全量同步: 推送本地所有实例
+    ///
+    /// 溢出期间在本地注销的实例不会再有心跳, 在对等节点上由租约过期清理
+    async fn full_sync(&self, repository: &RegistryRepository) -> Result<(), ReplicationError> {
+        let instances = repository.get_all_instances();
+        for chunk in instances.chunks(self.batch_size) {
+            let revisions = chunk
+                .iter()
+                .map(|instance| repository.revisions().current(&instance.key()).unwrap_or_default())
+                .collect();
+            self.send_batch(&EventBatch::Registers(chunk.to_vec(), revisions)).await?;
+        }
+        info!("Full sync of {} instances to {} completed", instances.len(), self.peer_url);
+        Ok(())
+    }
+}
+
+/// 使用批量 API 发送批次
+async fn send_batch(
+    client: &ReplicationClient,
+    peer_url: &str,
+    batch: &EventBatch,
+) -> Result<(), ReplicationError> {
+    match batch.clone() {
+        EventBatch::Registers(instances, revisions) => client
+            .batch_register(peer_url, BatchRegisterRequest { instances, revisions })
+            .await
+            .map(|_| ()),
+        EventBatch::Heartbeats(instance_keys) => client
+            .replicate_heartbeat(peer_url, ReplicateHeartbeatRequest { instance_keys })
+            .await
+            .map(|_| ()),
+        EventBatch::Unregisters(instance_keys, revisions) => client
+            .batch_unregister(peer_url, BatchUnregisterRequest { instance_keys, revisions })
+            .await
+            .map(|_| ()),
+    }
+}
+
+/// 使用单事件端点 (协议版本 1) 发送批次, 这些端点同样接受多个实例
+///
+/// revisions 为 false 时 (协商的版本为 1) 请求不携带修订号
+async fn send_events(
+    client: &ReplicationClient,
+    peer_url: &str,
+    batch: &EventBatch,
+    revisions: bool,
+) -> Result<(), ReplicationError> {
+    let revisions = |list: Vec<_>| if revisions { list } else { Vec::new() };
+    match batch.clone() {
+        EventBatch::Registers(instances, list) => client
+            .replicate_register(
+                peer_url,
+                ReplicateRegisterRequest { instances, revisions: revisions(list) },
+            )
+            .await
+            .map(|_| ()),
+        EventBatch::Heartbeats(instance_keys) => client
+            .replicate_heartbeat(peer_url, ReplicateHeartbeatRequest { instance_keys })
+            .await
+            .map(|_| ()),
+        EventBatch::Unregisters(instance_keys, list) => client
+            .replicate_unregister(
+                peer_url,
+                ReplicateUnregisterRequest { instance_keys, revisions: revisions(list) },
+            )
+            .await
+            .map(|_| ()),
+    }
+}
+
+/// 发送单个事件, revisions 为 false 时请求不携带修订号
+async fn send_event(
+    client: &ReplicationClient,
+    peer_url: &str,
+    event: &ReplicationEvent,
+    revisions: bool,
+) -> Result<(), ReplicationError> {
+    let revisions = |revision| if revisions { vec![revision] } else { Vec::new() };
+    match event.clone() {
+        ReplicationEvent::Register(instance, revision) => {
+            let request = ReplicateRegisterRequest {
+                instances: vec![instance],
+                revisions: revisions(revision),
+            };
+            client.replicate_register(peer_url, request).await.map(|_| ())
+        }
+        ReplicationEvent::Heartbeat(key) => {
+            let request = ReplicateHeartbeatRequest { instance_keys: vec![key] };
+            client.replicate_heartbeat(peer_url, request).await.map(|_| ())
+        }
+        ReplicationEvent::Unregister(key, revision) => {
+            let request = ReplicateUnregisterRequest {
+                instance_keys: vec![key],
+                revisions: revisions(revision),
+            };
+            client.replicate_unregister(peer_url, request).await.map(|_| ())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_submit_rejects_when_queue_full() {
+        // 接受连接但从不响应, 请求一直挂起
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let peer_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let config =
+            ReplicationConfig { peer_concurrency: 1, peer_queue_capacity: 1, ..Default::default() };
+        let (outcomes, _outcome_rx) = mpsc::unbounded_channel();
+        let sender = PeerSender::spawn(
+            "node-2".to_string(),
+            peer_url,
+            ReplicationClient::default(),
+            None,
+            &config,
+            outcomes,
+        );
+        let job = || PeerJob::Send(EventBatch::Heartbeats(Vec::new()));
+
+        // 第一个任务占用唯一的并发名额, 第二个任务填满队列
+        assert!(sender.submit(job()).is_ok());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sender.submit(job()).is_ok());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sender.is_full());
+        assert!(matches!(sender.submit(job()), Err(PeerJob::Send(_))));
+    }
+
+    #[test]
+    fn test_revisions_follow_negotiated_version() {
+        let protocol = PeerProtocol::default();
+        assert!(protocol.supports_revisions());
+
+        protocol.set_remote(Some(ProtocolInfo::legacy()));
+        assert!(!protocol.supports_revisions());
+        assert!(!protocol.supports_batch());
+
+        protocol.set_remote(Some(ProtocolInfo::local()));
+        assert!(protocol.supports_revisions());
+    }
+
+    #[tokio::test]
+    async fn test_outcome_reported() {
+        let config = ReplicationConfig::default();
+        let (outcomes, mut outcome_rx) = mpsc::unbounded_channel();
+        // 端口 1 上没有服务, 连接失败
+        let sender = PeerSender::spawn(
+            "node-2".to_string(),
+            "http://127.0.0.1:1".to_string(),
+            ReplicationClient::default(),
+            None,
+            &config,
+            outcomes,
+        );
+        sender.submit(PeerJob::Send(EventBatch::Heartbeats(Vec::new()))).unwrap();
+
+        let outcome = outcome_rx.recv().await.unwrap();
+        assert_eq!(outcome.node_id, "node-2");
+        assert!(matches!(outcome.job, PeerJob::Send(_)));
+        assert!(outcome.result.unwrap_err().is_retryable());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::cluster::{ClusterNode, ProtocolInfo};
+use crate::model::PeerReplicationStatus;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// 单个对等节点的复制统计
+#[derive(Debug, Default)]
+struct PeerStats {
+    last_success_time: Option<i64>,
+    consecutive_failures: u64,
+    dropped_events: u64,
+    retry_queue_depth: usize,
+    /// 重试队列中最早的事件第一次入队的时间
+    oldest_retry: Option<Instant>,
+    outbox: OutboxSummary,
+}
+
+/// 单个对等节点的发件箱统计
+#[derive(Debug, Clone, Default)]
+pub struct OutboxSummary {
+    pub depth: usize,
+    pub full_sync_pending: bool,
+    /// 最早一个未送达事件的入队时间
+    pub oldest: Option<Instant>,
+}
+
+/// 批处理缓冲区统计 (缓冲区由所有对等节点共享)
+#[derive(Debug, Default)]
+struct PendingStats {
+    registers: usize,
+    heartbeats: usize,
+    unregisters: usize,
+    /// 缓冲区从空变为非空的时间
+    since: Option<Instant>,
+}
+
+/// 复制统计
+///
+/// 由复制工作器更新, 状态端点和 Prometheus 指标读取快照
+#[derive(Debug, Default)]
+pub struct ReplicationStats {
+    peers: DashMap<String, PeerStats>,
+    pending: Mutex<PendingStats>,
+}
+
+impl ReplicationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新批处理缓冲区大小
+    pub fn set_pending(&self, registers: usize, heartbeats: usize, unregisters: usize) {
+        let mut pending = self.pending.lock();
+        let total = registers + heartbeats + unregisters;
+        pending.since = match (total, pending.since) {
+            (0, _) => None,
+            (_, None) => Some(Instant::now()),
+            (_, since) => since,
+        };
+        pending.registers = registers;
+        pending.heartbeats = heartbeats;
+        pending.unregisters = unregisters;
+    }
+
+    pub fn record_success(&self, node_id: &str) {
+        let mut peer = self.peers.entry(node_id.to_string()).or_default();
+        peer.last_success_time = Some(chrono::Utc::now().timestamp_millis());
+        peer.consecutive_failures = 0;
+    }
+
+    pub fn record_failure(&self, node_id: &str) {
+        self.peers.entry(node_id.to_string()).or_default().consecutive_failures += 1;
+    }
+
+    pub fn record_dropped(&self, node_id: &str, count: usize) {
+        self.peers.entry(node_id.to_string()).or_default().dropped_events += count as u64;
+    }
+
+    /// 更新各节点的重试队列深度和最早事件的入队时间
+    pub fn set_retry_queues(&self, queues: HashMap<String, (usize, Instant)>) {
+        for mut peer in self.peers.iter_mut() {
+            if !queues.contains_key(peer.key()) {
+                peer.retry_queue_depth = 0;
+                peer.oldest_retry = None;
+            }
+        }
+        for (node_id, (depth, oldest)) in queues {
+            let mut peer = self.peers.entry(node_id).or_default();
+            peer.retry_queue_depth = depth;
+            peer.oldest_retry = Some(oldest);
+        }
+    }
+
+    /// 更新各节点的发件箱统计
+    pub fn set_outboxes(&self, outboxes: HashMap<String, OutboxSummary>) {
+        for mut peer in self.peers.iter_mut() {
+            if !outboxes.contains_key(peer.key()) {
+                peer.outbox = OutboxSummary::default();
+            }
+        }
+        for (node_id, summary) in outboxes {
+            self.peers.entry(node_id).or_default().outbox = summary;
+        }
+    }
+
+    /// 生成对等节点的复制状态 (按节点 ID 排序)
+    pub fn snapshot(&self, peers: &[ClusterNode]) -> Vec<PeerReplicationStatus> {
+        let now = Instant::now();
+        let pending = self.pending.lock();
+
+        let mut statuses: Vec<PeerReplicationStatus> = peers
+            .iter()
+            .map(|node| {
+                let stats = self.peers.get(&node.node_id);
+                let stats = stats.as_deref();
+                let oldest_retry = stats.and_then(|stats| stats.oldest_retry);
+                // 健康节点还要等待缓冲区中的事件, 不健康的节点不会收到它们
+                let oldest_pending = pending.since.filter(|_| node.is_healthy());
+                let oldest_outbox = stats.and_then(|stats| stats.outbox.oldest);
+                let oldest =
+                    [oldest_pending, oldest_retry, oldest_outbox].into_iter().flatten().min();
+
+                PeerReplicationStatus {
+                    node_id: node.node_id.clone(),
+                    url: node.base_url(),
+                    healthy: node.is_healthy(),
+                    pending_registers: pending.registers,
+                    pending_heartbeats: pending.heartbeats,
+                    pending_unregisters: pending.unregisters,
+                    retry_queue_depth: stats.map_or(0, |stats| stats.retry_queue_depth),
+                    outbox_depth: stats.map_or(0, |stats| stats.outbox.depth),
+                    full_sync_pending: stats.is_some_and(|stats| stats.outbox.full_sync_pending),
+                    last_success_time: stats.and_then(|stats| stats.last_success_time),
+                    consecutive_failures: stats.map_or(0, |stats| stats.consecutive_failures),
+                    dropped_events: stats.map_or(0, |stats| stats.dropped_events),
+                    lag_ms: oldest
+                        .map_or(0, |oldest| now.duration_since(oldest).as_millis() as u64),
+                    protocol: node
+                        .protocol
+                        .as_ref()
+                        .map(|remote| ProtocolInfo::local().negotiate(remote)),
+                }
+            })
+            .collect();
+
+        statuses.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        statuses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_snapshot() {
+        let stats = ReplicationStats::new();
+        let node1 = ClusterNode::new("node-1".to_string(), "10.0.0.1".to_string(), 8080);
+        let node2 = ClusterNode::new("node-2".to_string(), "10.0.0.2".to_string(), 8080);
+
+        stats.record_failure("node-1");
+        stats.record_failure("node-1");
+        stats.record_dropped("node-1", 3);
+        stats.record_failure("node-2");
+        stats.record_success("node-2");
+        stats.set_pending(2, 5, 0);
+        let queued = Instant::now() - Duration::from_secs(10);
+        stats.set_retry_queues(HashMap::from([("node-1".to_string(), (4, queued))]));
+
+        let snapshot = stats.snapshot(&[node2.clone(), node1.clone()]);
+        assert_eq!(snapshot[0].node_id, "node-1");
+        assert_eq!(snapshot[0].consecutive_failures, 2);
+        assert_eq!(snapshot[0].dropped_events, 3);
+        assert_eq!(snapshot[0].retry_queue_depth, 4);
+        assert!(snapshot[0].last_success_time.is_none());
+        assert!(snapshot[0].lag_ms >= 10_000);
+        assert_eq!(snapshot[0].pending_heartbeats, 5);
+
+        assert_eq!(snapshot[1].consecutive_failures, 0);
+        assert!(snapshot[1].last_success_time.is_some());
+        assert!(snapshot[1].lag_ms < 10_000);
+
+        // 缓冲区和重试队列清空后没有延迟
+        stats.set_pending(0, 0, 0);
+        stats.set_retry_queues(HashMap::new());
+        let snapshot = stats.snapshot(std::slice::from_ref(&node1));
+        assert_eq!(snapshot[0].retry_queue_depth, 0);
+        assert_eq!(snapshot[0].lag_ms, 0);
+
+        // 节点不可用期间事件保存在发件箱中
+        let summary = OutboxSummary { depth: 7, full_sync_pending: false, oldest: Some(queued) };
+        stats.set_outboxes(HashMap::from([("node-1".to_string(), summary)]));
+        let snapshot = stats.snapshot(&[node1]);
+        assert_eq!(snapshot[0].outbox_depth, 7);
+        assert!(snapshot[0].lag_ms >= 10_000);
+    }
+}
@@ -0,0 +1,270 @@
+use super::client::{ReplicationClient, ReplicationStream};
+use super::error::{ReplicationError, ReplicationErrorKind};
+use super::outbox::EventBatch;
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// 复制流端点
+pub const STREAM_PATH: &str = "/api/replication/stream";
+
+/// 对等节点不支持复制流时, 隔多久再尝试
+const UNSUPPORTED_RECHECK: Duration = Duration::from_secs(300);
+
+/// 连接失败后, 隔多久再尝试 (期间使用批量接口)
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// 复制流上的帧, CBOR 编码后放在 WebSocket 二进制消息中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamFrame {
+    /// 一批复制事件, seq 在连接内递增
+    Batch { seq: u64, batch: EventBatch },
+    /// 对等节点已处理 seq 对应的批次
+    Ack { seq: u64 },
+}
+
+impl StreamFrame {
+    pub fn encode(&self) -> Result<Vec<u8>, ReplicationError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).map_err(|e| {
+            ReplicationError::new(
+                ReplicationErrorKind::PermanentFailure,
+                format!("Failed to encode stream frame: {}", e),
+            )
+        })?;
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplicationError> {
+        ciborium::from_reader(bytes).map_err(|e| {
+            ReplicationError::new(
+                ReplicationErrorKind::BadRequest,
+                format!("Failed to decode stream frame: {}", e),
+            )
+        })
+    }
+}
+
+/// 复制流状态
+enum StreamState {
+    /// 尚未连接
+    Idle,
+    /// 已连接
+    Open(Arc<StreamConnection>),
+    /// 连接失败的时间
+    Failed(Instant),
+    /// 对等节点不支持复制流 (旧版本), 记录发现的时间
+    Unsupported(Instant),
+}
+
+/// 到单个对等节点的复制流
+///
+/// 按需建立 WebSocket 长连接, 批次带序号发送, 对等节点按顺序处理并逐个确认。未确认的批次数
+/// 受发送任务的并发上限约束, 对等节点处理变慢时发送方自然停止发送 (流量控制)。
+/// 复制流不可用或对等节点不支持时返回 None, 调用方改用批量接口
+pub struct StreamTransport {
+    client: ReplicationClient,
+    peer_url: String,
+    state: tokio::sync::Mutex<StreamState>,
+}
+
+impl StreamTransport {
+    pub fn new(client: ReplicationClient, peer_url: String) -> Self {
+        Self { client, peer_url, state: tokio::sync::Mutex::new(StreamState::Idle) }
+    }
+
+    /// 通过复制流发送批次并等待确认, 复制流不可用时返回 None
+    pub async fn send(&self, batch: &EventBatch) -> Option<Result<(), ReplicationError>> {
+        let connection = self.connection().await?;
+        let result = connection.send(batch, self.client.timeout()).await;
+        if let Err(e) = &result {
+            debug!("Replication stream to {} failed: {}", self.peer_url, e);
+        }
+        Some(result)
+    }
+
+    /// 当前连接, 需要时重新连接
+    async fn connection(&self) -> Option<Arc<StreamConnection>> {
+        let mut state = self.state.lock().await;
+        match &*state {
+            StreamState::Open(connection) if !connection.acks.is_closed() => {
+                return Some(connection.clone());
+            }
+            StreamState::Failed(since) if since.elapsed() < RECONNECT_DELAY => return None,
+            StreamState::Unsupported(since) if since.elapsed() < UNSUPPORTED_RECHECK => {
+                return None;
+            }
+            _ => {}
+        }
+
+        match self.client.open_stream(&self.peer_url).await {
+            Ok(stream) => {
+                info!("Opened replication stream to {}", self.peer_url);
+                let connection = Arc::new(StreamConnection::start(stream));
+                *state = StreamState::Open(connection.clone());
+                Some(connection)
+            }
+            Err(e) if e.kind == ReplicationErrorKind::Unsupported => {
+                info!(
+                    "Peer {} does not support replication streams, using batch endpoints",
+                    self.peer_url
+                );
+                *state = StreamState::Unsupported(Instant::now());
+                None
+            }
+            Err(e) => {
+                debug!("Failed to open replication stream to {}: {}", self.peer_url, e);
+                *state = StreamState::Failed(Instant::now());
+                None
+            }
+        }
+    }
+}
+
+/// 等待确认的批次
+#[derive(Default)]
+struct PendingAcks {
+    waiters: Mutex<HashMap<u64, oneshot::Sender<()>>>,
+    closed: AtomicBool,
+}
+
+impl PendingAcks {
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// 连接关闭, 所有等待中的批次立即失败
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.waiters.lock().clear();
+    }
+}
+
+/// 一条复制流连接: 发送端加锁写帧, 读取任务按序号唤醒等待确认的批次
+struct StreamConnection {
+    sink: tokio::sync::Mutex<SplitSink<ReplicationStream, Message>>,
+    next_seq: AtomicU64,
+    acks: Arc<PendingAcks>,
+    reader: JoinHandle<()>,
+}
+
+impl StreamConnection {
+    fn start(stream: ReplicationStream) -> Self {
+        let (sink, mut source) = stream.split();
+        let acks = Arc::new(PendingAcks::default());
+
+        let reader_acks = acks.clone();
+        let reader = tokio::spawn(async move {
+            while let Some(message) = source.next().await {
+                match message {
+                    Ok(Message::Binary(bytes)) => match StreamFrame::decode(&bytes) {
+                        Ok(StreamFrame::Ack { seq }) => {
+                            if let Some(waiter) = reader_acks.waiters.lock().remove(&seq) {
+                                let _ = waiter.send(());
+                            }
+                        }
+                        Ok(frame) => warn!("Unexpected replication stream frame: {:?}", frame),
+                        Err(e) => {
+                            warn!("Closing replication stream: {}", e);
+                            break;
+                        }
+                    },
+                    Ok(Message::Close(_)) | Err(_) => break,
+                    Ok(_) => {}
+                }
+            }
+            reader_acks.close();
+        });
+
+        Self { sink: tokio::sync::Mutex::new(sink), next_seq: AtomicU64::new(0), acks, reader }
+    }
+
+    async fn send(&self, batch: &EventBatch, timeout: Duration) -> Result<(), ReplicationError> {
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+        let frame = StreamFrame::Batch { seq, batch: batch.clone() }.encode()?;
+
+        let (waiter, ack) = oneshot::channel();
+        self.acks.waiters.lock().insert(seq, waiter);
+        if self.acks.is_closed() {
+            self.acks.waiters.lock().remove(&seq);
+            return Err(stream_closed());
+        }
+
+        if let Err(e) = self.sink.lock().await.send(Message::Binary(frame.into())).await {
+            self.acks.close();
+            return Err(ReplicationError::new(
+                ReplicationErrorKind::ServiceUnavailable,
+                format!("Replication stream send failed: {}", e),
+            ));
+        }
+
+        match tokio::time::timeout(timeout, ack).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err(stream_closed()),
+            Err(_) => {
+                // 对等节点长时间不确认, 关闭连接, 下一批重新连接
+                self.acks.close();
+                Err(ReplicationError::new(
+                    ReplicationErrorKind::NetworkTimeout,
+                    "Replication stream ack timeout",
+                ))
+            }
+        }
+    }
+}
+
+impl Drop for StreamConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+fn stream_closed() -> ReplicationError {
+    ReplicationError::new(ReplicationErrorKind::ServiceUnavailable, "Replication stream closed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use artemis_common::model::{InstanceKey, Revision};
+
+    #[test]
+    fn test_frame_round_trip() {
+        let key = InstanceKey {
+            region_id: "test".to_string(),
+            zone_id: "zone".to_string(),
+            service_id: "service".to_string(),
+            group_id: String::new(),
+            instance_id: "inst-1".to_string(),
+        };
+        let revision = Revision { clock: 42, node_id: "node-1".to_string() };
+        let frame = StreamFrame::Batch {
+            seq: 7,
+            batch: EventBatch::Unregisters(vec![key.clone()], vec![revision.clone()]),
+        };
+
+        let bytes = frame.encode().unwrap();
+        let json = serde_json::to_vec(&frame).unwrap();
+        assert!(bytes.len() < json.len());
+
+        match StreamFrame::decode(&bytes).unwrap() {
+            StreamFrame::Batch { seq, batch: EventBatch::Unregisters(keys, revisions) } => {
+                assert_eq!(seq, 7);
+                assert_eq!(keys, vec![key]);
+                assert_eq!(revisions, vec![revision]);
+            }
+            other => panic!("unexpected frame {:?}", other),
+        }
+        assert!(StreamFrame::decode(b"not cbor").is_err());
+    }
+}
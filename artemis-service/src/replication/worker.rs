@@ -1,15 +1,17 @@
 use super::client::ReplicationClient;
 use super::manager::ReplicationEvent;
-use crate::cluster::ClusterManager;
+use super::outbox::{EventBatch, Outbox};
+use super::peer::{PeerJob, PeerOutcome, PeerSender};
+use super::stats::{OutboxSummary, ReplicationStats};
+use crate::cluster::{ClusterManager, ClusterNode, NodeStatus};
 use crate::config::ReplicationConfig;
-use artemis_common::model::{
-    BatchRegisterRequest, BatchUnregisterRequest, Instance, InstanceKey, ReplicateHeartbeatRequest,
-    ReplicateRegisterRequest, ReplicateUnregisterRequest,
-};
-use std::collections::VecDeque;
+use crate::registry::RegistryRepository;
+use crate::security::RequestSigner;
+use artemis_common::model::{Instance, InstanceKey, Revision};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
@@ -24,6 +26,8 @@ struct RetryItem {
     retry_count: u32,
     /// 下次重试时间
     next_retry_time: Instant,
+    /// 第一次加入重试队列的时间 (估算复制延迟)
+    first_queued: Instant,
 }
 
 /// 复制工作器
@@ -31,7 +35,8 @@ struct RetryItem {
 /// 后台异步处理复制事件,支持:
 /// - 注册/心跳/注销三种事件的批处理(减少网络请求 90%+)
 /// - 智能重试队列(临时失败自动重试)
-/// - 并发复制到多个节点
+/// - 每个对等节点独立的发送任务、任务队列和并发上限, 慢节点不会拖慢其他节点和事件循环
+/// - 对等节点不可用期间事件保存在发件箱中, 恢复后按顺序重放, 发件箱溢出后改为全量同步
 pub struct ReplicationWorker {
     event_rx: UnboundedReceiver<ReplicationEvent>,
     cluster_manager: Arc<ClusterManager>,
@@ -39,13 +44,30 @@ pub struct ReplicationWorker {
     config: ReplicationConfig,
 
     // 批处理缓冲区 (Phase 23 批量 API)
-    register_buffer: Vec<Instance>,
+    register_buffer: Vec<(Instance, Revision)>,
     heartbeat_buffer: Vec<InstanceKey>,
-    unregister_buffer: Vec<InstanceKey>,
+    unregister_buffer: Vec<(InstanceKey, Revision)>,
     last_batch_time: Instant,
 
     // 重试队列
     retry_queue: VecDeque<RetryItem>,
+
+    // 不可用节点的发件箱
+    outbox: Outbox,
+
+    // 本地注册表 (发件箱溢出后全量同步)
+    repository: Option<RegistryRepository>,
+
+    // 复制统计 (状态端点和指标)
+    stats: Arc<ReplicationStats>,
+
+    // 各节点的发送任务, 执行结果通过 outcome 通道返回
+    senders: HashMap<String, PeerSender>,
+    outcome_tx: UnboundedSender<PeerOutcome>,
+    outcome_rx: UnboundedReceiver<PeerOutcome>,
+
+    // 正在重放发件箱或全量同步的节点
+    replaying: HashSet<String>,
 }
 
 impl ReplicationWorker {
@@ -56,6 +78,14 @@ impl ReplicationWorker {
     ) -> Self {
         let timeout = Duration::from_secs(config.timeout_secs);
         let client = ReplicationClient::new(timeout);
+        let outbox = match &config.outbox_dir {
+            Some(dir) => Outbox::open(config.outbox_capacity, dir).unwrap_or_else(|e| {
+                warn!("Failed to open replication outbox {}: {}, using memory only", dir, e);
+                Outbox::in_memory(config.outbox_capacity)
+            }),
+            None => Outbox::in_memory(config.outbox_capacity),
+        };
+        let (outcome_tx, outcome_rx) = tokio::sync::mpsc::unbounded_channel();
 
         Self {
             event_rx,
@@ -67,9 +97,34 @@ impl ReplicationWorker {
             unregister_buffer: Vec::new(),
             last_batch_time: Instant::now(),
             retry_queue: VecDeque::new(),
+            outbox,
+            repository: None,
+            stats: Arc::new(ReplicationStats::new()),
+            senders: HashMap::new(),
+            outcome_tx,
+            outcome_rx,
+            replaying: HashSet::new(),
         }
     }
 
+    /// 使用集群共享密钥对复制请求签名
+    pub fn with_signer(mut self, signer: Option<RequestSigner>) -> Self {
+        self.client = self.client.with_signer(signer);
+        self
+    }
+
+    /// 本地注册表, 发件箱溢出后向对等节点推送全部实例
+    pub fn with_repository(mut self, repository: RegistryRepository) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// 共享复制统计, 供状态端点读取
+    pub fn with_stats(mut self, stats: Arc<ReplicationStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
     /// 启动工作器
     pub fn start(mut self) -> JoinHandle<()> {
         tokio::spawn(async move {
@@ -87,36 +142,48 @@ impl ReplicationWorker {
                     // 处理新事件 - 全部使用缓冲区
                     Some(event) = self.event_rx.recv() => {
                         match event {
-                            ReplicationEvent::Register(instance) => {
-                                self.register_buffer.push(instance);
+                            ReplicationEvent::Register(instance, revision) => {
+                                self.register_buffer.push((instance, revision));
                                 // 达到批次大小立即刷新
                                 if self.register_buffer.len() >= self.config.batch_size {
-                                    self.flush_register_batch().await;
+                                    self.flush_register_batch();
                                 }
                             }
                             ReplicationEvent::Heartbeat(key) => {
                                 self.heartbeat_buffer.push(key);
                                 if self.heartbeat_buffer.len() >= self.config.batch_size {
-                                    self.flush_heartbeat_batch().await;
+                                    self.flush_heartbeat_batch();
                                 }
                             }
-                            ReplicationEvent::Unregister(key) => {
-                                self.unregister_buffer.push(key);
+                            ReplicationEvent::Unregister(key, revision) => {
+                                self.unregister_buffer.push((key, revision));
                                 if self.unregister_buffer.len() >= self.config.batch_size {
-                                    self.flush_unregister_batch().await;
+                                    self.flush_unregister_batch();
                                 }
                             }
                         }
+                        self.publish_pending();
+                    }
+
+                    // 处理各节点发送任务的结果
+                    Some(outcome) = self.outcome_rx.recv() => {
+                        self.handle_outcome(outcome);
+                        self.publish_retry_queues();
+                        self.publish_outboxes();
                     }
 
                     // 定期刷新所有批处理缓冲区
                     _ = interval.tick() => {
-                        self.flush_all_batches().await;
+                        self.flush_all_batches();
                     }
 
-                    // 定期处理重试队列
+                    // 定期重放发件箱和处理重试队列
                     _ = retry_timer.tick() => {
-                        self.process_retry_queue().await;
+                        self.replay_outboxes();
+                        self.process_retry_queue();
+                        self.outbox.persist();
+                        self.publish_retry_queues();
+                        self.publish_outboxes();
                     }
                 }
             }
@@ -124,159 +191,329 @@ impl ReplicationWorker {
     }
 
     /// 刷新所有批处理缓冲区
-    async fn flush_all_batches(&mut self) {
-        self.flush_register_batch().await;
-        self.flush_heartbeat_batch().await;
-        self.flush_unregister_batch().await;
+    fn flush_all_batches(&mut self) {
+        self.flush_register_batch();
+        self.flush_heartbeat_batch();
+        self.flush_unregister_batch();
+        self.outbox.persist();
+        self.publish_pending();
+        self.publish_retry_queues();
+        self.publish_outboxes();
     }
 
-    /// 刷新注册批处理 (Phase 23 批量 API)
-    async fn flush_register_batch(&mut self) {
-        if self.register_buffer.is_empty() {
-            return;
+    /// 更新批处理缓冲区统计
+    fn publish_pending(&self) {
+        self.stats.set_pending(
+            self.register_buffer.len(),
+            self.heartbeat_buffer.len(),
+            self.unregister_buffer.len(),
+        );
+    }
+
+    /// 更新各节点的重试队列统计
+    fn publish_retry_queues(&self) {
+        let mut queues: HashMap<String, (usize, Instant)> = HashMap::new();
+        for item in &self.retry_queue {
+            let entry = queues.entry(item.node_id.clone()).or_insert((0, item.first_queued));
+            entry.0 += 1;
+            entry.1 = entry.1.min(item.first_queued);
         }
+        self.stats.set_retry_queues(queues);
+    }
 
-        let instances = std::mem::take(&mut self.register_buffer);
-        let peers = self.cluster_manager.get_healthy_peers();
+    /// 更新各节点的发件箱统计
+    fn publish_outboxes(&self) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let outboxes = self
+            .outbox
+            .iter()
+            .filter(|outbox| outbox.has_pending())
+            .map(|outbox| {
+                let oldest = outbox.oldest_queued_at().map(|queued_at| {
+                    let age = Duration::from_millis((now - queued_at).max(0) as u64);
+                    Instant::now().checked_sub(age).unwrap_or_else(Instant::now)
+                });
+                let summary = OutboxSummary {
+                    depth: outbox.len(),
+                    full_sync_pending: outbox.full_sync_required(),
+                    oldest,
+                };
+                (outbox.node_id().to_string(), summary)
+            })
+            .collect();
+        self.stats.set_outboxes(outboxes);
+    }
 
-        if peers.is_empty() {
-            debug!("No healthy peers to replicate registers");
-            return;
+    /// 选择直接发送的节点: 健康、发件箱为空且发送队列未满的节点直接发送,
+    /// 其他节点的事件按顺序进入发件箱, 等节点恢复后重放
+    fn select_peers(&mut self, events: impl Fn() -> Vec<ReplicationEvent>) -> Vec<ClusterNode> {
+        let mut direct = Vec::new();
+        for peer in self.cluster_manager.get_peers() {
+            if peer.status == NodeStatus::Left {
+                continue;
+            }
+            let queue_full = self.senders.get(&peer.node_id).is_some_and(PeerSender::is_full);
+            if peer.is_healthy() && !queue_full && !self.outbox.has_pending(&peer.node_id) {
+                direct.push(peer);
+            } else {
+                self.enqueue(&peer.node_id, events());
+            }
         }
+        direct
+    }
 
-        info!("Batch replicating {} registers to {} peers", instances.len(), peers.len());
+    /// 事件加入节点的发件箱
+    fn enqueue(&mut self, node_id: &str, events: Vec<ReplicationEvent>) {
+        let outbox = self.outbox.peer_mut(node_id);
+        let discarded: usize = events.into_iter().map(|event| outbox.push(event)).sum();
+        if discarded > 0 {
+            warn!(
+                "Replication outbox for {} overflowed, discarded {} events, will fall back to full sync",
+                node_id, discarded
+            );
+        }
+    }
 
-        for peer in peers {
-            let request = BatchRegisterRequest { instances: instances.clone() };
+    /// 提交任务到节点的发送队列, 队列已满时返回该任务
+    fn submit(&mut self, peer: &ClusterNode, job: PeerJob) -> Result<(), PeerJob> {
+        let sender = self.senders.entry(peer.node_id.clone()).or_insert_with(|| {
+            PeerSender::spawn(
+                peer.node_id.clone(),
+                peer.base_url(),
+                self.client.clone(),
+                self.repository.clone(),
+                &self.config,
+                self.outcome_tx.clone(),
+            )
+        });
+        sender.set_protocol(peer.protocol.clone());
+        sender.submit(job)
+    }
 
-            match self.client.batch_register(&peer.base_url(), request).await {
-                Ok(_) => {
-                    debug!(
-                        "Successfully batch replicated {} registers to {}",
-                        instances.len(),
-                        peer.node_id
-                    );
-                }
-                Err(e) if e.is_retryable() => {
-                    warn!("Retryable error batch replicating registers to {}: {}", peer.node_id, e);
-                    // 批处理失败,将每个实例单独加入重试队列
-                    for instance in &instances {
-                        self.add_to_retry_queue(
-                            peer.node_id.clone(),
-                            ReplicationEvent::Register(instance.clone()),
-                            0,
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!("Permanent error batch replicating registers to {}: {}", peer.node_id, e);
-                }
+    /// 向恢复健康的节点重放发件箱, 停止已退出节点的发送任务
+    fn replay_outboxes(&mut self) {
+        let peers = self.cluster_manager.get_peers();
+
+        // 已退出或不再是成员的节点不会恢复, 丢弃它们的发件箱
+        for node_id in self.outbox.node_ids() {
+            let member = peers.iter().find(|peer| peer.node_id == node_id);
+            if member.is_none_or(|peer| peer.status == NodeStatus::Left)
+                && let Some(outbox) = self.outbox.remove(&node_id)
+                && !outbox.is_empty()
+            {
+                info!("Peer {} left, dropping {} outbox events", node_id, outbox.len());
+                self.stats.record_dropped(&node_id, outbox.len());
+            }
+        }
+        self.senders.retain(|node_id, _| {
+            peers.iter().any(|peer| peer.node_id == *node_id && peer.status != NodeStatus::Left)
+        });
+
+        for peer in peers {
+            if peer.is_healthy() && self.outbox.has_pending(&peer.node_id) {
+                self.replay(&peer);
             }
         }
     }
 
-    /// 刷新注销批处理 (Phase 23 批量 API)
-    async fn flush_unregister_batch(&mut self) {
-        if self.unregister_buffer.is_empty() {
+    /// 提交节点发件箱的下一个重放批次 (需要时先全量同步)
+    ///
+    /// 同一节点同时只有一个重放任务, 完成后立即提交下一批, 临时错误时等下一轮再继续
+    fn replay(&mut self, peer: &ClusterNode) {
+        if self.replaying.contains(&peer.node_id) {
             return;
         }
+        let Some(outbox) = self.outbox.get_mut(&peer.node_id) else {
+            return;
+        };
 
-        let keys = std::mem::take(&mut self.unregister_buffer);
-        let peers = self.cluster_manager.get_healthy_peers();
+        if outbox.full_sync_required() && self.repository.is_none() {
+            warn!("No registry for full sync to {}, relying on anti-entropy", peer.node_id);
+            outbox.complete_full_sync();
+        }
+        let job = if outbox.full_sync_required() {
+            PeerJob::FullSync
+        } else {
+            match outbox.next_batch(self.config.batch_size) {
+                Some(batch) => PeerJob::Replay(batch),
+                None => return,
+            }
+        };
 
-        if peers.is_empty() {
-            debug!("No healthy peers to replicate unregisters");
-            return;
+        // 发送队列已满时发件箱保持不变, 下一轮再重放
+        if self.submit(peer, job).is_ok() {
+            self.replaying.insert(peer.node_id.clone());
         }
+    }
 
-        info!("Batch replicating {} unregisters to {} peers", keys.len(), peers.len());
+    /// 重放完成后继续重放同一节点的发件箱
+    fn replay_next(&mut self, node_id: &str) {
+        let peer = self.cluster_manager.get_peers().into_iter().find(|p| p.node_id == node_id);
+        match peer {
+            Some(peer) if peer.is_healthy() && self.outbox.has_pending(node_id) => {
+                self.replay(&peer)
+            }
+            _ if !self.outbox.has_pending(node_id) => {
+                info!("Replication outbox for {} fully replayed", node_id)
+            }
+            _ => {}
+        }
+    }
 
-        for peer in peers {
-            let request = BatchUnregisterRequest { instance_keys: keys.clone() };
+    /// 刷新注册批处理 (Phase 23 批量 API)
+    fn flush_register_batch(&mut self) {
+        if self.register_buffer.is_empty() {
+            return;
+        }
 
-            match self.client.batch_unregister(&peer.base_url(), request).await {
-                Ok(_) => {
-                    debug!(
-                        "Successfully batch replicated {} unregisters to {}",
-                        keys.len(),
-                        peer.node_id
-                    );
-                }
-                Err(e) if e.is_retryable() => {
-                    warn!(
-                        "Retryable error batch replicating unregisters to {}: {}",
-                        peer.node_id, e
-                    );
-                    // 批处理失败,将每个实例单独加入重试队列
-                    for key in &keys {
-                        self.add_to_retry_queue(
-                            peer.node_id.clone(),
-                            ReplicationEvent::Unregister(key.clone()),
-                            0,
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "Permanent error batch replicating unregisters to {}: {}",
-                        peer.node_id, e
-                    );
-                }
-            }
+        let (instances, revisions) = std::mem::take(&mut self.register_buffer).into_iter().unzip();
+        self.dispatch(EventBatch::Registers(instances, revisions));
+    }
+
+    /// 刷新注销批处理 (Phase 23 批量 API)
+    fn flush_unregister_batch(&mut self) {
+        if self.unregister_buffer.is_empty() {
+            return;
         }
+
+        let (keys, revisions) = std::mem::take(&mut self.unregister_buffer).into_iter().unzip();
+        self.dispatch(EventBatch::Unregisters(keys, revisions));
     }
 
     /// 刷新心跳批处理
-    async fn flush_heartbeat_batch(&mut self) {
+    fn flush_heartbeat_batch(&mut self) {
         if self.heartbeat_buffer.is_empty() {
             return;
         }
 
         let keys = std::mem::take(&mut self.heartbeat_buffer);
-        let peers = self.cluster_manager.get_healthy_peers();
+        self.dispatch(EventBatch::Heartbeats(keys));
+        self.last_batch_time = Instant::now();
+    }
+
+    /// 批次提交到可直接发送的节点, 其他节点的事件进入发件箱
+    fn dispatch(&mut self, batch: EventBatch) {
+        let peers = self.select_peers(|| batch.clone().into_events());
 
         if peers.is_empty() {
-            debug!("No healthy peers to replicate heartbeats");
+            debug!("No healthy peers to replicate {}", batch.kind());
             return;
         }
 
-        info!("Replicating {} heartbeats to {} peers", keys.len(), peers.len());
+        debug!("Replicating {} {} to {} peers", batch.len(), batch.kind(), peers.len());
 
         for peer in peers {
-            let request = ReplicateHeartbeatRequest { instance_keys: keys.clone() };
+            if let Err(job) = self.submit(&peer, PeerJob::Send(batch.clone())) {
+                self.enqueue(&peer.node_id, job.into_events());
+            }
+        }
+    }
 
-            match self.client.replicate_heartbeat(&peer.base_url(), request).await {
-                Ok(_) => {
-                    debug!("Successfully replicated {} heartbeats to {}", keys.len(), peer.node_id);
+    /// 处理发送任务的结果
+    fn handle_outcome(&mut self, outcome: PeerOutcome) {
+        let PeerOutcome { node_id, job, result } = outcome;
+        match job {
+            PeerJob::Send(batch) => match result {
+                Ok(()) => {
+                    self.stats.record_success(&node_id);
+                    debug!(
+                        "Successfully replicated {} {} to {}",
+                        batch.len(),
+                        batch.kind(),
+                        node_id
+                    );
                 }
                 Err(e) if e.is_retryable() => {
-                    warn!("Retryable error replicating heartbeats to {}: {}", peer.node_id, e);
-                    // 心跳批处理失败,将每个心跳单独加入重试队列
-                    for key in &keys {
-                        self.add_to_retry_queue(
-                            peer.node_id.clone(),
-                            ReplicationEvent::Heartbeat(key.clone()),
-                            0,
-                        );
+                    self.stats.record_failure(&node_id);
+                    warn!("Retryable error replicating {} to {}: {}", batch.kind(), node_id, e);
+                    // 批处理失败,将每个事件单独加入重试队列
+                    for event in batch.into_events() {
+                        self.add_to_retry_queue(node_id.clone(), event, 0);
                     }
                 }
                 Err(e) => {
-                    warn!("Permanent error replicating heartbeats to {}: {}", peer.node_id, e);
+                    self.stats.record_failure(&node_id);
+                    self.stats.record_dropped(&node_id, batch.len());
+                    warn!("Permanent error replicating {} to {}: {}", batch.kind(), node_id, e);
+                }
+            },
+            PeerJob::Retry { event, retry_count, first_queued } => match result {
+                Ok(()) => {
+                    self.stats.record_success(&node_id);
+                    info!(
+                        "Successfully retried event to {} (attempt {})",
+                        node_id,
+                        retry_count + 1
+                    );
                 }
+                Err(e) if e.is_retryable() => {
+                    warn!("Retry attempt {} failed for {}: {}", retry_count + 1, node_id, e);
+                    self.stats.record_failure(&node_id);
+                    self.requeue(node_id, *event, retry_count + 1, first_queued);
+                }
+                Err(e) => {
+                    warn!("Permanent error on retry to {}: {}, dropping", node_id, e);
+                    self.stats.record_failure(&node_id);
+                    self.stats.record_dropped(&node_id, 1);
+                }
+            },
+            PeerJob::Replay(batch) => {
+                self.replaying.remove(&node_id);
+                let count = batch.len();
+                match result {
+                    Ok(()) => self.stats.record_success(&node_id),
+                    Err(e) if e.is_retryable() => {
+                        self.stats.record_failure(&node_id);
+                        warn!("Retryable error replaying outbox to {}: {}", node_id, e);
+                        return;
+                    }
+                    Err(e) => {
+                        self.stats.record_failure(&node_id);
+                        self.stats.record_dropped(&node_id, count);
+                        warn!("Permanent error replaying outbox to {}: {}", node_id, e);
+                    }
+                }
+                if let Some(outbox) = self.outbox.get_mut(&node_id) {
+                    outbox.ack(count);
+                }
+                self.replay_next(&node_id);
+            }
+            PeerJob::FullSync => {
+                self.replaying.remove(&node_id);
+                if let Err(e) = result {
+                    self.stats.record_failure(&node_id);
+                    warn!("Full sync to {} failed: {}", node_id, e);
+                    return;
+                }
+                self.stats.record_success(&node_id);
+                if let Some(outbox) = self.outbox.get_mut(&node_id) {
+                    outbox.complete_full_sync();
+                }
+                self.replay_next(&node_id);
             }
         }
-
-        self.last_batch_time = Instant::now();
     }
 
     /// 添加项到重试队列
     fn add_to_retry_queue(&mut self, node_id: String, event: ReplicationEvent, retry_count: u32) {
+        self.requeue(node_id, event, retry_count, Instant::now());
+    }
+
+    /// 重新加入重试队列, 保留第一次入队的时间
+    fn requeue(
+        &mut self,
+        node_id: String,
+        event: ReplicationEvent,
+        retry_count: u32,
+        first_queued: Instant,
+    ) {
         // 检查是否超过最大重试次数
         if retry_count >= self.config.max_retries {
             warn!(
                 "Max retries ({}) exceeded for event to {}, dropping",
                 self.config.max_retries, node_id
             );
+            self.stats.record_dropped(&node_id, 1);
             return;
         }
 
@@ -284,7 +521,13 @@ impl ReplicationWorker {
         let backoff_secs = 2u64.pow(retry_count);
         let next_retry_time = Instant::now() + Duration::from_secs(backoff_secs);
 
-        let item = RetryItem { node_id: node_id.clone(), event, retry_count, next_retry_time };
+        let item = RetryItem {
+            node_id: node_id.clone(),
+            event,
+            retry_count,
+            next_retry_time,
+            first_queued,
+        };
 
         self.retry_queue.push_back(item);
 
@@ -298,7 +541,7 @@ impl ReplicationWorker {
     }
 
     /// 处理重试队列
-    async fn process_retry_queue(&mut self) {
+    fn process_retry_queue(&mut self) {
         let now = Instant::now();
         let mut items_to_retry = Vec::new();
 
@@ -319,99 +562,34 @@ impl ReplicationWorker {
 
         // 重试每个项
         for item in items_to_retry {
-            self.retry_event(item).await;
+            self.retry_event(item);
         }
     }
 
-    /// 重试单个事件
-    async fn retry_event(&mut self, item: RetryItem) {
-        let RetryItem { node_id, event, retry_count, .. } = item;
+    /// 提交单个事件的重试, 结果在 handle_outcome 中处理
+    fn retry_event(&mut self, item: RetryItem) {
+        let RetryItem { node_id, event, retry_count, first_queued, .. } = item;
 
         // 获取节点信息
-        let peer = match self
-            .cluster_manager
-            .get_healthy_peers()
-            .into_iter()
-            .find(|p| p.node_id == node_id)
+        let peer = match self.cluster_manager.get_peers().into_iter().find(|p| p.node_id == node_id)
         {
-            Some(p) => p,
-            None => {
-                warn!("Node {} not found or unhealthy, dropping retry", node_id);
+            Some(p) if p.is_healthy() => p,
+            Some(p) if p.status != NodeStatus::Left => {
+                debug!("Node {} unhealthy, moving retry to outbox", node_id);
+                self.enqueue(&node_id, vec![event]);
+                return;
+            }
+            _ => {
+                warn!("Node {} not found or left, dropping retry", node_id);
+                self.stats.record_dropped(&node_id, 1);
                 return;
             }
         };
 
-        // 根据事件类型执行重试并处理结果
-        match event {
-            ReplicationEvent::Register(instance) => {
-                let request = ReplicateRegisterRequest { instances: vec![instance.clone()] };
-                match self.client.replicate_register(&peer.base_url(), request).await {
-                    Ok(_) => {
-                        info!(
-                            "Successfully retried register to {} (attempt {})",
-                            node_id,
-                            retry_count + 1
-                        );
-                    }
-                    Err(e) if e.is_retryable() => {
-                        warn!("Retry attempt {} failed for {}: {}", retry_count + 1, node_id, e);
-                        self.add_to_retry_queue(
-                            node_id,
-                            ReplicationEvent::Register(instance),
-                            retry_count + 1,
-                        );
-                    }
-                    Err(e) => {
-                        warn!("Permanent error on retry to {}: {}, dropping", node_id, e);
-                    }
-                }
-            }
-            ReplicationEvent::Heartbeat(key) => {
-                let request = ReplicateHeartbeatRequest { instance_keys: vec![key.clone()] };
-                match self.client.replicate_heartbeat(&peer.base_url(), request).await {
-                    Ok(_) => {
-                        info!(
-                            "Successfully retried heartbeat to {} (attempt {})",
-                            node_id,
-                            retry_count + 1
-                        );
-                    }
-                    Err(e) if e.is_retryable() => {
-                        warn!("Retry attempt {} failed for {}: {}", retry_count + 1, node_id, e);
-                        self.add_to_retry_queue(
-                            node_id,
-                            ReplicationEvent::Heartbeat(key),
-                            retry_count + 1,
-                        );
-                    }
-                    Err(e) => {
-                        warn!("Permanent error on retry to {}: {}, dropping", node_id, e);
-                    }
-                }
-            }
-            ReplicationEvent::Unregister(key) => {
-                let request = ReplicateUnregisterRequest { instance_keys: vec![key.clone()] };
-                match self.client.replicate_unregister(&peer.base_url(), request).await {
-                    Ok(_) => {
-                        info!(
-                            "Successfully retried unregister to {} (attempt {})",
-                            node_id,
-                            retry_count + 1
-                        );
-                    }
-                    Err(e) if e.is_retryable() => {
-                        warn!("Retry attempt {} failed for {}: {}", retry_count + 1, node_id, e);
-                        self.add_to_retry_queue(
-                            node_id,
-                            ReplicationEvent::Unregister(key),
-                            retry_count + 1,
-                        );
-                    }
-                    Err(e) => {
-                        warn!("Permanent error on retry to {}: {}, dropping", node_id, e);
-                    }
-                }
-            }
+        let job = PeerJob::Retry { event: Box::new(event), retry_count, first_queued };
+        if let Err(job) = self.submit(&peer, job) {
+            debug!("Send queue for {} full, moving retry to outbox", node_id);
+            self.enqueue(&node_id, job.into_events());
         }
     }
 }
@@ -428,6 +606,13 @@ mod tests {
             batch_size: 100,
             batch_interval_ms: 100,
             max_retries: 3,
+            anti_entropy_interval_secs: 60,
+            outbox_capacity: 10000,
+            outbox_dir: None,
+            tombstone_window_secs: 300,
+            peer_concurrency: 4,
+            peer_queue_capacity: 256,
+            stream_enabled: false,
         }
     }
 
@@ -483,6 +668,13 @@ mod tests {
             batch_size: 50,
             batch_interval_ms: 200,
             max_retries: 5,
+            anti_entropy_interval_secs: 60,
+            outbox_capacity: 10000,
+            outbox_dir: None,
+            tombstone_window_secs: 300,
+            peer_concurrency: 4,
+            peer_queue_capacity: 256,
+            stream_enabled: false,
         };
 
         let worker = ReplicationWorker::new(event_rx, cluster_manager, config.clone());
@@ -509,7 +701,7 @@ mod tests {
     #[test]
     fn test_retry_item_creation() {
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let next_retry_time = Instant::now() + Duration::from_secs(2);
 
         let item = RetryItem {
@@ -517,6 +709,7 @@ mod tests {
             event: event.clone(),
             retry_count: 0,
             next_retry_time,
+            first_queued: Instant::now(),
         };
 
         assert_eq!(item.node_id, "node-1");
@@ -526,11 +719,16 @@ mod tests {
     #[test]
     fn test_retry_item_clone() {
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let next_retry_time = Instant::now() + Duration::from_secs(2);
 
-        let item =
-            RetryItem { node_id: "node-1".to_string(), event, retry_count: 1, next_retry_time };
+        let item = RetryItem {
+            node_id: "node-1".to_string(),
+            event,
+            retry_count: 1,
+            next_retry_time,
+            first_queued: Instant::now(),
+        };
 
         let cloned = item.clone();
         assert_eq!(cloned.node_id, item.node_id);
@@ -548,7 +746,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 添加实例到缓冲区
-        worker.register_buffer.push(create_test_instance());
+        worker.register_buffer.push((create_test_instance(), Revision::default()));
         assert_eq!(worker.register_buffer.len(), 1);
 
         // 清空缓冲区
@@ -582,7 +780,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 添加注销到缓冲区
-        worker.unregister_buffer.push(create_test_instance_key());
+        worker.unregister_buffer.push((create_test_instance_key(), Revision::default()));
         assert_eq!(worker.unregister_buffer.len(), 1);
 
         // 清空缓冲区
@@ -601,7 +799,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
 
         worker.add_to_retry_queue("node-1".to_string(), event, 0);
 
@@ -617,7 +815,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
 
         // 尝试添加超过最大重试次数的项 (max_retries = 3)
         worker.add_to_retry_queue("node-1".to_string(), event.clone(), 3);
@@ -634,7 +832,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
 
         let before = Instant::now();
         worker.add_to_retry_queue("node-1".to_string(), event.clone(), 0);
@@ -659,7 +857,7 @@ mod tests {
 
         // 测试指数退避: 2^0=1s, 2^1=2s, 2^2=4s
         for retry_count in 0..3 {
-            let event = ReplicationEvent::Register(instance.clone());
+            let event = ReplicationEvent::Register(instance.clone(), Revision::default());
             let before = Instant::now();
             worker.add_to_retry_queue(format!("node-{}", retry_count), event, retry_count);
 
@@ -688,7 +886,7 @@ mod tests {
 
         // 添加 3 个项到重试队列
         for i in 1..=3 {
-            let event = ReplicationEvent::Register(instance.clone());
+            let event = ReplicationEvent::Register(instance.clone(), Revision::default());
             worker.add_to_retry_queue(format!("node-{}", i), event, 0);
         }
 
@@ -732,7 +930,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 刷新空缓冲区不应panic
-        worker.flush_register_batch().await;
+        worker.flush_register_batch();
         assert_eq!(worker.register_buffer.len(), 0);
     }
 
@@ -745,7 +943,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 刷新空缓冲区不应panic
-        worker.flush_heartbeat_batch().await;
+        worker.flush_heartbeat_batch();
         assert_eq!(worker.heartbeat_buffer.len(), 0);
     }
 
@@ -758,7 +956,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 刷新空缓冲区不应panic
-        worker.flush_unregister_batch().await;
+        worker.flush_unregister_batch();
         assert_eq!(worker.unregister_buffer.len(), 0);
     }
 
@@ -771,12 +969,12 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 添加数据到各个缓冲区
-        worker.register_buffer.push(create_test_instance());
+        worker.register_buffer.push((create_test_instance(), Revision::default()));
         worker.heartbeat_buffer.push(create_test_instance_key());
-        worker.unregister_buffer.push(create_test_instance_key());
+        worker.unregister_buffer.push((create_test_instance_key(), Revision::default()));
 
         // 刷新所有缓冲区
-        worker.flush_all_batches().await;
+        worker.flush_all_batches();
 
         // 验证所有缓冲区已清空 (在无健康节点时会清空但不复制)
         assert_eq!(worker.register_buffer.len(), 0);
@@ -795,7 +993,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         // 处理空重试队列不应panic
-        worker.process_retry_queue().await;
+        worker.process_retry_queue();
         assert_eq!(worker.retry_queue.len(), 0);
     }
 
@@ -809,17 +1007,18 @@ mod tests {
 
         // 添加一个未来的重试项
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let item = RetryItem {
             node_id: "node-1".to_string(),
             event,
             retry_count: 0,
             next_retry_time: Instant::now() + Duration::from_secs(100), // 远未来
+            first_queued: Instant::now(),
         };
         worker.retry_queue.push_back(item);
 
         // 处理重试队列,但没有到期的项
-        worker.process_retry_queue().await;
+        worker.process_retry_queue();
 
         // 队列应该仍然有 1 个项
         assert_eq!(worker.retry_queue.len(), 1);
@@ -835,17 +1034,18 @@ mod tests {
 
         // 添加已到期的重试项
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let item = RetryItem {
             node_id: "nonexistent-node".to_string(), // 不存在的节点会被丢弃
             event,
             retry_count: 0,
             next_retry_time: Instant::now() - Duration::from_secs(1), // 已到期
+            first_queued: Instant::now(),
         };
         worker.retry_queue.push_back(item);
 
         // 处理重试队列
-        worker.process_retry_queue().await;
+        worker.process_retry_queue();
 
         // 项应该被处理并从队列中移除 (因为节点不存在)
         assert_eq!(worker.retry_queue.len(), 0);
@@ -863,7 +1063,7 @@ mod tests {
 
         // 添加多个实例到缓冲区
         for _ in 0..150 {
-            worker.register_buffer.push(create_test_instance());
+            worker.register_buffer.push((create_test_instance(), Revision::default()));
         }
 
         assert_eq!(worker.register_buffer.len(), 150);
@@ -895,7 +1095,7 @@ mod tests {
 
         // 添加多个注销到缓冲区
         for _ in 0..150 {
-            worker.unregister_buffer.push(create_test_instance_key());
+            worker.unregister_buffer.push((create_test_instance_key(), Revision::default()));
         }
 
         assert_eq!(worker.unregister_buffer.len(), 150);
@@ -912,7 +1112,7 @@ mod tests {
         let mut worker = ReplicationWorker::new(event_rx, cluster_manager, config);
 
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
 
         // 第一次重试
         worker.add_to_retry_queue("node-1".to_string(), event.clone(), 0);
@@ -937,13 +1137,21 @@ mod tests {
         let key = create_test_instance_key();
 
         // 添加不同类型的事件
-        worker.add_to_retry_queue("node-1".to_string(), ReplicationEvent::Register(instance), 0);
+        worker.add_to_retry_queue(
+            "node-1".to_string(),
+            ReplicationEvent::Register(instance, Revision::default()),
+            0,
+        );
         worker.add_to_retry_queue(
             "node-2".to_string(),
             ReplicationEvent::Heartbeat(key.clone()),
             0,
         );
-        worker.add_to_retry_queue("node-3".to_string(), ReplicationEvent::Unregister(key), 0);
+        worker.add_to_retry_queue(
+            "node-3".to_string(),
+            ReplicationEvent::Unregister(key, Revision::default()),
+            0,
+        );
 
         assert_eq!(worker.retry_queue.len(), 3);
     }
@@ -957,12 +1165,13 @@ mod tests {
     #[test]
     fn test_retry_item_debug_format() {
         let instance = create_test_instance();
-        let event = ReplicationEvent::Register(instance);
+        let event = ReplicationEvent::Register(instance, Revision::default());
         let item = RetryItem {
             node_id: "test-node".to_string(),
             event,
             retry_count: 2,
             next_retry_time: Instant::now(),
+            first_queued: Instant::now(),
         };
 
         let debug_str = format!("{:?}", item);
@@ -0,0 +1,135 @@
+use crate::config::ApiKeyConfig;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// 通过 API Key 认证的应用身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIdentity {
+    pub app_id: String,
+    /// 应用拥有的服务 ID 模式, 支持 `*` 通配符
+    pub services: Vec<String>,
+}
+
+impl AppIdentity {
+    /// 应用是否拥有该服务 (服务 ID 大小写不敏感)
+    pub fn owns_service(&self, service_id: &str) -> bool {
+        let service_id = service_id.to_lowercase();
+        self.services.iter().any(|pattern| wildcard_match(&pattern.to_lowercase(), &service_id))
+    }
+}
+
+/// API Key 存储
+///
+/// 只保存 Key 的 SHA-256 摘要, 认证时按摘要查找
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeyStore {
+    keys: HashMap<String, AppIdentity>,
+}
+
+impl ApiKeyStore {
+    pub fn new(configs: &[ApiKeyConfig]) -> Self {
+        let keys = configs
+            .iter()
+            .filter(|c| !c.key.is_empty())
+            .map(|c| {
+                let identity =
+                    AppIdentity { app_id: c.app_id.clone(), services: c.services.clone() };
+                (hash_key(&c.key), identity)
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// 认证 API Key, 返回对应的应用身份
+    pub fn authenticate(&self, key: &str) -> Option<AppIdentity> {
+        self.keys.get(&hash_key(key)).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// 简单通配符匹配, `*` 匹配任意长度字符串
+///
+/// 服务 ID 模式 (API Key 归属、联邦导出、令牌范围) 共用此实现
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !value.starts_with(first) || !value.ends_with(last) || value.len() < first.len() + last.len()
+    {
+        return false;
+    }
+
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> ApiKeyStore {
+        ApiKeyStore::new(&[
+            ApiKeyConfig {
+                app_id: "order-app".to_string(),
+                key: "order-secret".to_string(),
+                services: vec!["order-*".to_string(), "payment-service".to_string()],
+            },
+            ApiKeyConfig { app_id: "empty".to_string(), key: String::new(), services: vec![] },
+        ])
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let store = store();
+        assert_eq!(store.len(), 1);
+
+        let identity = store.authenticate("order-secret").unwrap();
+        assert_eq!(identity.app_id, "order-app");
+        assert!(store.authenticate("wrong").is_none());
+        assert!(store.authenticate("").is_none());
+    }
+
+    #[test]
+    fn test_owns_service() {
+        let identity = store().authenticate("order-secret").unwrap();
+        assert!(identity.owns_service("order-service"));
+        assert!(identity.owns_service("ORDER-api"));
+        assert!(identity.owns_service("payment-service"));
+        assert!(!identity.owns_service("payment-service-v2"));
+        assert!(!identity.owns_service("user-service"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("a*c", "abc"));
+        assert!(wildcard_match("a*b*c", "a-b-c"));
+        assert!(!wildcard_match("a*c", "ab"));
+        assert!(!wildcard_match("abc", "abcd"));
+        assert!(wildcard_match("team-*-api-*", "team-a-api-gateway"));
+        assert!(!wildcard_match("team-*-api", "team-a-web"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+    }
+}
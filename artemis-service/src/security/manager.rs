@@ -0,0 +1,125 @@
+use super::api_key::{ApiKeyStore, AppIdentity};
+use super::signer::RequestSigner;
+use crate::config::SecurityConfig;
+use tracing::{info, warn};
+
+/// 核心接口安全管理器
+///
+/// 未启用 (默认) 时所有检查均放行, 保持与旧客户端兼容
+#[derive(Debug, Clone, Default)]
+pub struct SecurityManager {
+    require_api_key: bool,
+    discovery_requires_api_key: bool,
+    api_keys: ApiKeyStore,
+    signer: Option<RequestSigner>,
+    federation_signer: Option<RequestSigner>,
+}
+
+impl SecurityManager {
+    pub fn new(config: &SecurityConfig) -> Self {
+        let api_keys = ApiKeyStore::new(&config.api_keys);
+        if config.require_api_key && api_keys.is_empty() {
+            warn!("security.require_api_key is enabled but no api_keys are configured");
+        }
+
+        let signer = config
+            .cluster_secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| RequestSigner::new(secret, config.max_clock_skew_secs));
+        let federation_signer = config
+            .federation_secret
+            .as_deref()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| RequestSigner::new(secret, config.max_clock_skew_secs));
+
+        info!(
+            "Core API security: api_key={}, discovery_api_key={}, signed_replication={}",
+            config.require_api_key,
+            config.require_api_key && config.discovery_requires_api_key,
+            signer.is_some()
+        );
+
+        Self {
+            require_api_key: config.require_api_key,
+            discovery_requires_api_key: config.discovery_requires_api_key,
+            api_keys,
+            signer,
+            federation_signer,
+        }
+    }
+
+    /// 注册接口是否要求 API Key
+    pub fn registry_requires_api_key(&self) -> bool {
+        self.require_api_key
+    }
+
+    /// 发现接口是否要求 API Key
+    pub fn discovery_requires_api_key(&self) -> bool {
+        self.require_api_key && self.discovery_requires_api_key
+    }
+
+    /// 认证 API Key
+    pub fn authenticate(&self, key: &str) -> Option<AppIdentity> {
+        self.api_keys.authenticate(key)
+    }
+
+    /// 复制请求签名器 (未配置集群密钥时为 None)
+    pub fn signer(&self) -> Option<&RequestSigner> {
+        self.signer.as_ref()
+    }
+
+    /// 跨区域联邦请求签名器 (未配置联邦密钥时为 None)
+    pub fn federation_signer(&self) -> Option<&RequestSigner> {
+        self.federation_signer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiKeyConfig;
+
+    #[test]
+    fn test_default_is_disabled() {
+        let manager = SecurityManager::default();
+        assert!(!manager.registry_requires_api_key());
+        assert!(!manager.discovery_requires_api_key());
+        assert!(manager.signer().is_none());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = SecurityConfig {
+            require_api_key: true,
+            api_keys: vec![ApiKeyConfig {
+                app_id: "order-app".to_string(),
+                key: "k1".to_string(),
+                services: vec!["order-*".to_string()],
+            }],
+            cluster_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let manager = SecurityManager::new(&config);
+
+        assert!(manager.registry_requires_api_key());
+        assert!(manager.discovery_requires_api_key());
+        assert!(manager.signer().is_some());
+        assert_eq!(manager.authenticate("k1").unwrap().app_id, "order-app");
+    }
+
+    #[test]
+    fn test_empty_cluster_secret_disables_signing() {
+        let config = SecurityConfig { cluster_secret: Some(String::new()), ..Default::default() };
+        assert!(SecurityManager::new(&config).signer().is_none());
+    }
+
+    #[test]
+    fn test_federation_secret_is_separate() {
+        let config =
+            SecurityConfig { federation_secret: Some("wan".to_string()), ..Default::default() };
+        let manager = SecurityManager::new(&config);
+        assert!(manager.signer().is_none());
+        assert!(manager.federation_signer().is_some());
+    }
+}
@@ -0,0 +1,23 @@
+//! Authentication for registry, discovery and replication endpoints
+//!
+//! This module provides:
+//! - Per-application API keys with owned service id patterns
+//! - HMAC-SHA256 request signing with a cluster shared secret
+//! - A `SecurityManager` built from `[security]` configuration
+
+pub mod api_key;
+pub mod manager;
+pub mod signer;
+
+pub use api_key::{ApiKeyStore, AppIdentity, wildcard_match};
+pub use manager::SecurityManager;
+pub use signer::{RequestSigner, SignatureError};
+
+/// API Key 请求头
+pub const API_KEY_HEADER: &str = "X-Artemis-Api-Key";
+/// 签名时间戳请求头 (Unix 秒)
+pub const TIMESTAMP_HEADER: &str = "X-Artemis-Timestamp";
+/// 签名 nonce 请求头 (每个请求唯一, 用于拒绝重放)
+pub const NONCE_HEADER: &str = "X-Artemis-Nonce";
+/// 签名请求头 (十六进制 HMAC-SHA256)
+pub const SIGNATURE_HEADER: &str = "X-Artemis-Signature";
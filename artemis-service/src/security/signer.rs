@@ -0,0 +1,208 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 签名校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// 缺少时间戳、nonce 或签名
+    Missing,
+    /// 时间戳格式错误或超出允许的时钟偏差
+    Expired,
+    /// 签名不匹配
+    Invalid,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::Missing => write!(f, "Missing request signature"),
+            SignatureError::Expired => write!(f, "Request timestamp outside allowed skew"),
+            SignatureError::Invalid => write!(f, "Invalid request signature"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// 基于集群共享密钥的请求签名器
+///
+/// 签名内容为 `timestamp\nnonce\nMETHOD\npath\nhex(sha256(body))`, 使用 HMAC-SHA256。
+/// nonce 每个请求唯一, 接收方在重放窗口内记录已见过的 nonce 以拒绝重放
+#[derive(Clone)]
+pub struct RequestSigner {
+    secret: Vec<u8>,
+    max_clock_skew_secs: u64,
+}
+
+impl fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestSigner")
+            .field("max_clock_skew_secs", &self.max_clock_skew_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestSigner {
+    pub fn new(secret: impl AsRef<[u8]>, max_clock_skew_secs: u64) -> Self {
+        Self { secret: secret.as_ref().to_vec(), max_clock_skew_secs }
+    }
+
+    /// 对请求签名, 返回十六进制签名
+    pub fn sign(
+        &self,
+        timestamp: i64,
+        nonce: &str,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> String {
+        hex::encode(self.mac(timestamp, nonce, method, path, body).finalize().into_bytes())
+    }
+
+    /// 以当前时间和随机 nonce 签名, 返回 (timestamp, nonce, signature)
+    pub fn sign_now(&self, method: &str, path: &str, body: &[u8]) -> (i64, String, String) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let signature = self.sign(timestamp, &nonce, method, path, body);
+        (timestamp, nonce, signature)
+    }
+
+    /// 重放窗口 (秒): 时间戳与当前时间相差超过该值的请求被拒绝
+    pub fn max_clock_skew_secs(&self) -> u64 {
+        self.max_clock_skew_secs
+    }
+
+    /// 校验请求签名 (常量时间比较), 成功时返回请求时间戳
+    ///
+    /// 只校验签名和时间戳, nonce 是否重复由调用方检查
+    pub fn verify(
+        &self,
+        timestamp: Option<&str>,
+        nonce: Option<&str>,
+        signature: Option<&str>,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<i64, SignatureError> {
+        let (Some(timestamp), Some(nonce), Some(signature)) = (timestamp, nonce, signature) else {
+            return Err(SignatureError::Missing);
+        };
+        if nonce.is_empty() {
+            return Err(SignatureError::Missing);
+        }
+
+        let timestamp: i64 = timestamp.parse().map_err(|_| SignatureError::Expired)?;
+        let now = chrono::Utc::now().timestamp();
+        if now.abs_diff(timestamp) > self.max_clock_skew_secs {
+            return Err(SignatureError::Expired);
+        }
+
+        let signature = hex::decode(signature).map_err(|_| SignatureError::Invalid)?;
+        self.mac(timestamp, nonce, method, path, body)
+            .verify_slice(&signature)
+            .map_err(|_| SignatureError::Invalid)?;
+        Ok(timestamp)
+    }
+
+    fn mac(
+        &self,
+        timestamp: i64,
+        nonce: &str,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b"\n");
+        mac.update(nonce.as_bytes());
+        mac.update(b"\n");
+        mac.update(method.to_uppercase().as_bytes());
+        mac.update(b"\n");
+        mac.update(path.as_bytes());
+        mac.update(b"\n");
+        mac.update(hex::encode(Sha256::digest(body)).as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/api/replication/registry/register.json";
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = RequestSigner::new("cluster-secret", 300);
+        let (timestamp, nonce, signature) = signer.sign_now("POST", PATH, b"{}");
+
+        assert_eq!(
+            signer.verify(
+                Some(&timestamp.to_string()),
+                Some(&nonce),
+                Some(&signature),
+                "post",
+                PATH,
+                b"{}"
+            ),
+            Ok(timestamp)
+        );
+
+        // 每次签名使用不同的 nonce
+        let (_, other_nonce, _) = signer.sign_now("POST", PATH, b"{}");
+        assert_ne!(nonce, other_nonce);
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let signer = RequestSigner::new("cluster-secret", 300);
+        let (timestamp, nonce, signature) = signer.sign_now("POST", PATH, b"{}");
+        let timestamp = timestamp.to_string();
+        let verify = |signer: &RequestSigner, nonce: &str, path: &str, body: &[u8]| {
+            signer.verify(Some(&timestamp), Some(nonce), Some(&signature), "POST", path, body)
+        };
+
+        assert_eq!(verify(&signer, &nonce, PATH, b"{\"a\":1}"), Err(SignatureError::Invalid));
+        assert_eq!(verify(&signer, &nonce, "/other", b"{}"), Err(SignatureError::Invalid));
+        assert_eq!(verify(&signer, "other-nonce", PATH, b"{}"), Err(SignatureError::Invalid));
+
+        let other = RequestSigner::new("other-secret", 300);
+        assert_eq!(verify(&other, &nonce, PATH, b"{}"), Err(SignatureError::Invalid));
+    }
+
+    #[test]
+    fn test_verify_rejects_missing_and_expired() {
+        let signer = RequestSigner::new("cluster-secret", 300);
+        assert_eq!(
+            signer.verify(None, None, None, "POST", PATH, b""),
+            Err(SignatureError::Missing)
+        );
+
+        let now = chrono::Utc::now().timestamp();
+        let signature = signer.sign(now, "", "POST", PATH, b"");
+        assert_eq!(
+            signer.verify(Some(&now.to_string()), None, Some(&signature), "POST", PATH, b""),
+            Err(SignatureError::Missing)
+        );
+        assert_eq!(
+            signer.verify(Some(&now.to_string()), Some(""), Some(&signature), "POST", PATH, b""),
+            Err(SignatureError::Missing)
+        );
+
+        let stale = now - 600;
+        let signature = signer.sign(stale, "n", "POST", PATH, b"");
+        assert_eq!(
+            signer.verify(Some(&stale.to_string()), Some("n"), Some(&signature), "POST", PATH, b""),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            signer.verify(Some("not-a-number"), Some("n"), Some(&signature), "POST", PATH, b""),
+            Err(SignatureError::Expired)
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use artemis_common::model::ResponseStatus;
 use crate::model::status::node_status;
 use crate::model::{
     GetClusterNodeStatusRequest, GetClusterNodeStatusResponse, GetClusterStatusRequest,
@@ -6,12 +5,48 @@ use crate::model::{
     GetDeploymentStatusRequest, GetDeploymentStatusResponse, GetLeasesStatusRequest,
     GetLeasesStatusResponse, LeaseStatus, ServiceNode, ServiceNodeStatus,
 };
+use artemis_common::model::ResponseStatus;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::cluster::ClusterManager;
 use crate::lease::LeaseManager;
 
+/// 节点就绪状态
+///
+/// 集群节点启动后先从对等节点全量同步, 同步完成或超时之前不对外提供发现服务
+#[derive(Debug)]
+pub struct Readiness {
+    ready: AtomicBool,
+}
+
+impl Readiness {
+    /// 已就绪
+    pub fn ready() -> Self {
+        Self { ready: AtomicBool::new(true) }
+    }
+
+    /// 启动中, 需要调用 mark_ready 后才就绪
+    pub fn starting() -> Self {
+        Self { ready: AtomicBool::new(false) }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::ready()
+    }
+}
+
 /// StatusService 提供系统状态查询功能
 pub struct StatusService {
     cluster_manager: Option<Arc<ClusterManager>>,
@@ -21,6 +56,7 @@ pub struct StatusService {
     zone_id: String,
     server_url: String,
     app_id: String,
+    readiness: Arc<Readiness>,
 }
 
 impl StatusService {
@@ -33,33 +69,56 @@ impl StatusService {
         server_url: String,
         app_id: String,
     ) -> Self {
-        Self { cluster_manager, lease_manager, node_id, region_id, zone_id, server_url, app_id }
+        Self {
+            cluster_manager,
+            lease_manager,
+            node_id,
+            region_id,
+            zone_id,
+            server_url,
+            app_id,
+            readiness: Arc::new(Readiness::default()),
+        }
     }
 
-    // ==================== Node Status ====================
+    /// 使用共享的就绪状态 (启动同步完成后标记就绪)
+    pub fn with_readiness(mut self, readiness: Arc<Readiness>) -> Self {
+        self.readiness = readiness;
+        self
+    }
 
-    pub async fn get_cluster_node_status(
-        &self,
-        _request: GetClusterNodeStatusRequest,
-    ) -> GetClusterNodeStatusResponse {
+    pub fn readiness(&self) -> &Arc<Readiness> {
+        &self.readiness
+    }
+
+    /// 当前节点状态: 启动同步完成前为 starting
+    fn current_node_status(&self) -> ServiceNodeStatus {
         let node = ServiceNode {
             node_id: self.node_id.clone(),
             url: self.server_url.clone(),
             region_id: self.region_id.clone(),
             zone_id: self.zone_id.clone(),
         };
+        let ready = self.readiness.is_ready();
 
-        let node_status = ServiceNodeStatus {
+        ServiceNodeStatus {
             node,
-            status: node_status::UP.to_string(),
-            can_service_discovery: true,
+            status: if ready { node_status::UP } else { node_status::STARTING }.to_string(),
+            can_service_discovery: ready,
             can_service_registry: true,
             allow_registry_from_other_zone: true,
             allow_discovery_from_other_zone: true,
-        };
+        }
+    }
+
+    // ==================== Node Status ====================
 
+    pub async fn get_cluster_node_status(
+        &self,
+        _request: GetClusterNodeStatusRequest,
+    ) -> GetClusterNodeStatusResponse {
         GetClusterNodeStatusResponse {
-            node_status: Some(node_status),
+            node_status: Some(self.current_node_status()),
             response_status: ResponseStatus::success(),
         }
     }
@@ -73,25 +132,11 @@ impl StatusService {
         let mut nodes_status = Vec::new();
 
         // 添加当前节点
-        let current_node = ServiceNode {
-            node_id: self.node_id.clone(),
-            url: self.server_url.clone(),
-            region_id: self.region_id.clone(),
-            zone_id: self.zone_id.clone(),
-        };
-
-        nodes_status.push(ServiceNodeStatus {
-            node: current_node,
-            status: node_status::UP.to_string(),
-            can_service_discovery: true,
-            can_service_registry: true,
-            allow_registry_from_other_zone: true,
-            allow_discovery_from_other_zone: true,
-        });
+        nodes_status.push(self.current_node_status());
 
-        // 添加集群节点
+        // 添加集群节点 (gossip 模式下成员表包含当前节点, 需要排除)
         if let Some(ref cluster_mgr) = self.cluster_manager {
-            let cluster_nodes = cluster_mgr.get_healthy_nodes();
+            let cluster_nodes = cluster_mgr.get_healthy_peers();
             for cluster_node in cluster_nodes.iter() {
                 let node = ServiceNode {
                     node_id: cluster_node.node_id.clone(),
@@ -100,7 +145,7 @@ impl StatusService {
                     zone_id: self.zone_id.clone(),     // 集群节点使用相同的 zone
                 };
 
-                // get_healthy_nodes() 已经过滤了健康节点,所以状态都是 UP
+                // get_healthy_peers() 已经过滤了健康节点,所以状态都是 UP
                 nodes_status.push(ServiceNodeStatus {
                     node,
                     status: node_status::UP.to_string(),
@@ -417,6 +462,21 @@ mod tests {
         assert!(node_status.can_service_registry);
     }
 
+    #[tokio::test]
+    async fn test_node_status_while_starting() {
+        let readiness = Arc::new(Readiness::starting());
+        let service = create_test_service().with_readiness(readiness.clone());
+
+        let response = service.get_cluster_node_status(GetClusterNodeStatusRequest {}).await;
+        let node_status = response.node_status.unwrap();
+        assert_eq!(node_status.status, node_status::STARTING);
+        assert!(!node_status.can_service_discovery);
+
+        readiness.mark_ready();
+        let response = service.get_cluster_node_status(GetClusterNodeStatusRequest {}).await;
+        assert_eq!(response.node_status.unwrap().status, node_status::UP);
+    }
+
     // ========== Cluster Status 测试 ==========
 
     #[tokio::test]
@@ -448,6 +508,31 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_get_cluster_status_with_gossip_membership() {
+        let cluster = ClusterManager::new("test-node".to_string(), vec![])
+            .with_local_node("http://localhost:8080");
+        let mut peer = crate::cluster::ClusterNode::new_from_url("10.0.0.2:8080".to_string());
+        peer.node_id = "node-2".to_string();
+        cluster.merge_members(vec![peer]);
+
+        let lease_manager = Arc::new(LeaseManager::new(std::time::Duration::from_secs(30)));
+        let service = StatusService::new(
+            Some(Arc::new(cluster)),
+            lease_manager,
+            "test-node".to_string(),
+            "test-region".to_string(),
+            "test-zone".to_string(),
+            "http://localhost:8080".to_string(),
+            "test-app".to_string(),
+        );
+
+        // 成员表中的当前节点不重复计入
+        let response = service.get_cluster_status(GetClusterStatusRequest {}).await;
+        assert_eq!(response.node_count, 2);
+        assert_eq!(response.nodes_status[1].node.node_id, "node-2");
+    }
+
     // ========== Config Status 测试 ==========
 
     #[tokio::test]
@@ -22,8 +22,8 @@ use artemis_common::model::{
     ServicesDeltaRequest, SyncFullDataRequest, UnregisterRequest,
 };
 use artemis_service::{
-    traits::RegistryService, RegistryServiceImpl, cache::VersionedCacheManager,
-    change::InstanceChangeManager, lease::LeaseManager, registry::RegistryRepository,
+    RegistryServiceImpl, cache::VersionedCacheManager, change::InstanceChangeManager,
+    lease::LeaseManager, registry::RegistryRepository, traits::RegistryService,
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -261,7 +261,7 @@ async fn test_register_from_replication() {
     let (service, repo) = create_test_registry_service();
 
     let instance = create_test_instance("my-service", "inst-1");
-    let request = ReplicateRegisterRequest { instances: vec![instance] };
+    let request = ReplicateRegisterRequest { instances: vec![instance], revisions: Vec::new() };
 
     let response = service.register_from_replication(request).await;
 
@@ -297,7 +297,7 @@ async fn test_unregister_from_replication() {
     service.register(RegisterRequest { instances: vec![instance] }).await;
 
     // 复制注销
-    let request = ReplicateUnregisterRequest { instance_keys: vec![key] };
+    let request = ReplicateUnregisterRequest { instance_keys: vec![key], revisions: Vec::new() };
     let response = service.unregister_from_replication(request).await;
 
     assert_eq!(response.response_status.error_code, ErrorCode::Success);
@@ -316,7 +316,7 @@ async fn test_batch_register() {
         create_test_instance("service-2", "inst-1"),
     ];
 
-    let request = BatchRegisterRequest { instances };
+    let request = BatchRegisterRequest { instances, revisions: Vec::new() };
     let response = service.batch_register(request).await;
 
     assert_eq!(response.response_status.error_code, ErrorCode::Success);
@@ -381,7 +381,7 @@ async fn test_batch_unregister() {
     assert_eq!(repo.count(), 3);
 
     // 批量注销
-    let request = BatchUnregisterRequest { instance_keys: keys };
+    let request = BatchUnregisterRequest { instance_keys: keys, revisions: Vec::new() };
     let response = service.batch_unregister(request).await;
 
     assert_eq!(response.response_status.error_code, ErrorCode::Success);
@@ -4,18 +4,28 @@
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+use artemis_management::apply::{ApplyPlan, DocumentFormat};
 use artemis_management::auth::UserRole;
+use artemis_management::db::{ConflictPolicy, ManagementArchive};
 use artemis_management::{
     AuthManager, ConfigLoader, Database, GroupManager, GroupRoutingFilter, InstanceManager,
     ManagementDiscoveryFilter, RouteEngine, RouteManager,
 };
+use artemis_server::{server::run_server, state::AppState};
+use artemis_service::alert::{AlertManager, WebhookSink};
 use artemis_service::config::ArtemisConfig;
+use artemis_service::security::SecurityManager;
 use artemis_service::{
-    RegistryServiceImpl, cache::VersionedCacheManager, cluster::ClusterManager,
-    discovery::DiscoveryServiceImpl, lease::LeaseManager, registry::RegistryRepository,
-    replication::ReplicationManager,
+    RegistryServiceImpl,
+    cache::VersionedCacheManager,
+    cluster::{ClusterManager, GossipConfig, GossipMembership},
+    discovery::DiscoveryServiceImpl,
+    federation::{FederationManager, FederationSync},
+    lease::LeaseManager,
+    registry::RegistryRepository,
+    replication::{AntiEntropyTask, BootstrapSync, ReplicationManager},
+    status::Readiness,
 };
-use artemis_server::{server::run_server, state::AppState};
 use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -52,6 +62,37 @@ enum Commands {
         input: String,
         output: String,
     },
+    /// Database schema migrations and management data export/import
+    Db {
+        /// Configuration file path (TOML format)
+        #[arg(short, long, global = true)]
+        config: Option<String>,
+
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Apply a declarative management document (groups, route rules, canary, zones)
+    Apply {
+        /// Document path (YAML, TOML or JSON, by extension)
+        #[arg(short, long)]
+        file: String,
+
+        /// Artemis server URL
+        #[arg(long, default_value = "http://localhost:8080")]
+        server: String,
+
+        /// Access token or API token (defaults to $ARTEMIS_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Only show the changes, do not apply them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Delete objects not declared in the document
+        #[arg(long)]
+        prune: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -64,6 +105,36 @@ enum InstanceAction {
     List { service_id: String },
 }
 
+#[derive(Subcommand)]
+enum DbAction {
+    /// Apply all pending migrations
+    Migrate,
+    /// Show applied and pending migrations
+    Status,
+    /// Roll back the most recently applied migrations
+    Rollback {
+        /// Number of migrations to roll back
+        #[arg(long, default_value_t = 1)]
+        steps: u32,
+    },
+    /// Export management data (groups, route rules, canary, zones, users) as JSON
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Import management data exported by `db export`
+    Import {
+        /// Archive file
+        #[arg(short, long)]
+        input: String,
+
+        /// How to handle existing objects: skip, overwrite or fail
+        #[arg(long, default_value = "skip")]
+        on_conflict: ConflictPolicy,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
@@ -88,7 +159,110 @@ async fn main() -> anyhow::Result<()> {
             println!("Converting {} to {}", input, output);
             Ok(())
         }
+        Commands::Db { config, action } => run_db_command(config, action).await,
+        Commands::Apply { file, server, token, dry_run, prune } => {
+            run_apply_command(&file, &server, token, dry_run, prune).await
+        }
+    }
+}
+
+async fn run_apply_command(
+    file: &str,
+    server: &str,
+    token: Option<String>,
+    dry_run: bool,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let Some(token) = token.or_else(|| std::env::var("ARTEMIS_TOKEN").ok()) else {
+        anyhow::bail!("No token given: use --token or set ARTEMIS_TOKEN");
+    };
+    let content = std::fs::read_to_string(file)?;
+    let format = DocumentFormat::from_path(file);
+    let url = format!(
+        "{}/api/management/apply?dryRun={}&prune={}",
+        server.trim_end_matches('/'),
+        dry_run,
+        prune
+    );
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .bearer_auth(&token)
+        .header(reqwest::header::CONTENT_TYPE, format.content_type())
+        .body(content)
+        .send()
+        .await?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await?;
+    if !status.is_success() || body["success"] != true {
+        anyhow::bail!(
+            "Apply failed ({}): {}",
+            status,
+            body["message"].as_str().unwrap_or_default()
+        );
+    }
+
+    let plan: ApplyPlan = serde_json::from_value(body["data"].clone())?;
+    for change in &plan.changes {
+        println!("{}", change);
+    }
+    if plan.dry_run {
+        println!("Dry run: {}", plan.summary());
+    } else {
+        println!("Applied: {}", plan.summary());
     }
+    Ok(())
+}
+
+async fn run_db_command(config_path: Option<String>, action: DbAction) -> anyhow::Result<()> {
+    let config = match config_path {
+        Some(path) => ArtemisConfig::from_file(&path)?,
+        None => ArtemisConfig::default(),
+    };
+    let Some(db_config) = &config.database else {
+        anyhow::bail!("No [database] section in configuration");
+    };
+    let db = Database::new(&db_config.url, db_config.max_connections).await?;
+
+    match action {
+        DbAction::Migrate => {
+            db.run_migrations().await?;
+            println!("Database migrations completed");
+        }
+        DbAction::Status => {
+            for (name, applied) in db.migration_status().await? {
+                println!("{:<8} {}", if applied { "applied" } else { "pending" }, name);
+            }
+        }
+        DbAction::Rollback { steps } => {
+            for name in db.rollback_migrations(steps).await? {
+                println!("Rolled back {}", name);
+            }
+        }
+        DbAction::Export { output } => {
+            let archive = db.export_archive().await?;
+            let json = serde_json::to_string_pretty(&archive)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json)?;
+                    eprintln!("Exported management data to {}", path);
+                }
+                None => println!("{}", json),
+            }
+        }
+        DbAction::Import { input, on_conflict } => {
+            let content = std::fs::read_to_string(&input)?;
+            let archive: ManagementArchive = serde_json::from_str(&content)?;
+            let report = db.import_archive(&archive, on_conflict).await?;
+            println!("Imported: {}", report);
+            for key in &report.skipped {
+                println!("  skipped {}", key);
+            }
+        }
+    }
+
+    db.close().await?;
+    Ok(())
 }
 
 async fn start_server(
@@ -104,6 +278,8 @@ async fn start_server(
         ArtemisConfig::default()
     };
 
+    config.validate()?;
+
     // 2. Determine listen address
     let listen_addr: SocketAddr =
         if let Some(addr_str) = addr_override { addr_str.parse()? } else { config.listen_addr() };
@@ -128,32 +304,69 @@ async fn start_server(
     };
 
     // 3. Initialize core components
-    let repository = RegistryRepository::new();
+    let repository = RegistryRepository::new().with_revisions(
+        config.server.node_id.clone(),
+        Duration::from_secs(config.replication.tombstone_window_secs),
+    );
     let lease_manager = Arc::new(LeaseManager::new(Duration::from_secs(config.lease.ttl_secs)));
     let cache = Arc::new(VersionedCacheManager::new());
     let change_manager = Arc::new(artemis_service::InstanceChangeManager::new());
+    let security_manager = Arc::new(SecurityManager::new(&config.security));
 
     // 4. Initialize cluster components (if enabled)
+    let mut membership = None;
     let (cluster_manager, replication_manager) = if config.cluster.enabled {
         println!("Initializing cluster components...");
 
-        // 创建集群管理器
-        let cluster = Arc::new(ClusterManager::new(
-            config.server.node_id.clone(),
-            config.cluster.peers.clone().unwrap_or_default(),
-        ));
-
-        // 启动健康检查任务
-        cluster.clone().start_health_check_task();
+        let peers = config.cluster.peers.clone().unwrap_or_default();
+        let cluster = if config.cluster.membership == "static" {
+            // 固定成员列表, 轮询 /health 判断节点状态
+            let cluster = Arc::new(ClusterManager::new(config.server.node_id.clone(), peers));
+            cluster.clone().start_health_check_task();
+            cluster
+        } else {
+            // peers 作为种子节点, 加入后通过 gossip 发现其他成员
+            let advertise_url = config.cluster.advertise_url(listen_addr)?;
+            let cluster = Arc::new(
+                ClusterManager::new(config.server.node_id.clone(), vec![])
+                    .with_local_node(&advertise_url),
+            );
+            let gossip = Arc::new(
+                GossipMembership::new(
+                    cluster.clone(),
+                    peers,
+                    GossipConfig {
+                        interval: Duration::from_millis(config.cluster.gossip_interval_ms),
+                        probe_timeout: Duration::from_millis(config.cluster.probe_timeout_ms),
+                        indirect_probes: config.cluster.indirect_probes,
+                        suspect_timeout: Duration::from_millis(config.cluster.suspect_timeout_ms),
+                    },
+                )
+                .with_signer(security_manager.signer().cloned()),
+            );
+
+            // 先加入集群, 启动同步才能找到对等节点
+            gossip.join().await;
+            gossip.clone().start();
+            membership = Some(gossip);
+            cluster
+        };
 
         // 创建复制管理器和工作器
         let (repl_mgr, event_rx) = ReplicationManager::new();
         let repl_mgr = Arc::new(repl_mgr);
 
         // 启动复制工作器
-        ReplicationManager::start_worker(event_rx, cluster.clone(), config.replication.clone());
+        ReplicationManager::start_worker(
+            event_rx,
+            cluster.clone(),
+            config.replication.clone(),
+            security_manager.signer().cloned(),
+            repl_mgr.stats().clone(),
+            repository.clone(),
+        );
 
-        println!("Cluster initialized with {} peers", cluster.node_count());
+        println!("Cluster initialized with {} peers", cluster.get_healthy_peers().len());
 
         (Some(cluster), Some(repl_mgr))
     } else {
@@ -186,19 +399,38 @@ async fn start_server(
     let route_engine = Arc::new(RouteEngine::new());
 
     // Initialize authentication manager
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .unwrap_or_else(|_| "artemis-default-secret-change-in-production".to_string());
-    let auth_manager = Arc::new(AuthManager::with_database(database.clone(), jwt_secret));
+    if std::env::var("JWT_SECRET").is_ok() {
+        println!(
+            "Warning: JWT_SECRET is no longer used, tokens are signed with rotating {} keys",
+            config.auth.jwt.algorithm
+        );
+    }
+    let auth_manager =
+        Arc::new(AuthManager::with_database(database.clone()).with_auth_config(&config.auth));
 
     // Load auth data from database
     if let Err(e) = auth_manager.load_from_database().await {
         println!("Warning: Failed to load auth data from database: {:?}", e);
     }
 
+    // Automatic signing key rotation
+    if config.auth.jwt.rotation_interval_secs > 0 {
+        auth_manager
+            .start_key_rotation_task(Duration::from_secs(config.auth.jwt.rotation_interval_secs));
+    }
+
+    // Pick up sessions, revocations and signing keys from other cluster nodes
+    if config.auth.session.sync_interval_secs > 0 {
+        auth_manager
+            .start_session_sync_task(Duration::from_secs(config.auth.session.sync_interval_secs));
+    }
+
     // Create default admin user if database is empty
     if auth_manager.list_users().is_empty() {
-        println!("Creating default admin user (username: admin, password: admin123)");
-        match auth_manager.create_user(
+        println!(
+            "Creating default admin user (username: admin, password: admin123, must be changed on first login)"
+        );
+        match auth_manager.create_user_requiring_password_change(
             "admin",
             Some("admin@artemis.local".to_string()),
             Some("Default administrator account".to_string()),
@@ -224,19 +456,79 @@ async fn start_server(
         println!("Configurations loaded successfully");
     }
 
-    // 8. Create discovery service with filters
-    let mut discovery_service = DiscoveryServiceImpl::new(repository, cache.clone());
+    // 7b. Initialize alert rules (evaluated against registry and lease data)
+    let alert_manager = Arc::new(AlertManager::new(repository.clone(), lease_manager.clone()));
+    alert_manager.add_sink(Arc::new(artemis_server::api::metrics::MetricsAlertSink));
+    for url in &config.alert.webhooks {
+        alert_manager.add_sink(Arc::new(WebhookSink::new(
+            url.clone(),
+            Duration::from_secs(config.alert.webhook_timeout_secs),
+        )));
+    }
+    for rule in config.alert.rules.clone() {
+        if let Err(e) = alert_manager.upsert_rule(rule) {
+            println!("Warning: Invalid alert rule in configuration: {}", e);
+        }
+    }
+    if config.alert.enabled {
+        alert_manager
+            .clone()
+            .start_evaluation_task(Duration::from_secs(config.alert.evaluation_interval_secs));
+        println!("Alert evaluation enabled with {} rules", alert_manager.list_rules().len());
+    }
+
+    // 7c. Approval workflow for high-risk management operations
+    let approval_manager = Arc::new(
+        artemis_management::ApprovalManager::new(
+            instance_manager.clone(),
+            zone_manager.clone(),
+            audit_manager.clone(),
+        )
+        .with_config(&config.approval)
+        .with_registry(repository.clone())
+        .with_database(database.clone()),
+    );
+    if !approval_manager.policies().is_empty() {
+        let operations: Vec<String> =
+            approval_manager.policies().iter().map(|p| p.operation.to_string()).collect();
+        println!("Approval required for: {}", operations.join(", "));
+    }
+
+    // 8. Multi-region federation (read-only views of remote regions for failover)
+    let federation = config.federation.enabled.then(|| {
+        Arc::new(FederationManager::new(
+            config.server.region.clone(),
+            repository.clone(),
+            &config.federation,
+        ))
+    });
+    if let Some(federation) = federation.clone().filter(|_| !config.federation.remotes.is_empty()) {
+        println!(
+            "Federation enabled with {} remote regions (interval: {}s)",
+            config.federation.remotes.len(),
+            config.federation.sync_interval_secs
+        );
+        FederationSync::new(
+            federation,
+            config.federation.remotes.clone(),
+            Duration::from_secs(config.federation.sync_interval_secs),
+            Duration::from_secs(config.federation.timeout_secs),
+        )
+        .with_signer(security_manager.federation_signer().cloned())
+        .start();
+    }
+
+    // 8a. Create discovery service with filters
+    let mut discovery_service =
+        DiscoveryServiceImpl::new(repository, cache.clone()).with_federation(federation.clone());
 
     // Add management filter (pull-in/pull-out)
-    discovery_service.add_filter(Arc::new(ManagementDiscoveryFilter::new(
-        instance_manager.clone(),
-    )));
+    discovery_service
+        .add_filter(Arc::new(ManagementDiscoveryFilter::new(instance_manager.clone())));
 
     // Add group routing filter
-    discovery_service.add_filter(Arc::new(GroupRoutingFilter::new(
-        route_manager.clone(),
-        route_engine.clone(),
-    )));
+    discovery_service
+        .add_filter(Arc::new(GroupRoutingFilter::new(route_manager.clone(), route_engine.clone())));
 
     let discovery_service = Arc::new(discovery_service);
 
@@ -245,16 +537,50 @@ async fn start_server(
     // Load balancer for discovery lookup
     let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
 
-    // Status service
-    let status_service = Arc::new(artemis_service::StatusService::new(
-        cluster_manager.clone(),
-        lease_manager,
-        config.server.node_id.clone(),
-        config.server.region.clone(),
-        config.server.zone.clone(),
-        format!("http://{}", config.server.listen_addr),
-        "artemis".to_string(), // app_id
-    ));
+    // Status service (集群节点在启动同步完成前不就绪)
+    let bootstrap_enabled = cluster_manager.is_some() && config.cluster.bootstrap_timeout_secs > 0;
+    let readiness =
+        Arc::new(if bootstrap_enabled { Readiness::starting() } else { Readiness::ready() });
+    let status_service = Arc::new(
+        artemis_service::StatusService::new(
+            cluster_manager.clone(),
+            lease_manager,
+            config.server.node_id.clone(),
+            config.server.region.clone(),
+            config.server.zone.clone(),
+            format!("http://{}", config.server.listen_addr),
+            "artemis".to_string(), // app_id
+        )
+        .with_readiness(readiness.clone()),
+    );
+
+    // 8b. Bootstrap full sync from peers (runs while the server starts listening)
+    if let Some(cluster) = cluster_manager.clone().filter(|_| bootstrap_enabled) {
+        println!("Bootstrap sync from peers (timeout: {}s)", config.cluster.bootstrap_timeout_secs);
+        BootstrapSync::new(
+            cluster,
+            config.server.region.clone(),
+            Duration::from_secs(config.cluster.bootstrap_timeout_secs),
+            Duration::from_secs(config.replication.timeout_secs),
+        )
+        .with_signer(security_manager.signer().cloned())
+        .start(registry_service.clone(), readiness);
+    }
+
+    // 8c. Anti-entropy reconciliation with peers
+    let anti_entropy_interval = config.replication.anti_entropy_interval_secs;
+    if let Some(cluster) = cluster_manager.clone().filter(|_| anti_entropy_interval > 0) {
+        println!("Anti-entropy enabled (interval: {}s)", anti_entropy_interval);
+        AntiEntropyTask::new(
+            cluster,
+            registry_service.clone(),
+            Duration::from_secs(anti_entropy_interval),
+            Duration::from_secs(config.replication.timeout_secs),
+        )
+        .with_signer(security_manager.signer().cloned())
+        .with_listener(Arc::new(artemis_server::api::metrics::MetricsAntiEntropyListener))
+        .start();
+    }
 
     // 9. Create AppState
     let state = AppState {
@@ -270,12 +596,22 @@ async fn start_server(
         zone_manager,
         canary_manager,
         audit_manager,
+        approval_manager,
         auth_manager,
         load_balancer,
         status_service,
+        alert_manager,
+        security_manager,
+        federation,
     };
 
     // 10. Start server
     println!("Artemis server listening on {}", listen_addr);
-    run_server(state, listen_addr).await
+    run_server(state, listen_addr).await?;
+
+    // 11. Leave the cluster gracefully
+    if let Some(membership) = membership {
+        membership.leave().await;
+    }
+    Ok(())
 }
@@ -4,11 +4,11 @@ use artemis_common::model::{
     DiscoveryConfig, GetServiceRequest, HeartbeatRequest, Instance, InstanceStatus,
     RegisterRequest, UnregisterRequest,
 };
+use artemis_server::state::AppState;
 use artemis_service::{
     RegistryServiceImpl, cache::VersionedCacheManager, change::InstanceChangeManager,
     discovery::DiscoveryServiceImpl, lease::LeaseManager, registry::RegistryRepository,
 };
-use artemis_server::state::AppState;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -28,6 +28,11 @@ async fn start_test_server(port: u16) -> tokio::task::JoinHandle<()> {
             change_manager.clone(),
             None, // No replication in test
         ));
+        let alert_manager = Arc::new(artemis_service::alert::AlertManager::new(
+            repository.clone(),
+            lease_manager.clone(),
+        ));
+
         let discovery_service = Arc::new(DiscoveryServiceImpl::new(repository, cache.clone()));
 
         let session_manager = Arc::new(artemis_server::websocket::SessionManager::new());
@@ -37,6 +42,11 @@ async fn start_test_server(port: u16) -> tokio::task::JoinHandle<()> {
         let zone_manager = Arc::new(artemis_management::ZoneManager::new());
         let canary_manager = Arc::new(artemis_management::CanaryManager::new());
         let audit_manager = Arc::new(artemis_management::AuditManager::new());
+        let approval_manager = Arc::new(artemis_management::ApprovalManager::new(
+            instance_manager.clone(),
+            zone_manager.clone(),
+            audit_manager.clone(),
+        ));
         let load_balancer = Arc::new(artemis_service::discovery::LoadBalancer::new());
         let status_service = Arc::new(artemis_service::StatusService::new(
             None, // cluster_manager
@@ -63,8 +73,12 @@ async fn start_test_server(port: u16) -> tokio::task::JoinHandle<()> {
             zone_manager,
             canary_manager,
             audit_manager,
+            approval_manager,
             load_balancer,
             status_service,
+            alert_manager,
+            security_manager: Arc::new(artemis_service::security::SecurityManager::default()),
+            federation: None,
             auth_manager,
         };
 
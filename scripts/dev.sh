@@ -426,7 +426,7 @@ Artemis 开发环境一键启动脚本
     前端日志:  ${DEV_DIR}/frontend.log
 
 环境变量:
-    DB_TYPE         数据库类型: sqlite (默认), mysql, none
+    DB_TYPE         数据库类型: sqlite (默认), mysql, postgres, none
     DB_URL          数据库连接 URL (可选)
     DB_MAX_CONN     最大连接数 (默认: 10)
 